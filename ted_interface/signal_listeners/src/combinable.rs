@@ -0,0 +1,135 @@
+use std::hash::Hash;
+
+// How the results of every listener to a signal are folded into one
+pub trait Combinable {
+    fn combine_result(self, other: Self) -> Self;
+    // Checked after each listener; once true, no more listeners are sent the signal
+    fn stops_propagation(&self) -> bool {
+        false
+    }
+}
+
+pub struct CombinedVecs<T>(pub Vec<T>);
+impl<T> Combinable for CombinedVecs<T> {
+    fn combine_result(mut self, mut other: Self) -> Self {
+        self.0.append(&mut other.0);
+        self
+    }
+}
+impl<T> CombinedVecs<T> {
+    pub fn extract(self) -> Vec<T> {
+        self.0
+    }
+}
+
+pub struct CombinedHashMaps<K, V>(pub wolf_hash_map::WolfHashMap<K, V>);
+
+impl<K: Eq + Hash + Clone, V: Clone> Combinable for CombinedHashMaps<K, V> {
+    fn combine_result(mut self, other: Self) -> Self {
+        for (k, v) in other.0.iter() {
+            self.0.insert(k.clone(), v.clone());
+        }
+        self
+    }
+}
+
+pub struct OrBool(pub bool);
+
+impl Combinable for OrBool {
+    fn combine_result(self, other: Self) -> Self {
+        OrBool(self.0 || other.0)
+    }
+}
+impl OrBool {
+    pub fn extract(self) -> bool {
+        self.0
+    }
+}
+
+// Keeps the first result, which is the highest priority listener's
+pub struct CantCombine<T>(pub T);
+impl<T> Combinable for CantCombine<T> {
+    fn combine_result(self, _other: Self) -> Self {
+        self
+    }
+}
+impl<T> CantCombine<T> {
+    pub fn extract(self) -> T {
+        self.0
+    }
+}
+
+pub struct Added<T>(pub T);
+impl<T: std::ops::Add<Output = T>> Combinable for Added<T> {
+    fn combine_result(self, other: Self) -> Self {
+        Added(self.0 + other.0)
+    }
+}
+impl<T> Added<T> {
+    pub fn extract(self) -> T {
+        self.0
+    }
+}
+
+pub struct AddedTuple<T, U>(pub T, pub U);
+
+impl<T: std::ops::Add<Output = T>, U: std::ops::Add<Output = U>> Combinable for AddedTuple<T, U> {
+    fn combine_result(self, other: Self) -> Self {
+        AddedTuple(self.0 + other.0, self.1 + other.1)
+    }
+}
+
+pub struct Multiplied<T>(pub T);
+impl<T: std::ops::Mul<Output = T>> Combinable for Multiplied<T> {
+    fn combine_result(self, other: Self) -> Self {
+        Multiplied(self.0 * other.0)
+    }
+}
+impl<T> Multiplied<T> {
+    pub fn extract(self) -> T {
+        self.0
+    }
+}
+
+// Lets a listener end the signal early, keeping what was combined so far
+pub enum Stoppable<T> {
+    Continue(T),
+    Stop(T),
+}
+impl<T: Combinable> Combinable for Stoppable<T> {
+    fn combine_result(self, other: Self) -> Self {
+        match (self, other) {
+            (Stoppable::Stop(first), _) => Stoppable::Stop(first),
+            (Stoppable::Continue(first), Stoppable::Continue(second)) => {
+                Stoppable::Continue(first.combine_result(second))
+            }
+            (Stoppable::Continue(first), Stoppable::Stop(second)) => {
+                Stoppable::Stop(first.combine_result(second))
+            }
+        }
+    }
+    fn stops_propagation(&self) -> bool {
+        matches!(self, Stoppable::Stop(_))
+    }
+}
+impl<T> Stoppable<T> {
+    pub fn extract(self) -> T {
+        match self {
+            Stoppable::Continue(result) | Stoppable::Stop(result) => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stopping_keeps_everything_combined_before_it() {
+        let combined = Stoppable::Continue(Added(1))
+            .combine_result(Stoppable::Stop(Added(2)))
+            .combine_result(Stoppable::Continue(Added(4)));
+        assert!(combined.stops_propagation());
+        assert_eq!(combined.extract().extract(), 3);
+    }
+}
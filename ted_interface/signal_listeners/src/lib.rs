@@ -0,0 +1,10 @@
+/*
+What the client and server both need for signals: the listeners on one object, and how their
+results are combined. define_signal_listener! is given the path to this crate.
+*/
+
+mod combinable;
+pub use combinable::*;
+
+mod signal_listeners;
+pub use crate::signal_listeners::*;
@@ -0,0 +1,142 @@
+use id::IdMap;
+use std::cmp::Reverse;
+use std::rc::Rc;
+
+// Where a listener comes in the send order: highest priority first, then in the order they were added
+type SendOrder = (Reverse<i32>, u64);
+
+// The listeners for one signal on one game object, indexed by id and kept in the order they're sent to
+pub struct SignalListeners<Id, L: ?Sized> {
+    listeners: IdMap<Id, (SendOrder, Rc<L>)>,
+    send_order: Vec<(SendOrder, Id)>,
+    next_added: u64,
+}
+
+// How far a send has got. Listeners can add and remove listeners mid-send, so rather than holding
+// on to the listeners, sending looks up the next one after where it was each time
+#[derive(Clone, Copy, Debug)]
+pub struct SendCursor {
+    last_sent: Option<SendOrder>,
+    // Listeners added after the send started don't get it
+    added_before: u64,
+}
+
+impl<Id: Into<u32> + From<u32> + Copy, L: ?Sized> SignalListeners<Id, L> {
+    pub fn new() -> Self {
+        SignalListeners {
+            listeners: IdMap::new(),
+            send_order: Vec::new(),
+            next_added: 0,
+        }
+    }
+    pub fn insert(&mut self, id: Id, priority: i32, listener: Rc<L>) {
+        self.remove(id);
+        let order = (Reverse(priority), self.next_added);
+        self.next_added += 1;
+        let position = self
+            .send_order
+            .partition_point(|(other_order, _id)| *other_order < order);
+        self.send_order.insert(position, (order, id));
+        self.listeners.insert(id, (order, listener));
+    }
+    pub fn remove(&mut self, id: Id) -> Option<Rc<L>> {
+        let (order, listener) = self.listeners.remove(id)?;
+        if let Ok(position) = self
+            .send_order
+            .binary_search_by(|(other_order, _id)| other_order.cmp(&order))
+        {
+            self.send_order.remove(position);
+        }
+        Some(listener)
+    }
+    pub fn get(&self, id: Id) -> Option<&Rc<L>> {
+        self.listeners.get(id).map(|(_order, listener)| listener)
+    }
+    pub fn start_send(&self) -> SendCursor {
+        SendCursor {
+            last_sent: None,
+            added_before: self.next_added,
+        }
+    }
+    // The next listener to send to, moving the cursor on past it
+    pub fn next_listener(&self, cursor: &mut SendCursor) -> Option<Rc<L>> {
+        let start = match cursor.last_sent {
+            Some(last_sent) => self
+                .send_order
+                .partition_point(|(order, _id)| *order <= last_sent),
+            None => 0,
+        };
+        let (order, id) = self.send_order[start..]
+            .iter()
+            .find(|((_priority, added), _id)| *added < cursor.added_before)?;
+        cursor.last_sent = Some(*order);
+        self.get(*id).map(Rc::clone)
+    }
+    pub fn get_ids(&self) -> Vec<Id> {
+        self.send_order.iter().map(|(_order, id)| *id).collect()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.send_order.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    struct TestId(u32);
+    impl From<u32> for TestId {
+        fn from(id: u32) -> Self {
+            TestId(id)
+        }
+    }
+    impl Into<u32> for TestId {
+        fn into(self) -> u32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn listeners_are_ordered_by_priority_then_insertion() {
+        let mut listeners: SignalListeners<TestId, str> = SignalListeners::new();
+        listeners.insert(TestId(5), 0, Rc::from("first default"));
+        listeners.insert(TestId(2), 0, Rc::from("second default"));
+        listeners.insert(TestId(9), 10, Rc::from("high"));
+        listeners.insert(TestId(1), -3, Rc::from("low"));
+        assert_eq!(
+            listeners.get_ids(),
+            vec![TestId(9), TestId(5), TestId(2), TestId(1)]
+        );
+        // Re-adding a listener moves it rather than duplicating it
+        listeners.insert(TestId(5), 20, Rc::from("now highest"));
+        assert_eq!(listeners.get_ids()[0], TestId(5));
+        assert_eq!(listeners.get_ids().len(), 4);
+        assert_eq!(&*listeners.remove(TestId(2)).unwrap(), "second default");
+        assert!(listeners.get(TestId(2)).is_none());
+        assert_eq!(&**listeners.get(TestId(9)).unwrap(), "high");
+    }
+
+    #[test]
+    fn sends_skip_listeners_removed_or_added_part_way_through() {
+        let mut listeners: SignalListeners<TestId, str> = SignalListeners::new();
+        listeners.insert(TestId(1), 0, Rc::from("one"));
+        listeners.insert(TestId(2), 0, Rc::from("two"));
+        listeners.insert(TestId(3), 0, Rc::from("three"));
+        let mut cursor = listeners.start_send();
+        assert_eq!(&*listeners.next_listener(&mut cursor).unwrap(), "one");
+        // As if the first listener took the second off and added another
+        listeners.remove(TestId(2));
+        listeners.insert(TestId(4), 0, Rc::from("four"));
+        listeners.insert(TestId(5), 10, Rc::from("five"));
+        assert_eq!(&*listeners.next_listener(&mut cursor).unwrap(), "three");
+        assert!(listeners.next_listener(&mut cursor).is_none());
+
+        let mut cursor = listeners.start_send();
+        let mut sent = Vec::new();
+        while let Some(listener) = listeners.next_listener(&mut cursor) {
+            sent.push(listener.to_string());
+        }
+        assert_eq!(sent, vec!["five", "one", "three", "four"]);
+    }
+}
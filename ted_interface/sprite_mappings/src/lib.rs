@@ -42,6 +42,54 @@ pub const ANT_SPRITE: u32 = 34;
 pub const ANT_SPAWNER_SPRITE: u32 = 35;
 pub const WOLF_SPRITE: u32 = 36;
 
+// For data files, which refer to entity sprites by name rather than number
+const ENTITY_SPRITE_NAMES: &[(&str, u32)] = &[
+    ("marker_rune", MARKER_RUNE_SPRITE),
+    ("cross", CROSS_SPRITE),
+    ("nun", NUN_SPRITE),
+    ("knight_down", KNIGHT_SPRITE_DOWN),
+    ("knight_up", KNIGHT_SPRITE_UP),
+    ("knight_right", KNIGHT_SPRITE_RIGHT),
+    ("knight_left", KNIGHT_SPRITE_LEFT),
+    ("tree", TREE_SPRITE),
+    ("zombie", ZOMBIE_SPRITE),
+    ("fireball", FIREBALL_SPRITE),
+    ("corpse", CORPSE_SPRITE),
+    ("necrobolt", NECROBOLT_SPRITE),
+    ("spellbook", SPELLBOOK_SPRITE),
+    ("necromancer", NECROMANCER_SPRITE),
+    ("apple", APPLE_SPRITE),
+    ("wall", WALL_SPRITE),
+    ("confused", CONFUSED_SPRITE),
+    ("ambush", AMBUSH_SPRITE),
+    ("error", ERROR_SPRITE),
+    ("creature_left", CREATURE_SPRITE_LEFT),
+    ("creature_right", CREATURE_SPRITE_RIGHT),
+    ("creature_up", CREATURE_SPRITE_UP),
+    ("creature_down", CREATURE_SPRITE_DOWN),
+    ("villager", VILLAGER_SPRITE),
+    ("flag", FLAG_SPRITE),
+    ("dragon_up", DRAGON_SPRITE_UP),
+    ("dragon_left", DRAGON_SPRITE_LEFT),
+    ("dragon_right", DRAGON_SPRITE_RIGHT),
+    ("dragon_down", DRAGON_SPRITE_DOWN),
+    ("holy_slash", HOLY_SLASH_SPRITE),
+    ("holy_beam", HOLY_BEAM_SPRITE),
+    ("holy_slash_1", HOLY_SLASH_SPRITE_1),
+    ("holy_slash_2", HOLY_SLASH_SPRITE_2),
+    ("holy_slash_3", HOLY_SLASH_SPRITE_3),
+    ("ant", ANT_SPRITE),
+    ("ant_spawner", ANT_SPAWNER_SPRITE),
+    ("wolf", WOLF_SPRITE),
+];
+
+pub fn get_entity_sprite_by_name(name: &str) -> Option<u32> {
+    ENTITY_SPRITE_NAMES
+        .iter()
+        .find(|(sprite_name, _sprite)| *sprite_name == name)
+        .map(|(_sprite_name, sprite)| *sprite)
+}
+
 /*
 Terrain sprites - can overlap with entity sprites
 */
@@ -54,6 +102,7 @@ pub const SCAFFOLD_SPRITE: u32 = 7;
 pub const WATER_SPRITE: u32 = 8;
 pub const SAND_SPRITE: u32 = 9;
 pub const GEM_FLOWER_SPRITE: u32 = 10;
+pub const BRIDGE_SPRITE: u32 = 11;
 
 /*
 Ability Icons
@@ -61,6 +110,25 @@ Ability Icons
 pub const NO_ABILITY_SPRITE: u32 = 0;
 pub const UNKNOWN_ABILITY_SPRITE: u32 = APPLE_SPRITE;
 
+/*
+Status icons
+*/
+pub const BLEED_STATUS_ICON: u32 = AMBUSH_SPRITE;
+pub const SLOW_STATUS_ICON: u32 = MARKER_RUNE_SPRITE;
+pub const STUN_STATUS_ICON: u32 = CROSS_SPRITE;
+pub const CONFUSED_STATUS_ICON: u32 = CONFUSED_SPRITE;
+pub const REGENERATION_STATUS_ICON: u32 = APPLE_SPRITE;
+pub const BURNING_STATUS_ICON: u32 = FIREBALL_SPRITE;
+
+/*
+Item icons
+*/
+pub const HEALTH_POTION_ICON: u32 = APPLE_SPRITE;
+pub const IRON_HELMET_ICON: u32 = KNIGHT_SPRITE_DOWN;
+pub const CHAIN_MAIL_ICON: u32 = CROSS_SPRITE;
+pub const SWIFT_BOOTS_ICON: u32 = HOLY_BEAM_SPRITE;
+pub const FIRE_STAFF_ICON: u32 = FIREBALL_SPRITE;
+
 /*
 Animations
 */
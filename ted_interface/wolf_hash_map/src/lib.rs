@@ -1,5 +1,3 @@
-#![feature(hash_raw_entry)]
-
 mod wolf_hash_map;
 pub use crate::wolf_hash_map::*;
 
@@ -53,11 +53,6 @@ impl<K: std::cmp::Eq + std::hash::Hash, V> WolfHashMap<K, V> {
     pub fn entry(&mut self, key: K) -> Entry<K, V> {
         self.0.entry(key)
     }
-    pub fn raw_entry_mut(
-        &mut self,
-    ) -> RawEntryBuilderMut<K, V, std::hash::BuildHasherDefault<seahash::SeaHasher>> {
-        self.0.raw_entry_mut()
-    }
     pub fn reserve(&mut self, amount: usize) {
         self.0.reserve(amount)
     }
@@ -0,0 +1,23 @@
+#[derive(Debug, Clone, Copy, PartialEq, WolfSerialise)]
+pub enum ChatChannel {
+    // Everyone close enough to see the speech bubble
+    Local,
+    Party,
+    Global,
+    // Only sent by the server, e.g. to say a message was dropped
+    System,
+}
+
+#[derive(Debug, Clone, PartialEq, WolfSerialise)]
+pub struct ChatCommand {
+    pub channel: ChatChannel,
+    pub text: String,
+    // Shown as something the player did, "* Player 3 waves"
+    pub is_emote: bool,
+}
+
+// Joins the named party, or leaves the current one
+#[derive(Debug, Clone, PartialEq, WolfSerialise)]
+pub struct SetPartyCommand {
+    pub party_name: Option<String>,
+}
@@ -0,0 +1,28 @@
+#[derive(Debug, Clone, Copy, PartialEq, WolfSerialise)]
+pub enum InventoryLocation {
+    Backpack(u8),
+    Equipment(u8),
+}
+
+// Swaps whatever is at the two locations, or merges them if they stack
+#[derive(Debug, Clone, PartialEq, WolfSerialise)]
+pub struct MoveItemCommand {
+    pub from: InventoryLocation,
+    pub to: InventoryLocation,
+}
+
+// Puts an item from the backpack into whichever equipment slot it belongs in
+#[derive(Debug, Clone, PartialEq, WolfSerialise)]
+pub struct EquipItemCommand {
+    pub backpack_slot: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, WolfSerialise)]
+pub struct DropItemCommand {
+    pub from: InventoryLocation,
+}
+
+#[derive(Debug, Clone, PartialEq, WolfSerialise)]
+pub struct UseItemCommand {
+    pub backpack_slot: u8,
+}
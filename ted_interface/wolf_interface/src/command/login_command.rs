@@ -0,0 +1,5 @@
+// Sent once on connecting, so the server can pick up where this player left off
+#[derive(Debug, Clone, PartialEq, WolfSerialise)]
+pub struct LoginCommand {
+    pub name: String,
+}
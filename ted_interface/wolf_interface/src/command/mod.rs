@@ -2,12 +2,28 @@ mod move_command;
 pub use move_command::*;
 mod ability_command;
 pub use ability_command::*;
+mod inventory_command;
+pub use inventory_command::*;
+mod progression_command;
+pub use progression_command::*;
+mod chat_command;
+pub use chat_command::*;
+mod login_command;
+pub use login_command::*;
 
 #[derive(Debug, WolfSerialise, PartialEq, Clone)]
 pub enum Command {
     Move(MoveCommand),
     Ability(AbilityCommand),
     TraverseDoorsCommand,
+    MoveItem(MoveItemCommand),
+    EquipItem(EquipItemCommand),
+    DropItem(DropItemCommand),
+    UseItem(UseItemCommand),
+    LearnTalent(LearnTalentCommand),
+    Chat(ChatCommand),
+    SetParty(SetPartyCommand),
+    Login(LoginCommand),
 }
 
 #[cfg(test)]
@@ -26,4 +42,18 @@ mod tests {
             Command::wolf_deserialise(&mut buffer.as_slice()).expect("Failed to deserialise!");
         assert_eq!(command, new_command);
     }
+    #[test]
+    fn move_item_command() {
+        let command = Command::MoveItem(MoveItemCommand {
+            from: InventoryLocation::Backpack(3),
+            to: InventoryLocation::Equipment(1),
+        });
+        let mut buffer = Vec::new();
+        command
+            .wolf_serialise(&mut buffer)
+            .expect("Failed to serialise!");
+        let new_command =
+            Command::wolf_deserialise(&mut buffer.as_slice()).expect("Failed to deserialise!");
+        assert_eq!(command, new_command);
+    }
 }
@@ -0,0 +1,5 @@
+// Spends a talent point on the talent at this index in the current character's tree
+#[derive(Debug, Clone, PartialEq, WolfSerialise)]
+pub struct LearnTalentCommand {
+    pub talent_index: u8,
+}
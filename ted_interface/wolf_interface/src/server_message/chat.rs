@@ -0,0 +1,11 @@
+use crate::ChatChannel;
+
+#[derive(Debug, WolfSerialise, PartialEq, Clone)]
+pub struct ChatMessage {
+    pub channel: ChatChannel,
+    // Empty for system messages
+    pub sender_name: String,
+    pub text: String,
+    pub is_emote: bool,
+    pub sent_at: u32,
+}
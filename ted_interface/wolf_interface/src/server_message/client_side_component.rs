@@ -24,6 +24,13 @@ pub struct SlashAnimationData {
     pub start_tick: u32,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, WolfSerialise)]
+pub struct StatusData {
+    pub icon: u32,
+    pub stacks: u32,
+    pub expires_at: u32,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, WolfSerialise)]
 pub enum CreateComponentData {
     HealthBar,
@@ -33,6 +40,7 @@ pub enum CreateComponentData {
     HealthProportionTenThousandths(u32),
     SlashAnimation(SlashAnimationData),
     Speech(String),
+    Status(StatusData),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -0,0 +1,12 @@
+#[derive(Debug, WolfSerialise, PartialEq, Clone)]
+pub struct ItemStackMessage {
+    pub icon: u32,
+    pub count: u32,
+}
+
+// Every slot is sent, empty or not, so the client can lay them out as they are on the server
+#[derive(Debug, WolfSerialise, PartialEq, Clone)]
+pub struct InventoryMessage {
+    pub backpack: Vec<Option<ItemStackMessage>>,
+    pub equipment: Vec<Option<ItemStackMessage>>,
+}
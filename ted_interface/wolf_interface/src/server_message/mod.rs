@@ -6,10 +6,32 @@ mod client_side_component;
 pub use client_side_component::*;
 mod game_object;
 pub use game_object::*;
+mod inventory;
+pub use inventory::*;
+mod progression;
+pub use progression::*;
+mod chat;
+pub use chat::*;
 
 #[derive(Debug, WolfSerialise, PartialEq, Clone)]
 pub struct SlotMappingMessage {
     pub slot_to_ability_icon: Vec<Option<u32>>,
+    pub slot_to_cooldown: Vec<Option<SlotCooldownMessage>>,
+    pub slot_to_cast: Vec<Option<SlotCastMessage>>,
+}
+
+// Times are in server ticks
+#[derive(Debug, WolfSerialise, PartialEq, Clone)]
+pub struct SlotCooldownMessage {
+    pub started_at: u32,
+    pub ready_at: u32,
+}
+
+#[derive(Debug, WolfSerialise, PartialEq, Clone)]
+pub struct SlotCastMessage {
+    pub started_at: u32,
+    pub finishes_at: u32,
+    pub is_channel: bool,
 }
 
 #[derive(Debug, WolfSerialise, PartialEq, Clone)]
@@ -30,6 +52,9 @@ pub enum ServerMessage {
     ChunkUnload(ChunkUnloadMessage),
     SlotMapping(SlotMappingMessage),
     SetNotifications(SetNotificationsMessage),
+    Inventory(InventoryMessage),
+    Progression(ProgressionMessage),
+    Chat(ChatMessage),
 }
 
 #[derive(Debug, WolfSerialise, PartialEq, Clone)]
@@ -0,0 +1,16 @@
+#[derive(Debug, WolfSerialise, PartialEq, Clone)]
+pub struct TalentMessage {
+    pub name: String,
+    pub learned: bool,
+    // Learned talents it depends on, and a point to spend
+    pub can_learn: bool,
+}
+
+#[derive(Debug, WolfSerialise, PartialEq, Clone)]
+pub struct ProgressionMessage {
+    pub level: u32,
+    pub experience: u32,
+    pub experience_for_next_level: u32,
+    pub talent_points: u32,
+    pub talents: Vec<TalentMessage>,
+}
@@ -12,9 +12,6 @@ impl MutateTextureSignalListener for ColouredComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<(dyn drawable::MutateTextureSignalListener + 'static)> {
-        Box::new(self.clone())
-    }
     fn receive_mutate_texture_signal(
         &self,
         _game: &game::Game,
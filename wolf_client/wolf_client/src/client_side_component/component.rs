@@ -8,4 +8,5 @@ pub trait Component {
 // Stores info on what a component is for removal purposes
 pub enum ComponentInfo {
     Speech,
+    Status,
 }
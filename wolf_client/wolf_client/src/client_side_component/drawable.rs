@@ -5,7 +5,6 @@ use sdl2::pixels::PixelFormatEnum;
 use sdl2::rect::Rect;
 use sdl2::render::*;
 use sdl2::video::*;
-use signal_listener_macro::define_signal_listener;
 
 define_signal_listener!(
     Draw,
@@ -170,6 +169,7 @@ impl Drawable {
             sprites,
             &effective_dest_rect,
         );
+        draw_status_icons_for_id(game, game_object_id, canvas, sprites, &effective_dest_rect);
         game_object_id.send_draw_signal(
             game,
             canvas,
@@ -18,6 +18,7 @@ pub struct Game {
     pub slash_animations: IdMap<SlashAnimationId, SlashAnimation>,
     pub to_delete: Vec<GameObjectId>,
     pub currently_saying: IdMap<GameObjectId, (ComponentId, String)>,
+    pub statuses: IdMap<GameObjectId, Vec<(ComponentId, StatusData)>>,
     pub notifications: Vec<Notification>,
 }
 
@@ -40,6 +41,7 @@ impl Game {
             slash_animations: IdMap::new(),
             to_delete: Vec::new(),
             currently_saying: IdMap::new(),
+            statuses: IdMap::new(),
             notifications: Vec::new(),
         }
     }
@@ -132,6 +134,14 @@ impl Game {
                 self.component_info
                     .insert(component_id, ComponentInfo::Speech);
             }
+            CreateComponentData::Status(status_data) => {
+                // The server resends statuses when they change, under the same id
+                let statuses = self.statuses.entry(game_object_id).or_insert_with(Vec::new);
+                statuses.retain(|(other_id, _)| *other_id != component_id);
+                statuses.push((component_id, status_data));
+                self.component_info
+                    .insert(component_id, ComponentInfo::Status);
+            }
         }
     }
     pub fn handle_update_component_message(
@@ -159,6 +169,12 @@ impl Game {
                         std::collections::hash_map::Entry::Vacant(_) => {}
                     }
                 }
+                ComponentInfo::Status => {
+                    if let Some(statuses) = self.statuses.get_mut(game_object_id) {
+                        let component_id: ComponentId = msg.component_id.into();
+                        statuses.retain(|(other_id, _)| *other_id != component_id);
+                    }
+                }
             };
         }
         if let Some(game_object) = self.game_objects.get_mut(game_object_id) {
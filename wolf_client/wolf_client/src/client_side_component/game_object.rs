@@ -83,5 +83,6 @@ impl GameObjectId {
             component.on_remove(game, *self);
         }
         game.game_objects.remove(*self);
+        game.statuses.remove(*self);
     }
 }
@@ -9,9 +9,6 @@ pub struct HealthBarComponent {
 }
 
 impl DrawSignalListener for HealthBarComponent {
-    fn clone_box(&self) -> Box<dyn DrawSignalListener> {
-        Box::new(self.clone())
-    }
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
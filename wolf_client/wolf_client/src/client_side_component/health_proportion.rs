@@ -1,6 +1,5 @@
 use super::*;
-use combinable::CantCombine;
-use signal_listener_macro::define_signal_listener;
+use signal_listeners::CantCombine;
 
 define_signal_listener!(GetHealthProportion, &Game -> CantCombine<f64>);
 
@@ -11,9 +10,6 @@ pub struct HealthProportionComponent {
 }
 
 impl GetHealthProportionSignalListener for HealthProportionComponent {
-    fn clone_box(&self) -> Box<dyn GetHealthProportionSignalListener> {
-        Box::new(self.clone())
-    }
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
@@ -3,6 +3,14 @@ use crate::id_types::*;
 mod game_object;
 pub use game_object::*;
 
+// Every signal uses the listener types shared with the server
+#[macro_export]
+macro_rules! define_signal_listener {
+    ($($signal:tt)*) => {
+        signal_listener_macro::define_signal_listener!(::signal_listeners; $($signal)*);
+    };
+}
+
 mod game;
 pub use game::*;
 
@@ -28,8 +36,10 @@ mod slash_animation;
 mod vision;
 pub use vision::*;
 
-mod combinable;
-pub use combinable::*;
+pub use signal_listeners::*;
 
 mod speech;
 pub use speech::*;
+
+mod status_icons;
+pub use status_icons::*;
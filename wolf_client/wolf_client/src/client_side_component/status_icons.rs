@@ -0,0 +1,53 @@
+use super::*;
+use crate::sprites::Sprites;
+use sdl2::rect::Rect;
+use sdl2::render::*;
+
+const STATUS_ICON_SIZE: u32 = 12;
+const STATUS_ICON_SPACING: i32 = 2;
+const STATUS_ICON_OFFSET_Y: i32 = -26;
+// Stacks are shown as pips under the icon
+const STACK_PIP_SIZE: u32 = 2;
+
+pub fn draw_status_icons_for_id<T: RenderTarget>(
+    game: &Game,
+    id: GameObjectId,
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    draw_rect: &Rect,
+) {
+    let statuses = match game.statuses.get(id) {
+        Some(statuses) if !statuses.is_empty() => statuses,
+        _ => return,
+    };
+    let total_width = statuses.len() as i32 * (STATUS_ICON_SIZE as i32 + STATUS_ICON_SPACING)
+        - STATUS_ICON_SPACING;
+    let center_x = draw_rect.x + draw_rect.width() as i32 / 2;
+    let mut x = center_x - total_width / 2;
+    let y = draw_rect.y + STATUS_ICON_OFFSET_Y;
+    for (_component_id, status) in statuses.iter() {
+        if let Some(sprite_info) = sprites.entity_sprite_infos.get(status.icon) {
+            let dest_rect = Rect::new(x, y, STATUS_ICON_SIZE, STATUS_ICON_SIZE);
+            canvas
+                .copy(
+                    &sprites.main_sprite_sheet,
+                    sprite_info.get_source_rect(),
+                    dest_rect,
+                )
+                .unwrap();
+            if status.stacks > 1 {
+                canvas.set_draw_color(sdl2::pixels::Color::RGB(255, 255, 255));
+                for stack in 0..status.stacks as i32 {
+                    let pip = Rect::new(
+                        x + stack * (STACK_PIP_SIZE as i32 + 1),
+                        y + STATUS_ICON_SIZE as i32 + 1,
+                        STACK_PIP_SIZE,
+                        STACK_PIP_SIZE,
+                    );
+                    canvas.fill_rect(pip).unwrap();
+                }
+            }
+        }
+        x += STATUS_ICON_SIZE as i32 + STATUS_ICON_SPACING;
+    }
+}
@@ -1,5 +1,4 @@
 use super::*;
-use signal_listener_macro::define_signal_listener;
 
 define_signal_listener!(GetVisionScale, &Game -> CantCombine<f64>);
 
@@ -17,9 +16,6 @@ impl Component for WideVisionComponent {
     }
 }
 impl GetVisionScaleSignalListener for WideVisionComponent {
-    fn clone_box(&self) -> Box<dyn GetVisionScaleSignalListener> {
-        Box::new(self.clone())
-    }
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
@@ -1,4 +1,4 @@
-use crate::overlays::AbilitiesOverlay;
+use crate::overlays::{AbilitiesOverlay, ChatOverlay, InventoryOverlay, TalentsOverlay};
 use crate::sprites::*;
 use coords::*;
 use sdl2;
@@ -16,6 +16,9 @@ pub struct Drawing<'a> {
     pub next_state: DrawingState,
     pub terrain: Terrain<'a>,
     pub abilities_overlay: AbilitiesOverlay,
+    pub inventory_overlay: InventoryOverlay,
+    pub talents_overlay: TalentsOverlay,
+    pub chat_overlay: ChatOverlay,
 }
 
 impl<'a> Drawing<'a> {
@@ -25,6 +28,9 @@ impl<'a> Drawing<'a> {
             next_state: DrawingState::new(),
             terrain: Terrain::new(),
             abilities_overlay: AbilitiesOverlay::new(),
+            inventory_overlay: InventoryOverlay::new(),
+            talents_overlay: TalentsOverlay::new(),
+            chat_overlay: ChatOverlay::new(),
         }
     }
     pub fn get_view_coords(&self) -> PixelCoords {
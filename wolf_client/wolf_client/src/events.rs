@@ -1,4 +1,5 @@
 use crate::network;
+use crate::overlays::{parse_chat_line, ChatOverlay, InventoryOverlay, TalentsOverlay};
 use crate::{SCREEN_HEIGHT, SCREEN_WIDTH};
 use coords::PixelCoords;
 use sdl2::event::*;
@@ -15,6 +16,8 @@ pub struct EventState {
     moving_down: bool,
     mouse_x: i32,
     mouse_y: i32,
+    // Where an item being dragged around the inventory was picked up from
+    dragging_item_from: Option<InventoryLocation>,
 }
 impl EventState {
     pub fn new() -> Self {
@@ -27,6 +30,7 @@ impl EventState {
             moving_down: false,
             mouse_x: 0,
             mouse_y: 0,
+            dragging_item_from: None,
         }
     }
     pub fn poll(
@@ -34,23 +38,91 @@ impl EventState {
         event_pump: &mut sdl2::EventPump,
         server_connection: &mut network::ServerConnection,
         view_coords: PixelCoords,
+        inventory_overlay: &mut InventoryOverlay,
+        talents_overlay: &mut TalentsOverlay,
+        chat_overlay: &mut ChatOverlay,
     ) {
         for event in event_pump.poll_iter() {
+            // While the chat box is open the keyboard goes to it
+            if chat_overlay.typing.is_some() {
+                if let Event::KeyDown { .. } | Event::KeyUp { .. } | Event::TextInput { .. } = event
+                {
+                    self.parse_chat_event(event, server_connection, chat_overlay);
+                    continue;
+                }
+            }
             match event {
                 Event::Quit {
                     timestamp: _timestamp,
                 } => {
                     self.quitting = true;
                 }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Return),
+                    ..
+                } => {
+                    chat_overlay.typing = Some(String::new());
+                    self.stop_moving(server_connection);
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::I),
+                    ..
+                } => {
+                    inventory_overlay.open = !inventory_overlay.open;
+                    self.dragging_item_from = None;
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::T),
+                    ..
+                } => {
+                    talents_overlay.open = !talents_overlay.open;
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::F),
+                    ..
+                } => {
+                    self.equip_hovered_item(server_connection, inventory_overlay);
+                }
                 Event::KeyDown { keycode, .. } => {
                     self.parse_key_event(view_coords, keycode, server_connection, true);
                 }
                 Event::KeyUp { keycode, .. } => {
                     self.parse_key_event(view_coords, keycode, server_connection, false);
                 }
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } if talents_overlay.get_learnable_talent_at(x, y).is_some() => {
+                    let talent_index = talents_overlay.get_learnable_talent_at(x, y).unwrap();
+                    server_connection
+                        .commands
+                        .send(Command::LearnTalent(LearnTalentCommand { talent_index }))
+                        .unwrap();
+                }
+                Event::MouseButtonDown {
+                    mouse_btn, x, y, ..
+                } if inventory_overlay.covers(x, y) => {
+                    self.parse_inventory_mouse_down(
+                        server_connection,
+                        inventory_overlay,
+                        mouse_btn,
+                        x,
+                        y,
+                    );
+                }
                 Event::MouseButtonDown { mouse_btn, .. } => {
                     self.parse_mouse_button_event(view_coords, server_connection, mouse_btn);
                 }
+                Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } => {
+                    self.finish_dragging_item(server_connection, inventory_overlay, x, y);
+                }
                 Event::MouseMotion { x, y, .. } => {
                     self.mouse_x = x;
                     self.mouse_y = y;
@@ -59,6 +131,51 @@ impl EventState {
             }
         }
     }
+    fn parse_chat_event(
+        &mut self,
+        event: Event,
+        server_connection: &mut network::ServerConnection,
+        chat_overlay: &mut ChatOverlay,
+    ) {
+        let typing = match chat_overlay.typing {
+            Some(ref mut typing) => typing,
+            None => return,
+        };
+        match event {
+            Event::TextInput { text, .. } => {
+                typing.push_str(&text);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Backspace),
+                ..
+            } => {
+                typing.pop();
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Return),
+                ..
+            } => {
+                if let Some(command) = parse_chat_line(typing) {
+                    server_connection.commands.send(command).unwrap();
+                }
+                chat_overlay.typing = None;
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Escape),
+                ..
+            } => {
+                chat_overlay.typing = None;
+            }
+            _ => {}
+        }
+    }
+    fn stop_moving(&mut self, server_connection: &mut network::ServerConnection) {
+        self.moving_left = false;
+        self.moving_right = false;
+        self.moving_up = false;
+        self.moving_down = false;
+        self.update_current_velocity(server_connection);
+    }
     fn parse_mouse_button_event(
         &self,
         view_coords: PixelCoords,
@@ -71,6 +188,61 @@ impl EventState {
         };
         self.cast_ability(view_coords, server_connection, slot);
     }
+    fn parse_inventory_mouse_down(
+        &mut self,
+        server_connection: &mut network::ServerConnection,
+        inventory_overlay: &InventoryOverlay,
+        mouse_button: MouseButton,
+        x: i32,
+        y: i32,
+    ) {
+        let location = inventory_overlay.get_location_at(x, y);
+        match (mouse_button, location) {
+            (MouseButton::Left, Some(location)) => {
+                self.dragging_item_from = Some(location);
+            }
+            (MouseButton::Right, Some(InventoryLocation::Backpack(backpack_slot))) => {
+                server_connection
+                    .commands
+                    .send(Command::UseItem(UseItemCommand { backpack_slot }))
+                    .unwrap();
+            }
+            _ => {}
+        }
+    }
+    // Dropping onto another slot moves the item there, dropping outside the inventory drops it on the ground
+    fn finish_dragging_item(
+        &mut self,
+        server_connection: &mut network::ServerConnection,
+        inventory_overlay: &InventoryOverlay,
+        x: i32,
+        y: i32,
+    ) {
+        let from = match self.dragging_item_from.take() {
+            Some(from) => from,
+            None => return,
+        };
+        let command = match inventory_overlay.get_location_at(x, y) {
+            Some(to) if to == from => return,
+            Some(to) => Command::MoveItem(MoveItemCommand { from, to }),
+            None => Command::DropItem(DropItemCommand { from }),
+        };
+        server_connection.commands.send(command).unwrap();
+    }
+    fn equip_hovered_item(
+        &self,
+        server_connection: &mut network::ServerConnection,
+        inventory_overlay: &InventoryOverlay,
+    ) {
+        if let Some(InventoryLocation::Backpack(backpack_slot)) =
+            inventory_overlay.get_location_at(self.mouse_x, self.mouse_y)
+        {
+            server_connection
+                .commands
+                .send(Command::EquipItem(EquipItemCommand { backpack_slot }))
+                .unwrap();
+        }
+    }
     fn cast_ability(
         &self,
         view_coords: PixelCoords,
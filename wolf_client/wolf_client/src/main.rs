@@ -73,6 +73,9 @@ fn draw_thread() {
             &mut event_pump,
             &mut server_connection,
             drawing.get_view_coords(),
+            &mut drawing.inventory_overlay,
+            &mut drawing.talents_overlay,
+            &mut drawing.chat_overlay,
         );
         game.step();
         {
@@ -95,7 +98,18 @@ fn draw_thread() {
                         &mut sprites,
                     );
                     game.draw(canvas, &mut sprites, &texture_creator);
-                    drawing.abilities_overlay.draw(canvas, &sprites);
+                    drawing
+                        .abilities_overlay
+                        .draw(canvas, &sprites, game.tick_counter);
+                    drawing
+                        .inventory_overlay
+                        .draw(canvas, &texture_creator, &sprites);
+                    drawing
+                        .talents_overlay
+                        .draw(canvas, &texture_creator, &sprites);
+                    drawing
+                        .chat_overlay
+                        .draw(canvas, &texture_creator, &sprites);
                 })
                 .unwrap();
             canvas.copy(&mut buffer, None, None).unwrap();
@@ -146,6 +160,15 @@ fn parse_server_messages<'a, T: sdl2::render::RenderTarget>(
             ServerMessage::SetNotifications(notifications) => {
                 game.notifications = notifications.notifications;
             }
+            ServerMessage::Inventory(inventory) => {
+                drawing.inventory_overlay.update_inventory(inventory);
+            }
+            ServerMessage::Progression(progression) => {
+                drawing.talents_overlay.update_progression(progression);
+            }
+            ServerMessage::Chat(chat) => {
+                drawing.chat_overlay.add_message(chat);
+            }
         }
     }
 }
@@ -12,6 +12,14 @@ pub fn connect_to_server() -> ServerConnection {
     let (server_message_sender, server_message_receiver) = mpsc::channel();
     let (command_sender, command_receiver) = mpsc::channel();
     std::thread::spawn(move || net_thread(server_message_sender, command_receiver));
+    // Without a name the server still lets us play, it just won't remember our progress
+    if let Ok(name) = std::fs::read_to_string("name.txt") {
+        command_sender
+            .send(Command::Login(LoginCommand {
+                name: name.trim().to_string(),
+            }))
+            .expect("Failed to queue login!");
+    }
     ServerConnection {
         server_messages: server_message_receiver,
         commands: command_sender,
@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+
+use sdl2::{
+    render::{self, Canvas, TextureCreator},
+    video::WindowContext,
+};
+use wolf_interface::*;
+
+use super::draw_text;
+use crate::{sprites::Sprites, SCREEN_HEIGHT};
+
+const CHAT_LEFT_X: i32 = 10;
+const CHAT_BOTTOM_MARGIN: i32 = 40;
+const CHAT_LINE_HEIGHT: i32 = 20;
+const MAX_CHAT_LINES: usize = 8;
+
+pub struct ChatOverlay {
+    log: VecDeque<ChatMessage>,
+    // What's been typed so far, when the chat box is open
+    pub typing: Option<String>,
+}
+
+impl ChatOverlay {
+    pub fn new() -> Self {
+        ChatOverlay {
+            log: VecDeque::new(),
+            typing: None,
+        }
+    }
+    pub fn add_message(&mut self, message: ChatMessage) {
+        self.log.push_back(message);
+        while self.log.len() > MAX_CHAT_LINES {
+            self.log.pop_front();
+        }
+    }
+    pub fn draw<T: render::RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        texture_creator: &TextureCreator<WindowContext>,
+        sprites: &Sprites,
+    ) {
+        // Newest at the bottom, just above whatever is being typed
+        let mut y = SCREEN_HEIGHT as i32 - CHAT_BOTTOM_MARGIN;
+        if let Some(ref typing) = self.typing {
+            draw_text(
+                canvas,
+                texture_creator,
+                sprites,
+                &format!("> {}_", typing),
+                CHAT_LEFT_X,
+                y,
+            );
+        }
+        for message in self.log.iter().rev() {
+            y -= CHAT_LINE_HEIGHT;
+            draw_text(
+                canvas,
+                texture_creator,
+                sprites,
+                &format_message(message),
+                CHAT_LEFT_X,
+                y,
+            );
+        }
+    }
+}
+
+fn format_message(message: &ChatMessage) -> String {
+    let prefix = match message.channel {
+        ChatChannel::Local => "",
+        ChatChannel::Party => "[Party] ",
+        ChatChannel::Global => "[Global] ",
+        ChatChannel::System => return message.text.clone(),
+    };
+    if message.is_emote {
+        format!("{}* {} {}", prefix, message.sender_name, message.text)
+    } else {
+        format!("{}{}: {}", prefix, message.sender_name, message.text)
+    }
+}
+
+// "/p", "/g" and "/me" pick the channel, "/party <name>" joins a party and "/party" on its own leaves
+pub fn parse_chat_line(line: &str) -> Option<Command> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let (first_word, rest) = match line.find(' ') {
+        Some(i) => (&line[..i], line[i + 1..].trim()),
+        None => (line, ""),
+    };
+    let chat = |channel, text: &str, is_emote| {
+        if text.is_empty() {
+            return None;
+        }
+        Some(Command::Chat(ChatCommand {
+            channel,
+            text: text.to_string(),
+            is_emote,
+        }))
+    };
+    match first_word {
+        "/p" => chat(ChatChannel::Party, rest, false),
+        "/g" => chat(ChatChannel::Global, rest, false),
+        "/me" => chat(ChatChannel::Local, rest, true),
+        "/party" => Some(Command::SetParty(SetPartyCommand {
+            party_name: if rest.is_empty() {
+                None
+            } else {
+                Some(rest.to_string())
+            },
+        })),
+        _ => chat(ChatChannel::Local, line, false),
+    }
+}
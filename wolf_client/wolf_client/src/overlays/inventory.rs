@@ -0,0 +1,159 @@
+use sdl2::{
+    pixels::Color,
+    rect::Rect,
+    render::{self, BlendMode, Canvas, TextureCreator},
+    video::WindowContext,
+};
+use wolf_interface::{InventoryLocation, InventoryMessage, ItemStackMessage};
+
+use crate::{sprites::Sprites, SCREEN_WIDTH};
+
+pub const INVENTORY_COLUMNS: i32 = 4;
+pub const INVENTORY_CELL_SIZE: i32 = 40;
+pub const INVENTORY_MARGIN: i32 = 10;
+// Space between the equipment row and the backpack
+pub const EQUIPMENT_GAP: i32 = 12;
+const INVENTORY_TOP_Y: i32 = 90;
+
+pub struct InventoryOverlay {
+    last_inventory: Option<InventoryMessage>,
+    pub open: bool,
+}
+
+fn get_left_x() -> i32 {
+    SCREEN_WIDTH as i32 - INVENTORY_MARGIN - INVENTORY_COLUMNS * INVENTORY_CELL_SIZE
+}
+fn get_equipment_rect(i: usize) -> Rect {
+    Rect::new(
+        get_left_x() + i as i32 * INVENTORY_CELL_SIZE,
+        INVENTORY_TOP_Y,
+        INVENTORY_CELL_SIZE as u32,
+        INVENTORY_CELL_SIZE as u32,
+    )
+}
+fn get_backpack_rect(i: usize) -> Rect {
+    let column = i as i32 % INVENTORY_COLUMNS;
+    let row = i as i32 / INVENTORY_COLUMNS;
+    Rect::new(
+        get_left_x() + column * INVENTORY_CELL_SIZE,
+        INVENTORY_TOP_Y + INVENTORY_CELL_SIZE + EQUIPMENT_GAP + row * INVENTORY_CELL_SIZE,
+        INVENTORY_CELL_SIZE as u32,
+        INVENTORY_CELL_SIZE as u32,
+    )
+}
+
+impl InventoryOverlay {
+    pub fn new() -> Self {
+        InventoryOverlay {
+            last_inventory: None,
+            open: false,
+        }
+    }
+    pub fn update_inventory(&mut self, message: InventoryMessage) {
+        self.last_inventory = Some(message);
+    }
+    // Which slot is under the mouse, if the inventory is open
+    pub fn get_location_at(&self, x: i32, y: i32) -> Option<InventoryLocation> {
+        if !self.open {
+            return None;
+        }
+        let inventory = self.last_inventory.as_ref()?;
+        if let Some(i) =
+            (0..inventory.equipment.len()).find(|i| get_equipment_rect(*i).contains_point((x, y)))
+        {
+            return Some(InventoryLocation::Equipment(i as u8));
+        }
+        (0..inventory.backpack.len())
+            .find(|i| get_backpack_rect(*i).contains_point((x, y)))
+            .map(|i| InventoryLocation::Backpack(i as u8))
+    }
+    // True if the point is over the open inventory, so clicks there shouldn't cast anything
+    pub fn covers(&self, x: i32, y: i32) -> bool {
+        self.get_location_at(x, y).is_some()
+    }
+    fn draw_slot<T: render::RenderTarget>(
+        canvas: &mut Canvas<T>,
+        texture_creator: &TextureCreator<WindowContext>,
+        sprites: &Sprites,
+        rect: Rect,
+        stack: &Option<ItemStackMessage>,
+    ) {
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, 160));
+        canvas.fill_rect(rect).unwrap();
+        canvas.set_draw_color(Color::RGBA(200, 200, 200, 200));
+        canvas.draw_rect(rect).unwrap();
+        let stack = match stack {
+            Some(stack) => stack,
+            None => return,
+        };
+        if let Some(sprite_info) = sprites.entity_sprite_infos.get(stack.icon) {
+            let width = (sprite_info.sprite_width as i32).min(INVENTORY_CELL_SIZE - 4);
+            let height = (sprite_info.sprite_height as i32).min(INVENTORY_CELL_SIZE - 4);
+            let dest_rect = Rect::new(
+                rect.x + (INVENTORY_CELL_SIZE - width) / 2,
+                rect.y + (INVENTORY_CELL_SIZE - height) / 2,
+                width as u32,
+                height as u32,
+            );
+            canvas
+                .copy(
+                    &sprites.main_sprite_sheet,
+                    sprite_info.get_source_rect(),
+                    dest_rect,
+                )
+                .unwrap();
+        }
+        if stack.count > 1 {
+            let surface = sprites
+                .outline_font
+                .render(&stack.count.to_string())
+                .solid((255, 255, 255, 255))
+                .expect("Unable to render text!");
+            let (width, height) = (surface.width(), surface.height());
+            let texture = texture_creator
+                .create_texture_from_surface(surface)
+                .unwrap();
+            let dest_rect = Rect::new(
+                rect.x + INVENTORY_CELL_SIZE - width as i32 - 2,
+                rect.y + INVENTORY_CELL_SIZE - height as i32,
+                width,
+                height,
+            );
+            canvas.copy(&texture, None, dest_rect).unwrap();
+        }
+    }
+    pub fn draw<T: render::RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        texture_creator: &TextureCreator<WindowContext>,
+        sprites: &Sprites,
+    ) {
+        if !self.open {
+            return;
+        }
+        let inventory = match self.last_inventory {
+            Some(ref inventory) => inventory,
+            None => return,
+        };
+        canvas.set_blend_mode(BlendMode::Blend);
+        for (i, stack) in inventory.equipment.iter().enumerate() {
+            InventoryOverlay::draw_slot(
+                canvas,
+                texture_creator,
+                sprites,
+                get_equipment_rect(i),
+                stack,
+            );
+        }
+        for (i, stack) in inventory.backpack.iter().enumerate() {
+            InventoryOverlay::draw_slot(
+                canvas,
+                texture_creator,
+                sprites,
+                get_backpack_rect(i),
+                stack,
+            );
+        }
+        canvas.set_blend_mode(BlendMode::None);
+    }
+}
@@ -1,7 +1,8 @@
 use coords::PixelCoords;
 use sdl2::{
+    pixels::Color,
     rect::Rect,
-    render::{self, Canvas, TextureCreator},
+    render::{self, BlendMode, Canvas, TextureCreator},
     video::WindowContext,
 };
 use sprite_mappings::NUN_SPRITE;
@@ -9,11 +10,59 @@ use wolf_interface::{AbilityCommand, SlotMappingMessage};
 
 use crate::{client_side_component::Drawable, sprite_info::SpriteInfos, sprites::Sprites};
 
+mod inventory;
+pub use inventory::*;
+mod chat;
+pub use chat::*;
+mod talents;
+pub use talents::*;
+
 pub struct AbilitiesOverlay {
     last_mapping: Option<SlotMappingMessage>,
 }
 pub const TOP_LEFT_X: i32 = 10;
 pub const TOP_LEFT_Y: i32 = 10;
+pub const SLOT_WIDTH: i32 = 51;
+pub const CAST_BAR_HEIGHT: u32 = 6;
+
+// How far through a period of ticks we are, from 0 to 1
+fn get_progress(started_at: u32, ends_at: u32, tick_counter: u32) -> f64 {
+    if ends_at <= started_at {
+        return 1.0;
+    }
+    let elapsed = tick_counter.saturating_sub(started_at) as f64;
+    (elapsed / (ends_at - started_at) as f64).min(1.0)
+}
+fn draw_text<T: render::RenderTarget>(
+    canvas: &mut Canvas<T>,
+    texture_creator: &TextureCreator<WindowContext>,
+    sprites: &Sprites,
+    text: &str,
+    x: i32,
+    y: i32,
+) {
+    // Drawn twice, once as a black outline and once in white, like notifications
+    let surface_outline = sprites
+        .outline_font
+        .render(text)
+        .solid((0, 0, 0, 255))
+        .expect("Unable to render text!");
+    let surface = sprites
+        .basic_font
+        .render(text)
+        .solid((255, 255, 255, 255))
+        .expect("Unable to render text!");
+    let dst_outline = Rect::new(x, y, surface_outline.width(), surface_outline.height());
+    let dst = Rect::new(x, y, surface.width(), surface.height());
+    let texture_outline = texture_creator
+        .create_texture_from_surface(surface_outline)
+        .unwrap();
+    canvas.copy(&texture_outline, None, dst_outline).unwrap();
+    let texture = texture_creator
+        .create_texture_from_surface(surface)
+        .unwrap();
+    canvas.copy(&texture, None, dst).unwrap();
+}
 impl AbilitiesOverlay {
     pub fn new() -> Self {
         AbilitiesOverlay { last_mapping: None }
@@ -21,7 +70,12 @@ impl AbilitiesOverlay {
     pub fn update_slot_mapping(&mut self, message: SlotMappingMessage) {
         self.last_mapping = Some(message);
     }
-    pub fn draw<T: render::RenderTarget>(&self, canvas: &mut Canvas<T>, sprites: &Sprites) {
+    pub fn draw<T: render::RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        sprites: &Sprites,
+        tick_counter: u32,
+    ) {
         let sprite_info = SpriteInfos::ability_overlay_sprite_info();
         let source_rect = sprite_info.get_source_rect();
         let dest_rect = Rect::new(10, 10, sprite_info.sprite_width, sprite_info.sprite_height);
@@ -48,6 +102,45 @@ impl AbilitiesOverlay {
                         .unwrap();
                 }
             }
+            canvas.set_blend_mode(BlendMode::Blend);
+            // Cooldowns sweep down from the top of the slot as they run out
+            for (i, cooldown) in last_mapping.slot_to_cooldown.iter().enumerate() {
+                if let Some(cooldown) = cooldown {
+                    let remaining =
+                        1.0 - get_progress(cooldown.started_at, cooldown.ready_at, tick_counter);
+                    let height = (sprite_info.sprite_height as f64 * remaining) as u32;
+                    if height == 0 {
+                        continue;
+                    }
+                    let x = SLOT_WIDTH * i as i32 + TOP_LEFT_X;
+                    let y = TOP_LEFT_Y + sprite_info.sprite_height as i32 - height as i32;
+                    canvas.set_draw_color(Color::RGBA(0, 0, 0, 160));
+                    canvas
+                        .fill_rect(Rect::new(x, y, SLOT_WIDTH as u32 - 1, height))
+                        .unwrap();
+                }
+            }
+            // Casts fill the bar under the overlay, channels drain it
+            for cast in last_mapping.slot_to_cast.iter() {
+                if let Some(cast) = cast {
+                    let progress = get_progress(cast.started_at, cast.finishes_at, tick_counter);
+                    let fraction = if cast.is_channel {
+                        1.0 - progress
+                    } else {
+                        progress
+                    };
+                    let width = (sprite_info.sprite_width as f64 * fraction) as u32;
+                    if width == 0 {
+                        continue;
+                    }
+                    let y = TOP_LEFT_Y + sprite_info.sprite_height as i32 + 4;
+                    canvas.set_draw_color(Color::RGBA(255, 200, 0, 200));
+                    canvas
+                        .fill_rect(Rect::new(TOP_LEFT_X, y, width, CAST_BAR_HEIGHT))
+                        .unwrap();
+                }
+            }
+            canvas.set_blend_mode(BlendMode::None);
         }
     }
 }
@@ -0,0 +1,101 @@
+use sdl2::{
+    rect::Rect,
+    render::{self, Canvas, TextureCreator},
+    video::WindowContext,
+};
+use wolf_interface::ProgressionMessage;
+
+use super::draw_text;
+use crate::sprites::Sprites;
+
+const TALENTS_LEFT_X: i32 = 10;
+const TALENTS_TOP_Y: i32 = 90;
+const TALENT_ROW_HEIGHT: i32 = 24;
+const TALENT_ROW_WIDTH: i32 = 300;
+
+pub struct TalentsOverlay {
+    last_progression: Option<ProgressionMessage>,
+    pub open: bool,
+}
+
+// Rows start below the level summary
+fn get_talent_rect(i: usize) -> Rect {
+    Rect::new(
+        TALENTS_LEFT_X,
+        TALENTS_TOP_Y + (i as i32 + 1) * TALENT_ROW_HEIGHT,
+        TALENT_ROW_WIDTH as u32,
+        TALENT_ROW_HEIGHT as u32,
+    )
+}
+
+impl TalentsOverlay {
+    pub fn new() -> Self {
+        TalentsOverlay {
+            last_progression: None,
+            open: false,
+        }
+    }
+    pub fn update_progression(&mut self, message: ProgressionMessage) {
+        self.last_progression = Some(message);
+    }
+    // Only talents that can be learned right now are clickable
+    pub fn get_learnable_talent_at(&self, x: i32, y: i32) -> Option<u8> {
+        if !self.open {
+            return None;
+        }
+        let progression = self.last_progression.as_ref()?;
+        progression
+            .talents
+            .iter()
+            .enumerate()
+            .find(|(i, talent)| talent.can_learn && get_talent_rect(*i).contains_point((x, y)))
+            .map(|(i, _talent)| i as u8)
+    }
+    pub fn draw<T: render::RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        texture_creator: &TextureCreator<WindowContext>,
+        sprites: &Sprites,
+    ) {
+        let progression = match self.last_progression {
+            Some(ref progression) => progression,
+            None => return,
+        };
+        let summary = format!(
+            "Level {} - {}/{} xp - {} talent points",
+            progression.level,
+            progression.experience,
+            progression.experience_for_next_level,
+            progression.talent_points
+        );
+        draw_text(
+            canvas,
+            texture_creator,
+            sprites,
+            &summary,
+            TALENTS_LEFT_X,
+            TALENTS_TOP_Y,
+        );
+        if !self.open {
+            return;
+        }
+        for (i, talent) in progression.talents.iter().enumerate() {
+            let marker = if talent.learned {
+                "[x]"
+            } else if talent.can_learn {
+                "[+]"
+            } else {
+                "[ ]"
+            };
+            let rect = get_talent_rect(i);
+            draw_text(
+                canvas,
+                texture_creator,
+                sprites,
+                &format!("{} {}", marker, talent.name),
+                rect.x,
+                rect.y,
+            );
+        }
+    }
+}
@@ -24,6 +24,7 @@ impl SpriteInfos {
         id_to_sprite_info.insert(WATER_SPRITE, SpriteInfo::new_default_size(80, 80));
         id_to_sprite_info.insert(SAND_SPRITE, SpriteInfo::new_default_size(0, 80));
         id_to_sprite_info.insert(GEM_FLOWER_SPRITE, SpriteInfo::new_default_size(160, 40));
+        id_to_sprite_info.insert(BRIDGE_SPRITE, SpriteInfo::new_default_size(280, 120));
         SpriteInfos { id_to_sprite_info }
     }
     pub fn load_entity_sprite_infos() -> Self {
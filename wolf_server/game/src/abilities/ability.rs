@@ -3,12 +3,15 @@ use super::*;
 #[derive(Debug, Clone)]
 pub struct AbilityDetails {
     pub is_attack_ability: bool,
+    /// Silent abilities don't alert nearby perceivers when cast
+    pub is_silent: bool,
 }
 
 impl Default for AbilityDetails {
     fn default() -> Self {
         Self {
             is_attack_ability: false,
+            is_silent: false,
         }
     }
 }
@@ -20,6 +23,11 @@ impl AbilityDetails {
         ret.is_attack_ability = true;
         ret
     }
+    pub fn silent(&self) -> Self {
+        let mut ret = self.clone();
+        ret.is_silent = true;
+        ret
+    }
 }
 
 pub trait Ability {
@@ -31,6 +39,9 @@ pub trait Ability {
     fn get_details(&self) -> AbilityDetails {
         AbilityDetails::default()
     }
+    fn get_casting_details(&self) -> CastingDetails {
+        CastingDetails::default()
+    }
 }
 
 pub trait IntoAbility {
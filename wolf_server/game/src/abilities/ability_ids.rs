@@ -1,9 +1,10 @@
 use super::*;
 use crate::characters::{HolyShieldAbility, HolySlashAbility, HolySteedAbility};
 use crate::necromancy::CorpseTossAbility;
+use crate::scripting::ScriptId;
 use crate::villages::BuildingAbility;
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum AbilityTypeId {
     NecroboltId,
     FireballId,
@@ -19,6 +20,31 @@ pub enum AbilityTypeId {
     HolyShieldId,
     HolySteedId,
     HolySlashId,
+    // The script that does what the ability does, see scripting
+    ScriptedId(ScriptId),
+}
+
+impl AbilityTypeId {
+    // Doesn't know about scripted abilities, look for a script with the name instead
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "necrobolt" => Some(AbilityTypeId::NecroboltId),
+            "fireball" => Some(AbilityTypeId::FireballId),
+            "railgun" => Some(AbilityTypeId::RailgunId),
+            "corpse_toss" => Some(AbilityTypeId::CorpseTossId),
+            "debug" => Some(AbilityTypeId::DebugId),
+            "cloak" => Some(AbilityTypeId::CloakId),
+            "ambush" => Some(AbilityTypeId::AmbushId),
+            "sprint" => Some(AbilityTypeId::SprintId),
+            "harvest" => Some(AbilityTypeId::HarvestId),
+            "building" => Some(AbilityTypeId::BuildingId),
+            "plane_walk" => Some(AbilityTypeId::PlaneWalkId),
+            "holy_shield" => Some(AbilityTypeId::HolyShieldId),
+            "holy_steed" => Some(AbilityTypeId::HolySteedId),
+            "holy_slash" => Some(AbilityTypeId::HolySlashId),
+            _ => None,
+        }
+    }
 }
 
 fn id_to_ability(type_id: AbilityTypeId, ability_id: AbilityId) -> Box<dyn Ability> {
@@ -37,6 +63,9 @@ fn id_to_ability(type_id: AbilityTypeId, ability_id: AbilityId) -> Box<dyn Abili
         AbilityTypeId::HolyShieldId => Box::new(HolyShieldAbility::new(ability_id)),
         AbilityTypeId::HolySteedId => Box::new(HolySteedAbility::new(ability_id)),
         AbilityTypeId::HolySlashId => Box::new(HolySlashAbility::new(ability_id)),
+        AbilityTypeId::ScriptedId(script_id) => {
+            Box::new(ScriptedAbility::new(ability_id, script_id))
+        }
     }
 }
 pub fn ability_ids_to_abilities(
@@ -1,6 +1,8 @@
 use super::*;
-use crate::combinable::CombinedVecs;
-use signal_listener_macro::define_signal_listener;
+use crate::damage::{DamageTakenSignalListener, Health};
+use crate::define_signal_listener;
+use crate::perception::{PerceptionSystem, CAST_NOISE_RANGE};
+use signal_listeners::CombinedVecs;
 use wolf_hash_map::WolfHashMap;
 
 define_signal_listener!(
@@ -13,26 +15,156 @@ define_signal_listener!(SetAbilities, &mut Game, ability_ids: &Vec<AbilityTypeId
 define_signal_listener!(GetAbilities, &Game -> CombinedVecs<AbilityId>);
 define_signal_listener!(GetAbilityDetails, &Game -> CombinedVecs<(AbilityId, AbilityDetails)>);
 define_signal_listener!(GetAbilityIcons, &Game -> CombinedVecs<(AbilityId, u32)>);
+define_signal_listener!(GetAbilityCastingStates, &Game -> CombinedVecs<AbilityCastingState>);
+// Also sent when cooldowns or casts change, so slot mappings can be resent
 define_signal_listener!(AbilitiesChanged, &mut Game);
 
 pub struct BasicAbilityUser {
+    pub owner_id: GameObjectId,
     pub abilities: WolfHashMap<AbilityId, Option<Box<dyn Ability>>>, //the option is used to take the ability while it's being cast, and so will be none mid-cast to prevent self-modification.
+    pub ability_types: WolfHashMap<AbilityId, AbilityTypeId>,
+    pub upgrades: Vec<(AbilityTypeId, AbilityUpgrade)>,
+    pub cooldowns: WolfHashMap<AbilityId, Cooldown>,
+    pub group_cooldowns: WolfHashMap<CooldownGroup, Cooldown>,
+    pub current_cast: Option<CurrentCast>,
+    pub mana: Pool,
+    pub stamina: Pool,
 }
 
 impl BasicAbilityUser {
-    fn new(game: &mut Game, ability_ids: Vec<AbilityTypeId>) -> BasicAbilityUserId {
+    fn new(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        ability_ids: Vec<AbilityTypeId>,
+    ) -> BasicAbilityUserId {
         let id = game.get_id();
-        let abilities: WolfHashMap<AbilityId, Option<Box<dyn Ability>>> =
-            ability_ids_to_abilities(game, &ability_ids)
-                .into_iter()
-                .map(|x| (x.get_ability_id(), Some(x)))
-                .collect();
-        let basic_ability_user = BasicAbilityUser { abilities };
+        let abilities = ability_ids_to_abilities(game, &ability_ids);
+        let ability_types = get_ability_types(&abilities, &ability_ids);
+        let abilities: WolfHashMap<AbilityId, Option<Box<dyn Ability>>> = abilities
+            .into_iter()
+            .map(|x| (x.get_ability_id(), Some(x)))
+            .collect();
+        let basic_ability_user = BasicAbilityUser {
+            owner_id,
+            abilities,
+            ability_types,
+            upgrades: Vec::new(),
+            cooldowns: WolfHashMap::new(),
+            group_cooldowns: WolfHashMap::new(),
+            current_cast: None,
+            mana: Pool::new(DEFAULT_POOL_SIZE),
+            stamina: Pool::new(DEFAULT_POOL_SIZE),
+        };
         game.ability_system
             .basic_ability_users
             .insert(id, basic_ability_user);
         id
     }
+    pub fn activate(
+        game: &mut Game,
+        id: BasicAbilityUserId,
+        owner_id: GameObjectId,
+        ability_id: AbilityId,
+        target_coords: PixelCoords,
+    ) {
+        let mut ability = {
+            let basic_ability_user = match game.ability_system.basic_ability_users.get_mut(id) {
+                Some(basic_ability_user) => basic_ability_user,
+                None => return,
+            };
+            if let Some(ability_slot) = basic_ability_user.abilities.get_mut(&ability_id) {
+                ability_slot.take().expect("Ability was cast from a cast!")
+            } else {
+                return;
+            }
+        };
+        ability.activate(game, owner_id, target_coords);
+        if !ability.get_details().is_silent {
+            PerceptionSystem::make_noise(game, owner_id, CAST_NOISE_RANGE);
+        }
+        if let Some(user) = game.ability_system.basic_ability_users.get_mut(id) {
+            user.abilities.insert(ability_id, Some(ability));
+        }
+    }
+    fn get_user_for(game: &Game, owner_id: GameObjectId) -> Option<BasicAbilityUserId> {
+        game.ability_system
+            .basic_ability_users
+            .iter()
+            .find(|(_id, user)| user.owner_id == owner_id)
+            .map(|(id, _user)| id)
+    }
+    // Adds abilities on top of the owner's own, returning their ids so they can be revoked later
+    pub fn grant_abilities(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        ability_type_ids: &Vec<AbilityTypeId>,
+    ) -> Vec<AbilityId> {
+        let id = match BasicAbilityUser::get_user_for(game, owner_id) {
+            Some(id) => id,
+            None => return Vec::new(),
+        };
+        let abilities = ability_ids_to_abilities(game, ability_type_ids);
+        let ability_ids: Vec<AbilityId> = abilities.iter().map(|x| x.get_ability_id()).collect();
+        let user = game.ability_system.basic_ability_users.get_mut(id).unwrap();
+        for (ability_id, ability_type) in get_ability_types(&abilities, ability_type_ids) {
+            user.ability_types.insert(ability_id, ability_type);
+        }
+        for ability in abilities {
+            user.abilities
+                .insert(ability.get_ability_id(), Some(ability));
+        }
+        owner_id.send_abilities_changed_signal(game);
+        ability_ids
+    }
+    pub fn revoke_abilities(game: &mut Game, owner_id: GameObjectId, ability_ids: &[AbilityId]) {
+        let id = match BasicAbilityUser::get_user_for(game, owner_id) {
+            Some(id) => id,
+            None => return,
+        };
+        let user = game.ability_system.basic_ability_users.get_mut(id).unwrap();
+        for ability_id in ability_ids {
+            user.abilities.remove(ability_id);
+            user.ability_types.remove(ability_id);
+            user.cooldowns.remove(ability_id);
+        }
+        let casting_revoked = user
+            .current_cast
+            .as_ref()
+            .map(|cast| ability_ids.contains(&cast.ability_id))
+            .unwrap_or(false);
+        if casting_revoked {
+            user.current_cast = None;
+        }
+        owner_id.send_abilities_changed_signal(game);
+    }
+    // Replaces any upgrades the owner had, applying to every ability of each type
+    pub fn set_upgrades(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        upgrades: Vec<(AbilityTypeId, AbilityUpgrade)>,
+    ) {
+        let id = match BasicAbilityUser::get_user_for(game, owner_id) {
+            Some(id) => id,
+            None => return,
+        };
+        game.ability_system
+            .basic_ability_users
+            .get_mut(id)
+            .unwrap()
+            .upgrades = upgrades;
+        owner_id.send_abilities_changed_signal(game);
+    }
+}
+
+fn get_ability_types(
+    abilities: &Vec<Box<dyn Ability>>,
+    ability_type_ids: &Vec<AbilityTypeId>,
+) -> WolfHashMap<AbilityId, AbilityTypeId> {
+    abilities
+        .iter()
+        .zip(ability_type_ids.iter())
+        .map(|(ability, ability_type)| (ability.get_ability_id(), *ability_type))
+        .collect()
 }
 
 #[derive(Clone)]
@@ -45,9 +177,6 @@ impl CastAbilitySignalListener for BasicAbilityUserComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn CastAbilitySignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_cast_ability_signal(
         &self,
         game: &mut Game,
@@ -55,26 +184,47 @@ impl CastAbilitySignalListener for BasicAbilityUserComponent {
         ability_id: AbilityId,
         target_coords: PixelCoords,
     ) {
-        let mut ability = {
-            let basic_ability_user = game
-                .ability_system
+        BasicAbilityUser::begin_cast(
+            game,
+            self.basic_ability_user_id,
+            owner_id,
+            ability_id,
+            target_coords,
+        );
+    }
+}
+
+impl DamageTakenSignalListener for BasicAbilityUserComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_damage_taken_signal(
+        &self,
+        game: &mut Game,
+        owner_id: GameObjectId,
+        _amount: Health,
+        _health_remaining: Health,
+    ) {
+        BasicAbilityUser::interrupt_on_damage(game, self.basic_ability_user_id, owner_id);
+    }
+}
+
+impl GetAbilityCastingStatesSignalListener for BasicAbilityUserComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_get_ability_casting_states_signal(
+        &self,
+        game: &Game,
+        _owner_id: GameObjectId,
+    ) -> CombinedVecs<AbilityCastingState> {
+        CombinedVecs(
+            game.ability_system
                 .basic_ability_users
-                .get_mut(self.basic_ability_user_id)
-                .unwrap();
-            if let Some(ability_slot) = basic_ability_user.abilities.get_mut(&ability_id) {
-                ability_slot.take().expect("Ability was cast from a cast!")
-            } else {
-                return;
-            }
-        };
-        ability.activate(game, owner_id, target_coords);
-        if let Some(user) = game
-            .ability_system
-            .basic_ability_users
-            .get_mut(self.basic_ability_user_id)
-        {
-            user.abilities.insert(ability_id, Some(ability));
-        }
+                .get(self.basic_ability_user_id)
+                .unwrap()
+                .get_casting_states(game.tick_counter),
+        )
     }
 }
 
@@ -82,9 +232,6 @@ impl GetAbilitiesSignalListener for BasicAbilityUserComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn GetAbilitiesSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_get_abilities_signal(
         &self,
         game: &Game,
@@ -107,9 +254,6 @@ impl GetAbilityDetailsSignalListener for BasicAbilityUserComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn GetAbilityDetailsSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_get_ability_details_signal(
         &self,
         game: &Game,
@@ -132,9 +276,6 @@ impl GetAbilityIconsSignalListener for BasicAbilityUserComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn GetAbilityIconsSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_get_ability_icons_signal(
         &self,
         game: &Game,
@@ -159,9 +300,6 @@ impl SetAbilitiesSignalListener for BasicAbilityUserComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn SetAbilitiesSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_set_abilities_signal(
         &self,
         game: &mut Game,
@@ -174,10 +312,13 @@ impl SetAbilitiesSignalListener for BasicAbilityUserComponent {
             .basic_ability_users
             .get_mut(self.basic_ability_user_id)
             .unwrap();
+        ability_user.ability_types = get_ability_types(&abilities, ability_type_ids);
         ability_user.abilities = abilities
             .into_iter()
             .map(|x| (x.get_ability_id(), Some(x)))
             .collect();
+        ability_user.cooldowns = WolfHashMap::new();
+        ability_user.current_cast = None;
         owner_id.send_abilities_changed_signal(game);
     }
 }
@@ -192,6 +333,8 @@ impl Component for BasicAbilityUserComponent {
         owner_id.remove_get_abilities_signal_listener(game, self.component_id);
         owner_id.remove_get_ability_details_signal_listener(game, self.component_id);
         owner_id.remove_get_ability_icons_signal_listener(game, self.component_id);
+        owner_id.remove_get_ability_casting_states_signal_listener(game, self.component_id);
+        owner_id.remove_damage_taken_signal_listener(game, self.component_id);
     }
 }
 
@@ -202,7 +345,7 @@ impl BasicAbilityUserComponent {
         abilities: Vec<AbilityTypeId>,
     ) -> ComponentId {
         let component_id = game.get_id();
-        let basic_ability_user_id = BasicAbilityUser::new(game, abilities);
+        let basic_ability_user_id = BasicAbilityUser::new(game, owner_id, abilities);
         let comp = BasicAbilityUserComponent {
             component_id,
             basic_ability_user_id,
@@ -212,6 +355,8 @@ impl BasicAbilityUserComponent {
         owner_id.add_get_abilities_signal_listener(game, comp.clone());
         owner_id.add_get_ability_details_signal_listener(game, comp.clone());
         owner_id.add_get_ability_icons_signal_listener(game, comp.clone());
+        owner_id.add_get_ability_casting_states_signal_listener(game, comp.clone());
+        owner_id.add_damage_taken_signal_listener(game, comp.clone());
         owner_id.add_component(game, comp);
         component_id
     }
@@ -1,8 +1,10 @@
-use super::Ability;
+use super::{Ability, AbilityDetails, CastingDetails, CooldownGroup, PoolType};
 use crate::damage::*;
 use crate::damage::{melee_attack, MEDIUM_DAMAGE};
 use crate::game::*;
-use crate::statuses::BleedAttackComponent;
+use crate::generic::RemoveComponentAtComponent;
+use crate::perception::VisibilityModComponent;
+use crate::statuses::{StatusAttackComponent, StatusKind};
 
 pub struct AmbushAbility {
     ability_id: AbilityId,
@@ -17,6 +19,13 @@ impl AmbushAbility {
 pub const AMBUSH_ATTACK_DAMAGE: i32 = MEDIUM_DAMAGE;
 pub const AMBUSH_RANGE: f64 = 20.0;
 pub const AMBUSH_ATTACK_LIFETIME: u32 = 10;
+/// After striking, the ambusher stays hard to spot for a moment so they can slip away
+pub const AMBUSH_VISIBILITY: f64 = 0.3;
+pub const AMBUSH_CONCEALMENT_TIME: u32 = 60;
+pub const AMBUSH_BLEED_DURATION: u32 = 100;
+pub const AMBUSH_BLEED_TICK_DAMAGE: i32 = MEDIUM_DAMAGE / 10;
+pub const AMBUSH_COOLDOWN: u32 = 100;
+pub const AMBUSH_STAMINA_COST: u32 = 30;
 
 impl Ability for AmbushAbility {
     fn get_ability_id(&self) -> AbilityId {
@@ -30,12 +39,35 @@ impl Ability for AmbushAbility {
             caster,
             AMBUSH_SPRITE,
             AMBUSH_ATTACK_DAMAGE,
+            DamageType::Physical,
             Some(1),
             starting_coords,
             angle,
             AMBUSH_RANGE,
             AMBUSH_ATTACK_LIFETIME,
         );
-        BleedAttackComponent::add_to(game, game_object_id, Health(MEDIUM_DAMAGE));
+        StatusAttackComponent::add_to(
+            game,
+            game_object_id,
+            StatusKind::Bleed,
+            AMBUSH_BLEED_DURATION,
+            AMBUSH_BLEED_TICK_DAMAGE,
+        );
+        let visibility_mod_id = VisibilityModComponent::add_to(game, caster, AMBUSH_VISIBILITY);
+        RemoveComponentAtComponent::add_to(
+            game,
+            caster,
+            game.tick_counter + AMBUSH_CONCEALMENT_TIME,
+            visibility_mod_id,
+        );
+    }
+    fn get_details(&self) -> AbilityDetails {
+        AbilityDetails::default().silent()
+    }
+    fn get_casting_details(&self) -> CastingDetails {
+        CastingDetails::default()
+            .cooldown(AMBUSH_COOLDOWN)
+            .cooldown_group(CooldownGroup::Weapons)
+            .cost(PoolType::Stamina, AMBUSH_STAMINA_COST)
     }
 }
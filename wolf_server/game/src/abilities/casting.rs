@@ -0,0 +1,719 @@
+use super::*;
+use crate::statuses::StatusKind;
+
+// Every ability in a cooldown group is locked out for this long after any of them is used
+pub const GLOBAL_COOLDOWN: u32 = 20;
+pub const DEFAULT_POOL_SIZE: u32 = 100;
+const POOL_REGEN_INTERVAL: u32 = 3;
+// Casters can be nudged this far without losing their cast
+const CAST_MOVEMENT_TOLERANCE: f64 = 2.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CooldownGroup {
+    Spells,
+    Weapons,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PoolType {
+    Mana,
+    Stamina,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CastTime {
+    Instant,
+    // Activates once, when the cast finishes
+    Cast(u32),
+    // Activates straight away, then every interval until the channel ends
+    Channel { duration: u32, interval: u32 },
+}
+
+#[derive(Clone, Debug)]
+pub struct CastingDetails {
+    pub cooldown: u32,
+    pub cooldown_group: Option<CooldownGroup>,
+    pub cost: Option<(PoolType, u32)>,
+    pub cast_time: CastTime,
+    pub interrupted_by_movement: bool,
+    pub interrupted_by_damage: bool,
+}
+
+impl Default for CastingDetails {
+    fn default() -> Self {
+        Self {
+            cooldown: 0,
+            cooldown_group: None,
+            cost: None,
+            cast_time: CastTime::Instant,
+            interrupted_by_movement: true,
+            interrupted_by_damage: true,
+        }
+    }
+}
+
+// Talents make abilities come back sooner or cost less
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AbilityUpgrade {
+    pub cooldown_multiplier: f64,
+    pub cost_multiplier: f64,
+}
+
+impl AbilityUpgrade {
+    pub fn cooldown(multiplier: f64) -> Self {
+        AbilityUpgrade {
+            cooldown_multiplier: multiplier,
+            cost_multiplier: 1.0,
+        }
+    }
+    pub fn cost(multiplier: f64) -> Self {
+        AbilityUpgrade {
+            cooldown_multiplier: 1.0,
+            cost_multiplier: multiplier,
+        }
+    }
+}
+
+impl CastingDetails {
+    pub fn upgraded(&self, upgrade: &AbilityUpgrade) -> Self {
+        let mut ret = self.clone();
+        ret.cooldown = (ret.cooldown as f64 * upgrade.cooldown_multiplier).round() as u32;
+        ret.cost = ret.cost.map(|(pool_type, amount)| {
+            (
+                pool_type,
+                (amount as f64 * upgrade.cost_multiplier).round() as u32,
+            )
+        });
+        ret
+    }
+    pub fn cooldown(&self, cooldown: u32) -> Self {
+        let mut ret = self.clone();
+        ret.cooldown = cooldown;
+        ret
+    }
+    pub fn cooldown_group(&self, cooldown_group: CooldownGroup) -> Self {
+        let mut ret = self.clone();
+        ret.cooldown_group = Some(cooldown_group);
+        ret
+    }
+    pub fn cost(&self, pool_type: PoolType, amount: u32) -> Self {
+        let mut ret = self.clone();
+        ret.cost = Some((pool_type, amount));
+        ret
+    }
+    pub fn cast_time(&self, cast_time: CastTime) -> Self {
+        let mut ret = self.clone();
+        ret.cast_time = cast_time;
+        ret
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Pool {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Pool {
+    pub fn new(max: u32) -> Self {
+        Pool { current: max, max }
+    }
+    pub fn try_spend(&mut self, amount: u32) -> bool {
+        if self.current < amount {
+            return false;
+        }
+        self.current -= amount;
+        true
+    }
+    pub fn regenerate(&mut self, amount: u32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cooldown {
+    pub started_at: u32,
+    pub ready_at: u32,
+}
+
+impl Cooldown {
+    pub fn new(started_at: u32, length: u32) -> Self {
+        Cooldown {
+            started_at,
+            ready_at: started_at + length,
+        }
+    }
+    pub fn is_ready(&self, tick_counter: u32) -> bool {
+        self.ready_at <= tick_counter
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CurrentCast {
+    pub ability_id: AbilityId,
+    pub target_coords: PixelCoords,
+    pub started_at: u32,
+    pub finishes_at: u32,
+    pub next_pulse_at: Option<u32>,
+    pub start_coords: PixelCoords,
+    pub details: CastingDetails,
+}
+
+impl CurrentCast {
+    pub fn is_channel(&self) -> bool {
+        matches!(self.details.cast_time, CastTime::Channel { .. })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AbilityCastingState {
+    pub ability_id: AbilityId,
+    pub cooldown: Option<Cooldown>,
+    // Start, end and whether it's a channel
+    pub cast: Option<(u32, u32, bool)>,
+}
+
+impl BasicAbilityUser {
+    pub fn get_pool_mut(&mut self, pool_type: PoolType) -> &mut Pool {
+        match pool_type {
+            PoolType::Mana => &mut self.mana,
+            PoolType::Stamina => &mut self.stamina,
+        }
+    }
+    pub fn get_casting_details(&self, ability_id: AbilityId) -> Option<CastingDetails> {
+        let details = self
+            .abilities
+            .get(&ability_id)?
+            .as_ref()?
+            .get_casting_details();
+        let ability_type = match self.ability_types.get(&ability_id) {
+            Some(ability_type) => *ability_type,
+            None => return Some(details),
+        };
+        Some(
+            self.upgrades
+                .iter()
+                .filter(|(upgraded_type, _)| *upgraded_type == ability_type)
+                .fold(details, |details, (_, upgrade)| details.upgraded(upgrade)),
+        )
+    }
+    // The later of the ability's own cooldown and its group's
+    pub fn get_cooldown(
+        &self,
+        ability_id: AbilityId,
+        details: &CastingDetails,
+    ) -> Option<Cooldown> {
+        let own = self.cooldowns.get(&ability_id);
+        let group = details
+            .cooldown_group
+            .and_then(|group| self.group_cooldowns.get(&group));
+        match (own, group) {
+            (Some(own), Some(group)) => Some(if own.ready_at >= group.ready_at {
+                *own
+            } else {
+                *group
+            }),
+            (Some(cooldown), None) | (None, Some(cooldown)) => Some(*cooldown),
+            (None, None) => None,
+        }
+    }
+    pub fn get_casting_states(&self, tick_counter: u32) -> Vec<AbilityCastingState> {
+        self.abilities
+            .iter()
+            .filter_map(|(ability_id, _ability)| {
+                let details = self.get_casting_details(*ability_id)?;
+                let cooldown = self
+                    .get_cooldown(*ability_id, &details)
+                    .filter(|cooldown| !cooldown.is_ready(tick_counter));
+                let cast = self
+                    .current_cast
+                    .as_ref()
+                    .filter(|cast| cast.ability_id == *ability_id)
+                    .map(|cast| (cast.started_at, cast.finishes_at, cast.is_channel()));
+                Some(AbilityCastingState {
+                    ability_id: *ability_id,
+                    cooldown,
+                    cast,
+                })
+            })
+            .collect()
+    }
+    fn start_cooldown(&mut self, ability_id: AbilityId, cooldown: u32, tick_counter: u32) {
+        if cooldown > 0 {
+            self.cooldowns
+                .insert(ability_id, Cooldown::new(tick_counter, cooldown));
+        }
+    }
+    // Checks cooldowns and pays the cost, returning the cast if the ability can be used, and
+    // whether that started a cast or a cooldown
+    fn try_begin_cast(
+        game: &mut Game,
+        id: BasicAbilityUserId,
+        owner_id: GameObjectId,
+        ability_id: AbilityId,
+        target_coords: PixelCoords,
+    ) -> Option<(CastTime, bool)> {
+        let tick_counter = game.tick_counter;
+        let start_coords = owner_id.get_coords_game(game);
+        if game.status_system.has_status(owner_id, StatusKind::Stun) {
+            return None;
+        }
+        let user = game.ability_system.basic_ability_users.get_mut(id)?;
+        if user.current_cast.is_some() {
+            return None;
+        }
+        let details = user.get_casting_details(ability_id)?;
+        if let Some(cooldown) = user.get_cooldown(ability_id, &details) {
+            if !cooldown.is_ready(tick_counter) {
+                return None;
+            }
+        }
+        if let Some((pool_type, amount)) = details.cost {
+            if !user.get_pool_mut(pool_type).try_spend(amount) {
+                return None;
+            }
+        }
+        if let Some(group) = details.cooldown_group {
+            user.group_cooldowns
+                .insert(group, Cooldown::new(tick_counter, GLOBAL_COOLDOWN));
+        }
+        let cast_time = details.cast_time;
+        let casting_state_changed = cast_time != CastTime::Instant
+            || details.cooldown > 0
+            || details.cooldown_group.is_some();
+        match cast_time {
+            CastTime::Instant => {
+                user.start_cooldown(ability_id, details.cooldown, tick_counter);
+            }
+            CastTime::Cast(ticks) => {
+                user.current_cast = Some(CurrentCast {
+                    ability_id,
+                    target_coords,
+                    started_at: tick_counter,
+                    finishes_at: tick_counter + ticks,
+                    next_pulse_at: None,
+                    start_coords,
+                    details,
+                });
+            }
+            CastTime::Channel { duration, interval } => {
+                user.start_cooldown(ability_id, details.cooldown, tick_counter);
+                user.current_cast = Some(CurrentCast {
+                    ability_id,
+                    target_coords,
+                    started_at: tick_counter,
+                    finishes_at: tick_counter + duration,
+                    next_pulse_at: Some(tick_counter + interval),
+                    start_coords,
+                    details,
+                });
+            }
+        }
+        Some((cast_time, casting_state_changed))
+    }
+    pub fn begin_cast(
+        game: &mut Game,
+        id: BasicAbilityUserId,
+        owner_id: GameObjectId,
+        ability_id: AbilityId,
+        target_coords: PixelCoords,
+    ) {
+        let (cast_time, casting_state_changed) =
+            match BasicAbilityUser::try_begin_cast(game, id, owner_id, ability_id, target_coords) {
+                Some(begun) => begun,
+                None => return,
+            };
+        if let CastTime::Instant | CastTime::Channel { .. } = cast_time {
+            BasicAbilityUser::activate(game, id, owner_id, ability_id, target_coords);
+        }
+        if casting_state_changed {
+            owner_id.send_abilities_changed_signal(game);
+        }
+    }
+    // Casts that haven't gone off yet get their cost back. Channels have already gone off when
+    // they're interrupted, so their cost is lost, as is any cooldown already started.
+    pub fn interrupt(game: &mut Game, id: BasicAbilityUserId, owner_id: GameObjectId) {
+        let user = match game.ability_system.basic_ability_users.get_mut(id) {
+            Some(user) => user,
+            None => return,
+        };
+        let interrupted = match user.current_cast.take() {
+            Some(cast) => {
+                if let (CastTime::Cast(_), Some((pool_type, amount))) =
+                    (cast.details.cast_time, cast.details.cost)
+                {
+                    user.get_pool_mut(pool_type).regenerate(amount);
+                }
+                true
+            }
+            None => false,
+        };
+        if interrupted {
+            owner_id.send_abilities_changed_signal(game);
+        }
+    }
+    pub fn interrupt_all(game: &mut Game, owner_id: GameObjectId) {
+        let ids: Vec<BasicAbilityUserId> = game
+            .ability_system
+            .basic_ability_users
+            .iter()
+            .filter(|(_id, user)| user.owner_id == owner_id)
+            .map(|(id, _user)| id)
+            .collect();
+        for id in ids {
+            BasicAbilityUser::interrupt(game, id, owner_id);
+        }
+    }
+    pub fn interrupt_on_damage(game: &mut Game, id: BasicAbilityUserId, owner_id: GameObjectId) {
+        let interruptible = game
+            .ability_system
+            .basic_ability_users
+            .get(id)
+            .and_then(|user| user.current_cast.as_ref())
+            .map(|cast| cast.details.interrupted_by_damage)
+            .unwrap_or(false);
+        if interruptible {
+            BasicAbilityUser::interrupt(game, id, owner_id);
+        }
+    }
+    pub fn step(game: &mut Game) {
+        let tick_counter = game.tick_counter;
+        let mut to_interrupt = Vec::new();
+        let mut to_activate = Vec::new();
+        let mut to_finish = Vec::new();
+        let game_objects = &game.game_objects;
+        for (id, user) in game.ability_system.basic_ability_users.iter_mut() {
+            if tick_counter % POOL_REGEN_INTERVAL == 0 {
+                user.mana.regenerate(1);
+                user.stamina.regenerate(1);
+            }
+            let cast = match user.current_cast.as_mut() {
+                Some(cast) => cast,
+                None => continue,
+            };
+            if cast.details.interrupted_by_movement {
+                let moved = user
+                    .owner_id
+                    .get_coords_safe(game_objects)
+                    .map(|coords| {
+                        coords.get_plane() != cast.start_coords.get_plane()
+                            || coords.get_distance_to(&cast.start_coords) > CAST_MOVEMENT_TOLERANCE
+                    })
+                    .unwrap_or(true);
+                if moved {
+                    to_interrupt.push((id, user.owner_id));
+                    continue;
+                }
+            }
+            if let (Some(next_pulse_at), CastTime::Channel { interval, .. }) =
+                (cast.next_pulse_at, cast.details.cast_time)
+            {
+                if next_pulse_at <= tick_counter && next_pulse_at < cast.finishes_at {
+                    cast.next_pulse_at = Some(next_pulse_at + interval);
+                    to_activate.push((id, user.owner_id, cast.ability_id, cast.target_coords));
+                }
+            }
+            if cast.finishes_at <= tick_counter {
+                if let CastTime::Cast(_) = cast.details.cast_time {
+                    to_activate.push((id, user.owner_id, cast.ability_id, cast.target_coords));
+                }
+                to_finish.push((id, user.owner_id));
+            }
+        }
+        for (id, owner_id) in to_interrupt {
+            BasicAbilityUser::interrupt(game, id, owner_id);
+        }
+        for (id, owner_id, ability_id, target_coords) in to_activate {
+            BasicAbilityUser::activate(game, id, owner_id, ability_id, target_coords);
+        }
+        for (id, owner_id) in to_finish {
+            if let Some(user) = game.ability_system.basic_ability_users.get_mut(id) {
+                if let Some(cast) = user.current_cast.take() {
+                    if let CastTime::Cast(_) = cast.details.cast_time {
+                        user.start_cooldown(cast.ability_id, cast.details.cooldown, tick_counter);
+                    }
+                }
+            }
+            owner_id.send_abilities_changed_signal(game);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movement::MovementSystem;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct TestAbility {
+        ability_id: AbilityId,
+        details: CastingDetails,
+        activations: Rc<Cell<u32>>,
+    }
+
+    impl Ability for TestAbility {
+        fn get_ability_id(&self) -> AbilityId {
+            self.ability_id
+        }
+        fn activate(&mut self, _game: &mut Game, _caster: GameObjectId, _target: PixelCoords) {
+            self.activations.set(self.activations.get() + 1);
+        }
+        fn get_casting_details(&self) -> CastingDetails {
+            self.details.clone()
+        }
+    }
+
+    #[derive(Clone)]
+    struct ChangeCounter {
+        component_id: ComponentId,
+        changes: Rc<Cell<u32>>,
+    }
+
+    impl AbilitiesChangedSignalListener for ChangeCounter {
+        fn get_listener_id(&self) -> ComponentId {
+            self.component_id
+        }
+        fn receive_abilities_changed_signal(&self, _game: &mut Game, _owner_id: GameObjectId) {
+            self.changes.set(self.changes.get() + 1);
+        }
+    }
+
+    struct Caster {
+        owner_id: GameObjectId,
+        user_id: BasicAbilityUserId,
+        changes: Rc<Cell<u32>>,
+    }
+
+    impl Caster {
+        fn new(game: &mut Game) -> Self {
+            let owner_id = GameObject::create_game(game, PixelCoords::new_to_fixed(Plane(0), 0, 0));
+            BasicAbilityUserComponent::add_to(game, owner_id, Vec::new());
+            let user_id = owner_id
+                .get_components_of_type::<BasicAbilityUserComponent>(game)
+                .first()
+                .unwrap()
+                .basic_ability_user_id;
+            let changes = Rc::new(Cell::new(0));
+            let component_id = game.get_id();
+            owner_id.add_abilities_changed_signal_listener(
+                game,
+                ChangeCounter {
+                    component_id,
+                    changes: changes.clone(),
+                },
+            );
+            Caster {
+                owner_id,
+                user_id,
+                changes,
+            }
+        }
+        // Returns the ability's id and a count of how many times it's gone off
+        fn add_ability(
+            &self,
+            game: &mut Game,
+            details: CastingDetails,
+        ) -> (AbilityId, Rc<Cell<u32>>) {
+            let ability_id = game.get_id();
+            let activations = Rc::new(Cell::new(0));
+            let ability = TestAbility {
+                ability_id,
+                details,
+                activations: activations.clone(),
+            };
+            self.user_mut(game)
+                .abilities
+                .insert(ability_id, Some(Box::new(ability)));
+            (ability_id, activations)
+        }
+        fn cast(&self, game: &mut Game, ability_id: AbilityId) {
+            BasicAbilityUser::begin_cast(
+                game,
+                self.user_id,
+                self.owner_id,
+                ability_id,
+                PixelCoords::new_at_zero(),
+            );
+        }
+        fn user_mut<'a>(&self, game: &'a mut Game) -> &'a mut BasicAbilityUser {
+            game.ability_system
+                .basic_ability_users
+                .get_mut(self.user_id)
+                .unwrap()
+        }
+        fn mana(&self, game: &mut Game) -> u32 {
+            self.user_mut(game).mana.current
+        }
+    }
+
+    fn step_to(game: &mut Game, tick_counter: u32) {
+        while game.tick_counter < tick_counter {
+            game.tick_counter += 1;
+            BasicAbilityUser::step(game);
+        }
+    }
+
+    #[test]
+    fn cooldowns_and_the_global_cooldown_lock_abilities_out() {
+        let mut game = Game::new();
+        let caster = Caster::new(&mut game);
+        let spells = CastingDetails::default().cooldown_group(CooldownGroup::Spells);
+        let (slow, slow_activations) = caster.add_ability(&mut game, spells.cooldown(50));
+        let (quick, quick_activations) = caster.add_ability(&mut game, spells.clone());
+        let (weapon, weapon_activations) = caster.add_ability(
+            &mut game,
+            CastingDetails::default().cooldown_group(CooldownGroup::Weapons),
+        );
+
+        caster.cast(&mut game, slow);
+        assert_eq!(slow_activations.get(), 1);
+        // Other groups aren't locked out
+        caster.cast(&mut game, weapon);
+        assert_eq!(weapon_activations.get(), 1);
+        caster.cast(&mut game, quick);
+        assert_eq!(quick_activations.get(), 0);
+
+        step_to(&mut game, GLOBAL_COOLDOWN);
+        caster.cast(&mut game, quick);
+        caster.cast(&mut game, slow);
+        assert_eq!(quick_activations.get(), 1);
+        assert_eq!(slow_activations.get(), 1);
+
+        step_to(&mut game, 50);
+        caster.cast(&mut game, slow);
+        assert_eq!(slow_activations.get(), 2);
+    }
+
+    #[test]
+    fn abilities_cant_be_cast_without_paying_for_them() {
+        let mut game = Game::new();
+        let caster = Caster::new(&mut game);
+        let (ability_id, activations) = caster.add_ability(
+            &mut game,
+            CastingDetails::default().cost(PoolType::Mana, 60),
+        );
+        caster.cast(&mut game, ability_id);
+        assert_eq!(activations.get(), 1);
+        assert_eq!(caster.mana(&mut game), DEFAULT_POOL_SIZE - 60);
+        caster.cast(&mut game, ability_id);
+        assert_eq!(activations.get(), 1);
+        assert_eq!(caster.mana(&mut game), DEFAULT_POOL_SIZE - 60);
+
+        // Half as expensive once upgraded
+        caster.user_mut(&mut game).mana.current = 30;
+        let upgraded = CastingDetails::default()
+            .cost(PoolType::Mana, 60)
+            .upgraded(&AbilityUpgrade::cost(0.5));
+        let (cheap, cheap_activations) = caster.add_ability(&mut game, upgraded);
+        caster.cast(&mut game, cheap);
+        assert_eq!(cheap_activations.get(), 1);
+        assert_eq!(caster.mana(&mut game), 0);
+    }
+
+    #[test]
+    fn casts_go_off_when_they_finish() {
+        let mut game = Game::new();
+        let caster = Caster::new(&mut game);
+        let (ability_id, activations) = caster.add_ability(
+            &mut game,
+            CastingDetails::default()
+                .cast_time(CastTime::Cast(10))
+                .cooldown(30),
+        );
+        caster.cast(&mut game, ability_id);
+        assert_eq!(activations.get(), 0);
+        assert_eq!(caster.changes.get(), 1);
+        step_to(&mut game, 9);
+        assert_eq!(activations.get(), 0);
+        step_to(&mut game, 10);
+        assert_eq!(activations.get(), 1);
+        assert_eq!(caster.changes.get(), 2);
+        assert!(caster.user_mut(&mut game).current_cast.is_none());
+
+        // The cooldown starts when the cast finishes
+        step_to(&mut game, 39);
+        caster.cast(&mut game, ability_id);
+        assert!(caster.user_mut(&mut game).current_cast.is_none());
+        step_to(&mut game, 40);
+        caster.cast(&mut game, ability_id);
+        assert!(caster.user_mut(&mut game).current_cast.is_some());
+    }
+
+    #[test]
+    fn interrupted_casts_are_refunded_but_channels_arent() {
+        let mut game = Game::new();
+        let caster = Caster::new(&mut game);
+        let (cast, cast_activations) = caster.add_ability(
+            &mut game,
+            CastingDetails::default()
+                .cast_time(CastTime::Cast(10))
+                .cost(PoolType::Mana, 30),
+        );
+        let (channel, channel_activations) = caster.add_ability(
+            &mut game,
+            CastingDetails::default()
+                .cast_time(CastTime::Channel {
+                    duration: 30,
+                    interval: 10,
+                })
+                .cost(PoolType::Mana, 30),
+        );
+
+        caster.cast(&mut game, cast);
+        assert_eq!(caster.mana(&mut game), DEFAULT_POOL_SIZE - 30);
+        BasicAbilityUser::interrupt_on_damage(&mut game, caster.user_id, caster.owner_id);
+        assert_eq!(caster.mana(&mut game), DEFAULT_POOL_SIZE);
+        step_to(&mut game, 20);
+        assert_eq!(cast_activations.get(), 0);
+
+        caster.cast(&mut game, channel);
+        assert_eq!(channel_activations.get(), 1);
+        step_to(&mut game, 30);
+        assert_eq!(channel_activations.get(), 2);
+        let mana = caster.mana(&mut game);
+        caster.owner_id.move_to_game(
+            &mut game,
+            PixelCoords::new_to_fixed(Plane(0), CAST_MOVEMENT_TOLERANCE * 2.0, 0.0),
+        );
+        MovementSystem::movement(&mut game);
+        step_to(&mut game, 31);
+        assert!(caster.user_mut(&mut game).current_cast.is_none());
+        assert_eq!(caster.mana(&mut game), mana);
+        step_to(&mut game, 60);
+        assert_eq!(channel_activations.get(), 2);
+    }
+
+    #[test]
+    fn casts_that_ignore_damage_arent_interrupted_by_it() {
+        let mut game = Game::new();
+        let caster = Caster::new(&mut game);
+        let mut details = CastingDetails::default().cast_time(CastTime::Cast(10));
+        details.interrupted_by_damage = false;
+        let (ability_id, activations) = caster.add_ability(&mut game, details);
+        caster.cast(&mut game, ability_id);
+        BasicAbilityUser::interrupt_on_damage(&mut game, caster.user_id, caster.owner_id);
+        step_to(&mut game, 10);
+        assert_eq!(activations.get(), 1);
+    }
+
+    #[test]
+    fn abilities_changed_is_only_sent_when_casting_state_changes() {
+        let mut game = Game::new();
+        let caster = Caster::new(&mut game);
+        let (free, activations) = caster.add_ability(&mut game, CastingDetails::default());
+        let (cooldown, _) = caster.add_ability(&mut game, CastingDetails::default().cooldown(5));
+        caster.cast(&mut game, free);
+        caster.cast(&mut game, free);
+        assert_eq!(activations.get(), 2);
+        assert_eq!(caster.changes.get(), 0);
+        BasicAbilityUser::interrupt(&mut game, caster.user_id, caster.owner_id);
+        assert_eq!(caster.changes.get(), 0);
+
+        caster.cast(&mut game, cooldown);
+        assert_eq!(caster.changes.get(), 1);
+        // Still cooling down, so nothing happens
+        caster.cast(&mut game, cooldown);
+        assert_eq!(caster.changes.get(), 1);
+    }
+}
@@ -1,11 +1,13 @@
 use super::*;
-use crate::combinable::CantCombine;
+use crate::perception::GetVisibilitySignalListener;
 use crate::player::GetViewCoordsSignalListener;
 use crate::timers::TimerSystem;
+use signal_listeners::{CantCombine, Multiplied};
 use std::cell::*;
 use std::rc::Rc;
 
 pub const DECLOAK_TIME: u32 = 100;
+pub const CLOAK_VISIBILITY: f64 = 0.1;
 
 pub struct CloakAbility {
     ability_id: AbilityId,
@@ -38,6 +40,7 @@ impl CloakComponent {
             starting_coords,
         };
         owner_id.add_get_view_coords_signal_listener(game, comp.clone());
+        owner_id.add_get_visibility_signal_listener(game, comp.clone());
         owner_id.add_component(game, comp);
         component_id
     }
@@ -48,6 +51,20 @@ impl Component for CloakComponent {
     }
     fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
         owner_id.remove_get_view_coords_signal_listener(game, self.component_id);
+        owner_id.remove_get_visibility_signal_listener(game, self.component_id);
+    }
+}
+
+impl GetVisibilitySignalListener for CloakComponent {
+    fn receive_get_visibility_signal(
+        &self,
+        _game: &Game,
+        _owner_id: GameObjectId,
+    ) -> Multiplied<f64> {
+        Multiplied(CLOAK_VISIBILITY)
+    }
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
     }
 }
 
@@ -62,14 +79,14 @@ impl GetViewCoordsSignalListener for CloakComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn GetViewCoordsSignalListener> {
-        Box::new(self.clone())
-    }
 }
 impl Ability for Rc<RefCell<CloakAbility>> {
     fn get_ability_id(&self) -> AbilityId {
         self.borrow().ability_id
     }
+    fn get_details(&self) -> AbilityDetails {
+        AbilityDetails::default().silent()
+    }
     fn activate(&mut self, game: &mut Game, caster: GameObjectId, _target_coords: PixelCoords) {
         let self_cloned = Rc::clone(self);
         let mut this = self.borrow_mut();
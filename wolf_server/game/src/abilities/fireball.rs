@@ -1,6 +1,7 @@
 use super::*;
-use crate::damage::{ProjectileComponent, MEDIUM_DAMAGE};
+use crate::damage::{DamageType, ProjectileComponent, MEDIUM_DAMAGE, SMALL_DAMAGE};
 use crate::game::*;
+use crate::statuses::{StatusAttackComponent, StatusKind};
 
 pub struct FireballAbility {
     ability_id: AbilityId,
@@ -15,22 +16,34 @@ impl FireballAbility {
 pub const FIREBALL_PROJECTILE_DAMAGE: i32 = MEDIUM_DAMAGE;
 pub const FIREBALL_PROJECTILE_SPEED: f64 = 10.0;
 pub const FIREBALL_PROJECTILE_LIFETIME: u32 = 100;
+pub const FIREBALL_BURN_DURATION: u32 = 50;
+pub const FIREBALL_BURN_TICK_DAMAGE: i32 = SMALL_DAMAGE / 5;
+pub const FIREBALL_COOLDOWN: u32 = 25;
+pub const FIREBALL_MANA_COST: u32 = 10;
 
 impl Ability for FireballAbility {
     fn activate(&mut self, game: &mut Game, caster: GameObjectId, target_coords: PixelCoords) {
         let starting_coords = caster.get_coords_game(game);
         let angle = starting_coords.get_direction_to(&target_coords);
-        ProjectileComponent::fire_basic_projectile(
+        let game_object_id = ProjectileComponent::fire_basic_projectile(
             game,
             caster,
             FIREBALL_SPRITE,
             FIREBALL_PROJECTILE_DAMAGE,
+            DamageType::Fire,
             Some(1),
             starting_coords,
             angle,
             FIREBALL_PROJECTILE_SPEED,
             FIREBALL_PROJECTILE_LIFETIME,
         );
+        StatusAttackComponent::add_to(
+            game,
+            game_object_id,
+            StatusKind::Burning,
+            FIREBALL_BURN_DURATION,
+            FIREBALL_BURN_TICK_DAMAGE,
+        );
     }
     fn get_ability_id(&self) -> AbilityId {
         self.ability_id
@@ -38,4 +51,10 @@ impl Ability for FireballAbility {
     fn get_details(&self) -> super::AbilityDetails {
         AbilityDetails::default().attack_ability()
     }
+    fn get_casting_details(&self) -> CastingDetails {
+        CastingDetails::default()
+            .cooldown(FIREBALL_COOLDOWN)
+            .cooldown_group(CooldownGroup::Spells)
+            .cost(PoolType::Mana, FIREBALL_MANA_COST)
+    }
 }
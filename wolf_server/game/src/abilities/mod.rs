@@ -28,6 +28,9 @@ pub use ability::*;
 mod ability_user;
 pub use ability_user::*;
 
+mod casting;
+pub use casting::*;
+
 mod toggled;
 pub use toggled::*;
 
@@ -43,6 +46,9 @@ pub use slot_mapping::*;
 mod debug;
 pub use debug::*;
 
+mod scripted;
+pub use scripted::*;
+
 use crate::game::*;
 
 pub struct AbilitySystem {
@@ -60,6 +66,7 @@ impl AbilitySystem {
         }
     }
     pub fn step(game: &mut Game) {
+        BasicAbilityUser::step(game);
         SpellbookSystem::step(game);
         Harvester::step(game);
     }
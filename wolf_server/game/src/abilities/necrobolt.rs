@@ -1,7 +1,8 @@
-use super::Ability;
-use crate::damage::{ProjectileComponent, SMALL_DAMAGE};
+use super::{Ability, CastTime, CastingDetails, CooldownGroup, PoolType};
+use crate::damage::{DamageType, ProjectileComponent, SMALL_DAMAGE};
 use crate::game::*;
 use crate::necromancy::NecroboltComponent;
+use crate::statuses::{StatusAttackComponent, StatusKind};
 
 pub struct NecroboltAbility {
     ability_id: AbilityId,
@@ -16,6 +17,12 @@ impl NecroboltAbility {
 pub const NECROBOLT_PROJECTILE_DAMAGE: i32 = SMALL_DAMAGE;
 pub const NECROBOLT_PROJECTILE_SPEED: f64 = 10.0;
 pub const NECROBOLT_PROJECTILE_LIFETIME: u32 = 100;
+pub const NECROBOLT_SLOW_DURATION: u32 = 60;
+pub const NECROBOLT_SLOW_PERCENT: i32 = 40;
+pub const NECROBOLT_CHANNEL_TIME: u32 = 60;
+pub const NECROBOLT_CHANNEL_INTERVAL: u32 = 15;
+pub const NECROBOLT_COOLDOWN: u32 = 40;
+pub const NECROBOLT_MANA_COST: u32 = 25;
 
 impl Ability for NecroboltAbility {
     fn activate(&mut self, game: &mut Game, caster: GameObjectId, target_coords: PixelCoords) {
@@ -26,6 +33,7 @@ impl Ability for NecroboltAbility {
             caster,
             NECROBOLT_SPRITE,
             NECROBOLT_PROJECTILE_DAMAGE,
+            DamageType::Necrotic,
             None,
             starting_coords,
             angle,
@@ -33,9 +41,26 @@ impl Ability for NecroboltAbility {
             NECROBOLT_PROJECTILE_LIFETIME,
         );
         NecroboltComponent::add_to(game, game_object_id);
+        StatusAttackComponent::add_to(
+            game,
+            game_object_id,
+            StatusKind::Slow,
+            NECROBOLT_SLOW_DURATION,
+            NECROBOLT_SLOW_PERCENT,
+        );
     }
 
     fn get_ability_id(&self) -> AbilityId {
         self.ability_id
     }
+    fn get_casting_details(&self) -> CastingDetails {
+        CastingDetails::default()
+            .cooldown(NECROBOLT_COOLDOWN)
+            .cooldown_group(CooldownGroup::Spells)
+            .cost(PoolType::Mana, NECROBOLT_MANA_COST)
+            .cast_time(CastTime::Channel {
+                duration: NECROBOLT_CHANNEL_TIME,
+                interval: NECROBOLT_CHANNEL_INTERVAL,
+            })
+    }
 }
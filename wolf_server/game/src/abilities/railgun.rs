@@ -1,9 +1,9 @@
-use super::Ability;
-use crate::damage::{DamagerComponent, ProjectileComponent, LARGE_DAMAGE};
+use super::{Ability, CastTime, CastingDetails, CooldownGroup, PoolType};
+use crate::damage::{DamageType, DamagerComponent, ProjectileComponent, LARGE_DAMAGE};
 use crate::drawable::BasicDrawingComponent;
 use crate::game::*;
 use crate::generic::DeleteAtComponent;
-use crate::timers::TimerSystem;
+use crate::statuses::{StatusAttackComponent, StatusKind};
 
 pub struct RailgunAbility {
     ability_id: AbilityId,
@@ -18,7 +18,10 @@ impl RailgunAbility {
 pub const RAILGUN_PROJECTILE_DAMAGE: i32 = LARGE_DAMAGE;
 pub const RAILGUN_PROJECTILE_SPEED: f64 = 50.0;
 pub const RAILGUN_PROJECTILE_LIFETIME: u32 = 100;
-pub const RAILGUN_DELAY: u32 = 15;
+pub const RAILGUN_STUN_DURATION: u32 = 30;
+pub const RAILGUN_CAST_TIME: u32 = 15;
+pub const RAILGUN_COOLDOWN: u32 = 100;
+pub const RAILGUN_STAMINA_COST: u32 = 40;
 
 impl Ability for RailgunAbility {
     fn get_ability_id(&self) -> AbilityId {
@@ -29,28 +32,40 @@ impl Ability for RailgunAbility {
         let game_object_id = GameObject::create_game(game, starting_coords);
         let angle = starting_coords.get_direction_to(&target_coords);
         BasicDrawingComponent::add_to(game, game_object_id, FIREBALL_SPRITE, PROJECTILE_DEPTH);
-        let callback = Box::new(move |game: &mut Game| {
-            let damager_id = DamagerComponent::add_to(
-                game,
-                game_object_id,
-                caster,
-                Some(1),
-                RAILGUN_PROJECTILE_DAMAGE,
-            )
-            .damager_id;
-            DeleteAtComponent::add_to(
-                game,
-                game_object_id,
-                game.tick_counter + RAILGUN_PROJECTILE_LIFETIME,
-            );
-            ProjectileComponent::add_to(
-                game,
-                game_object_id,
-                damager_id,
-                angle,
-                RAILGUN_PROJECTILE_SPEED,
-            );
-        });
-        TimerSystem::add_timer(game, callback, RAILGUN_DELAY);
+        let damager_id = DamagerComponent::add_to(
+            game,
+            game_object_id,
+            caster,
+            Some(1),
+            RAILGUN_PROJECTILE_DAMAGE,
+            DamageType::Physical,
+        )
+        .damager_id;
+        StatusAttackComponent::add_to(
+            game,
+            game_object_id,
+            StatusKind::Stun,
+            RAILGUN_STUN_DURATION,
+            0,
+        );
+        DeleteAtComponent::add_to(
+            game,
+            game_object_id,
+            game.tick_counter + RAILGUN_PROJECTILE_LIFETIME,
+        );
+        ProjectileComponent::add_to(
+            game,
+            game_object_id,
+            damager_id,
+            angle,
+            RAILGUN_PROJECTILE_SPEED,
+        );
+    }
+    fn get_casting_details(&self) -> CastingDetails {
+        CastingDetails::default()
+            .cooldown(RAILGUN_COOLDOWN)
+            .cooldown_group(CooldownGroup::Weapons)
+            .cost(PoolType::Stamina, RAILGUN_STAMINA_COST)
+            .cast_time(CastTime::Cast(RAILGUN_CAST_TIME))
     }
 }
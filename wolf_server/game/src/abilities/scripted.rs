@@ -0,0 +1,28 @@
+use super::Ability;
+use crate::game::*;
+use crate::scripting::ScriptId;
+
+pub struct ScriptedAbility {
+    ability_id: AbilityId,
+    script_id: ScriptId,
+}
+
+impl ScriptedAbility {
+    pub fn new(ability_id: AbilityId, script_id: ScriptId) -> Self {
+        ScriptedAbility {
+            ability_id,
+            script_id,
+        }
+    }
+}
+
+impl Ability for ScriptedAbility {
+    fn get_ability_id(&self) -> AbilityId {
+        self.ability_id
+    }
+    // The script is looked up each time, so it can be reloaded while someone has the ability
+    fn activate(&mut self, game: &mut Game, caster: GameObjectId, target_coords: PixelCoords) {
+        let script_name = game.script_system.get_name(self.script_id).to_string();
+        ScriptSystem::activate_ability(game, &script_name, caster, target_coords);
+    }
+}
@@ -1,5 +1,9 @@
-use crate::abilities::ability_user::{AbilitiesChangedSignalListener, GetAbilityIconsSignalSender};
-use wolf_interface::{ServerMessage, SlotMappingMessage};
+use crate::abilities::ability_user::{
+    AbilitiesChangedSignalListener, GetAbilityCastingStatesSignalSender,
+    GetAbilityIconsSignalSender,
+};
+use crate::abilities::AbilityCastingState;
+use wolf_interface::{ServerMessage, SlotCastMessage, SlotCooldownMessage, SlotMappingMessage};
 
 use crate::game::*;
 
@@ -30,9 +34,6 @@ impl AbilitiesChangedSignalListener for SlotMappingAssigner {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn AbilitiesChangedSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_abilities_changed_signal(&self, game: &mut Game, owner_id: GameObjectId) {
         self.update_mapping(game, owner_id);
     }
@@ -45,6 +46,10 @@ impl SlotMappingAssigner {
             .map(|x| x.0)
             .unwrap_or(Vec::new());
         let ability_ids: Vec<AbilityId> = ability_ids_with_icons.iter().map(|x| x.0).collect();
+        let casting_states = owner_id
+            .send_get_ability_casting_states_signal(game)
+            .map(|x| x.0)
+            .unwrap_or(Vec::new());
         let slot_mapping = game
             .player_system
             .slot_mappings
@@ -99,8 +104,41 @@ impl SlotMappingAssigner {
                 }
             })
             .collect();
+        let slot_casting_states: Vec<Option<&AbilityCastingState>> = slot_mapping
+            .slot_to_ability_id
+            .iter()
+            .map(|ability_id_opt| {
+                let ability_id = ability_id_opt.as_ref()?;
+                casting_states
+                    .iter()
+                    .find(|state| state.ability_id == *ability_id)
+            })
+            .collect();
+        let slot_to_cooldown = slot_casting_states
+            .iter()
+            .map(|state| {
+                let cooldown = state.as_ref()?.cooldown?;
+                Some(SlotCooldownMessage {
+                    started_at: cooldown.started_at,
+                    ready_at: cooldown.ready_at,
+                })
+            })
+            .collect();
+        let slot_to_cast = slot_casting_states
+            .iter()
+            .map(|state| {
+                let (started_at, finishes_at, is_channel) = state.as_ref()?.cast?;
+                Some(SlotCastMessage {
+                    started_at,
+                    finishes_at,
+                    is_channel,
+                })
+            })
+            .collect();
         let message = SlotMappingMessage {
             slot_to_ability_icon,
+            slot_to_cooldown,
+            slot_to_cast,
         };
         game.player_system
             .players
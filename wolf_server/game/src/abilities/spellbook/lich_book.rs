@@ -18,9 +18,6 @@ impl SpellbookWasAbsorbedSignalListener for LichBookComponent {
     ) {
         LichCharacterComponent::add_to(game, absorber);
     }
-    fn clone_box(&self) -> Box<dyn SpellbookWasAbsorbedSignalListener> {
-        Box::new(self.clone())
-    }
 }
 
 impl LichBookComponent {
@@ -1,5 +1,5 @@
 use super::*;
-use signal_listener_macro::define_signal_listener;
+use crate::define_signal_listener;
 
 define_signal_listener!(SpellbookWasAbsorbed, &mut Game, absorber: GameObjectId);
 
@@ -47,6 +47,10 @@ impl SpellbookAbsorber {
         }
         for (absorber, id) in spellbooks_to_destroy {
             id.send_spellbook_was_absorbed_signal(game, absorber);
+            game.event_bus.publish(GameEvent::SpellbookAbsorbed {
+                spellbook_id: id,
+                absorber_id: absorber,
+            });
             id.remove(game);
         }
     }
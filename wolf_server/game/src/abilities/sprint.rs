@@ -12,7 +12,7 @@ impl SprintAbility {
 
 impl ComponentsToggledAbility for SprintAbility {
     fn add_components(&self, game: &mut Game, caster: GameObjectId) -> Vec<ComponentId> {
-        let component_id = SpeedModComponent::add_to(game, caster, 2.0);
+        let component_id = SpeedModComponent::add_to(game, caster, 1.0);
         vec![component_id]
     }
 
@@ -0,0 +1,537 @@
+use crate::abilities::{AbilityTypeId, BasicAbilityUser};
+use crate::characters::{
+    KnightCharacterComponent, LichCharacterComponent, PaladinCharacterComponent,
+};
+use crate::component_registry::parse_argument;
+use crate::monsters::WolfLeader;
+use crate::prefabs::PrefabSystem;
+use crate::quest::CrossDesertQuest;
+use std::rc::Rc;
+
+use crate::game::*;
+
+/*
+Commands for whoever is running the server, typed as a line of words, e.g. "spawn zombie 0 0".
+Objects are given by id, which players and near list. Running the server loop (pausing,
+stepping) is up to the server, these only ever see the Game.
+*/
+
+type AdminCommandFn = fn(&mut Game, &[&str]) -> Result<Vec<String>, String>;
+
+struct AdminCommand {
+    name: &'static str,
+    usage: &'static str,
+    run: AdminCommandFn,
+}
+
+const DEFAULT_NEAR_RADIUS: f64 = 500.0;
+
+// Saves only go in here, under a plain name, so admin commands can't write anywhere else
+const WORLD_SAVE_DIRECTORY: &str = "saves/world";
+const MAX_SAVE_NAME_LENGTH: usize = 32;
+
+const ADMIN_COMMANDS: [AdminCommand; 16] = [
+    AdminCommand {
+        name: "help",
+        usage: "help",
+        run: help,
+    },
+    AdminCommand {
+        name: "players",
+        usage: "players",
+        run: list_players,
+    },
+    AdminCommand {
+        name: "near",
+        usage: "near <x> <y> [radius] [plane]",
+        run: list_near,
+    },
+    AdminCommand {
+        name: "inspect",
+        usage: "inspect <object>",
+        run: inspect,
+    },
+    AdminCommand {
+        name: "spawn",
+        usage: "spawn <prefab> <x> <y> [plane]",
+        run: spawn,
+    },
+    AdminCommand {
+        name: "wolves",
+        usage: "wolves <pack> <x> <y> [plane]",
+        run: spawn_wolves,
+    },
+    AdminCommand {
+        name: "village",
+        usage: "village <population> <x> <y> [plane]",
+        run: found_village,
+    },
+    AdminCommand {
+        name: "teleport",
+        usage: "teleport <object> <x> <y> [plane]",
+        run: teleport,
+    },
+    AdminCommand {
+        name: "plane",
+        usage: "plane <object> <plane>",
+        run: change_plane,
+    },
+    AdminCommand {
+        name: "health",
+        usage: "health <object> <health>",
+        run: set_health,
+    },
+    AdminCommand {
+        name: "grant",
+        usage: "grant <object> <ability or ability script>",
+        run: grant_ability,
+    },
+    AdminCommand {
+        name: "character",
+        usage: "character <object> <knight|lich|paladin>",
+        run: change_character,
+    },
+    AdminCommand {
+        name: "quest",
+        usage: "quest <object> <desert or quest script>",
+        run: begin_quest,
+    },
+    AdminCommand {
+        name: "save",
+        usage: "save <name>",
+        run: save,
+    },
+    AdminCommand {
+        name: "load",
+        usage: "load <name>",
+        run: load,
+    },
+    AdminCommand {
+        name: "metrics",
+        usage: "metrics",
+        run: show_metrics,
+    },
+];
+
+// Returns the lines to show whoever sent the command
+pub fn run_admin_command(game: &mut Game, line: &str) -> Result<Vec<String>, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let name = match words.first() {
+        Some(name) => *name,
+        None => return Ok(Vec::new()),
+    };
+    match ADMIN_COMMANDS.iter().find(|command| command.name == name) {
+        Some(command) => (command.run)(game, &words[1..])
+            .map_err(|error| format!("{} (usage: {})", error, command.usage)),
+        None => Err(format!("Unknown command {}, try help", name)),
+    }
+}
+
+pub fn get_admin_command_usages() -> Vec<&'static str> {
+    ADMIN_COMMANDS.iter().map(|command| command.usage).collect()
+}
+
+fn help(_game: &mut Game, _args: &[&str]) -> Result<Vec<String>, String> {
+    Ok(get_admin_command_usages()
+        .into_iter()
+        .map(|usage| usage.to_string())
+        .collect())
+}
+
+fn show_metrics(game: &mut Game, _args: &[&str]) -> Result<Vec<String>, String> {
+    Ok(game
+        .metrics_system
+        .render()
+        .lines()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+fn parse_object(game: &Game, args: &[&str], index: usize) -> Result<GameObjectId, String> {
+    let id = GameObjectId(parse_argument(args, index, "object id")?);
+    if id.is_deleted(&game.game_objects) {
+        return Err(format!("No object {}", id.0));
+    }
+    Ok(id)
+}
+
+// Plane 0 if it isn't given
+fn parse_coords(args: &[&str], index: usize) -> Result<PixelCoords, String> {
+    let x: f64 = parse_argument(args, index, "x")?;
+    let y: f64 = parse_argument(args, index + 1, "y")?;
+    let plane = match args.get(index + 2) {
+        Some(_) => Plane(parse_argument(args, index + 2, "plane")?),
+        None => Plane(0),
+    };
+    Ok(PixelCoords::new_to_fixed(plane, x, y))
+}
+
+fn describe_coords(coords: PixelCoords) -> String {
+    format!(
+        "({}, {}) on plane {}",
+        coords.get_x().to_num::<f64>().round(),
+        coords.get_y().to_num::<f64>().round(),
+        coords.get_plane().0
+    )
+}
+
+fn list_players(game: &mut Game, _args: &[&str]) -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
+    for (player_id, player) in game.player_system.players.iter() {
+        // Bodies are deleted when they die, and the player stays bound until they respawn
+        lines.push(match player.bound_object_id {
+            Some(object_id) => format!(
+                "player {}: object {} {}",
+                player_id.0,
+                object_id.0,
+                match object_id.get_coords_safe(&game.game_objects) {
+                    Some(coords) => format!("at {}", describe_coords(coords)),
+                    None => "body deleted".to_string(),
+                }
+            ),
+            None => format!("player {}: not bound to anything", player_id.0),
+        });
+    }
+    lines.sort();
+    Ok(lines)
+}
+
+fn list_near(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let x: f64 = parse_argument(args, 0, "x")?;
+    let y: f64 = parse_argument(args, 1, "y")?;
+    let radius = match args.get(2) {
+        Some(_) => parse_argument(args, 2, "radius")?,
+        None => DEFAULT_NEAR_RADIUS,
+    };
+    let plane = match args.get(3) {
+        Some(_) => Plane(parse_argument(args, 3, "plane")?),
+        None => Plane(0),
+    };
+    let center = PixelCoords::new_to_fixed(plane, x, y);
+    let mut near: Vec<(f64, GameObjectId)> = game
+        .game_objects
+        .iter()
+        .filter(|(_id, game_object)| game_object.coords.get_plane() == plane)
+        .map(|(id, game_object)| (center.get_distance_to(&game_object.coords), id))
+        .filter(|(distance, _id)| *distance <= radius)
+        .collect();
+    near.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    Ok(near
+        .into_iter()
+        .map(|(distance, id)| {
+            format!(
+                "object {} at {}, {} away: {}",
+                id.0,
+                describe_coords(id.get_coords(&game.game_objects)),
+                distance.round(),
+                id.get_component_names(game).join(" ")
+            )
+        })
+        .collect())
+}
+
+fn inspect(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let id = parse_object(game, args, 0)?;
+    let mut lines = vec![format!(
+        "object {} at {}",
+        id.0,
+        describe_coords(id.get_coords(&game.game_objects))
+    )];
+    lines.extend(id.dump_components(game));
+    Ok(lines)
+}
+
+fn spawn(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let prefab_name: String = parse_argument(args, 0, "prefab")?;
+    let coords = parse_coords(args, 1)?;
+    let id = PrefabSystem::spawn(game, &prefab_name, coords)?;
+    Ok(vec![format!("Spawned {} as object {}", prefab_name, id.0)])
+}
+
+fn spawn_wolves(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let pack_name: String = parse_argument(args, 0, "pack")?;
+    if !game.monsters.has_wolf_pack(&pack_name) {
+        return Err(format!("Unknown wolf pack {}", pack_name));
+    }
+    let coords = parse_coords(args, 1)?;
+    let id = WolfLeader::create(game, coords, &pack_name);
+    Ok(vec![format!(
+        "Spawned {} pack led by object {}",
+        pack_name, id.0
+    )])
+}
+
+fn found_village(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let population: usize = parse_argument(args, 0, "population")?;
+    let coords = parse_coords(args, 1)?;
+    let village_id = VillagesSystem::found_village(game, coords.into(), population);
+    Ok(vec![format!(
+        "Founded village {} with {} villagers at {}",
+        village_id.0,
+        population,
+        describe_coords(coords)
+    )])
+}
+
+fn teleport(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let id = parse_object(game, args, 0)?;
+    let coords = parse_coords(args, 1)?;
+    id.move_to_game(game, coords);
+    Ok(vec![format!(
+        "Moving object {} to {}",
+        id.0,
+        describe_coords(coords)
+    )])
+}
+
+fn change_plane(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let id = parse_object(game, args, 0)?;
+    let plane = Plane(parse_argument(args, 1, "plane")?);
+    let coords = id.get_coords(&game.game_objects).set_plane(plane);
+    id.move_to_game(game, coords);
+    Ok(vec![format!(
+        "Moving object {} to {}",
+        id.0,
+        describe_coords(coords)
+    )])
+}
+
+// Goes through damage and heal signals so dying and health bars work as normal. Max health is
+// raised if needed.
+fn set_health(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let id = parse_object(game, args, 0)?;
+    let health: i32 = parse_argument(args, 1, "health")?;
+    let damageable_id = match id
+        .get_components_of_type::<Rc<DamageableComponent>>(game)
+        .first()
+    {
+        Some(component) => component.damageable_id,
+        None => return Err(format!("Object {} has no health", id.0)),
+    };
+    let max_health = game
+        .damage_system
+        .damageables
+        .get(damageable_id)
+        .unwrap()
+        .max_health;
+    if health > max_health.0 {
+        id.send_set_max_health_signal(game, Health(health));
+    }
+    let current = game
+        .damage_system
+        .damageables
+        .get(damageable_id)
+        .unwrap()
+        .health;
+    if health > current.0 {
+        id.send_heal_signal(game, Health(health - current.0));
+    } else if health < current.0 {
+        id.send_damage_signal(game, Health(current.0 - health));
+    }
+    Ok(vec![format!("Object {} has {} health", id.0, health)])
+}
+
+fn grant_ability(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let id = parse_object(game, args, 0)?;
+    let name: String = parse_argument(args, 1, "ability")?;
+    let ability_type_id = match AbilityTypeId::from_name(&name) {
+        Some(ability_type_id) => ability_type_id,
+        None => match game.script_system.get_id(&name) {
+            Some(script_id) => AbilityTypeId::ScriptedId(script_id),
+            None => return Err(format!("Unknown ability {}", name)),
+        },
+    };
+    let granted = BasicAbilityUser::grant_abilities(game, id, &vec![ability_type_id]);
+    if granted.is_empty() {
+        return Err(format!("Object {} can't use abilities", id.0));
+    }
+    Ok(vec![format!("Granted {} to object {}", name, id.0)])
+}
+
+fn change_character(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let id = parse_object(game, args, 0)?;
+    let name: String = parse_argument(args, 1, "character")?;
+    match name.as_str() {
+        "knight" => KnightCharacterComponent::add_to(game, id),
+        "lich" => LichCharacterComponent::add_to(game, id),
+        "paladin" => PaladinCharacterComponent::add_to(game, id),
+        _ => return Err(format!("Unknown character {}", name)),
+    }
+    Ok(vec![format!("Object {} is now a {}", id.0, name)])
+}
+
+fn begin_quest(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let id = parse_object(game, args, 0)?;
+    let name: String = parse_argument(args, 1, "quest")?;
+    if name == "desert" {
+        CrossDesertQuest::begin(game, id);
+    } else {
+        ScriptSystem::begin_quest(game, &name, id)?;
+    }
+    Ok(vec![format!("Object {} has begun {}", id.0, name)])
+}
+
+fn get_save_path(args: &[&str]) -> Result<std::path::PathBuf, String> {
+    let name: String = parse_argument(args, 0, "save name")?;
+    let valid = !name.is_empty()
+        && name.len() <= MAX_SAVE_NAME_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(format!(
+            "{} isn't a save name, use up to {} letters, digits, _ or -",
+            name, MAX_SAVE_NAME_LENGTH
+        ));
+    }
+    Ok(std::path::Path::new(WORLD_SAVE_DIRECTORY).join(format!("{}.txt", name)))
+}
+
+// Writes every object with components that can be written out, one "object <x> <y> <plane>"
+// line followed by its components
+fn save(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let path = get_save_path(args)?;
+    let mut ids: Vec<GameObjectId> = game.game_objects.iter().map(|(id, _)| id).collect();
+    ids.sort_by_key(|id| id.0);
+    let mut lines = vec![format!("// Saved at tick {}", game.tick_counter)];
+    let mut saved = 0;
+    for id in ids {
+        let components = id.serialise_components(game);
+        if components.is_empty() {
+            continue;
+        }
+        let coords = id.get_coords(&game.game_objects);
+        lines.push(format!(
+            "object {} {} {}",
+            coords.get_x().to_num::<f64>(),
+            coords.get_y().to_num::<f64>(),
+            coords.get_plane().0
+        ));
+        lines.extend(components);
+        saved += 1;
+    }
+    std::fs::create_dir_all(WORLD_SAVE_DIRECTORY).map_err(|error| error.to_string())?;
+    std::fs::write(&path, lines.join("\n") + "\n").map_err(|error| error.to_string())?;
+    Ok(vec![format!(
+        "Saved {} objects to {}",
+        saved,
+        path.display()
+    )])
+}
+
+// Adds the saved objects to the world as new objects, alongside whatever is already there.
+// Objects and components that can't be added are skipped and listed, rather than leaving the
+// world half loaded.
+fn load(game: &mut Game, args: &[&str]) -> Result<Vec<String>, String> {
+    let path = get_save_path(args)?;
+    let file = std::fs::read_to_string(&path).map_err(|error| error.to_string())?;
+    let mut output = Vec::new();
+    let mut current = None;
+    let mut loaded = 0;
+    for line in file.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words[0] == "object" {
+            // A bad object line takes its components with it
+            current = match parse_coords(&words[1..], 0) {
+                Ok(coords) => {
+                    loaded += 1;
+                    Some(GameObject::create_game(game, coords))
+                }
+                Err(error) => {
+                    output.push(format!("Skipped {}: {}", line, error));
+                    None
+                }
+            };
+            continue;
+        }
+        let id = match current {
+            Some(id) => id,
+            None => {
+                output.push(format!("Skipped {}: no object to add it to", line));
+                continue;
+            }
+        };
+        if let Err(error) = ComponentRegistry::add_component_from_text(game, id, line) {
+            output.push(format!("Skipped {} on object {}: {}", line, id.0, error));
+        }
+    }
+    output.insert(
+        0,
+        format!("Loaded {} objects from {}", loaded, path.display()),
+    );
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn admin_commands_change_the_game() {
+        let mut game = Game::new();
+        assert!(run_admin_command(&mut game, "fly away").is_err());
+        assert!(run_admin_command(&mut game, "spawn zombie here 0").is_err());
+
+        let output = run_admin_command(&mut game, "spawn zombie 100 0").unwrap();
+        let id = output[0].rsplit(' ').next().unwrap();
+        let near = run_admin_command(&mut game, "near 90 0 20").unwrap();
+        assert_eq!(near.len(), 1);
+        assert!(near[0].starts_with(&format!("object {} ", id)));
+
+        run_admin_command(&mut game, &format!("health {} 50", id)).unwrap();
+        let inspected = run_admin_command(&mut game, &format!("inspect {}", id)).unwrap();
+        assert!(inspected.iter().any(|line| line.contains("50/")));
+        assert!(run_admin_command(&mut game, &format!("grant {} flying", id)).is_err());
+
+        assert!(run_admin_command(&mut game, "save ../../elsewhere").is_err());
+        run_admin_command(&mut game, "save admin_test").unwrap();
+        let objects = game.game_objects.len();
+        let loaded = run_admin_command(&mut game, "load admin_test");
+        std::fs::remove_file(get_save_path(&["admin_test"]).unwrap()).unwrap();
+        assert!(loaded.unwrap()[0].starts_with("Loaded 1 objects"));
+        assert_eq!(game.game_objects.len(), objects + 1);
+
+        let path = get_save_path(&["admin_test_bad"]).unwrap();
+        std::fs::write(
+            &path,
+            "object 0 0 0\ndamageable\nobject nowhere\ndamageable\nobject 40 0 0\n",
+        )
+        .unwrap();
+        let objects = game.game_objects.len();
+        let loaded = run_admin_command(&mut game, "load admin_test_bad");
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert!(loaded[0].starts_with("Loaded 2 objects"));
+        assert!(loaded[1].starts_with("Skipped object nowhere"));
+        assert!(loaded[2].starts_with("Skipped damageable"));
+        assert_eq!(game.game_objects.len(), objects + 2);
+
+        let player_id = Player::create(&mut game);
+        let body_id = game
+            .player_system
+            .players
+            .get(player_id)
+            .unwrap()
+            .bound_object_id
+            .unwrap();
+        GameObject::_remove(&mut game, body_id);
+        let players = run_admin_command(&mut game, "players").unwrap();
+        assert_eq!(
+            players,
+            vec![format!(
+                "player {}: object {} body deleted",
+                player_id.0, body_id.0
+            )]
+        );
+
+        assert!(run_admin_command(&mut game, "village many 0 0").is_err());
+        let objects = game.game_objects.len();
+        run_admin_command(&mut game, "village 2 400 400").unwrap();
+        // The villagers and their guards
+        assert!(game.game_objects.len() > objects + 2);
+    }
+}
@@ -15,17 +15,19 @@ pub trait Action: std::fmt::Debug {
     fn get_name(&self) -> String;
 }
 
-pub trait ActionSeed: std::fmt::Debug {
+// Seeds are made while planning on the thread pool, then handed back to the main thread
+pub trait ActionSeed: std::fmt::Debug + Send {
     fn get_action(self: Box<Self>, game: &Game, owner_id: GameObjectId) -> Option<Box<dyn Action>>; //returns None if can't germinate
 }
 
-pub trait ActionGenerator {
-    //starting_state is used to load and cache data from the world
+// Generators run on the thread pool without the Game, so everything they read from the world
+// has to be declared up front and is loaded into the starting state before planning
+pub trait ActionGenerator: Send + Sync {
+    fn get_reads(&self) -> Vec<PlannerStateKey>;
     fn generate_actions(
         &self,
-        game: &Game,
         owner_id: GameObjectId,
-        starting_state: &mut StartingPlannerState,
+        starting_state: &StartingPlannerState,
         state: &DerivedPlannerState,
         needs: &Needs,
     ) -> Vec<(DerivedPlannerState, Needs, ActionCost, Box<dyn ActionSeed>)>;
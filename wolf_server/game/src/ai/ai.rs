@@ -1,6 +1,7 @@
 use super::*;
-use crate::combinable::CombinedVecs;
-use signal_listener_macro::define_signal_listener;
+use crate::define_signal_listener;
+use crate::statuses::{StatusKind, StatusSystem};
+use signal_listeners::CombinedVecs;
 
 const AI_CONFUSION_LENGTH: u32 = 200;
 
@@ -19,9 +20,6 @@ pub enum AiMode {
 
 pub struct Ai {
     pub game_object_id: GameObjectId,
-
-    //confusion is when the ai couldn't make a plan, or had no valid goals
-    pub confusion_ends: Option<u32>,
     pub ai_mode: Option<AiMode>,
 }
 
@@ -32,15 +30,15 @@ impl Ai {
             let mut failed = false;
             let mut ai = game.ai_system.ais.remove(ai_id).unwrap();
             let game_object_id = ai.game_object_id;
-            if let Some(confusion_ends) = ai.confusion_ends {
-                if confusion_ends > game.tick_counter {
-                    game.ai_system.ais.insert(ai_id, ai);
-                    continue;
-                }
+            //confusion is when the ai couldn't make a plan, or had no valid goals
+            if game
+                .status_system
+                .has_status(game_object_id, StatusKind::Confusion)
+            {
+                game.ai_system.ais.insert(ai_id, ai);
+                continue;
             }
 
-            ai.confusion_ends = None;
-
             if let Some(ref mut ai_mode) = ai.ai_mode {
                 match ai_mode {
                     AiMode::Plan(plan_mode) => {
@@ -81,73 +79,46 @@ impl Ai {
                         }
                     }
                 }
-            } else {
-                //Generate new plan
-                let mut max_importance = None;
-                let mut current_goal_index = None;
-
-                //first we have to get a goal
-                let mut goals = game_object_id
-                    .send_get_goals_signal(game)
-                    .map(|x| x.extract())
-                    .unwrap_or(Vec::new());
-
-                for (index, goal) in goals.iter().enumerate() {
-                    if let Some(current_importance) = goal.get_importance(game, game_object_id) {
-                        if max_importance.is_none() || max_importance.unwrap() < current_importance
-                        {
-                            max_importance = Some(current_importance);
-                            current_goal_index = Some(index);
-                        }
-                    }
-                }
-
-                match current_goal_index {
-                    None => failed = true,
-                    Some(goal_index) => {
-                        let goal = goals.remove(goal_index);
-
-                        let needs_or_simple_plan = goal.get_method(game, game_object_id);
-                        match needs_or_simple_plan {
-                            GoalResult::SimplePlan(simple_plan) => {
-                                ai.ai_mode = Some(AiMode::SimplePlan(simple_plan));
-                            }
-                            GoalResult::Needs(needs) => {
-                                let action_generators = game_object_id
-                                    .send_get_action_generators_signal(game)
-                                    .map(|x| x.extract())
-                                    .unwrap_or(Vec::new());
-
-                                let finished_plan: Option<VecDeque<Box<dyn ActionSeed>>> =
-                                    plan(game, game_object_id, needs, action_generators);
-
-                                if let Some(finished_plan) = finished_plan {
-                                    ai.ai_mode = Some(AiMode::Plan(PlanAiMode {
-                                        current_plan: finished_plan,
-                                        current_action: None,
-                                    }));
-                                } else {
-                                    failed = true;
-                                }
-                            }
-                            GoalResult::Failure => failed = true,
-                        }
-                    }
-                }
             }
+            // AIs without a mode are given one by AiSystem's planning, before this runs
+            game.ai_system.ais.insert(ai_id, ai);
             if failed {
-                ai.ai_mode = None;
-                ai.confusion_ends = Some(game.tick_counter + AI_CONFUSION_LENGTH);
+                Ai::confuse(game, ai_id);
             }
-            game.ai_system.ais.insert(ai_id, ai);
         }
     }
+    pub fn is_confused(game: &Game, ai_id: AiId) -> bool {
+        game.ai_system
+            .ais
+            .get(ai_id)
+            .map(|ai| {
+                game.status_system
+                    .has_status(ai.game_object_id, StatusKind::Confusion)
+            })
+            .unwrap_or(false)
+    }
+    // For when the ai couldn't make a plan, or had no valid goals
+    pub fn confuse(game: &mut Game, ai_id: AiId) {
+        let game_object_id = match game.ai_system.ais.get_mut(ai_id) {
+            Some(ai) => {
+                ai.ai_mode = None;
+                ai.game_object_id
+            }
+            None => return,
+        };
+        StatusSystem::apply_status(
+            game,
+            game_object_id,
+            game_object_id,
+            StatusKind::Confusion,
+            AI_CONFUSION_LENGTH,
+            0,
+        );
+    }
     pub fn new(game: &mut Game, game_object_id: GameObjectId) -> AiId {
         let ai_id = game.get_id();
         let ai = Ai {
             game_object_id,
-
-            confusion_ends: None,
             ai_mode: None,
         };
         game.ai_system.ais.insert(ai_id, ai);
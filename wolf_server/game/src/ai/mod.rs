@@ -7,6 +7,7 @@ mod goal;
 mod needs;
 mod planner;
 mod planner_state;
+mod planning;
 mod simple_plan;
 
 pub use action::*;
@@ -20,11 +21,21 @@ pub use simple_plan::*;
 
 pub struct AiSystem {
     ais: IdMap<AiId, Ai>,
+    // How many nodes the planner has expanded since the server started
+    planner_node_count: u64,
 }
 
+// Planning for AIs that need a new plan is registered separately, as a parallel system
+
 impl AiSystem {
     pub fn new() -> AiSystem {
-        AiSystem { ais: IdMap::new() }
+        AiSystem {
+            ais: IdMap::new(),
+            planner_node_count: 0,
+        }
+    }
+    pub fn get_planner_node_count(&self) -> u64 {
+        self.planner_node_count
     }
     pub fn step(game: &mut Game) {
         Ai::step(game);
@@ -49,10 +49,9 @@ impl Needs {
     }
     #[allow(dead_code)]
     pub fn debug_still_need(&self) {
-        println!("the following needs are unsatisfied:");
         for (key, need) in self.needs.iter() {
             if !need.is_satisfied() {
-                println!("Still need {:?} ({:?})", key, need);
+                tracing::debug!(?key, ?need, "Still need");
             }
         }
     }
@@ -16,17 +16,17 @@ struct ActionNode {
 */
 const MAX_LOOPS: usize = 200;
 
-#[allow(dead_code)]
+// Runs on the thread pool, so the world is only seen through starting_state. Every node
+// expanded is added to node_count.
 pub fn plan(
-    game: &Game, //used for loading state
     owner_id: GameObjectId,
     starting_needs: Needs,
-    action_generators: Vec<Box<dyn ActionGenerator>>, //pairs a plan step with its prereqs & effects
+    action_generators: &[Box<dyn ActionGenerator>], //pairs a plan step with its prereqs & effects
+    starting_state: &StartingPlannerState,
+    node_count: &mut u64,
 ) -> Option<VecDeque<Box<dyn ActionSeed>>> {
     let mut active_nodes = VecDeque::new();
 
-    let mut starting_state = StartingPlannerState::new();
-
     let start_action_node = Rc::new(ActionNode {
         action: None,
         parent: None,
@@ -49,12 +49,12 @@ pub fn plan(
 
         for action_generator in action_generators.iter() {
             let new_states = action_generator.generate_actions(
-                game,
                 owner_id,
-                &mut starting_state,
+                starting_state,
                 &active_node.state,
                 &active_node.needs,
             );
+            *node_count += new_states.len() as u64;
             for (state, needs, cost, action) in new_states {
                 let satisfied = needs.is_satisfied();
                 let node = Rc::new(ActionNode {
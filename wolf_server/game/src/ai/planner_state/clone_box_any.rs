@@ -1,12 +1,12 @@
 use super::*;
 
-pub trait CloneBoxAny: std::fmt::Debug + 'static {
+pub trait CloneBoxAny: std::fmt::Debug + Send + Sync + 'static {
     fn clone_box_any(self: &Self) -> Box<dyn CloneBoxAny>;
     fn as_any_mut(&mut self) -> &mut dyn Any;
     fn as_any(&self) -> &dyn Any;
 }
 
-impl<T: Clone + std::fmt::Debug + 'static> CloneBoxAny for T {
+impl<T: Clone + std::fmt::Debug + Send + Sync + 'static> CloneBoxAny for T {
     fn clone_box_any(self: &Self) -> Box<dyn CloneBoxAny> {
         Box::new(self.clone())
     }
@@ -18,31 +18,28 @@ impl DerivedPlannerState {
             changed_state: PlannerState::new(),
         }
     }
-    pub fn get<'a, 'b: 'a, T: Any>(
+    pub fn get<'a, T: Any>(
         &'a self,
-        game: &Game,
-        owner_id: GameObjectId,
-        starting_state: &'b mut StartingPlannerState,
+        starting_state: &'a StartingPlannerState,
         key: PlannerStateKey,
-    ) -> &T {
+    ) -> &'a T {
         if let Some(in_self) = self.changed_state.states.get(&key) {
             downcast_clone_box_any(in_self).unwrap()
         } else {
-            starting_state.lazy_get(game, owner_id, key)
+            starting_state.get(key)
         }
     }
-    pub fn get_mut<'a, 'b: 'a, T: Any>(
-        &'a mut self,
-        game: &Game,
-        owner_id: GameObjectId,
-        starting_state: &'b mut StartingPlannerState,
+    // The starting state is shared by every node, so it's copied in before being changed
+    pub fn get_mut<T: Any>(
+        &mut self,
+        starting_state: &StartingPlannerState,
         key: PlannerStateKey,
     ) -> &mut T {
-        if let Some(in_self) = self.changed_state.states.get_mut(&key) {
-            downcast_clone_box_any_mut(in_self).unwrap()
-        } else {
-            let reference = starting_state.lazy_get(game, owner_id, key);
-            reference
-        }
+        let in_self = self
+            .changed_state
+            .states
+            .entry(key)
+            .or_insert_with(|| (**starting_state.get_box_any(key)).clone_box_any());
+        downcast_clone_box_any_mut(in_self).unwrap()
     }
 }
@@ -1,5 +1,7 @@
 use super::*;
 
+// What the world looked like when planning started. Loaded on the main thread, then only read
+// while planning.
 #[derive(Debug)]
 pub struct StartingPlannerState {
     state: PlannerState,
@@ -11,15 +13,29 @@ impl StartingPlannerState {
             state: PlannerState::new(),
         }
     }
-    pub fn lazy_get<T: Any>(
-        &mut self,
-        game: &Game,
-        owner_id: GameObjectId,
-        key: PlannerStateKey,
-    ) -> &mut T {
-        let entry = self.state.states.entry(key);
-        let reference = entry.or_insert_with(|| get_starting_state(game, owner_id, key));
-        downcast_clone_box_any_mut(reference).unwrap()
+    pub fn load(game: &Game, owner_id: GameObjectId, keys: &[PlannerStateKey]) -> Self {
+        let mut starting_state = StartingPlannerState::new();
+        for key in keys.iter() {
+            if !starting_state.state.states.contains_key(key) {
+                starting_state
+                    .state
+                    .states
+                    .insert(*key, get_starting_state(game, owner_id, *key));
+            }
+        }
+        starting_state
+    }
+    pub fn get_box_any(&self, key: PlannerStateKey) -> &Box<dyn CloneBoxAny> {
+        match self.state.states.get(&key) {
+            Some(state) => state,
+            None => panic!(
+                "{:?} was read while planning without an action generator declaring it",
+                key
+            ),
+        }
+    }
+    pub fn get<T: Any>(&self, key: PlannerStateKey) -> &T {
+        downcast_clone_box_any(self.get_box_any(key)).unwrap()
     }
 }
 
@@ -0,0 +1,135 @@
+use super::*;
+use crate::parallel::{Access, GameField, ParallelSystem};
+
+// Everything one AI's plan needs, taken out of the Game so it can be planned on the pool
+pub struct PlanningJob {
+    ai_id: AiId,
+    owner_id: GameObjectId,
+    needs: Needs,
+    action_generators: Vec<Box<dyn ActionGenerator>>,
+    starting_state: StartingPlannerState,
+}
+
+pub struct PlanningResult {
+    ai_id: AiId,
+    finished_plan: Option<VecDeque<Box<dyn ActionSeed>>>,
+    node_count: u64,
+}
+
+/*
+Picking a goal goes through signals, so happens while gathering. Only the search for a plan
+runs on the pool, and plans are handed back to the AIs in the order they were gathered.
+*/
+impl ParallelSystem for AiSystem {
+    type Job = PlanningJob;
+    type Output = PlanningResult;
+    fn get_access() -> Access {
+        Access::new()
+            .read(GameField::GameObjects)
+            .read(GameField::Statuses)
+            .write(GameField::Ais)
+            .write(GameField::Statuses)
+    }
+    fn gather(game: &mut Game) -> Vec<PlanningJob> {
+        let mut jobs = Vec::new();
+        let ai_ids: Vec<AiId> = game
+            .ai_system
+            .ais
+            .iter()
+            .filter(|(_ai_id, ai)| ai.ai_mode.is_none())
+            .map(|(ai_id, _ai)| ai_id)
+            .collect();
+        for ai_id in ai_ids {
+            if Ai::is_confused(game, ai_id) {
+                continue;
+            }
+            let owner_id = game.ai_system.ais.get(ai_id).unwrap().game_object_id;
+            match Ai::choose_method(game, owner_id) {
+                GoalResult::SimplePlan(simple_plan) => {
+                    game.ai_system.ais.get_mut(ai_id).unwrap().ai_mode =
+                        Some(AiMode::SimplePlan(simple_plan));
+                }
+                GoalResult::Needs(needs) => {
+                    let action_generators = owner_id
+                        .send_get_action_generators_signal(game)
+                        .map(|x| x.extract())
+                        .unwrap_or(Vec::new());
+                    let reads: Vec<PlannerStateKey> = action_generators
+                        .iter()
+                        .flat_map(|action_generator| action_generator.get_reads())
+                        .collect();
+                    let starting_state = StartingPlannerState::load(game, owner_id, &reads);
+                    jobs.push(PlanningJob {
+                        ai_id,
+                        owner_id,
+                        needs,
+                        action_generators,
+                        starting_state,
+                    });
+                }
+                GoalResult::Failure => Ai::confuse(game, ai_id),
+            }
+        }
+        jobs
+    }
+    fn run(job: PlanningJob) -> PlanningResult {
+        let mut node_count = 0;
+        let finished_plan = plan(
+            job.owner_id,
+            job.needs,
+            &job.action_generators,
+            &job.starting_state,
+            &mut node_count,
+        );
+        PlanningResult {
+            ai_id: job.ai_id,
+            finished_plan,
+            node_count,
+        }
+    }
+    fn apply(game: &mut Game, outputs: Vec<PlanningResult>) {
+        for PlanningResult {
+            ai_id,
+            finished_plan,
+            node_count,
+        } in outputs
+        {
+            game.ai_system.planner_node_count += node_count;
+            match finished_plan {
+                Some(finished_plan) => {
+                    if let Some(ai) = game.ai_system.ais.get_mut(ai_id) {
+                        ai.ai_mode = Some(AiMode::Plan(PlanAiMode {
+                            current_plan: finished_plan,
+                            current_action: None,
+                        }));
+                    }
+                }
+                None => Ai::confuse(game, ai_id),
+            }
+        }
+    }
+}
+
+impl Ai {
+    // The method of the most important goal that can be done at all
+    fn choose_method(game: &Game, game_object_id: GameObjectId) -> GoalResult {
+        let mut goals = game_object_id
+            .send_get_goals_signal(game)
+            .map(|x| x.extract())
+            .unwrap_or(Vec::new());
+        let mut max_importance = None;
+        let mut current_goal_index = None;
+        for (index, goal) in goals.iter().enumerate() {
+            if let Some(current_importance) = goal.get_importance(game, game_object_id) {
+                if max_importance.is_none() || max_importance.unwrap() < current_importance {
+                    max_importance = Some(current_importance);
+                    current_goal_index = Some(index);
+                }
+            }
+        }
+        match current_goal_index {
+            Some(goal_index) => goals.remove(goal_index).get_method(game, game_object_id),
+            None => GoalResult::Failure,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use super::*;
-use crate::combinable::CombinedVecs;
-use signal_listener_macro::*;
+use crate::define_signal_listener;
+use signal_listeners::CombinedVecs;
 
 //model: each mob owns 1 allegiance, + some for special allegiances
 //zombies etc use links to make them undead-aligned:w
@@ -70,9 +70,6 @@ impl GetAllegiancesSignalListener for AllegianceComponent {
     ) -> CombinedVecs<AllegianceId> {
         CombinedVecs(vec![self.allegiance_id])
     }
-    fn clone_box(&self) -> Box<dyn GetAllegiancesSignalListener> {
-        Box::new(self.clone())
-    }
 }
 
 impl Component for AllegianceComponent {
@@ -21,4 +21,13 @@ impl SpecialAllegiances {
             wolf_allegiance,
         }
     }
+    pub fn get_by_name(&self, name: &str) -> Option<AllegianceId> {
+        match name {
+            "holy" => Some(self.holy_allegiance),
+            "undead" => Some(self.undead_allegiance),
+            "villager" => Some(self.villager_allegiance),
+            "wolf" => Some(self.wolf_allegiance),
+            _ => None,
+        }
+    }
 }
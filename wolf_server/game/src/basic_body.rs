@@ -2,6 +2,7 @@ use crate::abilities::SpellbookAbsorberComponent;
 use crate::characters::*;
 use crate::damage::DamageableComponent;
 use crate::game::*;
+use crate::inventory::{InventoryComponent, InventorySystem, ItemKind};
 use crate::terrain::{BasicChunkLoaderComponent, ChunkWatcherComponent};
 
 impl Game {
@@ -19,6 +20,9 @@ impl Game {
 
         PaladinCharacterComponent::add_to(self, object_to_watch);
 
+        InventoryComponent::add_to(self, object_to_watch);
+        InventorySystem::give_item(self, object_to_watch, ItemKind::HealthPotion, 3);
+
         object_to_watch
     }
 }
@@ -1,4 +1,7 @@
 use crate::abilities::{CastAbilitySignalSender, GetAbilityDetailsSignalSender};
+use crate::hunting::HuntingSystem;
+use crate::perception::{Perception, PerceptionSystem};
+use crate::threat::ThreatSystem;
 
 use super::*;
 
@@ -14,7 +17,9 @@ pub struct HunterBehaviour {
 impl HunterBehaviour {
     pub fn step(game: &mut Game) {
         let mut to_follow = Vec::new();
+        let mut to_search = Vec::new();
         let mut to_reset = Vec::new();
+        let mut to_retarget = Vec::new();
         for (id, hunter_behaviour) in game.behaviour_system.hunter_behaviours.iter() {
             let mind = game
                 .behaviour_system
@@ -28,6 +33,13 @@ impl HunterBehaviour {
             let current_target = hunter_behaviour
                 .current_target
                 .expect("Hunter behaviour had no target set");
+            if let Some(threat_prey) = ThreatSystem::get_valid_target(game, game_object_id)
+                .and_then(|target_id| HuntingSystem::get_prey_id(game, target_id))
+            {
+                if threat_prey != current_target {
+                    to_retarget.push((id, threat_prey));
+                }
+            }
             let allegiances = game_object_id.get_allegiances(game);
             let prey = if let Some(prey) = game.hunting_system.preys.get(current_target) {
                 prey
@@ -36,12 +48,6 @@ impl HunterBehaviour {
                 to_reset.push(hunter_behaviour.mind_id);
                 continue;
             };
-            let distance = game_object_id.get_distance_to(&game.game_objects, &prey.game_object_id);
-            if distance > TETHER_DISTANCE {
-                // Prey has escaped
-                to_reset.push(hunter_behaviour.mind_id);
-                continue;
-            }
             let target_allegiances = &prey.game_object_id.get_allegiances(game);
             if !game
                 .allegiance_system
@@ -51,7 +57,29 @@ impl HunterBehaviour {
                 to_reset.push(hunter_behaviour.mind_id);
                 continue;
             }
-            to_follow.push((game_object_id, prey.game_object_id));
+            let (perceived_coords, seen) =
+                match PerceptionSystem::perceive(game, game_object_id, prey.game_object_id) {
+                    Perception::Seen(coords) => (coords, true),
+                    Perception::Remembered(coords) => (coords, false),
+                    Perception::Unknown => {
+                        // Prey has been forgotten
+                        to_reset.push(hunter_behaviour.mind_id);
+                        continue;
+                    }
+                };
+            let my_coords = game_object_id.get_coords(&game.game_objects);
+            if my_coords.get_plane() != perceived_coords.get_plane()
+                || my_coords.get_distance_to(&perceived_coords) > TETHER_DISTANCE
+            {
+                // Prey has escaped
+                to_reset.push(hunter_behaviour.mind_id);
+                continue;
+            }
+            if seen {
+                to_follow.push((game_object_id, prey.game_object_id));
+            } else {
+                to_search.push((game_object_id, perceived_coords));
+            }
         }
         for (id, target_id) in to_follow {
             if game.tick_counter % 50 == 0 {
@@ -73,9 +101,18 @@ impl HunterBehaviour {
 
             id.intend_follow(&mut game.movement_system.intend_move_system, target_id);
         }
+        for (id, last_known_coords) in to_search {
+            id.intend_move_to_point(
+                &mut game.movement_system.intend_move_system,
+                last_known_coords,
+            );
+        }
         for id in to_reset {
             id.set_active_behaviour(&mut game.behaviour_system.minds, None);
         }
+        for (id, prey_id) in to_retarget {
+            HunterBehaviour::set_target(game, id, prey_id);
+        }
     }
     fn new(game: &mut Game, mind_id: MindId) -> BehaviourId {
         let hunter_behaviour_id = game.get_id();
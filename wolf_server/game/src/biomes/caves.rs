@@ -12,8 +12,9 @@ const CAVERN_LAYER_HALF_WIDTH: i64 = CAVERN_SPREAD + CAVERN_HALF_WIDTH + 20;
 const CAVERN_LAYER_HALF_WIDTH_CHUNKS: i64 =
     (CAVERN_LAYER_HALF_WIDTH + TERRAIN_CHUNK_SIZE_SQUARES - 1) / TERRAIN_CHUNK_SIZE_SQUARES;
 pub fn generate_caves(game: &mut Game) {
-    println!("Generating caves...");
+    tracing::info!("Generating caves");
     let caves_plane = game.get_plane();
+    game.biome_system.caves_plane = Some(caves_plane);
 
     for cx in -CAVERN_LAYER_HALF_WIDTH_CHUNKS..CAVERN_LAYER_HALF_WIDTH_CHUNKS + 1 {
         for cy in -CAVERN_LAYER_HALF_WIDTH_CHUNKS..CAVERN_LAYER_HALF_WIDTH_CHUNKS + 1 {
@@ -7,12 +7,9 @@ use crate::solids::SolidComponent;
 use crate::terrain::Chunk;
 use crate::terrain::ChunkComponent;
 use crate::villages::NeedsDeconstructionComponent;
-use crate::wildlife::create_hopper_creature;
-
-const HOPPER_CREATURE_SPAWN_CHANCE: i64 = 50 * 6000;
 
 pub struct GrassBiome {
-    coords: TerrainChunkCoords,
+    pub coords: TerrainChunkCoords,
 }
 
 impl GrassBiome {
@@ -62,7 +59,7 @@ pub fn add_tree(game: &mut Game, coords: PixelCoords) {
     BasicDrawingComponent::add_to(game, game_object_id, TREE_SPRITE, DEFAULT_DEPTH);
     SolidComponent::add_to(game, game_object_id);
     ChunkDependentComponent::add_to(game, game_object_id, coords.into());
-    HarvestableComponent::add_to(game, game_object_id, Resources::wood(ResourceAmount(1)));
+    HarvestableComponent::add_with_loot_table(game, game_object_id, "tree");
     DamageableComponent::add_to(game, game_object_id);
     DieOnNoHealthComponent::add_to(game, game_object_id);
     DeleteOnDeathComponent::add_to(game, game_object_id);
@@ -80,21 +77,4 @@ impl GrassBiome {
             add_tree(game, coords.pixel_offset(pixel_offset));
         }
     }
-
-    pub fn step(game: &mut Game) {
-        let mut to_spawn = Vec::new();
-        for (_id, grass_biome) in game.biome_system.grass_biomes.iter() {
-            if rand::thread_rng().gen_range(0..HOPPER_CREATURE_SPAWN_CHANCE) == 0 {
-                let dx = rand::thread_rng()
-                    .gen_range(-TERRAIN_CHUNK_SIZE_PIXELS / 2..TERRAIN_CHUNK_SIZE_PIXELS / 2);
-                let dy = rand::thread_rng()
-                    .gen_range(-TERRAIN_CHUNK_SIZE_PIXELS / 2..TERRAIN_CHUNK_SIZE_PIXELS / 2);
-                let spawn_at = grass_biome.coords.center_pixel().translate(dx, dy);
-                to_spawn.push(spawn_at);
-            }
-        }
-        for spawn_at in to_spawn {
-            create_hopper_creature(game, spawn_at);
-        }
-    }
 }
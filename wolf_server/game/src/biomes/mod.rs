@@ -13,6 +13,8 @@ pub struct BiomeSystem {
     pub rockiness: Perlin,
     pub wetness: Perlin,
     pub elevation: Perlin,
+    pub caves_plane: Option<Plane>,
+    pub has_ravine: bool,
 }
 
 impl BiomeSystem {
@@ -22,11 +24,10 @@ impl BiomeSystem {
             rockiness: Perlin::new(rand::thread_rng().gen()),
             wetness: Perlin::new(rand::thread_rng().gen()),
             elevation: Perlin::new(rand::thread_rng().gen()),
+            caves_plane: None,
+            has_ravine: false,
         }
     }
-    pub fn step(game: &mut Game) {
-        GrassBiome::step(game);
-    }
 }
 
 const NOISE_DISTANCE_SCALING: f64 = 0.5;
@@ -41,6 +42,54 @@ const GEM_FLOWER_CHANCE_ON_WATER: f64 = 0.04;
 fn chunk_relative_to_noise_coords(chunk: i64, relative: u8) -> f64 {
     (chunk as f64 + (relative as f64 / TERRAIN_CHUNK_SIZE_SQUARES as f64)) * NOISE_DISTANCE_SCALING
 }
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TerrainType {
+    Rock,
+    Water,
+    Grass,
+    Dirt,
+    Sand,
+}
+
+fn get_terrain_type_at_noise_coords(game: &Game, nx: f64, ny: f64) -> TerrainType {
+    let rockiness = game.biome_system.rockiness.get([nx, ny]);
+    let wetness = game.biome_system.wetness.get([nx, ny]);
+    let elevation = game.biome_system.elevation.get([nx, ny]);
+    if rockiness > WALL_ROCKINESS_THRESHOLD {
+        TerrainType::Rock
+    } else if wetness > WATER_WETNESS_THRESHOLD && elevation < WATER_ELEVATION_MAX {
+        TerrainType::Water
+    } else if wetness > GRASS_WETNESS_THRESHOLD {
+        TerrainType::Grass
+    } else if wetness > DIRT_WETNESS_THRESHOLD {
+        TerrainType::Dirt
+    } else {
+        TerrainType::Sand
+    }
+}
+
+// What generate_biome will put (or has put) on a square, without needing its chunk loaded
+pub fn get_generated_terrain_type(game: &Game, square: SquareCoords) -> TerrainType {
+    let chunk_coords: TerrainChunkCoords = square.into();
+    let relative = square.relative_to_chunk(chunk_coords).unwrap();
+    let nx = chunk_relative_to_noise_coords(chunk_coords.get_x(), relative.0.get_x());
+    let ny = chunk_relative_to_noise_coords(chunk_coords.get_y(), relative.0.get_y());
+    get_terrain_type_at_noise_coords(game, nx, ny)
+}
+// What's on a square now if its chunk is loaded, as it may have changed since it was generated
+pub fn get_terrain_type(game: &Game, square: SquareCoords) -> TerrainType {
+    match game.terrain.get_chunk_square(square) {
+        Some(chunk_square) if chunk_square.base_solid => TerrainType::Rock,
+        Some(chunk_square) => match chunk_square.base_sprite {
+            WATER_SPRITE => TerrainType::Water,
+            GRASS_SPRITE => TerrainType::Grass,
+            SAND_SPRITE => TerrainType::Sand,
+            _ => TerrainType::Dirt,
+        },
+        None => get_generated_terrain_type(game, square),
+    }
+}
 pub fn generate_biome(game: &mut Game, coords: TerrainChunkCoords) {
     // TODO: technically f64 doesn't cover whole range of terrain coords
     let mut chunk = Chunk::new(DIRT_SPRITE);
@@ -51,35 +100,36 @@ pub fn generate_biome(game: &mut Game, coords: TerrainChunkCoords) {
                 let chunk_index =
                     get_chunk_index_from_relative_coords(ChunkRelativeSquareCoords::new(rx, ry));
                 let ny = chunk_relative_to_noise_coords(coords.get_y(), ry);
-                let rockiness = game.biome_system.rockiness.get([nx, ny]);
-                let wetness = game.biome_system.wetness.get([nx, ny]);
-                let elevation = game.biome_system.elevation.get([nx, ny]);
-                let sprite_chosen = if rockiness > WALL_ROCKINESS_THRESHOLD {
-                    chunk.chunk_squares[chunk_index].base_solid = true;
-                    WALL_SPRITE
-                } else if wetness > WATER_WETNESS_THRESHOLD && elevation < WATER_ELEVATION_MAX {
-                    if rand::thread_rng().gen_bool(GEM_FLOWER_CHANCE_ON_WATER) {
-                        chunk.chunk_squares[chunk_index]
-                            .detritus
-                            .push(GEM_FLOWER_SPRITE);
+                let sprite_chosen = match get_terrain_type_at_noise_coords(game, nx, ny) {
+                    TerrainType::Rock => {
+                        chunk.chunk_squares[chunk_index].base_solid = true;
+                        WALL_SPRITE
+                    }
+                    TerrainType::Water => {
+                        if rand::thread_rng().gen_bool(GEM_FLOWER_CHANCE_ON_WATER) {
+                            chunk.chunk_squares[chunk_index]
+                                .detritus
+                                .push(GEM_FLOWER_SPRITE);
+                        }
+                        WATER_SPRITE
                     }
-                    WATER_SPRITE
-                } else if wetness > GRASS_WETNESS_THRESHOLD {
-                    if rand::thread_rng().gen_bool(GEM_FLOWER_CHANCE_ON_GRASS) {
-                        chunk.chunk_squares[chunk_index]
-                            .detritus
-                            .push(GEM_FLOWER_SPRITE);
+                    TerrainType::Grass => {
+                        if rand::thread_rng().gen_bool(GEM_FLOWER_CHANCE_ON_GRASS) {
+                            chunk.chunk_squares[chunk_index]
+                                .detritus
+                                .push(GEM_FLOWER_SPRITE);
+                        }
+                        GRASS_SPRITE
                     }
-                    GRASS_SPRITE
-                } else if wetness > DIRT_WETNESS_THRESHOLD {
-                    if rand::thread_rng().gen_bool(GEM_FLOWER_CHANCE_ON_DIRT) {
-                        chunk.chunk_squares[chunk_index]
-                            .detritus
-                            .push(GEM_FLOWER_SPRITE);
+                    TerrainType::Dirt => {
+                        if rand::thread_rng().gen_bool(GEM_FLOWER_CHANCE_ON_DIRT) {
+                            chunk.chunk_squares[chunk_index]
+                                .detritus
+                                .push(GEM_FLOWER_SPRITE);
+                        }
+                        DIRT_SPRITE
                     }
-                    DIRT_SPRITE
-                } else {
-                    SAND_SPRITE
+                    TerrainType::Sand => SAND_SPRITE,
                 };
                 chunk.chunk_squares[chunk_index].base_sprite = sprite_chosen;
             }
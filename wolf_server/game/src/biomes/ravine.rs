@@ -9,7 +9,13 @@ const RAVINE_END_X: i64 = 3;
 const RAVINE_OPEN_HALF_WIDTH: i64 = 5;
 const RAVINE_OPEN_FULL_WIDTH: i64 = RAVINE_OPEN_HALF_WIDTH * 2 + 1;
 const START_X_OFFSET: i64 = (RAVINE_START_X + RAVINE_END_X) * TERRAIN_CHUNK_SIZE_SQUARES / 2;
+pub fn is_in_ravine(chunk_coords: TerrainChunkCoords) -> bool {
+    chunk_coords.get_plane() == Plane(0)
+        && (RAVINE_START_X..=RAVINE_END_X).contains(&chunk_coords.get_x())
+        && (RAVINE_START_Y..=RAVINE_END_Y).contains(&chunk_coords.get_y())
+}
 pub fn generate_ravine(game: &mut Game) {
+    game.biome_system.has_ravine = true;
     let mut current_x_offset = START_X_OFFSET;
     for cy in RAVINE_START_Y..RAVINE_END_Y + 1 {
         let mut x_offsets = Vec::with_capacity(TERRAIN_CHUNK_SIZE_SQUARES as usize);
@@ -2,7 +2,9 @@ use wolf_hash_map::WolfHashMap;
 
 use super::*;
 use crate::abilities::*;
+use crate::damage::ResistancesComponent;
 use crate::drawable::*;
+use crate::progression::{CharacterClass, ProgressionComponent};
 use crate::resources::*;
 
 pub const KNIGHT_SPEED: f64 = 6.0;
@@ -16,6 +18,8 @@ pub struct KnightCharacterComponent {
     pub base_character_component_id: ComponentId,
     pub resource_holder_component_id: ComponentId,
     pub resource_collector_component_id: ComponentId,
+    pub resistances_component_id: Option<ComponentId>,
+    pub progression_component_id: ComponentId,
 }
 
 impl KnightCharacterComponent {
@@ -53,6 +57,11 @@ impl KnightCharacterComponent {
         let resource_holder_component_id =
             ResourceHolderComponent::add_to(game, owner_id).get_component_id();
         let resource_collector_component_id = ResourceCollectorComponent::add_to(game, owner_id);
+        let resistances_component_id = ResistancesComponent::add_to(game, owner_id, "plate")
+            .map_err(|error| tracing::warn!("Knight has no resistances: {}", error))
+            .ok();
+        let progression_component_id =
+            ProgressionComponent::add_to(game, owner_id, CharacterClass::Knight);
 
         let comp = KnightCharacterComponent {
             component_id,
@@ -61,6 +70,8 @@ impl KnightCharacterComponent {
             coloured_component_id,
             resource_holder_component_id,
             resource_collector_component_id,
+            resistances_component_id,
+            progression_component_id,
         };
         owner_id.add_component(game, comp);
         owner_id.add_get_character_component_ids_signal_listener(
@@ -80,6 +91,10 @@ impl Component for KnightCharacterComponent {
         owner_id.remove_component(game, self.base_character_component_id);
         owner_id.remove_component(game, self.resource_holder_component_id);
         owner_id.remove_component(game, self.resource_collector_component_id);
+        if let Some(resistances_component_id) = self.resistances_component_id {
+            owner_id.remove_component(game, resistances_component_id);
+        }
+        owner_id.remove_component(game, self.progression_component_id);
         owner_id.remove_get_character_component_ids_signal_listener(game, self.component_id);
     }
 }
@@ -88,9 +103,6 @@ impl GetCharacterComponentIdsSignalListener for KnightCharacterComponentId {
     fn get_listener_id(&self) -> ComponentId {
         self.0
     }
-    fn clone_box(&self) -> Box<dyn GetCharacterComponentIdsSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_get_character_component_ids_signal(
         &self,
         _game: &Game,
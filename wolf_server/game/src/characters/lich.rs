@@ -1,6 +1,9 @@
 use super::*;
 use crate::abilities::AbilityTypeId;
+use crate::damage::ResistancesComponent;
 use crate::drawable::BasicDrawingComponent;
+use crate::progression::{CharacterClass, ProgressionComponent};
+use crate::statuses::{StatusImmunityComponent, StatusTag};
 
 #[derive(Clone)]
 struct LichCharacterComponentId(ComponentId);
@@ -9,6 +12,9 @@ pub struct LichCharacterComponent {
     pub component_id: ComponentId,
     pub basic_drawable_component_id: ComponentId,
     pub base_character_component_id: ComponentId,
+    pub resistances_component_id: Option<ComponentId>,
+    pub status_immunity_component_id: ComponentId,
+    pub progression_component_id: ComponentId,
 }
 
 const NECROMANCER_SPEED: f64 = 4.0;
@@ -30,10 +36,21 @@ impl LichCharacterComponent {
             BasicDrawingComponent::add_to(game, owner_id, NECROMANCER_SPRITE, DEFAULT_DEPTH)
                 .component_id;
 
+        let resistances_component_id = ResistancesComponent::add_to(game, owner_id, "undead")
+            .map_err(|error| tracing::warn!("Lich has no resistances: {}", error))
+            .ok();
+        let status_immunity_component_id =
+            StatusImmunityComponent::add_to(game, owner_id, vec![StatusTag::Blood]);
+        let progression_component_id =
+            ProgressionComponent::add_to(game, owner_id, CharacterClass::Lich);
+
         let comp = LichCharacterComponent {
             component_id,
             basic_drawable_component_id,
             base_character_component_id,
+            resistances_component_id,
+            status_immunity_component_id,
+            progression_component_id,
         };
         owner_id.add_component(game, comp);
         owner_id.add_get_character_component_ids_signal_listener(
@@ -50,6 +67,11 @@ impl Component for LichCharacterComponent {
     fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
         owner_id.remove_component(game, self.basic_drawable_component_id);
         owner_id.remove_component(game, self.base_character_component_id);
+        if let Some(resistances_component_id) = self.resistances_component_id {
+            owner_id.remove_component(game, resistances_component_id);
+        }
+        owner_id.remove_component(game, self.status_immunity_component_id);
+        owner_id.remove_component(game, self.progression_component_id);
         owner_id.remove_get_character_component_ids_signal_listener(game, self.component_id);
     }
 }
@@ -58,9 +80,6 @@ impl GetCharacterComponentIdsSignalListener for LichCharacterComponentId {
     fn get_listener_id(&self) -> ComponentId {
         self.0
     }
-    fn clone_box(&self) -> Box<dyn GetCharacterComponentIdsSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_get_character_component_ids_signal(
         &self,
         _game: &Game,
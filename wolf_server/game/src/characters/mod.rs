@@ -1,6 +1,6 @@
-use crate::combinable::CantCombine;
+use crate::define_signal_listener;
 use crate::game::*;
-use signal_listener_macro::define_signal_listener;
+use signal_listeners::CantCombine;
 
 define_signal_listener!(GetCharacterComponentIds, &Game -> CantCombine<ComponentId>);
 mod base_character;
@@ -1,5 +1,15 @@
 use super::*;
+use crate::statuses::{StatusKind, StatusSystem, StatusTag};
+use crate::threat::ThreatSystem;
+use signal_listeners::Stoppable;
 pub const SHIELD_TIME: u32 = 50;
+pub const SHIELD_REGENERATION_TIME: u32 = 100;
+pub const SHIELD_REGENERATION_TICK_HEALING: i32 = SMALL_DAMAGE / 10;
+pub const SHIELD_COOLDOWN: u32 = 200;
+pub const SHIELD_MANA_COST: u32 = 30;
+pub const SHIELD_TAUNT_RADIUS: f64 = 300.0;
+// Hitting the shield makes the attacker angrier at the paladin
+pub const SHIELD_BLOCK_THREAT: f64 = 2000.0;
 
 pub struct HolyShieldAbility {
     ability_id: AbilityId,
@@ -71,16 +81,14 @@ impl AboutToBeDamagedSignalListener for HolyShieldComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn AboutToBeDamagedSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_about_to_be_damaged_signal(
         &self,
         game: &mut Game,
         owner_id: GameObjectId,
         _damager_id: GameObjectId,
         firer_id: GameObjectId,
-    ) -> OrBool {
+    ) -> Stoppable<OrBool> {
+        ThreatSystem::add_threat(game, firer_id, owner_id, SHIELD_BLOCK_THREAT);
         if let Ok(mut ported) = self.ported.try_borrow_mut() {
             if !*ported {
                 *ported = true;
@@ -105,7 +113,7 @@ impl AboutToBeDamagedSignalListener for HolyShieldComponent {
                 }
             }
         }
-        OrBool(true) // Block
+        Stoppable::Continue(OrBool(true)) // Block
     }
 }
 impl HolyShieldComponent {
@@ -131,6 +139,16 @@ impl Ability for HolyShieldAbility {
     }
 
     fn activate(&mut self, game: &mut Game, caster: GameObjectId, _target_coords: PixelCoords) {
+        StatusSystem::dispel(game, caster, StatusTag::Harmful);
+        ThreatSystem::taunt_nearby(game, caster, SHIELD_TAUNT_RADIUS);
+        StatusSystem::apply_status(
+            game,
+            caster,
+            caster,
+            StatusKind::Regeneration,
+            SHIELD_REGENERATION_TIME,
+            SHIELD_REGENERATION_TICK_HEALING,
+        );
         let holy_shield_component_id = HolyShieldComponent::add_to(game, caster);
         let colour_component_id = add_colour(game, caster, 255, 255, 255);
         TimerSystem::add_timer(
@@ -142,4 +160,10 @@ impl Ability for HolyShieldAbility {
             SHIELD_TIME,
         );
     }
+    fn get_casting_details(&self) -> CastingDetails {
+        CastingDetails::default()
+            .cooldown(SHIELD_COOLDOWN)
+            .cooldown_group(CooldownGroup::Spells)
+            .cost(PoolType::Mana, SHIELD_MANA_COST)
+    }
 }
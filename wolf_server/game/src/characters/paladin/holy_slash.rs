@@ -49,7 +49,14 @@ fn create_beam(game: &mut Game, firer_id: GameObjectId, start: PixelCoords, end:
                 let beam = GameObject::create_with_hit_box(game, pos, hit_box);
                 beam.set_rotation(game, direction);
                 BasicDrawingComponent::add_to(game, beam, HOLY_BEAM_SPRITE, PROJECTILE_DEPTH);
-                DamagerComponent::add_to(game, beam, firer_id, None, DEFAULT_HEALTH.0 / 10);
+                DamagerComponent::add_to(
+                    game,
+                    beam,
+                    firer_id,
+                    None,
+                    DEFAULT_HEALTH.0 / 10,
+                    DamageType::Holy,
+                );
                 TimerSystem::add_timer(
                     game,
                     Box::new(move |game| beam.remove(game)),
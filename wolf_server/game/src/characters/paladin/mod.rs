@@ -1,10 +1,15 @@
 use std::{cell::RefCell, rc::Rc};
 
+use signal_listeners::OrBool;
 use wolf_hash_map::WolfHashMap;
 use wolf_interface::CreateComponentData;
 
 use super::*;
-use crate::{abilities::*, combinable::OrBool, timers::TimerSystem};
+use crate::{
+    abilities::*,
+    progression::{CharacterClass, ProgressionComponent},
+    timers::TimerSystem,
+};
 
 const AFTER_IMAGE_EVERY: f64 = 20.0;
 const AFTER_IMAGE_LIFESPAN: u32 = 0; //How long the first after image lasts (minus one increment)
@@ -41,6 +46,7 @@ pub struct PaladinCharacterComponent {
     pub base_character_component_id: ComponentId,
     pub coloured_component_id: ComponentId,
     pub drawable_component_id: ComponentId,
+    pub progression_component_id: ComponentId,
 }
 impl Component for PaladinCharacterComponent {
     fn on_remove(self: Box<Self>, game: &mut Game, owner: GameObjectId) {
@@ -48,6 +54,7 @@ impl Component for PaladinCharacterComponent {
         owner.remove_component(game, self.base_character_component_id);
         owner.remove_component(game, self.coloured_component_id);
         owner.remove_component(game, self.drawable_component_id);
+        owner.remove_component(game, self.progression_component_id);
     }
 
     fn get_component_id(&self) -> ComponentId {
@@ -58,9 +65,6 @@ impl GetCharacterComponentIdsSignalListener for PaladinCharacterComponentId {
     fn get_listener_id(&self) -> ComponentId {
         self.0
     }
-    fn clone_box(&self) -> Box<dyn GetCharacterComponentIdsSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_get_character_component_ids_signal(
         &self,
         _game: &Game,
@@ -97,11 +101,14 @@ impl PaladinCharacterComponent {
         sprites.insert(CardinalDirection::Down, KNIGHT_SPRITE_DOWN);
         let drawable_component_id =
             FacingSpriteComponent::add_to(game, owner_id, sprites, DEFAULT_DEPTH);
+        let progression_component_id =
+            ProgressionComponent::add_to(game, owner_id, CharacterClass::Paladin);
         let component = PaladinCharacterComponent {
             component_id,
             base_character_component_id,
             coloured_component_id,
             drawable_component_id,
+            progression_component_id,
         };
         owner_id.add_get_character_component_ids_signal_listener(
             game,
@@ -0,0 +1,268 @@
+use crate::game::*;
+use crate::speech::SPEECH_TIME;
+use wolf_interface::*;
+
+/*
+Local chat goes to everyone in range of the speaker and shows as a speech bubble over them.
+Party chat goes to everyone who joined the same party name, global to every player. Players
+go by the name they logged in with, or their id until they've logged in.
+*/
+
+pub const MAX_CHAT_LENGTH: usize = 200;
+pub const MAX_PARTY_NAME_LENGTH: usize = 20;
+const LOCAL_CHAT_RANGE: f64 = 800.0;
+// At most this many messages in any RATE_LIMIT_TICKS
+const RATE_LIMIT_MESSAGES: usize = 5;
+const RATE_LIMIT_TICKS: u32 = 250;
+
+pub struct ChatSystem {
+    // When each player's messages inside the rate limit window were sent
+    recently_sent: IdMap<PlayerId, Vec<u32>>,
+    parties: IdMap<PlayerId, String>,
+}
+
+impl ChatSystem {
+    pub fn new() -> Self {
+        ChatSystem {
+            recently_sent: IdMap::new(),
+            parties: IdMap::new(),
+        }
+    }
+    pub fn receive_chat(game: &mut Game, player_id: PlayerId, chat_command: ChatCommand) {
+        let text = clean_text(&chat_command.text, MAX_CHAT_LENGTH);
+        if text.is_empty() || chat_command.channel == ChatChannel::System {
+            return;
+        }
+        if !ChatSystem::try_use_rate_limit(game, player_id) {
+            ChatSystem::send_system_message(
+                game,
+                player_id,
+                "You're sending messages too quickly".to_string(),
+            );
+            return;
+        }
+        let message = ServerMessage::Chat(ChatMessage {
+            channel: chat_command.channel,
+            sender_name: get_player_name(game, player_id),
+            text: text.clone(),
+            is_emote: chat_command.is_emote,
+            sent_at: game.tick_counter,
+        });
+        match chat_command.channel {
+            ChatChannel::Local => {
+                let speaker_id = match game
+                    .player_system
+                    .players
+                    .get(player_id)
+                    .and_then(|player| player.bound_object_id)
+                {
+                    Some(speaker_id) => speaker_id,
+                    None => return,
+                };
+                let coords = match speaker_id.get_coords_safe(&game.game_objects) {
+                    Some(coords) => coords,
+                    None => return,
+                };
+                Player::send_server_message_in_range(game, message, coords, LOCAL_CHAT_RANGE);
+                let bubble = if chat_command.is_emote {
+                    format!("*{}*", text)
+                } else {
+                    text
+                };
+                speaker_id.speak_safe(game, bubble, Some(game.tick_counter + SPEECH_TIME));
+            }
+            ChatChannel::Party => {
+                let party_name = match game.chat_system.parties.get(player_id) {
+                    Some(party_name) => party_name.clone(),
+                    None => {
+                        ChatSystem::send_system_message(
+                            game,
+                            player_id,
+                            "You're not in a party, join one with /party <name>".to_string(),
+                        );
+                        return;
+                    }
+                };
+                for member_id in ChatSystem::get_party_members(game, &party_name) {
+                    ChatSystem::send_to_player(game, member_id, message.clone());
+                }
+            }
+            ChatChannel::Global => {
+                let player_ids: Vec<PlayerId> = game
+                    .player_system
+                    .players
+                    .iter()
+                    .map(|(player_id, _player)| player_id)
+                    .collect();
+                for other_id in player_ids {
+                    ChatSystem::send_to_player(game, other_id, message.clone());
+                }
+            }
+            ChatChannel::System => {}
+        }
+    }
+    // Keeps the times of recent messages, so a burst is allowed but not a constant stream
+    fn try_use_rate_limit(game: &mut Game, player_id: PlayerId) -> bool {
+        let tick_counter = game.tick_counter;
+        let recently_sent = match game.chat_system.recently_sent.get_mut(player_id) {
+            Some(recently_sent) => recently_sent,
+            None => {
+                game.chat_system.recently_sent.insert(player_id, Vec::new());
+                game.chat_system.recently_sent.get_mut(player_id).unwrap()
+            }
+        };
+        recently_sent.retain(|sent_at| sent_at + RATE_LIMIT_TICKS > tick_counter);
+        if recently_sent.len() >= RATE_LIMIT_MESSAGES {
+            return false;
+        }
+        recently_sent.push(tick_counter);
+        true
+    }
+    pub fn set_party(game: &mut Game, player_id: PlayerId, party_name: Option<String>) {
+        let party_name = party_name
+            .map(|party_name| clean_text(&party_name, MAX_PARTY_NAME_LENGTH))
+            .filter(|party_name| !party_name.is_empty());
+        if let Some(old_party_name) = game.chat_system.parties.remove(player_id) {
+            ChatSystem::tell_party(
+                game,
+                &old_party_name,
+                format!("{} left the party", get_player_name(game, player_id)),
+            );
+        }
+        if let Some(party_name) = party_name {
+            game.chat_system
+                .parties
+                .insert(player_id, party_name.clone());
+            ChatSystem::tell_party(
+                game,
+                &party_name,
+                format!(
+                    "{} joined the party {}",
+                    get_player_name(game, player_id),
+                    party_name
+                ),
+            );
+        }
+    }
+    // Leaving tells the rest of the party, as if they'd left it themselves
+    pub fn remove_player(game: &mut Game, player_id: PlayerId) {
+        ChatSystem::set_party(game, player_id, None);
+        game.chat_system.recently_sent.remove(player_id);
+    }
+    fn get_party_members(game: &Game, party_name: &str) -> Vec<PlayerId> {
+        game.chat_system
+            .parties
+            .iter()
+            .filter(|(_player_id, other_party_name)| *other_party_name == party_name)
+            .map(|(player_id, _party_name)| player_id)
+            .collect()
+    }
+    fn tell_party(game: &mut Game, party_name: &str, text: String) {
+        for member_id in ChatSystem::get_party_members(game, party_name) {
+            ChatSystem::send_system_message(game, member_id, text.clone());
+        }
+    }
+    pub fn send_system_message(game: &mut Game, player_id: PlayerId, text: String) {
+        let message = ServerMessage::Chat(ChatMessage {
+            channel: ChatChannel::System,
+            sender_name: String::new(),
+            text,
+            is_emote: false,
+            sent_at: game.tick_counter,
+        });
+        ChatSystem::send_to_player(game, player_id, message);
+    }
+    fn send_to_player(game: &mut Game, player_id: PlayerId, message: ServerMessage) {
+        if let Some(player) = game.player_system.players.get_mut(player_id) {
+            player.server_messages.push(message);
+        }
+    }
+}
+
+fn get_player_name(game: &Game, player_id: PlayerId) -> String {
+    match game
+        .player_system
+        .players
+        .get(player_id)
+        .and_then(|player| player.name.clone())
+    {
+        Some(name) => name,
+        None => format!("Player {}", player_id.0),
+    }
+}
+
+// Drops anything that isn't printable and cuts it down to max_length characters
+fn clean_text(text: &str, max_length: usize) -> String {
+    text.chars()
+        .filter(|c| !c.is_control())
+        .take(max_length)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_chat_texts(game: &mut Game, player_id: PlayerId) -> Vec<String> {
+        let player = game.player_system.players.get_mut(player_id).unwrap();
+        std::mem::replace(&mut player.server_messages, Vec::new())
+            .into_iter()
+            .filter_map(|message| match message {
+                ServerMessage::Chat(chat) => Some(chat.text),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn say(channel: ChatChannel, text: &str) -> ChatCommand {
+        ChatCommand {
+            channel,
+            text: text.to_string(),
+            is_emote: false,
+        }
+    }
+
+    #[test]
+    fn chat_reaches_the_right_players() {
+        let mut game = Game::new();
+        let alice = Player::create(&mut game);
+        let bob = Player::create(&mut game);
+        get_chat_texts(&mut game, alice);
+        get_chat_texts(&mut game, bob);
+
+        ChatSystem::receive_chat(&mut game, alice, say(ChatChannel::Local, "hello\n"));
+        assert_eq!(get_chat_texts(&mut game, bob), vec!["hello"]);
+        let long = "a".repeat(MAX_CHAT_LENGTH * 2);
+        ChatSystem::receive_chat(&mut game, alice, say(ChatChannel::Global, &long));
+        assert_eq!(get_chat_texts(&mut game, bob)[0].len(), MAX_CHAT_LENGTH);
+
+        ChatSystem::set_party(&mut game, alice, Some("wolves".to_string()));
+        ChatSystem::receive_chat(&mut game, alice, say(ChatChannel::Party, "secret"));
+        assert!(get_chat_texts(&mut game, bob).is_empty());
+        assert!(get_chat_texts(&mut game, alice).contains(&"secret".to_string()));
+
+        for _ in 0..RATE_LIMIT_MESSAGES {
+            ChatSystem::receive_chat(&mut game, alice, say(ChatChannel::Global, "spam"));
+        }
+        assert_eq!(get_chat_texts(&mut game, bob).len(), 2);
+        game.tick_counter += RATE_LIMIT_TICKS;
+        ChatSystem::receive_chat(&mut game, alice, say(ChatChannel::Global, "later"));
+        assert_eq!(get_chat_texts(&mut game, bob), vec!["later"]);
+
+        ChatSystem::set_party(&mut game, alice, Some("wolves".to_string()));
+        get_chat_texts(&mut game, alice);
+        ChatSystem::set_party(&mut game, bob, Some("wolves".to_string()));
+        assert_eq!(
+            get_chat_texts(&mut game, alice),
+            vec![format!("Player {} joined the party wolves", bob.0)]
+        );
+        get_chat_texts(&mut game, bob);
+        Player::log_in(&mut game, alice, "alice".to_string());
+        Player::remove(&mut game, alice);
+        assert!(game.chat_system.parties.get(alice).is_none());
+        assert!(game.chat_system.recently_sent.get(alice).is_none());
+        assert_eq!(get_chat_texts(&mut game, bob), vec!["alice left the party"]);
+    }
+}
@@ -1,4 +1,5 @@
 use id::IdMap;
+use std::any::Any;
 use std::collections::hash_map::Entry;
 use strum_macros::EnumDiscriminants;
 use wolf_hash_map::WolfHashMap;
@@ -117,7 +118,22 @@ impl GameObjectComponents {
     }
 }
 
-pub trait Component {
+// Lets components be looked up by their concrete type, see component_registry
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+    fn get_type_name(&self) -> &'static str;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn get_type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+}
+
+pub trait Component: AsAny {
     fn on_remove(self: Box<Self>, game: &mut Game, owner: GameObjectId);
     fn get_component_id(&self) -> ComponentId;
 }
@@ -0,0 +1,294 @@
+use crate::game::*;
+use std::any::{Any, TypeId};
+use std::str::FromStr;
+
+mod registrations;
+pub use registrations::get_component_registry;
+
+/*
+Gives component types a stable name, so objects can be listed, inspected and built up from
+text. A component is written out as its name followed by whatever arguments it needs to be
+added again, e.g. "resistances undead". Not every type can be written out or read back in,
+as a lot of them only make sense attached by code that knows about the rest of the object.
+*/
+
+pub type Deserialiser = fn(&mut Game, GameObjectId, &[&str]) -> Result<(), String>;
+type Serialiser = Box<dyn Fn(&Game, GameObjectId, &dyn Any) -> String>;
+type DebugDumper = Box<dyn Fn(&Game, &dyn Any) -> String>;
+
+pub struct ComponentType {
+    pub name: &'static str,
+    // None for things that can be added from text but aren't a component type of their own,
+    // like a drawing, which is one of many kinds of client side component
+    type_id: Option<TypeId>,
+    serialise: Option<Serialiser>,
+    deserialise: Option<Deserialiser>,
+    debug_dump: Option<DebugDumper>,
+}
+
+pub struct ComponentRegistry {
+    component_types: Vec<ComponentType>,
+}
+
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        ComponentRegistry {
+            component_types: Vec::new(),
+        }
+    }
+    pub fn register<T: Component + 'static>(
+        &mut self,
+        name: &'static str,
+        deserialise: Option<Deserialiser>,
+        serialise: Option<fn(&Game, GameObjectId, &T) -> String>,
+        debug_dump: Option<fn(&Game, &T) -> String>,
+    ) {
+        if self.get_by_name(name).is_some() {
+            panic!("Component type {} registered twice", name);
+        }
+        self.component_types.push(ComponentType {
+            name,
+            type_id: Some(TypeId::of::<T>()),
+            serialise: serialise.map(|serialise| {
+                Box::new(move |game: &Game, owner_id, component: &dyn Any| {
+                    serialise(game, owner_id, component.downcast_ref::<T>().unwrap())
+                }) as Serialiser
+            }),
+            deserialise,
+            debug_dump: debug_dump.map(|debug_dump| {
+                Box::new(move |game: &Game, component: &dyn Any| {
+                    debug_dump(game, component.downcast_ref::<T>().unwrap())
+                }) as DebugDumper
+            }),
+        });
+    }
+    pub fn register_builder(&mut self, name: &'static str, deserialise: Deserialiser) {
+        if self.get_by_name(name).is_some() {
+            panic!("Component type {} registered twice", name);
+        }
+        self.component_types.push(ComponentType {
+            name,
+            type_id: None,
+            serialise: None,
+            deserialise: Some(deserialise),
+            debug_dump: None,
+        });
+    }
+    pub fn get_by_name(&self, name: &str) -> Option<&ComponentType> {
+        self.component_types
+            .iter()
+            .find(|component_type| component_type.name == name)
+    }
+    fn get_by_type_id(&self, type_id: TypeId) -> Option<&ComponentType> {
+        self.component_types
+            .iter()
+            .find(|component_type| component_type.type_id == Some(type_id))
+    }
+    pub fn get_names(&self) -> Vec<&'static str> {
+        self.component_types
+            .iter()
+            .map(|component_type| component_type.name)
+            .collect()
+    }
+    // Takes a line as written out by serialise_components
+    pub fn add_component_from_text(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        line: &str,
+    ) -> Result<(), String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.first() {
+            Some(name) => ComponentRegistry::add_component(game, owner_id, name, &words[1..]),
+            None => Err("No component given".to_string()),
+        }
+    }
+    pub fn add_component(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        name: &str,
+        args: &[&str],
+    ) -> Result<(), String> {
+        let deserialise = match game.component_registry.get_by_name(name) {
+            Some(component_type) => component_type.deserialise,
+            None => return Err(format!("Unknown component {}", name)),
+        };
+        match deserialise {
+            Some(deserialise) => deserialise(game, owner_id, args),
+            None => Err(format!("{} can't be added from text", name)),
+        }
+    }
+}
+
+pub fn parse_argument<T: FromStr>(args: &[&str], index: usize, what: &str) -> Result<T, String> {
+    let arg = args.get(index).ok_or_else(|| format!("Missing {}", what))?;
+    arg.parse()
+        .map_err(|_| format!("{} isn't a valid {}", arg, what))
+}
+
+// Sprites are given by their name in sprite_mappings, e.g. "zombie", so data files don't depend
+// on the numbering
+pub fn parse_sprite(args: &[&str], index: usize) -> Result<u32, String> {
+    let arg = args
+        .get(index)
+        .ok_or_else(|| "Missing sprite".to_string())?;
+    get_entity_sprite_by_name(arg).ok_or_else(|| format!("{} isn't a known sprite", arg))
+}
+
+pub fn expect_argument_count(args: &[&str], count: usize) -> Result<(), String> {
+    if args.len() == count {
+        Ok(())
+    } else {
+        Err(format!("Expected {} arguments, got {}", count, args.len()))
+    }
+}
+
+pub fn no_arguments<T>(_game: &Game, _owner_id: GameObjectId, _component: &T) -> String {
+    String::new()
+}
+
+// Has to be called on the component itself, not a reference to it, or it'd give the type of
+// the reference
+fn get_type_id(component: &dyn Component) -> TypeId {
+    component.as_any().type_id()
+}
+
+impl GameObjectId {
+    // Ordered by when they were added
+    fn get_components<'a>(&self, game: &'a Game) -> Vec<(ComponentId, &'a dyn Component)> {
+        let mut components: Vec<(ComponentId, &dyn Component)> = match game.game_objects.get(*self)
+        {
+            Some(game_object) => game_object
+                .components
+                .components
+                .iter()
+                .map(|(component_id, component)| (component_id, &**component))
+                .collect(),
+            None => Vec::new(),
+        };
+        components.sort_by_key(|(component_id, _component)| component_id.0);
+        components
+    }
+    // Unregistered components show up under their Rust type name
+    pub fn get_component_names(&self, game: &Game) -> Vec<&'static str> {
+        self.get_components(game)
+            .into_iter()
+            .map(|(_component_id, component)| {
+                match game
+                    .component_registry
+                    .get_by_type_id(get_type_id(component))
+                {
+                    Some(component_type) => component_type.name,
+                    None => component.get_type_name(),
+                }
+            })
+            .collect()
+    }
+    pub fn get_components_of_type<'a, T: Component + 'static>(&self, game: &'a Game) -> Vec<&'a T> {
+        self.get_components(game)
+            .into_iter()
+            .filter_map(|(_component_id, component)| component.as_any().downcast_ref::<T>())
+            .collect()
+    }
+    pub fn get_component_ids_by_name(&self, game: &Game, name: &str) -> Vec<ComponentId> {
+        let type_id = match game
+            .component_registry
+            .get_by_name(name)
+            .and_then(|component_type| component_type.type_id)
+        {
+            Some(type_id) => type_id,
+            None => return Vec::new(),
+        };
+        self.get_components(game)
+            .into_iter()
+            .filter(|(_component_id, component)| get_type_id(*component) == type_id)
+            .map(|(component_id, _component)| component_id)
+            .collect()
+    }
+    // Components that can't be written out are left out
+    pub fn serialise_components(&self, game: &Game) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (_component_id, component) in self.get_components(game) {
+            let component_type = match game
+                .component_registry
+                .get_by_type_id(get_type_id(component))
+            {
+                Some(component_type) => component_type,
+                None => continue,
+            };
+            if let Some(ref serialise) = component_type.serialise {
+                let args = serialise(game, *self, component.as_any());
+                if args.is_empty() {
+                    lines.push(component_type.name.to_string());
+                } else {
+                    lines.push(format!("{} {}", component_type.name, args));
+                }
+            }
+        }
+        lines
+    }
+    pub fn dump_components(&self, game: &Game) -> Vec<String> {
+        self.get_components(game)
+            .into_iter()
+            .map(|(component_id, component)| {
+                match game
+                    .component_registry
+                    .get_by_type_id(get_type_id(component))
+                {
+                    Some(component_type) => match component_type.debug_dump {
+                        Some(ref debug_dump) => format!(
+                            "{} {}: {}",
+                            component_type.name,
+                            component_id.0,
+                            debug_dump(game, component.as_any())
+                        ),
+                        None => format!("{} {}", component_type.name, component_id.0),
+                    },
+                    None => format!("{} {}", component.get_type_name(), component_id.0),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::damage::ResistancesComponent;
+
+    #[test]
+    fn components_round_trip_through_text() {
+        let mut game = Game::new();
+        let original = GameObject::create_game(&mut game, PixelCoords::new_at_zero());
+        for line in ["damageable", "die_on_no_health", "resistances undead"].iter() {
+            ComponentRegistry::add_component_from_text(&mut game, original, line).unwrap();
+        }
+        assert!(ComponentRegistry::add_component_from_text(
+            &mut game,
+            original,
+            "resistances nope"
+        )
+        .is_err());
+        assert!(ComponentRegistry::add_component_from_text(&mut game, original, "flying").is_err());
+        assert_eq!(
+            original.get_component_names(&game),
+            vec!["damageable", "die_on_no_health", "resistances"]
+        );
+        assert_eq!(
+            original
+                .get_components_of_type::<ResistancesComponent>(&game)
+                .len(),
+            1
+        );
+
+        let lines = original.serialise_components(&game);
+        let copy = GameObject::create_game(&mut game, PixelCoords::new_at_zero());
+        for line in lines.iter() {
+            ComponentRegistry::add_component_from_text(&mut game, copy, line).unwrap();
+        }
+        assert_eq!(copy.serialise_components(&game), lines);
+        assert_eq!(
+            copy.get_component_ids_by_name(&game, "resistances").len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use super::*;
+use crate::ai::AiComponent;
+use crate::allegiance::AllegianceComponent;
+use crate::damage::{
+    DamageType, DamagerComponent, DeleteOnDeathComponent, DieOnNoHealthComponent,
+    ResistancesComponent,
+};
+use crate::drawable::BasicDrawingComponent;
+use crate::hunting::{BasicHunterBehaviourComponent, PreyComponent};
+use crate::inventory::InventoryComponent;
+use crate::loot::LootComponent;
+use crate::necromancy::CorpseOnDeathComponent;
+use crate::perception::PerceiverComponent;
+use crate::solids::{BlockableMoverComponent, SolidComponent};
+use crate::statuses::{StatusImmunityComponent, StatusTag};
+use crate::threat::ThreatTableComponent;
+use crate::wildlife::WanderingHerbivoreComponent;
+use std::rc::Rc;
+use wolf_hash_map::WolfHashMap;
+
+pub fn get_component_registry() -> ComponentRegistry {
+    let mut registry = ComponentRegistry::new();
+    registry.register::<Rc<DamageableComponent>>(
+        "damageable",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            DamageableComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        Some(
+            |game, component| match game.damage_system.damageables.get(component.damageable_id) {
+                Some(damageable) => {
+                    format!("{}/{} health", damageable.health.0, damageable.max_health.0)
+                }
+                None => "no health".to_string(),
+            },
+        ),
+    );
+    registry.register::<DieOnNoHealthComponent>(
+        "die_on_no_health",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            DieOnNoHealthComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        None,
+    );
+    registry.register::<DeleteOnDeathComponent>(
+        "delete_on_death",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            DeleteOnDeathComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        None,
+    );
+    registry.register::<ResistancesComponent>(
+        "resistances",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 1)?;
+            let profile_name: String = parse_argument(args, 0, "resistance profile")?;
+            ResistancesComponent::add_to(game, owner_id, &profile_name)?;
+            Ok(())
+        }),
+        Some(|game, _owner_id, component| component.get_profile_name(game).to_string()),
+        Some(|game, component| component.get_profile_name(game).to_string()),
+    );
+    registry.register::<LootComponent>(
+        "loot",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 1)?;
+            let table_name: String = parse_argument(args, 0, "loot table")?;
+            if !game.loot_system.has_table(&table_name) {
+                return Err(format!("Unknown loot table {}", table_name));
+            }
+            LootComponent::add_to(game, owner_id, &table_name);
+            Ok(())
+        }),
+        Some(|game, _owner_id, component| component.get_table_name(game).to_string()),
+        Some(|game, component| component.get_table_name(game).to_string()),
+    );
+    registry.register::<ThreatTableComponent>(
+        "threat_table",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            ThreatTableComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        None,
+    );
+    registry.register::<PerceiverComponent>(
+        "perceiver",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            PerceiverComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        None,
+    );
+    registry.register::<PreyComponent>(
+        "prey",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            PreyComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        None,
+    );
+    registry.register::<InventoryComponent>(
+        "inventory",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            InventoryComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        None,
+        None,
+    );
+    registry.register::<SolidComponent>(
+        "solid",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            SolidComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        None,
+    );
+    registry.register::<BlockableMoverComponent>(
+        "blockable_mover",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            BlockableMoverComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        None,
+    );
+    // Their settings live in the locomotion system, so they can be added but not written out
+    registry.register::<WalkerComponent>(
+        "walker",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 2)?;
+            let max_speed = parse_argument(args, 0, "max speed")?;
+            let acceleration = parse_argument(args, 1, "acceleration")?;
+            WalkerComponent::add_to(game, owner_id, max_speed, acceleration);
+            Ok(())
+        }),
+        None,
+        None,
+    );
+    registry.register::<HopperComponent>(
+        "hopper",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 1)?;
+            let speed = parse_argument(args, 0, "speed")?;
+            HopperComponent::add_to(game, owner_id, speed);
+            Ok(())
+        }),
+        None,
+        None,
+    );
+    registry.register::<SpeedModComponentId>(
+        "speed_mod",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 1)?;
+            let speed_mod = parse_argument(args, 0, "speed modifier")?;
+            SpeedModComponent::add_to(game, owner_id, speed_mod);
+            Ok(())
+        }),
+        None,
+        None,
+    );
+    registry.register::<DamagerComponent>(
+        "damager",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 2)?;
+            let damage = parse_argument(args, 0, "damage")?;
+            let damage_type = DamageType::from_name(args[1])
+                .ok_or_else(|| format!("Unknown damage type {}", args[1]))?;
+            DamagerComponent::add_to(game, owner_id, owner_id, None, damage, damage_type);
+            Ok(())
+        }),
+        None,
+        None,
+    );
+    registry.register::<StatusImmunityComponent>(
+        "status_immunity",
+        Some(|game, owner_id, args| {
+            let mut immunities = Vec::new();
+            for arg in args {
+                immunities.push(
+                    StatusTag::from_name(arg)
+                        .ok_or_else(|| format!("Unknown status tag {}", arg))?,
+                );
+            }
+            StatusImmunityComponent::add_to(game, owner_id, immunities);
+            Ok(())
+        }),
+        None,
+        None,
+    );
+    // Allied with whichever special allegiances are named
+    registry.register::<AllegianceComponent>(
+        "allegiance",
+        Some(|game, owner_id, args| {
+            let mut friends = Vec::new();
+            for arg in args {
+                friends.push(
+                    game.allegiance_system
+                        .special_allegiances
+                        .get_by_name(arg)
+                        .ok_or_else(|| format!("Unknown allegiance {}", arg))?,
+                );
+            }
+            AllegianceComponent::add_to(game, owner_id, friends);
+            Ok(())
+        }),
+        None,
+        None,
+    );
+    registry.register::<AiComponent>(
+        "ai",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            AiComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        None,
+    );
+    registry.register::<BasicHunterBehaviourComponent>(
+        "basic_hunter_behaviour",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            BasicHunterBehaviourComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        None,
+    );
+    registry.register::<CorpseOnDeathComponent>(
+        "corpse_on_death",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            CorpseOnDeathComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        None,
+    );
+    registry.register::<WanderingHerbivoreComponent>(
+        "wandering_herbivore",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 0)?;
+            WanderingHerbivoreComponent::add_to(game, owner_id);
+            Ok(())
+        }),
+        Some(no_arguments),
+        None,
+    );
+    // Sprites for facing left, right, up and down, then the depth
+    registry.register::<Rc<FacingSpriteComponent>>(
+        "facing_sprites",
+        Some(|game, owner_id, args| {
+            expect_argument_count(args, 5)?;
+            let mut sprites = WolfHashMap::new();
+            let directions = [
+                CardinalDirection::Left,
+                CardinalDirection::Right,
+                CardinalDirection::Up,
+                CardinalDirection::Down,
+            ];
+            for (index, direction) in directions.iter().enumerate() {
+                sprites.insert(*direction, parse_sprite(args, index)?);
+            }
+            let depth = parse_argument(args, 4, "depth")?;
+            FacingSpriteComponent::add_to(game, owner_id, sprites, depth);
+            Ok(())
+        }),
+        None,
+        None,
+    );
+    registry.register_builder("drawing", |game, owner_id, args| {
+        expect_argument_count(args, 2)?;
+        let sprite = parse_sprite(args, 0)?;
+        let depth = parse_argument(args, 1, "depth")?;
+        BasicDrawingComponent::add_to(game, owner_id, sprite, depth);
+        Ok(())
+    });
+    registry.register_builder("health_bar", |game, owner_id, args| {
+        expect_argument_count(args, 0)?;
+        add_health_bar(game, owner_id);
+        Ok(())
+    });
+    // Has to come after damageable
+    registry.register_builder("max_health", |game, owner_id, args| {
+        expect_argument_count(args, 1)?;
+        let max_health = parse_argument(args, 0, "max health")?;
+        owner_id.send_set_max_health_signal(game, Health(max_health));
+        Ok(())
+    });
+    registry
+}
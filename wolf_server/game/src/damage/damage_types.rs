@@ -0,0 +1,374 @@
+use super::*;
+use crate::progression::{ProgressionSystem, KILL_EXPERIENCE};
+use crate::threat::ThreatSystem;
+use signal_listeners::CombinedVecs;
+
+/*
+Damage goes through a pipeline before it reaches the target's health:
+anything listening for GetDamageModifiers on the target can scale it, absorb some of it,
+or send part of it to someone else. Scaling is applied first, then absorption, then redirection.
+*/
+
+const RESISTANCE_PROFILES_FILE: &str = include_str!("../../data/damage/resistances.txt");
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Holy,
+    Necrotic,
+    // Comes from inside, so armour doesn't stop it
+    Bleed,
+}
+
+impl DamageType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "physical" => Some(DamageType::Physical),
+            "fire" => Some(DamageType::Fire),
+            "holy" => Some(DamageType::Holy),
+            "necrotic" => Some(DamageType::Necrotic),
+            "bleed" => Some(DamageType::Bleed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum DamageModifier {
+    Scale(f64),
+    Absorb(Health),
+    // Sends this fraction of the damage to another object instead
+    Redirect(GameObjectId, f64),
+}
+
+define_signal_listener!(GetDamageModifiers, &Game, damage_type: DamageType, firer_id: GameObjectId -> CombinedVecs<DamageModifier>);
+
+#[derive(Clone, Debug)]
+pub struct ResistanceProfile {
+    pub name: String,
+    pub armour: Health,
+    pub multipliers: Vec<(DamageType, f64)>,
+}
+
+impl ResistanceProfile {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let name = words.next().ok_or("Profile has no name")?.to_string();
+        let mut armour = Health(0);
+        let mut multipliers = Vec::new();
+        let values: Vec<&str> = words.collect();
+        for pair in values.chunks(2) {
+            let value = pair.get(1).ok_or(format!("{} has no value", pair[0]))?;
+            if pair[0] == "armour" {
+                armour = Health(
+                    value
+                        .parse()
+                        .map_err(|_| format!("Bad armour: {}", value))?,
+                );
+            } else {
+                let damage_type = DamageType::from_name(pair[0])
+                    .ok_or(format!("Unknown damage type: {}", pair[0]))?;
+                let multiplier = value
+                    .parse()
+                    .map_err(|_| format!("Bad multiplier: {}", value))?;
+                multipliers.push((damage_type, multiplier));
+            }
+        }
+        Ok(ResistanceProfile {
+            name,
+            armour,
+            multipliers,
+        })
+    }
+    pub fn get_modifiers(&self, damage_type: DamageType) -> Vec<DamageModifier> {
+        let mut modifiers: Vec<DamageModifier> = self
+            .multipliers
+            .iter()
+            .filter(|(other_type, _)| *other_type == damage_type)
+            .map(|(_, multiplier)| DamageModifier::Scale(*multiplier))
+            .collect();
+        if damage_type == DamageType::Physical && self.armour.0 > 0 {
+            modifiers.push(DamageModifier::Absorb(self.armour));
+        }
+        modifiers
+    }
+}
+
+pub fn load_resistance_profiles() -> Vec<ResistanceProfile> {
+    RESISTANCE_PROFILES_FILE
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        .map(|line| match ResistanceProfile::parse(line) {
+            Ok(profile) => profile,
+            Err(error) => panic!("Failed to load resistance profile {}: {}", line, error),
+        })
+        .collect()
+}
+
+// Runs damage through the target's modifiers, returning how much the target took
+pub fn deal_damage(
+    game: &mut Game,
+    target_id: GameObjectId,
+    firer_id: GameObjectId,
+    amount: Health,
+    damage_type: DamageType,
+) -> Health {
+    let modifiers = target_id
+        .send_get_damage_modifiers_signal(game, damage_type, firer_id)
+        .map(|x| x.0)
+        .unwrap_or(Vec::new());
+    let mut scaled = amount.0 as f64;
+    for modifier in modifiers.iter() {
+        if let DamageModifier::Scale(multiplier) = modifier {
+            scaled *= multiplier;
+        }
+    }
+    let mut remaining = Health(scaled.round() as i32);
+    for modifier in modifiers.iter() {
+        if let DamageModifier::Absorb(absorbed) = modifier {
+            remaining = Health((remaining - *absorbed).0.max(0));
+        }
+    }
+    let mut redirected = Vec::new();
+    let before_redirection = remaining;
+    for modifier in modifiers.iter() {
+        if let DamageModifier::Redirect(redirect_to, fraction) = modifier {
+            let portion = Health((before_redirection.0 as f64 * fraction).round() as i32)
+                .0
+                .min(remaining.0);
+            remaining -= Health(portion);
+            redirected.push((*redirect_to, Health(portion)));
+        }
+    }
+    // Redirected damage skips the pipeline so redirects can't bounce back and forth
+    for (redirect_to, portion) in redirected {
+        if portion.0 > 0 {
+            redirect_to.send_damage_signal(game, portion);
+        }
+    }
+    if remaining.0 > 0 {
+        let was_alive = is_alive(game, target_id);
+        target_id.send_damage_signal(game, remaining);
+        ThreatSystem::add_threat(game, target_id, firer_id, remaining.0 as f64);
+        if was_alive && !is_alive(game, target_id) {
+            ProgressionSystem::grant_experience(game, firer_id, KILL_EXPERIENCE);
+        }
+    }
+    remaining
+}
+
+fn is_alive(game: &Game, id: GameObjectId) -> bool {
+    id.send_get_healthiness_signal(game)
+        .map(|healthiness| healthiness.0 > 0.0)
+        .unwrap_or(false)
+}
+
+#[derive(Clone)]
+pub struct ResistancesComponent {
+    component_id: ComponentId,
+    profile_index: usize,
+}
+
+impl ResistancesComponent {
+    pub fn get_profile_name<'a>(&self, game: &'a Game) -> &'a str {
+        &game.damage_system.resistance_profiles[self.profile_index].name
+    }
+    pub fn add_to(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        profile_name: &str,
+    ) -> Result<ComponentId, String> {
+        let profile_index = game
+            .damage_system
+            .resistance_profiles
+            .iter()
+            .position(|profile| profile.name == profile_name)
+            .ok_or(format!("Unknown resistance profile {}", profile_name))?;
+        let component_id = game.get_id();
+        let comp = ResistancesComponent {
+            component_id,
+            profile_index,
+        };
+        owner_id.add_get_damage_modifiers_signal_listener(game, comp.clone());
+        owner_id.add_component(game, comp);
+        Ok(component_id)
+    }
+}
+
+impl Component for ResistancesComponent {
+    fn get_component_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
+        owner_id.remove_get_damage_modifiers_signal_listener(game, self.component_id);
+    }
+}
+
+impl GetDamageModifiersSignalListener for ResistancesComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_get_damage_modifiers_signal(
+        &self,
+        game: &Game,
+        _owner_id: GameObjectId,
+        damage_type: DamageType,
+        _firer_id: GameObjectId,
+    ) -> CombinedVecs<DamageModifier> {
+        CombinedVecs(
+            game.damage_system.resistance_profiles[self.profile_index].get_modifiers(damage_type),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[derive(Clone)]
+    struct FixedModifiers {
+        component_id: ComponentId,
+        modifiers: Vec<DamageModifier>,
+    }
+
+    impl GetDamageModifiersSignalListener for FixedModifiers {
+        fn get_listener_id(&self) -> ComponentId {
+            self.component_id
+        }
+        fn receive_get_damage_modifiers_signal(
+            &self,
+            _game: &Game,
+            _owner_id: GameObjectId,
+            _damage_type: DamageType,
+            _firer_id: GameObjectId,
+        ) -> CombinedVecs<DamageModifier> {
+            CombinedVecs(self.modifiers.clone())
+        }
+    }
+
+    fn create_damageable(game: &mut Game) -> GameObjectId {
+        let id = GameObject::create_game(game, PixelCoords::new_at_zero());
+        DamageableComponent::add_to(game, id);
+        id
+    }
+
+    fn get_damage_taken(game: &Game, id: GameObjectId) -> i32 {
+        let damageable_id = id
+            .get_components_of_type::<Rc<DamageableComponent>>(game)
+            .first()
+            .unwrap()
+            .damageable_id;
+        let damageable = game.damage_system.damageables.get(damageable_id).unwrap();
+        damageable.max_health.0 - damageable.health.0
+    }
+
+    // Modifiers are given out of order, to show the order they're listed in doesn't matter
+    fn deal_modified_damage(
+        game: &mut Game,
+        amount: i32,
+        modifiers: Vec<DamageModifier>,
+    ) -> (GameObjectId, Health) {
+        let target_id = create_damageable(game);
+        let component_id = game.get_id();
+        target_id.add_get_damage_modifiers_signal_listener(
+            game,
+            FixedModifiers {
+                component_id,
+                modifiers,
+            },
+        );
+        let firer_id = GameObject::create_game(game, PixelCoords::new_at_zero());
+        let taken = deal_damage(game, target_id, firer_id, Health(amount), DamageType::Fire);
+        (target_id, taken)
+    }
+
+    #[test]
+    fn damage_is_scaled_then_absorbed_then_redirected() {
+        let mut game = Game::new();
+        let redirect_to = create_damageable(&mut game);
+        let (target_id, taken) = deal_modified_damage(
+            &mut game,
+            100,
+            vec![
+                DamageModifier::Redirect(redirect_to, 0.2),
+                DamageModifier::Absorb(Health(10)),
+                DamageModifier::Scale(0.5),
+                DamageModifier::Scale(0.8),
+            ],
+        );
+        // 100 * 0.5 * 0.8 = 40, less 10 absorbed is 30, a fifth of which goes elsewhere
+        assert_eq!(taken, Health(24));
+        assert_eq!(get_damage_taken(&game, target_id), 24);
+        assert_eq!(get_damage_taken(&game, redirect_to), 6);
+    }
+
+    #[test]
+    fn absorbed_damage_isnt_redirected() {
+        let mut game = Game::new();
+        let redirect_to = create_damageable(&mut game);
+        let (target_id, taken) = deal_modified_damage(
+            &mut game,
+            20,
+            vec![
+                DamageModifier::Redirect(redirect_to, 0.5),
+                DamageModifier::Absorb(Health(15)),
+                DamageModifier::Absorb(Health(15)),
+            ],
+        );
+        assert_eq!(taken, Health(0));
+        assert_eq!(get_damage_taken(&game, target_id), 0);
+        assert_eq!(get_damage_taken(&game, redirect_to), 0);
+    }
+
+    #[test]
+    fn no_more_than_all_the_damage_is_redirected() {
+        let mut game = Game::new();
+        let first = create_damageable(&mut game);
+        let second = create_damageable(&mut game);
+        let (target_id, taken) = deal_modified_damage(
+            &mut game,
+            40,
+            vec![
+                DamageModifier::Redirect(first, 0.75),
+                DamageModifier::Redirect(second, 0.75),
+            ],
+        );
+        assert_eq!(taken, Health(0));
+        assert_eq!(get_damage_taken(&game, target_id), 0);
+        assert_eq!(get_damage_taken(&game, first), 30);
+        assert_eq!(get_damage_taken(&game, second), 10);
+    }
+
+    #[test]
+    fn unknown_resistance_profiles_are_an_error() {
+        let mut game = Game::new();
+        let id = create_damageable(&mut game);
+        assert!(ResistancesComponent::add_to(&mut game, id, "rubber").is_err());
+        assert!(id
+            .get_components_of_type::<ResistancesComponent>(&game)
+            .is_empty());
+        assert!(ResistancesComponent::add_to(&mut game, id, "undead").is_ok());
+    }
+
+    #[test]
+    fn all_resistance_profiles_load() {
+        let profiles = load_resistance_profiles();
+        assert!(profiles.iter().any(|profile| profile.name == "undead"));
+    }
+
+    #[test]
+    fn armour_only_absorbs_physical_damage() {
+        let profile = ResistanceProfile::parse("test armour 100 holy 2").unwrap();
+        assert!(matches!(
+            profile.get_modifiers(DamageType::Physical)[..],
+            [DamageModifier::Absorb(Health(100))]
+        ));
+        assert!(matches!(
+            profile.get_modifiers(DamageType::Holy)[..],
+            [DamageModifier::Scale(_)]
+        ));
+    }
+}
@@ -3,6 +3,8 @@ use std::rc::Rc;
 use wolf_interface::CreateComponentData;
 
 define_signal_listener!(Damage, &mut Game, amount: Health);
+define_signal_listener!(Heal, &mut Game, amount: Health);
+define_signal_listener!(SetMaxHealth, &mut Game, max_health: Health);
 define_signal_listener!(
     DamageTaken,
     &mut Game,
@@ -62,6 +64,8 @@ impl DamageableComponent {
         owner_id.add_collision_group(game, CollisionGroupId::Damageable);
         owner_id.add_get_healthiness_signal_listener(game, comp.clone());
         owner_id.add_damage_signal_listener(game, comp.clone());
+        owner_id.add_heal_signal_listener(game, comp.clone());
+        owner_id.add_set_max_health_signal_listener(game, comp.clone());
         owner_id.add_component(game, comp);
     }
 }
@@ -74,6 +78,8 @@ impl Component for Rc<DamageableComponent> {
         owner_id.remove_collision_group(game, CollisionGroupId::Damageable);
         owner_id.remove_get_healthiness_signal_listener(game, self.component_id);
         owner_id.remove_damage_signal_listener(game, self.component_id);
+        owner_id.remove_heal_signal_listener(game, self.component_id);
+        owner_id.remove_set_max_health_signal_listener(game, self.component_id);
         Damageable::remove(game, self.damageable_id);
     }
 }
@@ -81,20 +87,28 @@ impl DamageSignalListener for Rc<DamageableComponent> {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn DamageSignalListener> {
-        Box::new(Rc::clone(self))
-    }
     fn receive_damage_signal(&self, game: &mut Game, owner_id: GameObjectId, amount: Health) {
-        let (health, max_health) = {
+        let health = {
             let damageable = game
                 .damage_system
                 .damageables
                 .get_mut(self.damageable_id)
                 .unwrap();
             damageable.health -= amount;
-            (damageable.health.clone(), damageable.max_health.clone())
+            damageable.health.clone()
         };
-        let percent = health.0 as f64 / max_health.0 as f64;
+        self.refresh_health_proportion(game, owner_id);
+        owner_id.send_damage_taken_signal(game, amount, health);
+    }
+}
+impl DamageableComponent {
+    fn refresh_health_proportion(&self, game: &mut Game, owner_id: GameObjectId) {
+        let damageable = game
+            .damage_system
+            .damageables
+            .get(self.damageable_id)
+            .unwrap();
+        let percent = damageable.health.0 as f64 / damageable.max_health.0 as f64;
         let client_side_component_data =
             CreateComponentData::HealthProportionTenThousandths((percent * 10000.0) as u32);
         owner_id.refresh_client_side_component(
@@ -102,13 +116,52 @@ impl DamageSignalListener for Rc<DamageableComponent> {
             self.client_side_component_id,
             client_side_component_data,
         );
-        owner_id.send_damage_taken_signal(game, amount, health);
     }
 }
-impl GetHealthinessSignalListener for Rc<DamageableComponent> {
-    fn clone_box(&self) -> Box<dyn GetHealthinessSignalListener> {
-        Box::new(Rc::clone(self))
+impl HealSignalListener for Rc<DamageableComponent> {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_heal_signal(&self, game: &mut Game, owner_id: GameObjectId, amount: Health) {
+        {
+            let damageable = game
+                .damage_system
+                .damageables
+                .get_mut(self.damageable_id)
+                .unwrap();
+            if damageable.health.0 >= damageable.max_health.0 {
+                return;
+            }
+            damageable.health = Health((damageable.health + amount).0.min(damageable.max_health.0));
+        }
+        self.refresh_health_proportion(game, owner_id);
     }
+}
+impl SetMaxHealthSignalListener for Rc<DamageableComponent> {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    // Raising max health gives the extra health too, lowering it only caps what's left
+    fn receive_set_max_health_signal(
+        &self,
+        game: &mut Game,
+        owner_id: GameObjectId,
+        max_health: Health,
+    ) {
+        {
+            let damageable = game
+                .damage_system
+                .damageables
+                .get_mut(self.damageable_id)
+                .unwrap();
+            let gained = Health((max_health - damageable.max_health).0.max(0));
+            damageable.max_health = max_health;
+            damageable.health = Health((damageable.health + gained).0.min(max_health.0));
+        }
+        self.refresh_health_proportion(game, owner_id);
+    }
+}
+impl GetHealthinessSignalListener for Rc<DamageableComponent> {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
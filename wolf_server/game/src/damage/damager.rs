@@ -2,20 +2,22 @@ use wolf_hash_map::WolfHashMap;
 
 use super::*;
 use crate::collisions::CollisionSystem;
-use crate::combinable::OrBool;
+use crate::perception::{PerceptionSystem, DAMAGE_NOISE_RANGE};
+use signal_listeners::{OrBool, Stoppable};
 
 define_signal_listener!(DamagerFizzledOut, &mut Game, damager_id: DamagerId);
 define_signal_listener!(DealtDamage, &mut Game, target_id: GameObjectId);
 /*
 Returns whether damage is BLOCKED.
 */
-define_signal_listener!(AboutToBeDamaged, &mut Game, damager_id: GameObjectId, firer_id: GameObjectId -> OrBool);
+define_signal_listener!(AboutToBeDamaged, &mut Game, damager_id: GameObjectId, firer_id: GameObjectId -> Stoppable<OrBool>);
 
 pub struct Damager {
     pub game_object_id: GameObjectId,
     pub firer_id: GameObjectId,
     pub hits_remaining: Option<u32>,
     pub damage: Health,
+    pub damage_type: DamageType,
     pub last_hurt_timings: WolfHashMap<GameObjectId, u32>,
 }
 
@@ -27,6 +29,7 @@ impl Damager {
         firer_id: GameObjectId,
         hits_remaining: Option<u32>,
         damage: Health,
+        damage_type: DamageType,
     ) -> DamagerId {
         let id = game.get_id();
         let damager = Damager {
@@ -34,6 +37,7 @@ impl Damager {
             firer_id,
             hits_remaining,
             damage,
+            damage_type,
             last_hurt_timings: WolfHashMap::new(),
         };
         game.damage_system.damagers.insert(id, damager);
@@ -62,13 +66,24 @@ impl Damager {
                 }
                 let mut hit_something = false;
                 let mut hits_remaining = damager.hits_remaining.clone();
-                let colliding = collision_map
-                    .get_colliding_game(game, damager.game_object_id.get_hit_box(game));
+                // Check the whole path of anything moving this tick, so fast damagers can't skip past targets
+                let hit_box = damager.game_object_id.get_hit_box(game);
+                let (dx, dy) = match game.movement_system.to_move.get(&damager.game_object_id) {
+                    Some(target_coords) => {
+                        let coords = damager.game_object_id.get_coords_game(game);
+                        (
+                            (target_coords.get_x() - coords.get_x()).0.to_num::<f64>(),
+                            (target_coords.get_y() - coords.get_y()).0.to_num::<f64>(),
+                        )
+                    }
+                    None => (0.0, 0.0),
+                };
+                let colliding = collision_map.get_swept_colliding_game(game, hit_box, dx, dy);
                 if colliding.is_empty() {
                     continue;
                 }
                 let allegiances = damager.firer_id.get_allegiances(game);
-                for hit in colliding {
+                for (_contact, hit) in colliding {
                     let damageable_allegiances = hit.get_allegiances(game);
                     if !game
                         .allegiance_system
@@ -96,18 +111,29 @@ impl Damager {
             }
         }
         for (damager_id, hit_id) in to_hit {
-            let (damager_object_id, firer_id, damage) = {
+            let (damager_object_id, firer_id, damage, damage_type) = {
                 let damager = game.damage_system.damagers.get_mut(damager_id).unwrap();
                 damager.last_hurt_timings.insert(hit_id, game.tick_counter);
-                (damager.game_object_id, damager.firer_id, damager.damage)
+                (
+                    damager.game_object_id,
+                    damager.firer_id,
+                    damager.damage,
+                    damager.damage_type,
+                )
             };
             let blocked = hit_id
                 .send_about_to_be_damaged_signal(game, damager_object_id, firer_id)
-                .map(OrBool::extract)
+                .map(|result| result.extract().extract())
                 .unwrap_or(false);
             if !blocked {
-                hit_id.send_damage_signal(game, damage);
+                deal_damage(game, hit_id, firer_id, damage, damage_type);
                 damager_object_id.send_dealt_damage_signal(game, hit_id);
+                game.event_bus.publish(GameEvent::DealtDamage {
+                    damager_id: damager_object_id,
+                    firer_id,
+                    target_id: hit_id,
+                });
+                PerceptionSystem::make_noise(game, firer_id, DAMAGE_NOISE_RANGE);
             }
         }
         for (id, hits_remaining) in new_hits_remaining {
@@ -144,9 +170,17 @@ impl DamagerComponent {
         firer_id: GameObjectId,
         hits_remaining: Option<u32>,
         damage: i32,
+        damage_type: DamageType,
     ) -> Self {
         let component_id = game.get_id();
-        let damager_id = Damager::new(game, owner_id, firer_id, hits_remaining, Health(damage));
+        let damager_id = Damager::new(
+            game,
+            owner_id,
+            firer_id,
+            hits_remaining,
+            Health(damage),
+            damage_type,
+        );
         let comp = DamagerComponent {
             component_id,
             damager_id,
@@ -163,3 +197,48 @@ impl Component for DamagerComponent {
         game.damage_system.damagers.remove(self.damager_id);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn create_damageable(game: &mut Game, x: f64) -> GameObjectId {
+        let id = GameObject::create_game(game, PixelCoords::new_at_zero().translate(x, 0.0));
+        DamageableComponent::add_to(game, id);
+        id
+    }
+
+    fn get_damage_taken(game: &Game, id: GameObjectId) -> i32 {
+        let damageable_id = id
+            .get_components_of_type::<Rc<DamageableComponent>>(game)
+            .first()
+            .unwrap()
+            .damageable_id;
+        let damageable = game.damage_system.damageables.get(damageable_id).unwrap();
+        damageable.max_health.0 - damageable.health.0
+    }
+
+    #[test]
+    fn fast_damagers_hit_the_nearest_target_on_their_path_first() {
+        let mut game = Game::new();
+        // Made first, so it'd come first if targets weren't ordered by distance
+        let far = create_damageable(&mut game, 200.0);
+        let near = create_damageable(&mut game, 100.0);
+        let firer_id = GameObject::create_game(&mut game, PixelCoords::new_at_zero());
+        let damager_object_id = GameObject::create_game(&mut game, PixelCoords::new_at_zero());
+        DamagerComponent::add_to(
+            &mut game,
+            damager_object_id,
+            firer_id,
+            Some(1),
+            100,
+            DamageType::Physical,
+        );
+        // Far enough in one tick to pass straight through both
+        damager_object_id.move_by_game(&mut game, 300.0, 0.0);
+        Damager::step(&mut game);
+        assert_eq!(get_damage_taken(&game, near), 100);
+        assert_eq!(get_damage_taken(&game, far), 0);
+    }
+}
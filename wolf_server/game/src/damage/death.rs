@@ -1,17 +1,20 @@
 use super::*;
-use signal_listener_macro::define_signal_listener;
+use crate::define_signal_listener;
 
 define_signal_listener!(Death, &mut Game);
 
+// Everything that dies goes through here, so the Death event always goes out with the signal
+pub fn kill(game: &mut Game, id: GameObjectId) {
+    id.send_death_signal(game);
+    game.event_bus.publish(GameEvent::Death { id });
+}
+
 #[derive(Clone)]
 pub struct DieOnNoHealthComponent {
     component_id: ComponentId,
 }
 
 impl DamageTakenSignalListener for DieOnNoHealthComponent {
-    fn clone_box(&self) -> Box<(dyn damageable::DamageTakenSignalListener + 'static)> {
-        Box::new(self.clone())
-    }
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
@@ -23,7 +26,7 @@ impl DamageTakenSignalListener for DieOnNoHealthComponent {
         health_remaining: Health,
     ) {
         if health_remaining.0 <= 0 {
-            owner.send_death_signal(game);
+            kill(game, owner);
         }
     }
 }
@@ -56,9 +59,6 @@ impl DeathSignalListener for DeleteOnDeathComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn DeathSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_death_signal(&self, game: &mut Game, owner_id: GameObjectId) {
         owner_id.remove(game);
     }
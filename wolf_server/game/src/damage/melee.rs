@@ -7,6 +7,7 @@ pub fn melee_attack(
     attacker_id: GameObjectId,
     sprite: u32,
     damage: i32,
+    damage_type: DamageType,
     hits: Option<u32>,
     starting_coords: PixelCoords,
     rotation: Angle,
@@ -16,7 +17,7 @@ pub fn melee_attack(
     let spawn_point = starting_coords.offset_direction(rotation, range);
     let game_object_id = GameObject::create_game(game, spawn_point);
     game_object_id.set_rotation(game, rotation);
-    DamagerComponent::add_to(game, game_object_id, attacker_id, hits, damage);
+    DamagerComponent::add_to(game, game_object_id, attacker_id, hits, damage, damage_type);
     BasicDrawingComponent::add_to(game, game_object_id, sprite, PROJECTILE_DEPTH);
     DeleteAtComponent::add_to(game, game_object_id, game.tick_counter + lifetime);
     game_object_id
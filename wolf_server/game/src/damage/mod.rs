@@ -1,5 +1,6 @@
+use crate::define_signal_listener;
 use crate::game::*;
-use signal_listener_macro::define_signal_listener;
+use signal_listeners::Combinable;
 
 mod damageable;
 pub use damageable::*;
@@ -11,6 +12,8 @@ mod melee;
 pub use melee::*;
 mod death;
 pub use death::*;
+mod damage_types;
+pub use damage_types::*;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Health(pub i32);
@@ -20,7 +23,7 @@ pub const MEDIUM_DAMAGE: i32 = 3000;
 pub const LARGE_DAMAGE: i32 = 8000;
 
 pub struct MinimumHealthPercent(pub f64);
-impl MinimumHealthPercent {
+impl Combinable for MinimumHealthPercent {
     fn combine_result(self, other: Self) -> Self {
         MinimumHealthPercent(self.0.min(other.0))
     }
@@ -32,6 +35,7 @@ pub struct DamageSystem {
     pub damagers: IdMap<DamagerId, Damager>,
 
     pub projectiles: IdMap<ProjectileId, Projectile>,
+    pub resistance_profiles: Vec<ResistanceProfile>,
 }
 impl DamageSystem {
     pub fn new() -> DamageSystem {
@@ -40,6 +44,7 @@ impl DamageSystem {
             damagers: IdMap::new(),
 
             projectiles: IdMap::new(),
+            resistance_profiles: load_resistance_profiles(),
         }
     }
     pub fn step(game: &mut Game) {
@@ -1,4 +1,5 @@
 use super::*;
+use crate::collisions::CollisionSystem;
 use crate::drawable::BasicDrawingComponent;
 use crate::generic::DeleteAtComponent;
 
@@ -9,6 +10,37 @@ pub struct Projectile {
     game_object_id: GameObjectId,
     direction: Angle,
     speed: f64,
+    hit_wall: bool,
+}
+
+// How far through the move the box first touches solid terrain or a solid object
+fn get_wall_contact(game: &Game, hit_box: &HitBox, dx: f64, dy: f64) -> Option<f64> {
+    let square_half_size = SQUARE_SIZE_PIXELS / 2;
+    let terrain_contact = hit_box
+        .get_swept_bounds(dx, dy)
+        .get_overlapping_squares()
+        .into_iter()
+        .filter(|square| game.terrain.is_square_solid(*square))
+        .filter_map(|square| {
+            let square_box = HitBox::new(square.center_pixel(), square_half_size, square_half_size);
+            hit_box.get_sweep_contact(dx, dy, &square_box)
+        })
+        .fold(None, |nearest: Option<f64>, contact| {
+            Some(nearest.map_or(contact, |nearest| nearest.min(contact)))
+        });
+    let object_contact = CollisionSystem::get_collision_group(game, CollisionGroupId::Solid)
+        .and_then(|collision_group| {
+            collision_group
+                .collision_map
+                .borrow()
+                .get_swept_colliding_game(game, hit_box.clone(), dx, dy)
+                .first()
+                .map(|(contact, _solid)| *contact)
+        });
+    match (terrain_contact, object_contact) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (contact, None) | (None, contact) => contact,
+    }
 }
 
 impl Projectile {
@@ -23,21 +55,40 @@ impl Projectile {
             game_object_id,
             direction,
             speed,
+            hit_wall: false,
         };
         game.damage_system.projectiles.insert(id, projectile);
         id
     }
+    /*
+    Projectiles stop where they first touch a wall, so the damager only sweeps the path up to it.
+    They're removed the tick after, once that path has been checked for hits.
+    */
     pub fn step(game: &mut Game) {
         let mut to_move = Vec::new();
-        for (_id, projectile) in game.damage_system.projectiles.iter() {
-            to_move.push(projectile.clone());
+        let mut to_remove = Vec::new();
+        for (id, projectile) in game.damage_system.projectiles.iter() {
+            if projectile.hit_wall {
+                to_remove.push(projectile.game_object_id);
+            } else {
+                to_move.push((id, projectile.clone()));
+            }
+        }
+        for game_object_id in to_remove {
+            game_object_id.remove(game);
         }
-        for projectile in to_move {
-            projectile.game_object_id.move_direction_game(
-                game,
-                projectile.direction,
-                projectile.speed,
-            );
+        for (id, projectile) in to_move {
+            let dx = projectile.direction.cos() * projectile.speed;
+            let dy = projectile.direction.sin() * projectile.speed;
+            let hit_box = projectile.game_object_id.get_hit_box(game);
+            let contact = get_wall_contact(game, &hit_box, dx, dy);
+            if contact.is_some() {
+                game.damage_system.projectiles.get_mut(id).unwrap().hit_wall = true;
+            }
+            let travelled = contact.unwrap_or(1.0);
+            projectile
+                .game_object_id
+                .move_by_game(game, dx * travelled, dy * travelled);
         }
     }
 }
@@ -54,6 +105,7 @@ impl ProjectileComponent {
         firer_id: GameObjectId,
         sprite: u32,
         damage: i32,
+        damage_type: DamageType,
         hits: Option<u32>,
         starting_coords: PixelCoords,
         direction: Angle,
@@ -62,7 +114,7 @@ impl ProjectileComponent {
     ) -> GameObjectId {
         let game_object_id = GameObject::create_game(game, starting_coords);
         let DamagerComponent { damager_id, .. } =
-            DamagerComponent::add_to(game, game_object_id, firer_id, hits, damage);
+            DamagerComponent::add_to(game, game_object_id, firer_id, hits, damage, damage_type);
         BasicDrawingComponent::add_to(game, game_object_id, sprite, PROJECTILE_DEPTH);
         ProjectileComponent::add_to(game, game_object_id, damager_id, direction, speed);
         DeleteAtComponent::add_to(game, game_object_id, game.tick_counter + lifetime);
@@ -98,9 +150,6 @@ impl DamagerFizzledOutSignalListener for ProjectileComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.id
     }
-    fn clone_box(&self) -> Box<dyn DamagerFizzledOutSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_damager_fizzled_out_signal(
         &self,
         game: &mut Game,
@@ -112,3 +161,57 @@ impl DamagerFizzledOutSignalListener for ProjectileComponent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::Chunk;
+
+    fn create_game() -> Game {
+        let mut game = Game::new();
+        for cx in -1..2 {
+            for cy in -1..2 {
+                let square = SquareCoords::new(
+                    Plane(0),
+                    cx * TERRAIN_CHUNK_SIZE_SQUARES,
+                    cy * TERRAIN_CHUNK_SIZE_SQUARES,
+                );
+                Chunk::generate(&mut game, square.into(), DIRT_SPRITE);
+            }
+        }
+        game
+    }
+
+    #[test]
+    fn projectiles_stop_at_the_first_solid_square() {
+        let mut game = create_game();
+        let wall = SquareCoords::new(Plane(0), 5, 0);
+        game.terrain.modify_chunk_square(wall, |chunk_square| {
+            chunk_square.base_solid = true;
+        });
+        let firer_id = GameObject::create_game(&mut game, PixelCoords::new_at_zero());
+        let start = SquareCoords::new(Plane(0), 0, 0).center_pixel();
+        let projectile_id = ProjectileComponent::fire_basic_projectile(
+            &mut game,
+            firer_id,
+            0,
+            1,
+            DamageType::Physical,
+            Some(1),
+            start,
+            Angle::assume_in_range(0.0),
+            30.0,
+            100,
+        );
+        for _ in 0..10 {
+            Projectile::step(&mut game);
+            MovementSystem::movement(&mut game);
+        }
+        // Flush against the wall, rather than short of it or inside it
+        let hit_box = projectile_id.get_hit_box(&game);
+        let right_edge = (hit_box.coords.get_x() + hit_box.width).0.to_num::<f64>();
+        let wall_edge = (wall.get_x() * SQUARE_SIZE_PIXELS) as f64;
+        assert!(right_edge <= wall_edge);
+        assert!(right_edge > wall_edge - 1.0);
+    }
+}
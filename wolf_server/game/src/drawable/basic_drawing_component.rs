@@ -1,8 +1,8 @@
 use std::rc::Rc;
 
 use crate::basic_client_side_component::BasicClientSideComponent;
+use crate::define_signal_listener;
 use crate::game::*;
-use signal_listener_macro::define_signal_listener;
 use wolf_hash_map::WolfHashMap;
 use wolf_interface::*;
 
@@ -66,9 +66,6 @@ impl SetFacingSignalListener for Rc<FacingSpriteComponent> {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn SetFacingSignalListener> {
-        Box::new(Rc::clone(self))
-    }
 }
 
 impl Component for Rc<FacingSpriteComponent> {
@@ -1,4 +1,4 @@
-use crate::combinable::CombinedVecs;
+use signal_listeners::CombinedVecs;
 use crate::game::*;
 use wolf_interface::*;
 
@@ -8,9 +8,6 @@ pub struct HealthBarComponent {
 }
 
 impl GetClientSideComponentsSignalListener for HealthBarComponent {
-    fn clone_box(&self) -> Box<dyn GetClientSideComponentsSignalListener> {
-        Box::new(self.clone())
-    }
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
@@ -25,9 +22,6 @@ impl GetClientSideComponentsSignalListener for HealthBarComponent {
 }
 
 impl GetClientSideComponentCreateMessageSignalListener for HealthBarComponent {
-    fn clone_box(&self) -> Box<dyn GetClientSideComponentCreateMessageSignalListener> {
-        Box::new(self.clone())
-    }
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
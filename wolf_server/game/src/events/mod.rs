@@ -0,0 +1,222 @@
+use crate::game::*;
+use wolf_hash_map::WolfHashMap;
+
+/*
+Signals go to the components on one game object, straight away. Events go to whole systems
+that want to hear about everything of a kind (every death, every chunk loaded...) without a
+component on each object. They're queued as they happen and handed out once a step, after
+objects have been deleted, so handlers see the world in a settled state. Anything published
+while handing them out is delivered the next step.
+*/
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameEvent {
+    Death {
+        id: GameObjectId,
+    },
+    DealtDamage {
+        damager_id: GameObjectId,
+        firer_id: GameObjectId,
+        target_id: GameObjectId,
+    },
+    SpellbookAbsorbed {
+        spellbook_id: GameObjectId,
+        absorber_id: GameObjectId,
+    },
+    Deleted {
+        id: GameObjectId,
+    },
+    Moved {
+        id: GameObjectId,
+        old_coords: PixelCoords,
+    },
+    ChunkLoaded {
+        coords: TerrainChunkCoords,
+    },
+    PlayerJoined {
+        player_id: PlayerId,
+        game_object_id: GameObjectId,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameEventKind {
+    Death,
+    DealtDamage,
+    SpellbookAbsorbed,
+    Deleted,
+    Moved,
+    ChunkLoaded,
+    PlayerJoined,
+}
+
+impl GameEvent {
+    pub fn get_kind(&self) -> GameEventKind {
+        match self {
+            GameEvent::Death { .. } => GameEventKind::Death,
+            GameEvent::DealtDamage { .. } => GameEventKind::DealtDamage,
+            GameEvent::SpellbookAbsorbed { .. } => GameEventKind::SpellbookAbsorbed,
+            GameEvent::Deleted { .. } => GameEventKind::Deleted,
+            GameEvent::Moved { .. } => GameEventKind::Moved,
+            GameEvent::ChunkLoaded { .. } => GameEventKind::ChunkLoaded,
+            GameEvent::PlayerJoined { .. } => GameEventKind::PlayerJoined,
+        }
+    }
+    // The game object the event happened to, if any
+    pub fn get_subject(&self) -> Option<GameObjectId> {
+        match self {
+            GameEvent::Death { id } => Some(*id),
+            GameEvent::DealtDamage { target_id, .. } => Some(*target_id),
+            GameEvent::SpellbookAbsorbed { absorber_id, .. } => Some(*absorber_id),
+            GameEvent::Deleted { id } => Some(*id),
+            GameEvent::Moved { id, .. } => Some(*id),
+            GameEvent::ChunkLoaded { .. } => None,
+            GameEvent::PlayerJoined { game_object_id, .. } => Some(*game_object_id),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EventFilter {
+    All,
+    Subject(GameObjectId),
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &GameEvent) -> bool {
+        match self {
+            EventFilter::All => true,
+            EventFilter::Subject(id) => event.get_subject() == Some(*id),
+        }
+    }
+}
+
+pub type EventHandler = fn(&mut Game, &GameEvent);
+
+struct Subscription {
+    kind: GameEventKind,
+    filter: EventFilter,
+    handler: EventHandler,
+}
+
+pub struct EventBus {
+    // Kept in a Vec so handlers run in the order they subscribed
+    subscriptions: Vec<(SubscriptionId, Subscription)>,
+    subscriber_counts: WolfHashMap<GameEventKind, u32>,
+    pending: Vec<GameEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        EventBus {
+            subscriptions: Vec::new(),
+            subscriber_counts: WolfHashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+    pub fn subscribe(
+        game: &mut Game,
+        kind: GameEventKind,
+        filter: EventFilter,
+        handler: EventHandler,
+    ) -> SubscriptionId {
+        let id = game.get_id();
+        game.event_bus.add_subscription(id, kind, filter, handler);
+        id
+    }
+    fn add_subscription(
+        &mut self,
+        id: SubscriptionId,
+        kind: GameEventKind,
+        filter: EventFilter,
+        handler: EventHandler,
+    ) {
+        *self.subscriber_counts.entry(kind).or_insert(0) += 1;
+        self.subscriptions.push((
+            id,
+            Subscription {
+                kind,
+                filter,
+                handler,
+            },
+        ));
+    }
+    pub fn unsubscribe(&mut self, id: SubscriptionId) {
+        let position = match self
+            .subscriptions
+            .iter()
+            .position(|(other_id, _subscription)| *other_id == id)
+        {
+            Some(position) => position,
+            None => return,
+        };
+        let (_id, subscription) = self.subscriptions.remove(position);
+        if let Some(count) = self.subscriber_counts.get_mut(&subscription.kind) {
+            *count -= 1;
+        }
+    }
+    pub fn has_subscribers(&self, kind: GameEventKind) -> bool {
+        self.subscriber_counts
+            .get(&kind)
+            .map(|count| *count > 0)
+            .unwrap_or(false)
+    }
+    // Events nobody is listening for are dropped here, so publishing every move stays cheap
+    pub fn publish(&mut self, event: GameEvent) {
+        if self.has_subscribers(event.get_kind()) {
+            self.pending.push(event);
+        }
+    }
+    fn get_handlers(&self, event: &GameEvent) -> Vec<EventHandler> {
+        let kind = event.get_kind();
+        self.subscriptions
+            .iter()
+            .filter(|(_id, subscription)| {
+                subscription.kind == kind && subscription.filter.matches(event)
+            })
+            .map(|(_id, subscription)| subscription.handler)
+            .collect()
+    }
+    pub fn dispatch(game: &mut Game) {
+        if game.event_bus.pending.is_empty() {
+            return;
+        }
+        let events = std::mem::replace(&mut game.event_bus.pending, Vec::new());
+        for event in events {
+            let handlers = game.event_bus.get_handlers(&event);
+            for handler in handlers {
+                handler(game, &event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn do_nothing(_game: &mut Game, _event: &GameEvent) {}
+
+    #[test]
+    fn only_subscribed_events_are_queued_and_filtered() {
+        let mut event_bus = EventBus::new();
+        let watched = GameObjectId(1);
+        let death = |id| GameEvent::Death { id };
+        event_bus.publish(death(watched));
+        assert!(event_bus.pending.is_empty());
+        event_bus.add_subscription(
+            SubscriptionId(0),
+            GameEventKind::Death,
+            EventFilter::Subject(watched),
+            do_nothing,
+        );
+        event_bus.publish(death(watched));
+        event_bus.publish(death(GameObjectId(2)));
+        event_bus.publish(GameEvent::Deleted { id: watched });
+        assert_eq!(event_bus.pending.len(), 2);
+        assert_eq!(event_bus.get_handlers(&death(watched)).len(), 1);
+        assert!(event_bus.get_handlers(&death(GameObjectId(2))).is_empty());
+        event_bus.unsubscribe(SubscriptionId(0));
+        assert!(!event_bus.has_subscribers(GameEventKind::Death));
+    }
+}
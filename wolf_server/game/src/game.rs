@@ -1,31 +1,47 @@
 pub use crate::abilities::AbilitySystem;
+pub use crate::admin::{get_admin_command_usages, run_admin_command};
 use crate::ai::AiSystem;
 use crate::allegiance::AllegianceSystem;
 pub use crate::ants::AntSystem;
 pub use crate::behaviour::BehaviourSystem;
 use crate::biomes::BiomeSystem;
+pub use crate::chat::ChatSystem;
 pub use crate::collisions::{CollisionGroupId, CollisionSystem};
 pub use crate::component::*;
+use crate::component_registry::get_component_registry;
+pub use crate::component_registry::ComponentRegistry;
 pub use crate::damage::*;
 pub use crate::drawable::*;
+pub use crate::events::{EventBus, EventFilter, GameEvent, GameEventKind};
 pub use crate::game_object::*;
 use crate::generic::GenericSystem;
 use crate::hunting::HuntingSystem;
 pub use crate::id_types::*;
+use crate::inventory::InventorySystem;
 use crate::loading::LoadingSystem;
+use crate::loot::LootSystem;
+pub use crate::metrics::MetricsSystem;
 use crate::monsters::Monsters;
 pub use crate::movement::MovementSystem;
 pub use crate::movement::*;
 pub use crate::necromancy::NecromancySystem;
+use crate::perception::PerceptionSystem;
 pub use crate::player::*;
+use crate::prefabs::PrefabSystem;
+use crate::progression::ProgressionSystem;
 use crate::quest::{spawn_quest_guide, QuestSystem};
 use crate::resources::ResourceSystem;
+pub use crate::scheduler::{Constraint, Phase, Scheduler, SystemLabel};
+pub use crate::scripting::ScriptSystem;
 use crate::solids::SolidSystem;
 pub use crate::spatial_map::*;
 use crate::speech::Speech;
-use crate::terrain::Terrain;
+use crate::statuses::StatusSystem;
+use crate::terrain::{step_chunk_loaders, BasicChunkLoader, ChunkWatcher, Terrain, TerrainSprite};
+use crate::threat::ThreatSystem;
 use crate::timers::TimerSystem;
 pub use crate::utilities::*;
+use crate::villages::GuardMind;
 pub use crate::villages::VillagesSystem;
 pub use crate::wildlife::WildlifeSystem;
 
@@ -34,43 +50,6 @@ pub use id::*;
 pub use rand::Rng;
 pub use sprite_mappings::*;
 pub use std::collections::*;
-#[cfg(feature = "timing")]
-pub use std::time::{Duration, Instant};
-
-#[cfg(feature = "timing")]
-pub const TIME_EVERY: u32 = 100;
-
-#[cfg(feature = "timing")]
-use once_cell::sync::Lazy;
-
-#[cfg(feature = "timing")]
-pub static TIMINGS: Lazy<std::sync::Mutex<wolf_hash_map::WolfHashMap<String, Duration>>> =
-    Lazy::new(|| std::sync::Mutex::new(wolf_hash_map::WolfHashMap::new()));
-
-#[macro_export]
-macro_rules! time_system {
-    ($e: expr) => {{
-        #[cfg(feature = "timing")]
-        let start_time = Instant::now();
-        let return_value = $e;
-        #[cfg(feature = "timing")]
-        let passed = Instant::now() - start_time;
-        #[cfg(feature = "timing")]
-        let old_time = TIMINGS
-            .lock()
-            .unwrap()
-            .remove(stringify!($e))
-            .unwrap_or(Duration::from_secs(0));
-        #[cfg(feature = "timing")]
-        let time_taken = old_time + passed;
-        #[cfg(feature = "timing")]
-        TIMINGS
-            .lock()
-            .unwrap()
-            .insert(stringify!($e).to_string(), time_taken);
-        return_value
-    }};
-}
 
 pub struct Game {
     id_counter: u32,
@@ -93,34 +72,60 @@ pub struct Game {
 
     pub biome_system: BiomeSystem,
 
+    pub chat_system: ChatSystem,
+
     pub collision_system: CollisionSystem,
 
+    pub component_registry: ComponentRegistry,
+
     pub damage_system: DamageSystem,
 
+    pub event_bus: EventBus,
+
     pub generic_system: GenericSystem,
 
     pub hunting_system: HuntingSystem,
 
+    pub inventory_system: InventorySystem,
+
     pub loading_system: LoadingSystem,
 
+    pub loot_system: LootSystem,
+
+    pub metrics_system: MetricsSystem,
+
     pub monsters: Monsters,
 
     pub movement_system: MovementSystem,
 
     pub necromancy_system: NecromancySystem,
 
+    pub perception_system: PerceptionSystem,
+
     pub player_system: PlayerSystem,
 
+    pub prefab_system: PrefabSystem,
+
+    pub progression_system: ProgressionSystem,
+
     pub quest_system: QuestSystem,
 
     pub resource_system: ResourceSystem,
 
+    pub scheduler: Scheduler,
+
+    pub script_system: ScriptSystem,
+
     pub solid_system: SolidSystem,
 
+    pub status_system: StatusSystem,
+
     pub speeches: IdMap<GameObjectId, Speech>,
 
     pub terrain: Terrain,
 
+    pub threat_system: ThreatSystem,
+
     pub timer_system: TimerSystem,
 
     pub villages_system: VillagesSystem,
@@ -138,6 +143,8 @@ impl Game {
         Plane(self.plane_counter)
     }
     pub fn new() -> Self {
+        let component_registry = get_component_registry();
+        let prefab_system = PrefabSystem::new(&component_registry);
         Game {
             id_counter: 0,
             tick_counter: 0,
@@ -159,14 +166,26 @@ impl Game {
 
             biome_system: BiomeSystem::new(),
 
+            chat_system: ChatSystem::new(),
+
             damage_system: DamageSystem::new(),
 
+            event_bus: EventBus::new(),
+
             collision_system: CollisionSystem::new(),
 
+            component_registry,
+
             hunting_system: HuntingSystem::new(),
 
+            inventory_system: InventorySystem::new(),
+
             loading_system: LoadingSystem::new(),
 
+            loot_system: LootSystem::new(),
+
+            metrics_system: MetricsSystem::new(),
+
             behaviour_system: BehaviourSystem::new(),
 
             monsters: Monsters::new(),
@@ -175,18 +194,32 @@ impl Game {
 
             necromancy_system: NecromancySystem::new(),
 
+            perception_system: PerceptionSystem::new(),
+
             player_system: PlayerSystem::new(),
 
+            prefab_system,
+
+            progression_system: ProgressionSystem::new(),
+
             quest_system: QuestSystem::new(),
 
             resource_system: ResourceSystem::new(),
 
+            scheduler: get_scheduler(),
+
+            script_system: ScriptSystem::new(),
+
             speeches: IdMap::new(),
 
             solid_system: SolidSystem::new(),
 
+            status_system: StatusSystem::new(),
+
             terrain: Terrain::new(),
 
+            threat_system: ThreatSystem::new(),
+
             timer_system: TimerSystem::new(),
 
             villages_system: VillagesSystem::new(),
@@ -195,121 +228,29 @@ impl Game {
         }
     }
     pub fn initialise(&mut self) {
+        PrefabSystem::load_prefabs_directory(self);
+        GuardMind::subscribe_to_events(self);
         spawn_quest_guide(self);
     }
 
-    //naming convention: step for normal things
-    //pre-movement for things that need to happen after step and just before the movement phase
-    //post-movement for things that need to happen just after the movement phase
-    //end-step for things that must happen last
     pub fn step(&mut self) {
-        #[cfg(feature = "timing")]
-        {
-            if self.tick_counter % TIME_EVERY == 0 {
-                println!("------------------------------------------------------");
-            };
-        }
-        #[cfg(feature = "timing")]
-        let tick_start = Instant::now();
-
-        time_system!(BehaviourSystem::step(self));
-
-        time_system!(TimerSystem::step(self));
-
-        time_system!(GenericSystem::step(self));
-
-        time_system!(AiSystem::step(self));
-
-        time_system!(AntSystem::step(self));
-
-        time_system!(Projectile::step(self));
-
-        time_system!(DamageSystem::step(self));
-
-        time_system!(Monsters::step(self));
-
-        time_system!(WildlifeSystem::step(self));
-
-        time_system!(Terrain::step(self));
-
-        time_system!(ResourceSystem::step(self));
-
-        time_system!(AbilitySystem::step(self));
-
-        time_system!(NecromancySystem::step(self));
-
-        time_system!(BiomeSystem::step(self));
-
-        time_system!(Speech::step(self));
-
-        time_system!(VillagesSystem::step(self));
-
-        time_system!(QuestSystem::step(self));
-
-        time_system!(MovementSystem::pre_movement(self));
-
-        time_system!(SolidSystem::pre_movement(self));
-
-        time_system!(MovementSystem::movement(self));
-
-        time_system!(CollisionSystem::post_movement(self));
-
-        time_system!(SolidSystem::post_movement(self));
-
-        time_system!(PlayerSystem::end_step(self));
-
-        time_system!(self.delete_objects());
-
-        #[cfg(feature = "timing")]
-        let tick_end = Instant::now();
-        #[cfg(feature = "timing")]
-        let time_taken = tick_end - tick_start;
-        #[cfg(feature = "timing")]
-        {
-            let old_time = TIMINGS
-                .lock()
-                .unwrap()
-                .remove("tick")
-                .unwrap_or(Duration::from_secs(0));
-            TIMINGS
-                .lock()
-                .unwrap()
-                .insert("tick".to_string(), old_time + time_taken);
-        }
-        #[cfg(feature = "timing")]
-        {
-            if self.tick_counter % TIME_EVERY == 0 {
-                let mut timings = TIMINGS.lock().unwrap();
-                let mut total_time_taken = None;
-                let mut results = Vec::new();
-                for (name, timing) in timings.drain() {
-                    let insert_pos = match results.binary_search_by_key(&timing, |(_, t)| *t) {
-                        Ok(x) => x,
-                        Err(x) => x,
-                    };
-                    results.insert(insert_pos, (name, timing));
-                }
-                for (name, time_taken) in results {
-                    if name == "tick" {
-                        total_time_taken = Some(time_taken);
-                    } else {
-                        println!(
-                            "{} took {:?} over {} ticks (average {:?})",
-                            name,
-                            time_taken,
-                            TIME_EVERY,
-                            time_taken / TIME_EVERY
-                        );
-                    }
-                }
-                println!(
-                    "\nTotal: {:?} over {} ticks (average {:?})",
-                    total_time_taken.unwrap(),
-                    TIME_EVERY,
-                    total_time_taken.unwrap() / TIME_EVERY
-                );
-            };
-        }
+        let tick_span = tracing::debug_span!("tick", tick = self.tick_counter).entered();
+        let step_start = std::time::Instant::now();
+        Scheduler::run(self);
+        let step_time = step_start.elapsed().as_secs_f64();
+        self.metrics_system.add_counter(
+            "wolf_tick_seconds_total",
+            "Time spent stepping the game",
+            &[],
+            step_time,
+        );
+        self.metrics_system.set_gauge(
+            "wolf_last_tick_seconds",
+            "How long the last tick took to step",
+            &[],
+            step_time,
+        );
+        tick_span.exit();
         self.tick_counter += 1;
     }
     fn delete_objects(&mut self) {
@@ -322,3 +263,179 @@ impl Game {
         }
     }
 }
+
+// Registered in the order they used to be called in, constraints are only added where the order
+// actually matters
+fn get_scheduler() -> Scheduler {
+    use Constraint::*;
+    use Phase::*;
+    let mut scheduler = Scheduler::new();
+    scheduler.register(
+        SystemLabel::Behaviour,
+        Step,
+        BehaviourSystem::step,
+        vec![Before(SystemLabel::Ai)],
+    );
+    scheduler.register(SystemLabel::Timers, Step, TimerSystem::step, Vec::new());
+    scheduler.register(SystemLabel::Generic, Step, GenericSystem::step, Vec::new());
+    scheduler.register(
+        SystemLabel::Perception,
+        Step,
+        PerceptionSystem::step,
+        vec![Before(SystemLabel::Ai)],
+    );
+    scheduler.register_parallel::<AiSystem>(
+        SystemLabel::AiPlanning,
+        Step,
+        vec![Before(SystemLabel::Ai)],
+    );
+    scheduler.register(SystemLabel::Ai, Step, AiSystem::step, Vec::new());
+    scheduler.register(SystemLabel::Ants, Step, AntSystem::step, Vec::new());
+    scheduler.register(
+        SystemLabel::Projectiles,
+        Step,
+        Projectile::step,
+        vec![Before(SystemLabel::Damage)],
+    );
+    scheduler.register(SystemLabel::Damage, Step, DamageSystem::step, Vec::new());
+    scheduler.register(SystemLabel::Statuses, Step, StatusSystem::step, Vec::new());
+    scheduler.register(
+        SystemLabel::Threat,
+        Step,
+        ThreatSystem::step,
+        vec![After(SystemLabel::Damage)],
+    );
+    scheduler.register(SystemLabel::Monsters, Step, Monsters::step, Vec::new());
+    scheduler.register(
+        SystemLabel::Wildlife,
+        Step,
+        WildlifeSystem::step,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::BasicChunkLoaders,
+        Step,
+        BasicChunkLoader::step,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::ChunkLoaders,
+        Step,
+        step_chunk_loaders,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::ChunkWatchers,
+        Step,
+        ChunkWatcher::step,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::TerrainSprites,
+        Step,
+        TerrainSprite::step,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::Resources,
+        Step,
+        ResourceSystem::step,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::Inventory,
+        Step,
+        InventorySystem::step,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::Abilities,
+        Step,
+        AbilitySystem::step,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::Necromancy,
+        Step,
+        NecromancySystem::step,
+        Vec::new(),
+    );
+    scheduler.register(SystemLabel::Speech, Step, Speech::step, Vec::new());
+    scheduler.register(
+        SystemLabel::Villages,
+        Step,
+        VillagesSystem::step,
+        Vec::new(),
+    );
+    scheduler.register(SystemLabel::Quests, Step, QuestSystem::step, Vec::new());
+    scheduler.register(SystemLabel::Scripts, Step, ScriptSystem::step, Vec::new());
+    scheduler.register(
+        SystemLabel::IntendedMovement,
+        PreMovement,
+        MovementSystem::pre_movement,
+        vec![Before(SystemLabel::SolidsPreMovement)],
+    );
+    scheduler.register(
+        SystemLabel::SolidsPreMovement,
+        PreMovement,
+        SolidSystem::pre_movement,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::Movement,
+        Phase::Movement,
+        MovementSystem::movement,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::Collisions,
+        PostMovement,
+        CollisionSystem::post_movement,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::SolidsPostMovement,
+        PostMovement,
+        SolidSystem::post_movement,
+        vec![After(SystemLabel::Collisions)],
+    );
+    scheduler.register(
+        SystemLabel::PlayerCommands,
+        EndStep,
+        Player::handle_commands,
+        Vec::new(),
+    );
+    scheduler.register(
+        SystemLabel::PlayerUpdates,
+        EndStep,
+        Player::update_game_objects,
+        vec![After(SystemLabel::PlayerCommands)],
+    );
+    scheduler.register(
+        SystemLabel::ClientSideObjects,
+        EndStep,
+        PlayerSystem::update_active_client_side_objects,
+        vec![After(SystemLabel::PlayerUpdates)],
+    );
+    // Deleting comes after everything that might ask for something to be deleted, and events
+    // are handed out once everything has settled
+    scheduler.register(
+        SystemLabel::DeleteObjects,
+        EndStep,
+        |game| game.delete_objects(),
+        vec![After(SystemLabel::ClientSideObjects)],
+    );
+    scheduler.register(
+        SystemLabel::Events,
+        EndStep,
+        EventBus::dispatch,
+        vec![After(SystemLabel::DeleteObjects)],
+    );
+    scheduler.register(
+        SystemLabel::Metrics,
+        EndStep,
+        MetricsSystem::step,
+        vec![After(SystemLabel::Events)],
+    );
+    scheduler
+}
@@ -1,16 +1,23 @@
 use crate::component::*;
-use crate::game::{CollisionGroupId, Game};
+use crate::game::{CollisionGroupId, Game, GameEvent};
 use crate::id_types::*;
 use crate::spatial_map::{HitBox, HitBoxed};
 use anymap::AnyMap;
 use coords::*;
 use id::*;
-use signal_listener_macro::define_signal_listener;
 use wolf_hash_map::*;
 
 mod client_side_component;
 pub use client_side_component::*;
 
+// Every signal uses the listener types shared with the client
+#[macro_export]
+macro_rules! define_signal_listener {
+    ($($signal:tt)*) => {
+        signal_listener_macro::define_signal_listener!(::signal_listeners; $($signal)*);
+    };
+}
+
 define_signal_listener!(BeforeDelete, &mut Game);
 
 pub struct GameObject {
@@ -60,6 +67,7 @@ impl GameObject {
     }
     pub fn _remove(game: &mut Game, id: GameObjectId) {
         id.send_before_delete_signal(game);
+        game.event_bus.publish(GameEvent::Deleted { id });
         let game_object = match game.game_objects.get_mut(id) {
             Some(x) => x,
             None => return,
@@ -1,9 +1,12 @@
 use super::*;
 use crate::ai::*;
-use crate::combinable::*;
+use crate::perception::{PerceiverComponent, Perception, PerceptionSystem};
+use crate::threat::{ThreatSystem, ThreatTableComponent};
+use signal_listeners::*;
 
 const BASIC_HUNTER_AGGRO_RANGE: f64 = 200.0;
-const BASIC_HUNTER_DEAGGRO_RANGE: f64 = 450.0;
+/// How close to a last known position we need to get before giving up the search
+const BASIC_HUNTER_SEARCH_RANGE: f64 = 20.0;
 
 #[derive(Clone)]
 pub struct BasicHunterGoal {}
@@ -22,13 +25,15 @@ impl Goal for BasicHunterGoal {
         Some(GoalImportance(100))
     }
     fn get_method(&self, game: &Game, owner_id: GameObjectId) -> GoalResult {
-        let prey_id = HuntingSystem::get_closest_prey(game, owner_id, BASIC_HUNTER_AGGRO_RANGE);
-        let target_id = prey_id.map(|prey_id| {
-            game.hunting_system
-                .preys
-                .get(prey_id)
-                .unwrap()
-                .game_object_id
+        let target_id = ThreatSystem::get_valid_target(game, owner_id).or_else(|| {
+            PerceptionSystem::get_closest_perceived_prey(game, owner_id, BASIC_HUNTER_AGGRO_RANGE)
+                .map(|prey_id| {
+                    game.hunting_system
+                        .preys
+                        .get(prey_id)
+                        .unwrap()
+                        .game_object_id
+                })
         });
         if let Some(target_id) = target_id {
             GoalResult::SimplePlan(Box::new(BasicHunterPlan { target_id }))
@@ -40,33 +45,56 @@ impl Goal for BasicHunterGoal {
 
 impl SimplePlan for BasicHunterPlan {
     fn step(&mut self, game: &mut Game, owner_id: GameObjectId) -> ActionResult {
-        let target_coords = if let Some(target) = game.game_objects.get(self.target_id) {
-            target.coords
-        } else {
+        if self.target_id.is_deleted(&game.game_objects) {
             return ActionResult::Success;
-        };
+        }
         if !owner_id.can_hurt(game, self.target_id) {
             return ActionResult::Failure;
         }
-        let owner_coords = owner_id.get_coords_game(game);
-        let distance = owner_coords.get_distance_to(&target_coords);
-        if distance > BASIC_HUNTER_DEAGGRO_RANGE {
-            return ActionResult::Failure;
+        if let Some(threat_target) = ThreatSystem::get_valid_target(game, owner_id) {
+            if threat_target != self.target_id {
+                // Someone else has our attention now, so plan again
+                return ActionResult::Success;
+            }
+        }
+        match PerceptionSystem::perceive(game, owner_id, self.target_id) {
+            Perception::Seen(_) => {
+                owner_id.intend_follow_game(game, self.target_id);
+                ActionResult::Continue
+            }
+            Perception::Remembered(last_known_coords) => {
+                // Search where we last saw or heard them
+                let owner_coords = owner_id.get_coords_game(game);
+                if owner_coords.get_plane() != last_known_coords.get_plane()
+                    || owner_coords.get_distance_to(&last_known_coords) < BASIC_HUNTER_SEARCH_RANGE
+                {
+                    return ActionResult::Failure;
+                }
+                owner_id.intend_move_to_point_game(game, last_known_coords);
+                ActionResult::Continue
+            }
+            Perception::Unknown => ActionResult::Failure,
         }
-        owner_id.intend_follow_game(game, self.target_id);
-        ActionResult::Continue
     }
 }
 
 #[derive(Clone)]
 pub struct BasicHunterBehaviourComponent {
     component_id: ComponentId,
+    perceiver_component_id: ComponentId,
+    threat_table_component_id: ComponentId,
 }
 
 impl BasicHunterBehaviourComponent {
     pub fn add_to(game: &mut Game, owner_id: GameObjectId) {
         let component_id = game.get_id();
-        let comp = BasicHunterBehaviourComponent { component_id };
+        let perceiver_component_id = PerceiverComponent::add_to(game, owner_id);
+        let threat_table_component_id = ThreatTableComponent::add_to(game, owner_id);
+        let comp = BasicHunterBehaviourComponent {
+            component_id,
+            perceiver_component_id,
+            threat_table_component_id,
+        };
         owner_id.add_get_goals_signal_listener(game, comp.clone());
         owner_id.add_component(game, comp);
     }
@@ -76,9 +104,6 @@ impl GetGoalsSignalListener for BasicHunterBehaviourComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn GetGoalsSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_get_goals_signal(
         &self,
         _game: &Game,
@@ -94,5 +119,7 @@ impl Component for BasicHunterBehaviourComponent {
     }
     fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
         owner_id.remove_get_goals_signal_listener(game, self.component_id);
+        owner_id.remove_component(game, self.perceiver_component_id);
+        owner_id.remove_component(game, self.threat_table_component_id);
     }
 }
@@ -19,7 +19,23 @@ impl HuntingSystem {
             prey_chunk_map: ChunkMap::new(),
         }
     }
+    pub fn get_prey_id(game: &Game, game_object_id: GameObjectId) -> Option<PreyId> {
+        game.hunting_system
+            .preys
+            .iter()
+            .find(|(_id, prey)| prey.game_object_id == game_object_id)
+            .map(|(id, _prey)| id)
+    }
     pub fn get_closest_prey(game: &Game, hunter: GameObjectId, radius: f64) -> Option<PreyId> {
+        Self::get_closest_prey_matching(game, hunter, radius, |_| true)
+    }
+    /// As get_closest_prey, but only considers preys whose game object passes the filter
+    pub fn get_closest_prey_matching<F: Fn(GameObjectId) -> bool>(
+        game: &Game,
+        hunter: GameObjectId,
+        radius: f64,
+        filter: F,
+    ) -> Option<PreyId> {
         let hunter_coords = hunter.get_coords_game(game);
         let hunter_chunk_coords: TerrainChunkCoords = hunter_coords.into();
 
@@ -45,6 +61,9 @@ impl HuntingSystem {
                             continue;
                         }
                     }
+                    if !filter(prey.game_object_id) {
+                        continue;
+                    }
                     let prey_allegiances = prey.game_object_id.get_allegiances(game);
                     if !AllegianceSystem::can_hurt_multiple(
                         &game.allegiance_system,
@@ -53,9 +53,6 @@ impl MoveSignalListener for PreyComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn MoveSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_move_signal(
         &self,
         game: &mut Game,
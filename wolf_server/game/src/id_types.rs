@@ -59,7 +59,9 @@ makeId!(SolidId);
 makeId!(SpellbookAbsorberId);
 makeId!(SpellbookId);
 makeId!(SquadId);
+makeId!(StatusId);
 makeId!(StickyFacerId);
+makeId!(SubscriptionId);
 makeId!(TerrainSpriteId); // A sprite stored on a terrain Square
 makeId!(TestObjectId);
 makeId!(TimerId);
@@ -69,3 +71,4 @@ makeId!(VillagerMindId);
 makeId!(VoluntaryMoverId);
 makeId!(WalkerId);
 makeId!(WanderingHerbivoreId);
+makeId!(WorkshopId);
@@ -0,0 +1,121 @@
+use crate::abilities::AbilityTypeId;
+use crate::game::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum EquipmentSlot {
+    Head,
+    Body,
+    Feet,
+    Weapon,
+}
+
+pub const EQUIPMENT_SLOT_COUNT: usize = 4;
+
+impl EquipmentSlot {
+    pub fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(EquipmentSlot::Head),
+            1 => Some(EquipmentSlot::Body),
+            2 => Some(EquipmentSlot::Feet),
+            3 => Some(EquipmentSlot::Weapon),
+            _ => None,
+        }
+    }
+    pub fn get_index(&self) -> usize {
+        match self {
+            EquipmentSlot::Head => 0,
+            EquipmentSlot::Body => 1,
+            EquipmentSlot::Feet => 2,
+            EquipmentSlot::Weapon => 3,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ItemUse {
+    Heal(Health),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ItemKind {
+    HealthPotion,
+    IronHelmet,
+    ChainMail,
+    SwiftBoots,
+    FireStaff,
+}
+
+pub struct ItemDefinition {
+    pub icon: u32,
+    pub max_stack: u32,
+    pub slot: Option<EquipmentSlot>,
+    // Granted for as long as the item is equipped
+    pub abilities: Vec<AbilityTypeId>,
+    pub speed_mod: f64,
+    pub armour: Health,
+    pub on_use: Option<ItemUse>,
+}
+
+impl Default for ItemDefinition {
+    fn default() -> Self {
+        Self {
+            icon: UNKNOWN_ABILITY_SPRITE,
+            max_stack: 1,
+            slot: None,
+            abilities: Vec::new(),
+            speed_mod: 0.0,
+            armour: Health(0),
+            on_use: None,
+        }
+    }
+}
+
+impl ItemKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "health_potion" => Some(ItemKind::HealthPotion),
+            "iron_helmet" => Some(ItemKind::IronHelmet),
+            "chain_mail" => Some(ItemKind::ChainMail),
+            "swift_boots" => Some(ItemKind::SwiftBoots),
+            "fire_staff" => Some(ItemKind::FireStaff),
+            _ => None,
+        }
+    }
+    pub fn get_definition(&self) -> ItemDefinition {
+        match self {
+            ItemKind::HealthPotion => ItemDefinition {
+                icon: HEALTH_POTION_ICON,
+                max_stack: 10,
+                // A fifth of a full health bar
+                on_use: Some(ItemUse::Heal(Health(DEFAULT_HEALTH.0 / 5))),
+                ..Default::default()
+            },
+            ItemKind::IronHelmet => ItemDefinition {
+                icon: IRON_HELMET_ICON,
+                slot: Some(EquipmentSlot::Head),
+                armour: Health(SMALL_DAMAGE / 10),
+                ..Default::default()
+            },
+            ItemKind::ChainMail => ItemDefinition {
+                icon: CHAIN_MAIL_ICON,
+                slot: Some(EquipmentSlot::Body),
+                // Takes the edge off small hits, at the cost of some speed
+                armour: Health(SMALL_DAMAGE / 4),
+                speed_mod: -0.1,
+                ..Default::default()
+            },
+            ItemKind::SwiftBoots => ItemDefinition {
+                icon: SWIFT_BOOTS_ICON,
+                slot: Some(EquipmentSlot::Feet),
+                speed_mod: 0.2,
+                ..Default::default()
+            },
+            ItemKind::FireStaff => ItemDefinition {
+                icon: FIRE_STAFF_ICON,
+                slot: Some(EquipmentSlot::Weapon),
+                abilities: vec![AbilityTypeId::FireballId],
+                ..Default::default()
+            },
+        }
+    }
+}
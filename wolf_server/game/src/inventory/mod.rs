@@ -0,0 +1,600 @@
+use crate::abilities::BasicAbilityUser;
+use crate::game::*;
+use signal_listeners::{Added, CombinedVecs};
+use wolf_interface::{InventoryLocation, InventoryMessage, ItemStackMessage, ServerMessage};
+
+mod items;
+pub use items::*;
+
+/*
+Items live in a backpack of stacks, and some can be moved into equipment slots.
+Equipped items add their speed and armour through the usual signals, and grant their abilities
+to the owner's ability user until they're taken off again.
+Items on the ground are pickups, collected by anyone with an inventory who walks over them.
+*/
+
+pub const BACKPACK_SIZE: usize = 16;
+const PICKUP_INTERVAL: u32 = 5;
+const PICKUP_RANGE: f64 = 32.0;
+// Stops whoever dropped something from picking it straight back up
+const DROP_PICKUP_DELAY: u32 = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ItemStack {
+    pub kind: ItemKind,
+    pub count: u32,
+}
+
+impl ItemStack {
+    fn get_message(&self) -> ItemStackMessage {
+        ItemStackMessage {
+            icon: self.kind.get_definition().icon,
+            count: self.count,
+        }
+    }
+}
+
+pub struct Inventory {
+    pub backpack: Vec<Option<ItemStack>>,
+    pub equipment: Vec<Option<ItemStack>>,
+    // What each equipment slot's item has granted, so it can be taken away again
+    pub granted_abilities: Vec<Vec<AbilityId>>,
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Inventory {
+            backpack: vec![None; BACKPACK_SIZE],
+            equipment: vec![None; EQUIPMENT_SLOT_COUNT],
+            granted_abilities: vec![Vec::new(); EQUIPMENT_SLOT_COUNT],
+        }
+    }
+    // Tops up existing stacks before starting new ones, returning how many didn't fit
+    pub fn add_item(&mut self, kind: ItemKind, count: u32) -> u32 {
+        let max_stack = kind.get_definition().max_stack;
+        let mut remaining = count;
+        for stack in self.backpack.iter_mut().flatten() {
+            if stack.kind == kind && stack.count < max_stack {
+                let added = (max_stack - stack.count).min(remaining);
+                stack.count += added;
+                remaining -= added;
+            }
+        }
+        for slot in self.backpack.iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+            if slot.is_none() {
+                let added = remaining.min(max_stack);
+                *slot = Some(ItemStack { kind, count: added });
+                remaining -= added;
+            }
+        }
+        remaining
+    }
+    fn get_slot(&self, location: InventoryLocation) -> Option<&Option<ItemStack>> {
+        match location {
+            InventoryLocation::Backpack(i) => self.backpack.get(i as usize),
+            InventoryLocation::Equipment(i) => self.equipment.get(i as usize),
+        }
+    }
+    fn get_slot_mut(&mut self, location: InventoryLocation) -> Option<&mut Option<ItemStack>> {
+        match location {
+            InventoryLocation::Backpack(i) => self.backpack.get_mut(i as usize),
+            InventoryLocation::Equipment(i) => self.equipment.get_mut(i as usize),
+        }
+    }
+    fn fits(stack: &Option<ItemStack>, location: InventoryLocation) -> bool {
+        match (stack, location) {
+            (Some(stack), InventoryLocation::Equipment(i)) => {
+                stack
+                    .kind
+                    .get_definition()
+                    .slot
+                    .map(|slot| slot.get_index())
+                    == Some(i as usize)
+            }
+            _ => true,
+        }
+    }
+    // Merges the stacks if they can be merged, otherwise swaps them. Returns whether anything moved.
+    pub fn move_item(&mut self, from: InventoryLocation, to: InventoryLocation) -> bool {
+        if from == to {
+            return false;
+        }
+        let (from_stack, to_stack) = match (self.get_slot(from), self.get_slot(to)) {
+            (Some(from_stack), Some(to_stack)) => (*from_stack, *to_stack),
+            _ => return false,
+        };
+        if let (Some(moving), Some(onto)) = (from_stack, to_stack) {
+            let max_stack = moving.kind.get_definition().max_stack;
+            if moving.kind == onto.kind && onto.count < max_stack {
+                let moved = (max_stack - onto.count).min(moving.count);
+                *self.get_slot_mut(to).unwrap() = Some(ItemStack {
+                    kind: onto.kind,
+                    count: onto.count + moved,
+                });
+                *self.get_slot_mut(from).unwrap() = Some(ItemStack {
+                    kind: moving.kind,
+                    count: moving.count - moved,
+                })
+                .filter(|stack| stack.count > 0);
+                return true;
+            }
+        }
+        if from_stack.is_none()
+            || !Inventory::fits(&from_stack, to)
+            || !Inventory::fits(&to_stack, from)
+        {
+            return false;
+        }
+        *self.get_slot_mut(from).unwrap() = to_stack;
+        *self.get_slot_mut(to).unwrap() = from_stack;
+        true
+    }
+    fn get_equipped_definitions(&self) -> impl Iterator<Item = ItemDefinition> + '_ {
+        self.equipment
+            .iter()
+            .flatten()
+            .map(|stack| stack.kind.get_definition())
+    }
+    fn get_message(&self) -> InventoryMessage {
+        let to_messages = |slots: &Vec<Option<ItemStack>>| {
+            slots
+                .iter()
+                .map(|slot| slot.as_ref().map(ItemStack::get_message))
+                .collect()
+        };
+        InventoryMessage {
+            backpack: to_messages(&self.backpack),
+            equipment: to_messages(&self.equipment),
+        }
+    }
+}
+
+pub struct ItemPickup {
+    pub stack: ItemStack,
+    // Who dropped it, and when they're allowed to pick it back up
+    pub dropped_by: Option<(GameObjectId, u32)>,
+}
+
+pub struct InventorySystem {
+    pub inventories: IdMap<GameObjectId, Inventory>,
+    pub item_pickups: IdMap<GameObjectId, ItemPickup>,
+}
+
+impl InventorySystem {
+    pub fn new() -> Self {
+        InventorySystem {
+            inventories: IdMap::new(),
+            item_pickups: IdMap::new(),
+        }
+    }
+    fn send_inventory_message(game: &mut Game, owner_id: GameObjectId) {
+        let player_id = match game.player_system.players_by_game_object.get(owner_id) {
+            Some(player_id) => *player_id,
+            None => return,
+        };
+        let message = match game.inventory_system.inventories.get(owner_id) {
+            Some(inventory) => inventory.get_message(),
+            None => return,
+        };
+        if let Some(player) = game.player_system.players.get_mut(player_id) {
+            player
+                .server_messages
+                .push(ServerMessage::Inventory(message));
+        }
+    }
+    // Returns how many didn't fit
+    pub fn give_item(game: &mut Game, owner_id: GameObjectId, kind: ItemKind, count: u32) -> u32 {
+        let remaining = match game.inventory_system.inventories.get_mut(owner_id) {
+            Some(inventory) => inventory.add_item(kind, count),
+            None => return count,
+        };
+        if remaining != count {
+            InventorySystem::send_inventory_message(game, owner_id);
+        }
+        remaining
+    }
+    pub fn move_item(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        from: InventoryLocation,
+        to: InventoryLocation,
+    ) {
+        let moved = game
+            .inventory_system
+            .inventories
+            .get_mut(owner_id)
+            .map(|inventory| inventory.move_item(from, to))
+            .unwrap_or(false);
+        if !moved {
+            return;
+        }
+        for location in [from, to].iter() {
+            if let InventoryLocation::Equipment(i) = location {
+                InventorySystem::update_granted_abilities(game, owner_id, *i as usize);
+            }
+        }
+        InventorySystem::send_inventory_message(game, owner_id);
+    }
+    pub fn equip_item(game: &mut Game, owner_id: GameObjectId, backpack_slot: u8) {
+        let slot = game
+            .inventory_system
+            .inventories
+            .get(owner_id)
+            .and_then(|inventory| inventory.backpack.get(backpack_slot as usize).copied())
+            .flatten()
+            .and_then(|stack| stack.kind.get_definition().slot);
+        if let Some(slot) = slot {
+            InventorySystem::move_item(
+                game,
+                owner_id,
+                InventoryLocation::Backpack(backpack_slot),
+                InventoryLocation::Equipment(slot.get_index() as u8),
+            );
+        }
+    }
+    pub fn drop_item(game: &mut Game, owner_id: GameObjectId, location: InventoryLocation) {
+        let stack = match game
+            .inventory_system
+            .inventories
+            .get_mut(owner_id)
+            .and_then(|inventory| inventory.get_slot_mut(location))
+            .and_then(|slot| slot.take())
+        {
+            Some(stack) => stack,
+            None => return,
+        };
+        if let InventoryLocation::Equipment(i) = location {
+            InventorySystem::update_granted_abilities(game, owner_id, i as usize);
+        }
+        let coords = owner_id.get_coords_game(game);
+        let dropped_by = Some((owner_id, game.tick_counter + DROP_PICKUP_DELAY));
+        InventorySystem::create_item_pickup(game, coords, stack, dropped_by);
+        InventorySystem::send_inventory_message(game, owner_id);
+    }
+    pub fn use_item(game: &mut Game, owner_id: GameObjectId, backpack_slot: u8) {
+        let slot = match game
+            .inventory_system
+            .inventories
+            .get_mut(owner_id)
+            .and_then(|inventory| inventory.backpack.get_mut(backpack_slot as usize))
+        {
+            Some(slot) => slot,
+            None => return,
+        };
+        let on_use = match slot.and_then(|stack| stack.kind.get_definition().on_use) {
+            Some(on_use) => on_use,
+            None => return,
+        };
+        if let Some(stack) = slot.as_mut() {
+            stack.count -= 1;
+            if stack.count == 0 {
+                *slot = None;
+            }
+        }
+        match on_use {
+            ItemUse::Heal(amount) => owner_id.send_heal_signal(game, amount),
+        }
+        InventorySystem::send_inventory_message(game, owner_id);
+    }
+    fn update_granted_abilities(game: &mut Game, owner_id: GameObjectId, equipment_index: usize) {
+        let (old_abilities, new_ability_types) =
+            match game.inventory_system.inventories.get_mut(owner_id) {
+                Some(inventory) => (
+                    std::mem::replace(
+                        &mut inventory.granted_abilities[equipment_index],
+                        Vec::new(),
+                    ),
+                    inventory.equipment[equipment_index]
+                        .map(|stack| stack.kind.get_definition().abilities)
+                        .unwrap_or(Vec::new()),
+                ),
+                None => return,
+            };
+        if !old_abilities.is_empty() {
+            BasicAbilityUser::revoke_abilities(game, owner_id, &old_abilities);
+        }
+        if !new_ability_types.is_empty() {
+            let granted = BasicAbilityUser::grant_abilities(game, owner_id, &new_ability_types);
+            if let Some(inventory) = game.inventory_system.inventories.get_mut(owner_id) {
+                inventory.granted_abilities[equipment_index] = granted;
+            }
+        }
+    }
+    pub fn create_item_pickup(
+        game: &mut Game,
+        coords: PixelCoords,
+        stack: ItemStack,
+        dropped_by: Option<(GameObjectId, u32)>,
+    ) -> GameObjectId {
+        let game_object_id = GameObject::create_game(game, coords);
+        let icon = stack.kind.get_definition().icon;
+        BasicDrawingComponent::add_to(game, game_object_id, icon, DEFAULT_DEPTH);
+        ItemPickupComponent::add_to(game, game_object_id, ItemPickup { stack, dropped_by });
+        game_object_id
+    }
+    pub fn step(game: &mut Game) {
+        let tick_counter = game.tick_counter;
+        if tick_counter % PICKUP_INTERVAL != 0 {
+            return;
+        }
+        let mut to_pick_up = Vec::new();
+        for (pickup_id, pickup) in game.inventory_system.item_pickups.iter() {
+            let pickup_coords = match pickup_id.get_coords_game_safe(game) {
+                Some(coords) => coords,
+                None => continue,
+            };
+            let collector = game
+                .inventory_system
+                .inventories
+                .iter()
+                .map(|(owner_id, _inventory)| owner_id)
+                .filter(|owner_id| match pickup.dropped_by {
+                    Some((dropped_by, until)) => *owner_id != dropped_by || until <= tick_counter,
+                    None => true,
+                })
+                .find(|owner_id| {
+                    owner_id
+                        .get_coords_game_safe(game)
+                        .map(|coords| {
+                            coords.get_plane() == pickup_coords.get_plane()
+                                && coords.get_distance_to(&pickup_coords) <= PICKUP_RANGE
+                        })
+                        .unwrap_or(false)
+                });
+            if let Some(collector) = collector {
+                to_pick_up.push((pickup_id, collector, pickup.stack));
+            }
+        }
+        for (pickup_id, collector, stack) in to_pick_up {
+            let remaining = InventorySystem::give_item(game, collector, stack.kind, stack.count);
+            if remaining == 0 {
+                pickup_id.remove(game);
+            } else if let Some(pickup) = game.inventory_system.item_pickups.get_mut(pickup_id) {
+                pickup.stack.count = remaining;
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ItemPickupComponent {
+    component_id: ComponentId,
+}
+
+impl ItemPickupComponent {
+    pub fn add_to(game: &mut Game, owner_id: GameObjectId, pickup: ItemPickup) -> ComponentId {
+        let component_id = game.get_id();
+        game.inventory_system.item_pickups.insert(owner_id, pickup);
+        owner_id.add_component(game, ItemPickupComponent { component_id });
+        component_id
+    }
+}
+
+impl Component for ItemPickupComponent {
+    fn get_component_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
+        game.inventory_system.item_pickups.remove(owner_id);
+    }
+}
+
+#[derive(Clone)]
+pub struct InventoryComponent {
+    component_id: ComponentId,
+}
+
+impl InventoryComponent {
+    pub fn add_to(game: &mut Game, owner_id: GameObjectId) -> ComponentId {
+        let component_id = game.get_id();
+        let comp = InventoryComponent { component_id };
+        game.inventory_system
+            .inventories
+            .insert(owner_id, Inventory::new());
+        owner_id.add_get_speed_mod_signal_listener(game, comp.clone());
+        owner_id.add_get_damage_modifiers_signal_listener(game, comp.clone());
+        owner_id.add_bound_player_signal_listener(game, comp.clone());
+        owner_id.add_component(game, comp);
+        component_id
+    }
+}
+
+impl Component for InventoryComponent {
+    fn get_component_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
+        owner_id.remove_get_speed_mod_signal_listener(game, self.component_id);
+        owner_id.remove_get_damage_modifiers_signal_listener(game, self.component_id);
+        owner_id.remove_bound_player_signal_listener(game, self.component_id);
+        game.inventory_system.inventories.remove(owner_id);
+    }
+}
+
+impl GetSpeedModSignalListener for InventoryComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_get_speed_mod_signal(&self, game: &Game, owner_id: GameObjectId) -> Added<f64> {
+        Added(
+            game.inventory_system
+                .inventories
+                .get(owner_id)
+                .map(|inventory| {
+                    inventory
+                        .get_equipped_definitions()
+                        .map(|definition| definition.speed_mod)
+                        .sum()
+                })
+                .unwrap_or(0.0),
+        )
+    }
+}
+
+impl GetDamageModifiersSignalListener for InventoryComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_get_damage_modifiers_signal(
+        &self,
+        game: &Game,
+        owner_id: GameObjectId,
+        damage_type: DamageType,
+        _firer_id: GameObjectId,
+    ) -> CombinedVecs<DamageModifier> {
+        let armour = game
+            .inventory_system
+            .inventories
+            .get(owner_id)
+            .map(|inventory| {
+                inventory
+                    .get_equipped_definitions()
+                    .fold(Health(0), |total, definition| total + definition.armour)
+            })
+            .unwrap_or(Health(0));
+        if damage_type == DamageType::Physical && armour.0 > 0 {
+            CombinedVecs(vec![DamageModifier::Absorb(armour)])
+        } else {
+            CombinedVecs(Vec::new())
+        }
+    }
+}
+
+impl BoundPlayerSignalListener for InventoryComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_bound_player_signal(
+        &self,
+        game: &mut Game,
+        owner_id: GameObjectId,
+        player_id: PlayerId,
+    ) {
+        // The player isn't bound to us until after this signal, so send straight to them
+        if let Some(message) = game
+            .inventory_system
+            .inventories
+            .get(owner_id)
+            .map(|inventory| inventory.get_message())
+        {
+            if let Some(player) = game.player_system.players.get_mut(player_id) {
+                player
+                    .server_messages
+                    .push(ServerMessage::Inventory(message));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abilities::{AbilityTypeId, BasicAbilityUserComponent};
+
+    #[test]
+    fn items_stack_before_filling_new_slots() {
+        let mut inventory = Inventory::new();
+        assert_eq!(inventory.add_item(ItemKind::HealthPotion, 15), 0);
+        assert_eq!(inventory.backpack[0].unwrap().count, 10);
+        assert_eq!(inventory.backpack[1].unwrap().count, 5);
+        assert!(inventory.move_item(
+            InventoryLocation::Backpack(0),
+            InventoryLocation::Backpack(1)
+        ));
+        assert_eq!(inventory.backpack[0].unwrap().count, 5);
+        assert_eq!(inventory.backpack[1].unwrap().count, 10);
+        assert!(inventory.move_item(
+            InventoryLocation::Backpack(0),
+            InventoryLocation::Backpack(2)
+        ));
+        assert!(inventory.backpack[0].is_none());
+    }
+
+    #[test]
+    fn items_only_equip_into_their_own_slot() {
+        let mut inventory = Inventory::new();
+        inventory.add_item(ItemKind::SwiftBoots, 1);
+        let head = InventoryLocation::Equipment(EquipmentSlot::Head.get_index() as u8);
+        let feet = InventoryLocation::Equipment(EquipmentSlot::Feet.get_index() as u8);
+        assert!(!inventory.move_item(InventoryLocation::Backpack(0), head));
+        assert!(inventory.move_item(InventoryLocation::Backpack(0), feet));
+        assert_eq!(inventory.get_equipped_definitions().count(), 1);
+    }
+
+    fn create_owner(game: &mut Game) -> GameObjectId {
+        let owner_id = GameObject::create_game(game, PixelCoords::new_at_zero());
+        BasicAbilityUserComponent::add_to(game, owner_id, Vec::new());
+        DamageableComponent::add_to(game, owner_id);
+        InventoryComponent::add_to(game, owner_id);
+        owner_id
+    }
+
+    fn get_ability_types(game: &Game, owner_id: GameObjectId) -> Vec<AbilityTypeId> {
+        game.ability_system
+            .basic_ability_users
+            .iter()
+            .find(|(_id, user)| user.owner_id == owner_id)
+            .map(|(_id, user)| user.ability_types.iter().map(|(_, x)| *x).collect())
+            .unwrap()
+    }
+
+    #[test]
+    fn equipped_items_grant_their_abilities_until_taken_off() {
+        let mut game = Game::new();
+        let owner_id = create_owner(&mut game);
+        InventorySystem::give_item(&mut game, owner_id, ItemKind::FireStaff, 1);
+        assert!(get_ability_types(&game, owner_id).is_empty());
+        InventorySystem::equip_item(&mut game, owner_id, 0);
+        assert_eq!(
+            get_ability_types(&game, owner_id),
+            vec![AbilityTypeId::FireballId]
+        );
+        InventorySystem::move_item(
+            &mut game,
+            owner_id,
+            InventoryLocation::Equipment(EquipmentSlot::Weapon.get_index() as u8),
+            InventoryLocation::Backpack(0),
+        );
+        assert!(get_ability_types(&game, owner_id).is_empty());
+    }
+
+    #[test]
+    fn equipped_items_change_speed_and_soak_up_physical_damage() {
+        let mut game = Game::new();
+        let owner_id = create_owner(&mut game);
+        let attacker_id = GameObject::create_game(&mut game, PixelCoords::new_at_zero());
+        for kind in [
+            ItemKind::IronHelmet,
+            ItemKind::ChainMail,
+            ItemKind::SwiftBoots,
+        ]
+        .iter()
+        {
+            InventorySystem::give_item(&mut game, owner_id, *kind, 1);
+        }
+        for backpack_slot in 0..3 {
+            InventorySystem::equip_item(&mut game, owner_id, backpack_slot);
+        }
+        assert!((get_speed_multiplier(&game, owner_id) - 1.1).abs() < 1e-9);
+        let armour = SMALL_DAMAGE / 10 + SMALL_DAMAGE / 4;
+        let taken = deal_damage(
+            &mut game,
+            owner_id,
+            attacker_id,
+            Health(SMALL_DAMAGE),
+            DamageType::Physical,
+        );
+        assert_eq!(taken, Health(SMALL_DAMAGE - armour));
+        let taken = deal_damage(
+            &mut game,
+            owner_id,
+            attacker_id,
+            Health(SMALL_DAMAGE),
+            DamageType::Fire,
+        );
+        assert_eq!(taken, Health(SMALL_DAMAGE));
+    }
+}
@@ -2,8 +2,8 @@
 #![feature(proc_macro_hygiene)]
 #![feature(step_trait)]
 #![feature(entry_insert)]
-#![feature(hash_raw_entry)]
 mod abilities;
+mod admin;
 mod ai;
 mod allegiance;
 mod ants;
@@ -12,31 +12,42 @@ mod basic_client_side_component;
 mod behaviour;
 mod biomes;
 mod characters;
+mod chat;
 mod chunk_map;
 mod collisions;
-mod combinable;
 mod component;
+mod component_registry;
 mod damage;
 mod drawable;
+mod events;
 mod game;
 mod game_object;
 mod generic;
 mod hunting;
 mod id_types;
+mod inventory;
 mod loading;
 mod loot;
 mod meta;
+mod metrics;
 mod monsters;
 mod movement;
 mod necromancy;
+mod parallel;
+mod perception;
 mod player;
+mod prefabs;
+mod progression;
 mod quest;
 mod resources;
+mod scheduler;
+mod scripting;
 mod solids;
 mod spatial_map;
 mod speech;
 mod statuses;
 mod terrain;
+mod threat;
 mod timers;
 mod utilities;
 mod villages;
@@ -0,0 +1,261 @@
+use crate::inventory::{ItemKind, ItemStack};
+use crate::resources::{ResourceAmount, ResourceType, Resources};
+use rand::Rng;
+
+const LOOT_TABLES_FILE: &str = include_str!("../../data/loot/loot_tables.txt");
+// Tables can nest, so stop somewhere if they ever end up including each other
+const MAX_LOOT_DEPTH: u32 = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LootDrop {
+    Resource {
+        resource_type: ResourceType,
+        min: i32,
+        max: i32,
+    },
+    Item {
+        kind: ItemKind,
+        min: u32,
+        max: u32,
+    },
+    Spellbook,
+    Table(String),
+    Nothing,
+}
+
+// Either a single amount, or a range like 2-4
+fn parse_amount<T: std::str::FromStr + PartialOrd + Copy>(amount: &str) -> Result<(T, T), String> {
+    let bad_amount = || format!("Bad amount: {}", amount);
+    let (min, max) = match amount.split_once('-') {
+        Some((min, max)) => (
+            min.parse().map_err(|_| bad_amount())?,
+            max.parse().map_err(|_| bad_amount())?,
+        ),
+        None => {
+            let amount = amount.parse().map_err(|_| bad_amount())?;
+            (amount, amount)
+        }
+    };
+    if min > max {
+        return Err(bad_amount());
+    }
+    Ok((min, max))
+}
+
+impl LootDrop {
+    pub fn parse(words: &[&str]) -> Result<Self, String> {
+        match words {
+            ["resource", resource_type, amount] => {
+                let resource_type = ResourceType::from_name(resource_type)
+                    .ok_or(format!("Unknown resource: {}", resource_type))?;
+                let (min, max) = parse_amount(amount)?;
+                Ok(LootDrop::Resource {
+                    resource_type,
+                    min,
+                    max,
+                })
+            }
+            ["item", kind, amount] => {
+                let kind = ItemKind::from_name(kind).ok_or(format!("Unknown item: {}", kind))?;
+                let (min, max) = parse_amount(amount)?;
+                Ok(LootDrop::Item { kind, min, max })
+            }
+            ["spellbook"] => Ok(LootDrop::Spellbook),
+            ["table", name] => Ok(LootDrop::Table(name.to_string())),
+            ["nothing"] => Ok(LootDrop::Nothing),
+            _ => Err(format!("Unknown drop: {}", words.join(" "))),
+        }
+    }
+}
+
+// Everything a roll came up with
+#[derive(Clone, Debug, PartialEq)]
+pub struct Loot {
+    pub resources: Resources,
+    pub items: Vec<ItemStack>,
+    pub spellbooks: u32,
+}
+
+impl Loot {
+    pub fn new() -> Self {
+        Loot {
+            resources: Resources::new(),
+            items: Vec::new(),
+            spellbooks: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LootTable {
+    pub name: String,
+    pub rolls: u32,
+    pub guaranteed: Vec<LootDrop>,
+    pub weighted: Vec<(u32, LootDrop)>,
+}
+
+impl LootTable {
+    fn new(header: &[&str]) -> Result<Self, String> {
+        match header {
+            ["table", name] => Ok(LootTable {
+                name: name.to_string(),
+                rolls: 1,
+                guaranteed: Vec::new(),
+                weighted: Vec::new(),
+            }),
+            ["table", name, "rolls", rolls] => Ok(LootTable {
+                name: name.to_string(),
+                rolls: rolls.parse().map_err(|_| format!("Bad rolls: {}", rolls))?,
+                guaranteed: Vec::new(),
+                weighted: Vec::new(),
+            }),
+            _ => Err(format!("Bad table header: {}", header.join(" "))),
+        }
+    }
+    fn add_drop(&mut self, words: &[&str]) -> Result<(), String> {
+        match words {
+            ["always", drop @ ..] => self.guaranteed.push(LootDrop::parse(drop)?),
+            ["weight", weight, drop @ ..] => {
+                let weight = weight
+                    .parse()
+                    .map_err(|_| format!("Bad weight: {}", weight))?;
+                self.weighted.push((weight, LootDrop::parse(drop)?));
+            }
+            _ => return Err(format!("Bad drop line: {}", words.join(" "))),
+        }
+        Ok(())
+    }
+    fn pick_weighted<R: Rng>(&self, rng: &mut R) -> Option<&LootDrop> {
+        let total_weight: u32 = self.weighted.iter().map(|(weight, _)| weight).sum();
+        if total_weight == 0 {
+            return None;
+        }
+        let mut roll = rng.gen_range(0..total_weight);
+        for (weight, drop) in self.weighted.iter() {
+            if roll < *weight {
+                return Some(drop);
+            }
+            roll -= weight;
+        }
+        None
+    }
+}
+
+pub fn load_loot_tables() -> Vec<LootTable> {
+    let mut tables: Vec<LootTable> = Vec::new();
+    for line in LOOT_TABLES_FILE.lines().map(|line| line.trim()) {
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let result = if words[0] == "table" {
+            LootTable::new(&words).map(|table| tables.push(table))
+        } else {
+            match tables.last_mut() {
+                Some(table) => table.add_drop(&words),
+                None => Err("Drop before any table".to_string()),
+            }
+        };
+        if let Err(error) = result {
+            panic!("Failed to load loot table line {}: {}", line, error);
+        }
+    }
+    for table in tables.iter() {
+        let drops = table
+            .guaranteed
+            .iter()
+            .chain(table.weighted.iter().map(|(_, drop)| drop));
+        for drop in drops {
+            if let LootDrop::Table(name) = drop {
+                if !tables.iter().any(|other| other.name == *name) {
+                    panic!("Loot table {} refers to unknown table {}", table.name, name);
+                }
+            }
+        }
+    }
+    tables
+}
+
+pub fn roll_loot<R: Rng>(tables: &[LootTable], table_index: usize, rng: &mut R) -> Loot {
+    let mut loot = Loot::new();
+    roll_table(tables, &tables[table_index], rng, &mut loot, 0);
+    loot
+}
+
+fn roll_table<R: Rng>(
+    tables: &[LootTable],
+    table: &LootTable,
+    rng: &mut R,
+    loot: &mut Loot,
+    depth: u32,
+) {
+    if depth > MAX_LOOT_DEPTH {
+        return;
+    }
+    for drop in table.guaranteed.iter() {
+        roll_drop(tables, drop, rng, loot, depth);
+    }
+    for _ in 0..table.rolls {
+        if let Some(drop) = table.pick_weighted(rng) {
+            roll_drop(tables, drop, rng, loot, depth);
+        }
+    }
+}
+
+fn roll_drop<R: Rng>(
+    tables: &[LootTable],
+    drop: &LootDrop,
+    rng: &mut R,
+    loot: &mut Loot,
+    depth: u32,
+) {
+    match drop {
+        LootDrop::Resource {
+            resource_type,
+            min,
+            max,
+        } => {
+            let amount = ResourceAmount(rng.gen_range(*min..=*max));
+            loot.resources += &Resources::of(*resource_type, amount);
+        }
+        LootDrop::Item { kind, min, max } => {
+            let count = rng.gen_range(*min..=*max);
+            if count > 0 {
+                loot.items.push(ItemStack { kind: *kind, count });
+            }
+        }
+        LootDrop::Spellbook => loot.spellbooks += 1,
+        LootDrop::Table(name) => {
+            if let Some(nested) = tables.iter().find(|table| table.name == *name) {
+                roll_table(tables, nested, rng, loot, depth + 1);
+            }
+        }
+        LootDrop::Nothing => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_loot_tables_load() {
+        let tables = load_loot_tables();
+        assert!(tables.iter().any(|table| table.name == "zombie"));
+    }
+
+    #[test]
+    fn nested_tables_and_guaranteed_drops_are_rolled() {
+        let mut outer = LootTable::new(&["table", "outer", "rolls", "2"]).unwrap();
+        outer.add_drop(&["always", "spellbook"]).unwrap();
+        outer.add_drop(&["weight", "1", "table", "inner"]).unwrap();
+        let mut inner = LootTable::new(&["table", "inner"]).unwrap();
+        inner
+            .add_drop(&["always", "resource", "gems", "2-3"])
+            .unwrap();
+        let loot = roll_loot(&[outer, inner], 0, &mut rand::thread_rng());
+        assert_eq!(loot.spellbooks, 1);
+        let gems = loot.resources.get_resource_amount(ResourceType::Gems).0;
+        assert!((4..=6).contains(&gems));
+    }
+}
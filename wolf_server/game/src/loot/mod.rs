@@ -1,7 +1,64 @@
+use crate::abilities::SpellbookSystem;
+use crate::damage::DeathSignalListener;
 use crate::game::*;
+use crate::inventory::InventorySystem;
+use crate::resources::create_resource_pickup;
 
+mod loot_table;
+pub use loot_table::*;
+
+/*
+Creatures and harvestables name a loot table in data/loot, and whatever it rolls
+is dropped as pickups where they die: resources in a single pickup, items one pickup per stack,
+and spellbooks to be absorbed.
+*/
+
+pub struct LootSystem {
+    pub loot_tables: Vec<LootTable>,
+}
+
+impl LootSystem {
+    pub fn new() -> Self {
+        LootSystem {
+            loot_tables: load_loot_tables(),
+        }
+    }
+    pub fn has_table(&self, table_name: &str) -> bool {
+        self.loot_tables
+            .iter()
+            .any(|table| table.name == table_name)
+    }
+    pub fn get_table_index(&self, table_name: &str) -> usize {
+        self.loot_tables
+            .iter()
+            .position(|table| table.name == table_name)
+            .expect("Unknown loot table!")
+    }
+    pub fn roll(game: &Game, table_index: usize) -> Loot {
+        roll_loot(
+            &game.loot_system.loot_tables,
+            table_index,
+            &mut rand::thread_rng(),
+        )
+    }
+}
+
+impl Loot {
+    pub fn drop_at(self, game: &mut Game, coords: PixelCoords) {
+        create_resource_pickup(game, coords, self.resources);
+        for stack in self.items {
+            InventorySystem::create_item_pickup(game, coords, stack, None);
+        }
+        for _ in 0..self.spellbooks {
+            SpellbookSystem::create_spellbook(game, coords);
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct LootComponent {
     component_id: ComponentId,
+    table_index: usize,
 }
 
 impl Component for LootComponent {
@@ -9,15 +66,33 @@ impl Component for LootComponent {
         self.component_id
     }
     fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
+        owner_id.remove_death_signal_listener(game, self.component_id);
+    }
+}
+
+impl DeathSignalListener for LootComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_death_signal(&self, game: &mut Game, owner_id: GameObjectId) {
         let coords = owner_id.get_coords_game(game);
-        crate::abilities::SpellbookSystem::create_spellbook(game, coords);
+        LootSystem::roll(game, self.table_index).drop_at(game, coords);
     }
 }
 
 impl LootComponent {
-    pub fn add_to(game: &mut Game, owner_id: GameObjectId) {
+    pub fn get_table_name<'a>(&self, game: &'a Game) -> &'a str {
+        &game.loot_system.loot_tables[self.table_index].name
+    }
+    pub fn add_to(game: &mut Game, owner_id: GameObjectId, table_name: &str) -> ComponentId {
         let component_id = game.get_id();
-        let comp = LootComponent { component_id };
+        let table_index = game.loot_system.get_table_index(table_name);
+        let comp = LootComponent {
+            component_id,
+            table_index,
+        };
+        owner_id.add_death_signal_listener(game, comp.clone());
         owner_id.add_component(game, comp);
+        component_id
     }
 }
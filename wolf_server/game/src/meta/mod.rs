@@ -1,6 +1,7 @@
 use crate::game::*;
 
-struct ComponentListComponent {
+// Groups components so they can be removed together
+pub struct ComponentListComponent {
     component_id: ComponentId,
     managed_component_ids: Vec<ComponentId>,
 }
@@ -17,6 +18,20 @@ impl Component for ComponentListComponent {
 }
 
 impl ComponentListComponent {
+    // For components that have already been added
+    pub fn add_managing(
+        game: &mut Game,
+        owner: GameObjectId,
+        managed_component_ids: Vec<ComponentId>,
+    ) -> ComponentId {
+        let component_id = game.get_id();
+        let comp = ComponentListComponent {
+            component_id,
+            managed_component_ids,
+        };
+        owner.add_component(game, comp);
+        component_id
+    }
     fn add_to(
         game: &mut Game,
         owner: GameObjectId,
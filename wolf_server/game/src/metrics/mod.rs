@@ -0,0 +1,220 @@
+use crate::game::*;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/*
+Numbers worth graphing, kept the way Prometheus wants them: every metric has a name, help text
+and one value per set of labels. Counters only go up, gauges are sampled at the end of each tick.
+render gives the text format that gets scraped.
+*/
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum MetricKind {
+    Counter,
+    Gauge,
+}
+
+struct Metric {
+    help: &'static str,
+    kind: MetricKind,
+    // Keyed by the rendered labels, e.g. {system="Ai"}
+    values: BTreeMap<String, f64>,
+}
+
+pub struct MetricsSystem {
+    metrics: BTreeMap<&'static str, Metric>,
+}
+
+impl MetricsSystem {
+    pub fn new() -> Self {
+        MetricsSystem {
+            metrics: BTreeMap::new(),
+        }
+    }
+    pub fn set_gauge(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        *self.get_value_mut(name, help, MetricKind::Gauge, labels) = value;
+    }
+    pub fn add_counter(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        amount: f64,
+    ) {
+        *self.get_value_mut(name, help, MetricKind::Counter, labels) += amount;
+    }
+    pub fn get(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        self.metrics
+            .get(name)?
+            .values
+            .get(&render_labels(labels))
+            .copied()
+    }
+    // For series whose labels won't come up again, like a player who's left
+    pub fn remove_series(&mut self, name: &str, labels: &[(&str, &str)]) {
+        if let Some(metric) = self.metrics.get_mut(name) {
+            metric.values.remove(&render_labels(labels));
+        }
+    }
+    fn get_value_mut(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        kind: MetricKind,
+        labels: &[(&str, &str)],
+    ) -> &mut f64 {
+        let metric = self.metrics.entry(name).or_insert(Metric {
+            help,
+            kind,
+            values: BTreeMap::new(),
+        });
+        if metric.kind != kind {
+            panic!("{} is used as both a counter and a gauge", name);
+        }
+        metric.values.entry(render_labels(labels)).or_insert(0.0)
+    }
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        for (name, metric) in self.metrics.iter() {
+            let kind = match metric.kind {
+                MetricKind::Counter => "counter",
+                MetricKind::Gauge => "gauge",
+            };
+            writeln!(text, "# HELP {} {}", name, metric.help).unwrap();
+            writeln!(text, "# TYPE {} {}", name, kind).unwrap();
+            for (labels, value) in metric.values.iter() {
+                writeln!(text, "{}{} {}", name, labels, value).unwrap();
+            }
+        }
+        text
+    }
+    pub fn step(game: &mut Game) {
+        let id_map_sizes = [
+            ("game_objects", game.game_objects.len()),
+            ("players", game.player_system.players.len()),
+            ("minds", game.behaviour_system.minds.len()),
+            ("damageables", game.damage_system.damageables.len()),
+            ("projectiles", game.damage_system.projectiles.len()),
+            ("statuses", game.status_system.statuses.len()),
+            ("perceivers", game.perception_system.perceivers.len()),
+            ("threat_tables", game.threat_system.threat_tables.len()),
+            ("inventories", game.inventory_system.inventories.len()),
+            ("speeches", game.speeches.len()),
+            ("chunk_loaders", game.terrain.chunk_loaders.len()),
+            ("chunk_watchers", game.terrain.chunk_watchers.len()),
+            ("terrain_sprites", game.terrain.terrain_sprites.len()),
+        ];
+        let loaded_chunks = game.terrain.chunks.len();
+        let timings = game.scheduler.get_timings();
+        let planner_nodes = game.ai_system.get_planner_node_count();
+
+        let metrics = &mut game.metrics_system;
+        metrics.set_gauge(
+            "wolf_tick",
+            "The current tick",
+            &[],
+            game.tick_counter as f64,
+        );
+        for (map, size) in id_map_sizes.iter() {
+            metrics.set_gauge(
+                "wolf_id_map_entries",
+                "How many entries are in each IdMap",
+                &[("map", map)],
+                *size as f64,
+            );
+        }
+        metrics.set_gauge(
+            "wolf_loaded_chunks",
+            "How many terrain chunks are loaded",
+            &[],
+            loaded_chunks as f64,
+        );
+        // Set rather than added to, the AI system keeps the running total
+        *metrics.get_value_mut(
+            "wolf_planner_nodes_total",
+            "How many nodes the AI planner has expanded",
+            MetricKind::Counter,
+            &[],
+        ) = planner_nodes as f64;
+        for (label, timing) in timings {
+            let system = format!("{:?}", label);
+            // Set rather than added to, the scheduler already keeps the running total
+            *metrics.get_value_mut(
+                "wolf_system_seconds_total",
+                "Time spent running each system",
+                MetricKind::Counter,
+                &[("system", &system)],
+            ) = timing.total.as_secs_f64();
+            metrics.set_gauge(
+                "wolf_system_last_seconds",
+                "How long each system took the last time it ran",
+                &[("system", &system)],
+                timing.last.as_secs_f64(),
+            );
+        }
+    }
+}
+
+fn render_labels(labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{}=\"{}\"", name, value)
+        })
+        .collect();
+    format!("{{{}}}", labels.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_render_in_the_prometheus_text_format() {
+        let mut metrics = MetricsSystem::new();
+        metrics.add_counter("sent_total", "Bytes sent", &[("player", "1")], 10.0);
+        metrics.add_counter("sent_total", "Bytes sent", &[("player", "1")], 5.0);
+        metrics.add_counter("sent_total", "Bytes sent", &[("player", "2")], 1.0);
+        metrics.set_gauge("chunks", "Chunks", &[], 3.0);
+        metrics.set_gauge("chunks", "Chunks", &[], 4.0);
+        metrics.set_gauge("quoted", "Quoted", &[("name", "a \"b\"")], 1.0);
+        assert_eq!(metrics.get("sent_total", &[("player", "1")]), Some(15.0));
+        assert_eq!(
+            metrics.render(),
+            "# HELP chunks Chunks\n\
+             # TYPE chunks gauge\n\
+             chunks 4\n\
+             # HELP quoted Quoted\n\
+             # TYPE quoted gauge\n\
+             quoted{name=\"a \\\"b\\\"\"} 1\n\
+             # HELP sent_total Bytes sent\n\
+             # TYPE sent_total counter\n\
+             sent_total{player=\"1\"} 15\n\
+             sent_total{player=\"2\"} 1\n"
+        );
+        metrics.remove_series("sent_total", &[("player", "1")]);
+        assert_eq!(metrics.get("sent_total", &[("player", "1")]), None);
+        assert!(!metrics.render().contains("player=\"1\""));
+        assert_eq!(metrics.get("sent_total", &[("player", "2")]), Some(1.0));
+
+        let mut game = Game::new();
+        game.step();
+        assert!(game
+            .metrics_system
+            .get("wolf_system_seconds_total", &[("system", "Ai")])
+            .is_some());
+    }
+}
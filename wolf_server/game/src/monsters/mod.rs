@@ -8,14 +8,19 @@ pub use wolf::*;
 
 pub struct Monsters {
     wolf_system: WolfSystem,
+    spawner: Spawner,
 }
 
 impl Monsters {
     pub fn new() -> Self {
         Monsters {
             wolf_system: WolfSystem::new(),
+            spawner: Spawner::new(),
         }
     }
+    pub fn has_wolf_pack(&self, pack_name: &str) -> bool {
+        self.wolf_system.has_pack(pack_name)
+    }
     pub fn step(game: &mut Game) {
         Spawner::step(game);
         WolfSystem::step(game);
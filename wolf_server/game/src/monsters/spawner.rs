@@ -1,18 +1,483 @@
+use crate::biomes::ravine::is_in_ravine;
+use crate::biomes::{get_generated_terrain_type, TerrainType};
 use crate::game::*;
-use crate::monsters::add_zombie;
+use crate::monsters::{add_zombie, WolfLeader};
+use crate::wildlife::create_hopper_creature;
 use rand::Rng;
+use std::f64::consts::PI;
 
-const SPAWN_WIDTH: i32 = 200;
-const ZOMBIE_SPAWN_EVERY: u32 = 50000;
+/*
+Every so often we try to spawn something somewhere around each player.
+What comes depends on the biome of the spot chosen, and the time of day,
+and anything spawned gets tidied away once players have been gone for a while.
+*/
 
-pub struct Spawner {}
+const SPAWN_TABLES_FILE: &str = include_str!("../../data/spawning/spawn_tables.txt");
+
+const SPAWN_INTERVAL: u32 = 50;
+const MIN_SPAWN_RADIUS: f64 = 300.0;
+// Also the size of the area around each player that area caps count over
+const SPAWN_RADIUS: f64 = 1500.0;
+const DESPAWN_DISTANCE: f64 = 2000.0;
+const DESPAWN_AFTER: u32 = 3000;
+pub const DAY_LENGTH: u32 = 24000;
+const WOLF_PACK_NAME: &str = "desert";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TimeOfDay {
+    Day,
+    Night,
+}
+
+impl TimeOfDay {
+    pub fn at(tick_counter: u32) -> Self {
+        if tick_counter % DAY_LENGTH < DAY_LENGTH / 2 {
+            TimeOfDay::Day
+        } else {
+            TimeOfDay::Night
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpawnBiome {
+    Grass,
+    Dirt,
+    Water,
+    Sand,
+    Caves,
+    Ravine,
+}
+
+impl SpawnBiome {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "grass" => Some(SpawnBiome::Grass),
+            "dirt" => Some(SpawnBiome::Dirt),
+            "water" => Some(SpawnBiome::Water),
+            "sand" => Some(SpawnBiome::Sand),
+            "caves" => Some(SpawnBiome::Caves),
+            "ravine" => Some(SpawnBiome::Ravine),
+            _ => None,
+        }
+    }
+    // None where nothing should spawn, like inside rock or buildings
+    pub fn at(game: &Game, square: SquareCoords) -> Option<Self> {
+        if Some(square.get_plane()) == game.biome_system.caves_plane {
+            return Some(SpawnBiome::Caves);
+        }
+        if square.get_plane() != Plane(0) {
+            return None;
+        }
+        if game.biome_system.has_ravine && is_in_ravine(square.into()) {
+            return Some(SpawnBiome::Ravine);
+        }
+        match get_generated_terrain_type(game, square) {
+            TerrainType::Rock => None,
+            TerrainType::Water => Some(SpawnBiome::Water),
+            TerrainType::Grass => Some(SpawnBiome::Grass),
+            TerrainType::Dirt => Some(SpawnBiome::Dirt),
+            TerrainType::Sand => Some(SpawnBiome::Sand),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpawnCreature {
+    Hopper,
+    Zombie,
+    WolfPack,
+}
+
+impl SpawnCreature {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "hopper" => Some(SpawnCreature::Hopper),
+            "zombie" => Some(SpawnCreature::Zombie),
+            "wolf_pack" => Some(SpawnCreature::WolfPack),
+            _ => None,
+        }
+    }
+    fn spawn(&self, game: &mut Game, coords: PixelCoords) -> Result<GameObjectId, String> {
+        match self {
+            SpawnCreature::Hopper => create_hopper_creature(game, coords),
+            SpawnCreature::Zombie => add_zombie(game, coords),
+            SpawnCreature::WolfPack => Ok(WolfLeader::create(game, coords, WOLF_PACK_NAME)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpawnEntry {
+    pub biome: SpawnBiome,
+    pub creature: SpawnCreature,
+    pub weight: f64,
+    pub chunk_cap: usize,
+    pub area_cap: usize,
+    pub min_player_distance: f64,
+    pub day_multiplier: f64,
+    pub night_multiplier: f64,
+}
+
+impl SpawnEntry {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let biome_name = words.next().ok_or("Entry has no biome")?;
+        let biome =
+            SpawnBiome::from_name(biome_name).ok_or(format!("Unknown biome: {}", biome_name))?;
+        let creature_name = words.next().ok_or("Entry has no creature")?;
+        let creature = SpawnCreature::from_name(creature_name)
+            .ok_or(format!("Unknown creature: {}", creature_name))?;
+        let mut entry = SpawnEntry {
+            biome,
+            creature,
+            weight: 1.0,
+            chunk_cap: 1,
+            area_cap: 1,
+            min_player_distance: MIN_SPAWN_RADIUS,
+            day_multiplier: 1.0,
+            night_multiplier: 1.0,
+        };
+        let values: Vec<&str> = words.collect();
+        for pair in values.chunks(2) {
+            let value = pair.get(1).ok_or(format!("{} has no value", pair[0]))?;
+            let bad_value = || format!("Bad value for {}: {}", pair[0], value);
+            match pair[0] {
+                "weight" => entry.weight = value.parse().map_err(|_| bad_value())?,
+                "chunk_cap" => entry.chunk_cap = value.parse().map_err(|_| bad_value())?,
+                "area_cap" => entry.area_cap = value.parse().map_err(|_| bad_value())?,
+                "min_distance" => {
+                    entry.min_player_distance = value.parse().map_err(|_| bad_value())?
+                }
+                "day" => entry.day_multiplier = value.parse().map_err(|_| bad_value())?,
+                "night" => entry.night_multiplier = value.parse().map_err(|_| bad_value())?,
+                other => return Err(format!("Unknown setting: {}", other)),
+            }
+        }
+        Ok(entry)
+    }
+    pub fn get_weight(&self, time_of_day: TimeOfDay) -> f64 {
+        match time_of_day {
+            TimeOfDay::Day => self.weight * self.day_multiplier,
+            TimeOfDay::Night => self.weight * self.night_multiplier,
+        }
+    }
+}
+
+pub fn load_spawn_tables() -> Vec<SpawnEntry> {
+    SPAWN_TABLES_FILE
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        .map(|line| match SpawnEntry::parse(line) {
+            Ok(entry) => entry,
+            Err(error) => panic!("Failed to load spawn table entry {}: {}", line, error),
+        })
+        .collect()
+}
+
+pub struct SpawnedCreature {
+    creature: SpawnCreature,
+    last_near_player: u32,
+}
+
+pub struct Spawner {
+    entries: Vec<SpawnEntry>,
+    spawned: IdMap<GameObjectId, SpawnedCreature>,
+}
 
 impl Spawner {
+    pub fn new() -> Self {
+        Spawner {
+            entries: load_spawn_tables(),
+            spawned: IdMap::new(),
+        }
+    }
+    fn get_player_coords(game: &Game) -> Vec<PixelCoords> {
+        game.player_system
+            .players
+            .iter()
+            .map(|(_id, player)| player.last_view_coords)
+            .collect()
+    }
     pub fn step(game: &mut Game) {
-        if (game.tick_counter + 1) % ZOMBIE_SPAWN_EVERY == 0 {
-            let x = rand::thread_rng().gen_range(-SPAWN_WIDTH..SPAWN_WIDTH);
-            let y = rand::thread_rng().gen_range(-SPAWN_WIDTH..SPAWN_WIDTH);
-            add_zombie(game, PixelCoords::new_to_fixed(Plane(0), x, y));
+        if game.tick_counter % SPAWN_INTERVAL != 0 {
+            return;
+        }
+        let player_coords = Spawner::get_player_coords(game);
+        Spawner::despawn_abandoned(game, &player_coords);
+        for coords in player_coords.iter() {
+            Spawner::try_spawn_near(game, *coords, &player_coords);
+        }
+    }
+    fn try_spawn_near(game: &mut Game, near: PixelCoords, player_coords: &[PixelCoords]) {
+        let direction = Angle::enforce_range(rand::thread_rng().gen_range(0.0..2.0 * PI));
+        let distance = rand::thread_rng().gen_range(MIN_SPAWN_RADIUS..SPAWN_RADIUS);
+        let spawn_coords = near.offset_direction(direction, distance);
+        let square: SquareCoords = spawn_coords.into();
+        // Only spawn onto open ground that's actually there
+        if game.terrain.get_chunk_square(square).is_none() || game.terrain.is_square_solid(square) {
+            return;
+        }
+        let biome = match SpawnBiome::at(game, square) {
+            Some(biome) => biome,
+            None => return,
+        };
+        let time_of_day = TimeOfDay::at(game.tick_counter);
+        let candidates: Vec<(&SpawnEntry, f64)> = game
+            .monsters
+            .spawner
+            .entries
+            .iter()
+            .filter(|entry| entry.biome == biome)
+            .map(|entry| (entry, entry.get_weight(time_of_day)))
+            .filter(|(_entry, weight)| *weight > 0.0)
+            .collect();
+        let total_weight: f64 = candidates.iter().map(|(_entry, weight)| weight).sum();
+        if total_weight <= 0.0 {
+            return;
+        }
+        let mut roll = rand::thread_rng().gen_range(0.0..total_weight);
+        let mut chosen = candidates[candidates.len() - 1].0;
+        for (entry, weight) in candidates.iter() {
+            if roll < *weight {
+                chosen = entry;
+                break;
+            }
+            roll -= weight;
+        }
+        if !Spawner::has_room_for(game, chosen, spawn_coords, near, player_coords) {
+            return;
+        }
+        let creature = chosen.creature;
+        let id = match creature.spawn(game, spawn_coords) {
+            Ok(id) => id,
+            Err(error) => {
+                tracing::warn!(?creature, %error, "Couldn't spawn creature");
+                return;
+            }
+        };
+        game.monsters.spawner.spawned.insert(
+            id,
+            SpawnedCreature {
+                creature,
+                last_near_player: game.tick_counter,
+            },
+        );
+    }
+    // Whether the entry is far enough from every player, and under its caps around the spot and the player it's near
+    fn has_room_for(
+        game: &Game,
+        entry: &SpawnEntry,
+        spawn_coords: PixelCoords,
+        near: PixelCoords,
+        player_coords: &[PixelCoords],
+    ) -> bool {
+        let too_close_to_player = player_coords.iter().any(|coords| {
+            coords.get_plane() == spawn_coords.get_plane()
+                && coords.get_distance_to(&spawn_coords) < entry.min_player_distance
+        });
+        if too_close_to_player {
+            return false;
+        }
+        let chunk: TerrainChunkCoords = spawn_coords.into();
+        let mut in_chunk = 0;
+        let mut in_area = 0;
+        for (id, spawned) in game.monsters.spawner.spawned.iter() {
+            if spawned.creature != entry.creature {
+                continue;
+            }
+            if let Some(coords) = id.get_coords_game_safe(game) {
+                if TerrainChunkCoords::from(coords) == chunk {
+                    in_chunk += 1;
+                }
+                if coords.get_plane() == near.get_plane()
+                    && coords.get_distance_to(&near) < SPAWN_RADIUS
+                {
+                    in_area += 1;
+                }
+            }
+        }
+        in_chunk < entry.chunk_cap && in_area < entry.area_cap
+    }
+    fn despawn_abandoned(game: &mut Game, player_coords: &[PixelCoords]) {
+        let tick_counter = game.tick_counter;
+        let mut to_forget = Vec::new();
+        let mut to_despawn = Vec::new();
+        let game_objects = &game.game_objects;
+        for (id, spawned) in game.monsters.spawner.spawned.iter_mut() {
+            let coords = match id.get_coords_safe(game_objects) {
+                Some(coords) => coords,
+                None => {
+                    to_forget.push(id);
+                    continue;
+                }
+            };
+            let player_nearby = player_coords.iter().any(|player_coords| {
+                player_coords.get_plane() == coords.get_plane()
+                    && player_coords.get_distance_to(&coords) < DESPAWN_DISTANCE
+            });
+            if player_nearby {
+                spawned.last_near_player = tick_counter;
+            } else if spawned.last_near_player + DESPAWN_AFTER <= tick_counter {
+                to_despawn.push(id);
+            }
+        }
+        for id in to_forget {
+            game.monsters.spawner.spawned.remove(id);
+        }
+        for id in to_despawn {
+            let spawned = game.monsters.spawner.spawned.remove(id).unwrap();
+            match spawned.creature {
+                SpawnCreature::WolfPack => WolfLeader::despawn(game, id),
+                _ => id.remove(game),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monsters::WolfSystem;
+
+    #[test]
+    fn all_spawn_tables_load() {
+        let entries = load_spawn_tables();
+        assert!(entries.iter().any(
+            |entry| entry.biome == SpawnBiome::Grass && entry.creature == SpawnCreature::Hopper
+        ));
+    }
+
+    #[test]
+    fn night_only_creatures_never_spawn_in_the_day() {
+        let entry = SpawnEntry::parse("grass zombie weight 2 day 0 night 1.5").unwrap();
+        assert_eq!(entry.get_weight(TimeOfDay::Day), 0.0);
+        assert_eq!(entry.get_weight(TimeOfDay::Night), 3.0);
+        assert_eq!(TimeOfDay::at(DAY_LENGTH / 2), TimeOfDay::Night);
+    }
+
+    fn at(x: f64, y: f64) -> PixelCoords {
+        PixelCoords::new_to_fixed(Plane(0), x, y)
+    }
+
+    fn add_spawned(game: &mut Game, creature: SpawnCreature, coords: PixelCoords) -> GameObjectId {
+        let id = GameObject::create_game(game, coords);
+        game.monsters.spawner.spawned.insert(
+            id,
+            SpawnedCreature {
+                creature,
+                last_near_player: game.tick_counter,
+            },
+        );
+        id
+    }
+
+    #[test]
+    fn chunk_caps_only_count_the_same_creature_in_the_same_chunk() {
+        let mut game = Game::new();
+        let entry = SpawnEntry::parse("grass zombie chunk_cap 1 area_cap 5").unwrap();
+        let players = [at(0.0, 0.0)];
+        // Out in chunk (2, 0), beyond the area around the player
+        let spawn_coords = at(1600.0, 320.0);
+        let can_spawn =
+            |game: &Game| Spawner::has_room_for(game, &entry, spawn_coords, players[0], &players);
+        assert!(can_spawn(&game));
+        add_spawned(&mut game, SpawnCreature::Hopper, at(1610.0, 330.0));
+        add_spawned(&mut game, SpawnCreature::Zombie, at(2000.0, 320.0));
+        assert!(can_spawn(&game));
+        add_spawned(&mut game, SpawnCreature::Zombie, at(1610.0, 330.0));
+        assert!(!can_spawn(&game));
+    }
+
+    #[test]
+    fn area_caps_count_everything_around_the_player() {
+        let mut game = Game::new();
+        let entry = SpawnEntry::parse("grass zombie chunk_cap 5 area_cap 2").unwrap();
+        let players = [at(0.0, 0.0)];
+        let spawn_coords = at(1600.0, 320.0);
+        let can_spawn =
+            |game: &Game| Spawner::has_room_for(game, &entry, spawn_coords, players[0], &players);
+        add_spawned(&mut game, SpawnCreature::Zombie, at(500.0, 0.0));
+        add_spawned(&mut game, SpawnCreature::Zombie, at(-3000.0, 0.0));
+        assert!(can_spawn(&game));
+        add_spawned(&mut game, SpawnCreature::Zombie, at(0.0, 500.0));
+        assert!(!can_spawn(&game));
+    }
+
+    #[test]
+    fn nothing_spawns_too_close_to_any_player() {
+        let game = Game::new();
+        let entry = SpawnEntry::parse("grass zombie min_distance 800").unwrap();
+        let near = at(0.0, 0.0);
+        let players = [near, at(5000.0, 0.0)];
+        assert!(!Spawner::has_room_for(
+            &game,
+            &entry,
+            at(700.0, 0.0),
+            near,
+            &players
+        ));
+        assert!(Spawner::has_room_for(
+            &game,
+            &entry,
+            at(900.0, 0.0),
+            near,
+            &players
+        ));
+        let players = [near, at(1500.0, 0.0)];
+        assert!(!Spawner::has_room_for(
+            &game,
+            &entry,
+            at(900.0, 0.0),
+            near,
+            &players
+        ));
+    }
+
+    #[test]
+    fn creatures_despawn_once_players_have_been_away_long_enough() {
+        let mut game = Game::new();
+        let players = [at(0.0, 0.0)];
+        let abandoned = add_spawned(&mut game, SpawnCreature::Zombie, at(DESPAWN_DISTANCE, 0.0));
+        let watched = add_spawned(&mut game, SpawnCreature::Zombie, at(100.0, 0.0));
+        game.tick_counter = DESPAWN_AFTER - 1;
+        Spawner::despawn_abandoned(&mut game, &players);
+        assert!(game.to_delete.is_empty());
+        game.tick_counter = DESPAWN_AFTER;
+        Spawner::despawn_abandoned(&mut game, &players);
+        assert_eq!(game.to_delete, vec![abandoned]);
+        assert!(game.monsters.spawner.spawned.get(abandoned).is_none());
+        assert!(game.monsters.spawner.spawned.get(watched).is_some());
+    }
+
+    #[test]
+    fn wolf_packs_despawn_along_with_their_wave() {
+        let mut game = Game::new();
+        let target = GameObject::create_game(&mut game, at(5000.0, 0.0));
+        let leader = WolfLeader::create_hunting(&mut game, at(5500.0, 0.0), WOLF_PACK_NAME, target);
+        game.monsters.spawner.spawned.insert(
+            leader,
+            SpawnedCreature {
+                creature: SpawnCreature::WolfPack,
+                last_near_player: 0,
+            },
+        );
+        let before: Vec<GameObjectId> = game.game_objects.iter().map(|(id, _)| id).collect();
+        WolfSystem::step(&mut game);
+        let wave: Vec<GameObjectId> = game
+            .game_objects
+            .iter()
+            .map(|(id, _)| id)
+            .filter(|id| !before.contains(id))
+            .collect();
+        assert!(!wave.is_empty());
+        game.tick_counter = DESPAWN_AFTER;
+        Spawner::despawn_abandoned(&mut game, &[at(0.0, 0.0)]);
+        assert!(game.to_delete.contains(&leader));
+        for id in wave {
+            assert!(game.to_delete.contains(&id));
         }
+        assert!(!game.to_delete.contains(&target));
     }
 }
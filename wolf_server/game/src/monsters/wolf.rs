@@ -2,10 +2,446 @@ use std::f64::consts::PI;
 
 use wolf_hash_map::WolfHashSet;
 
+use crate::perception::{PerceiverComponent, PerceptionSystem};
+use crate::threat::{ThreatSystem, ThreatTableComponent};
 use crate::{allegiance::AllegianceComponent, game::*};
+
+const WOLF_PACKS_FILE: &str = include_str!("../../data/monsters/wolf_packs.txt");
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WaveComposition {
+    pub chargers: u32,
+    pub dashers: u32,
+}
+
+impl WaveComposition {
+    pub fn parse(words: &[&str]) -> Result<Self, String> {
+        let mut composition = WaveComposition::default();
+        for pair in words.chunks(2) {
+            let count = pair
+                .get(1)
+                .ok_or(format!("{} has no count", pair[0]))?
+                .parse()
+                .map_err(|_| format!("Bad count for {}", pair[0]))?;
+            match pair[0] {
+                "charger" => composition.chargers = count,
+                "dasher" => composition.dashers = count,
+                other => return Err(format!("Unknown wolf type: {}", other)),
+            }
+        }
+        Ok(composition)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PackDefinition {
+    pub name: String,
+    pub scouts: u32,
+    pub waves: Vec<WaveComposition>,
+}
+
+pub fn load_pack_definitions() -> Vec<PackDefinition> {
+    let mut packs: Vec<PackDefinition> = Vec::new();
+    for line in WOLF_PACKS_FILE
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+    {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words[..] {
+            ["pack", name, "scouts", scouts] => packs.push(PackDefinition {
+                name: name.to_string(),
+                scouts: scouts
+                    .parse()
+                    .unwrap_or_else(|_| panic!("Bad scout count in wolf packs: {}", line)),
+                waves: Vec::new(),
+            }),
+            ["wave", ..] => {
+                let wave = WaveComposition::parse(&words[1..])
+                    .unwrap_or_else(|error| panic!("Failed to load wave {}: {}", line, error));
+                packs
+                    .last_mut()
+                    .expect("Wave before any pack in wolf packs")
+                    .waves
+                    .push(wave);
+            }
+            _ => panic!("Failed to load wolf packs line: {}", line),
+        }
+    }
+    packs
+}
+
+const SCOUT_SPEED: f64 = 6.0;
+const SCOUT_SIGHT_RANGE: f64 = 400.0;
+// Scouts that get this far from the leader without finding anything come back
+const SCOUT_RANGE: f64 = 1500.0;
+const REJOIN_PACK_DISTANCE: f64 = 60.0;
+const SCOUT_EVERY: u32 = 100;
+const WAVE_COOLDOWN: u32 = 300;
+// A wave retreats once it's down to this much of the health it set out with
+const RETREAT_HEALTH_PROPORTION: f64 = 0.35;
+const WAVE_SPAWN_SPREAD: f64 = 60.0;
+// Waves come at the target from around this much of a circle, centred on the leader's side
+const FLANK_ARC: f64 = 1.5 * PI;
+
+/*
+The pack leader hangs back and directs the hunt:
+it sends scouts out until one comes back with a target, then sends waves at that target one at a time,
+pulling each wave back if it gets badly hurt.
+*/
 pub struct WolfLeader {
+    pack_index: usize,
     scouts_remaining: u32,
     waves_remaining: u32,
+    target: Option<GameObjectId>,
+    wave: Vec<GameObjectId>,
+    wave_size: usize,
+    next_action_at: u32,
+}
+
+impl WolfLeader {
+    pub fn create(game: &mut Game, coords: PixelCoords, pack_name: &str) -> GameObjectId {
+        let pack_index = game
+            .monsters
+            .wolf_system
+            .pack_definitions
+            .iter()
+            .position(|pack| pack.name == pack_name)
+            .expect("Unknown wolf pack!");
+        let pack = &game.monsters.wolf_system.pack_definitions[pack_index];
+        let leader = WolfLeader {
+            pack_index,
+            scouts_remaining: pack.scouts,
+            waves_remaining: pack.waves.len() as u32,
+            target: None,
+            wave: Vec::new(),
+            wave_size: 0,
+            next_action_at: game.tick_counter,
+        };
+        let id = GameObject::create_game(game, coords);
+        add_wolf_body(game, id);
+        WalkerComponent::add_to(game, id, SOLDIER_SPEED, SOLDIER_SPEED);
+        game.monsters.wolf_system.leaders.insert(id, leader);
+        id
+    }
+    // A leader that already knows who it's after, so it goes straight to sending waves
+    pub fn create_hunting(
+        game: &mut Game,
+        coords: PixelCoords,
+        pack_name: &str,
+        target: GameObjectId,
+    ) -> GameObjectId {
+        let id = WolfLeader::create(game, coords, pack_name);
+        game.monsters
+            .wolf_system
+            .leaders
+            .get_mut(id)
+            .unwrap()
+            .target = Some(target);
+        id
+    }
+    // Removes the leader along with its wave and any scouts it has out.
+    // Waves that are already retreating vanish once they get back anyway
+    pub fn despawn(game: &mut Game, leader_id: GameObjectId) {
+        let mut members = game
+            .monsters
+            .wolf_system
+            .leaders
+            .remove(leader_id)
+            .map(|leader| leader.wave)
+            .unwrap_or_default();
+        members.extend(
+            game.monsters
+                .wolf_system
+                .scouts
+                .iter()
+                .filter(|(_id, scout)| scout.leader_id == leader_id)
+                .map(|(id, _scout)| id),
+        );
+        members.push(leader_id);
+        for id in members {
+            if !id.is_deleted(&game.game_objects) {
+                id.remove(game);
+            }
+        }
+    }
+    fn get_wave_health(game: &Game, wave: &[GameObjectId], wave_size: usize) -> f64 {
+        let remaining: f64 = wave
+            .iter()
+            .filter_map(|id| id.send_get_healthiness_signal(game))
+            .map(|healthiness| healthiness.0)
+            .sum();
+        remaining / wave_size.max(1) as f64
+    }
+    fn launch_wave(game: &mut Game, leader_id: GameObjectId, target: GameObjectId) {
+        let leader = game.monsters.wolf_system.leaders.get(leader_id).unwrap();
+        let pack = &game.monsters.wolf_system.pack_definitions[leader.pack_index];
+        let wave_index = pack.waves.len() - leader.waves_remaining as usize;
+        let composition = pack.waves[wave_index].clone();
+        let leader_coords = leader_id.get_coords_game(game);
+        let target_coords = target.get_coords_game(game);
+        // Fill in around whoever's already after the target
+        let already_attacking = game
+            .monsters
+            .wolf_system
+            .chargers_by_target
+            .get(target)
+            .map(|chargers| chargers.len())
+            .unwrap_or(0);
+        let attackers = already_attacking + composition.chargers as usize;
+        let towards_leader: f64 = target_coords.get_direction_to(&leader_coords).into();
+        let mut wave = Vec::new();
+        for i in 0..composition.chargers as usize {
+            let slot = (already_attacking + i) as f64 + 0.5;
+            let approach_angle = Angle::enforce_range(
+                towards_leader - FLANK_ARC / 2.0 + FLANK_ARC * slot / attackers as f64,
+            );
+            let spread = Angle::enforce_range(rand::thread_rng().gen_range(0.0..2.0 * PI));
+            let spawn_coords = leader_coords.offset_direction(spread, WAVE_SPAWN_SPREAD);
+            let charger_id = Charger::new(game, spawn_coords, target);
+            if let Some(charger) = game.monsters.wolf_system.chargers.get_mut(charger_id) {
+                charger.approach_angle = Some(approach_angle);
+            }
+            wave.push(charger_id);
+        }
+        // Dashers wait off to the sides and dash through the target
+        for _ in 0..composition.dashers {
+            let side = if rand::thread_rng().gen_bool(0.5) {
+                PI / 2.0
+            } else {
+                -PI / 2.0
+            };
+            let direction = Angle::enforce_range(towards_leader + side);
+            let spawn_coords = target_coords
+                .offset_direction(direction, SOLDIER_SPEED * SOLDIER_LIFETIME as f64 / 2.0);
+            wave.push(Dasher::create(
+                game,
+                spawn_coords,
+                target_coords,
+                Some(leader_id),
+            ));
+        }
+        let leader = game
+            .monsters
+            .wolf_system
+            .leaders
+            .get_mut(leader_id)
+            .unwrap();
+        leader.waves_remaining -= 1;
+        leader.wave_size = wave.len();
+        leader.wave = wave;
+    }
+    fn retreat_wave(game: &mut Game, leader_id: GameObjectId) {
+        let leader_coords = leader_id.get_coords_game(game);
+        let leader = game
+            .monsters
+            .wolf_system
+            .leaders
+            .get_mut(leader_id)
+            .unwrap();
+        let wave = std::mem::replace(&mut leader.wave, Vec::new());
+        leader.next_action_at = game.tick_counter + WAVE_COOLDOWN;
+        for id in wave {
+            Charger::retreat(game, id, leader_coords);
+            Dasher::retreat(game, id, leader_coords);
+        }
+    }
+    pub fn step(game: &mut Game) {
+        let tick_counter = game.tick_counter;
+        let mut to_forget = Vec::new();
+        let mut to_retreat = Vec::new();
+        let mut to_launch = Vec::new();
+        let mut to_scout = Vec::new();
+        let mut to_disband = Vec::new();
+        let game_objects = &game.game_objects;
+        for (id, leader) in game.monsters.wolf_system.leaders.iter_mut() {
+            let leader_coords = match id.get_coords_safe(game_objects) {
+                Some(coords) => coords,
+                None => {
+                    to_forget.push(id);
+                    continue;
+                }
+            };
+            leader
+                .wave
+                .retain(|member| !member.is_deleted(game_objects));
+            let target_lost = leader
+                .target
+                .and_then(|target| target.get_coords_safe(game_objects))
+                .map(|coords| coords.get_plane() != leader_coords.get_plane())
+                .unwrap_or(true);
+            if target_lost {
+                leader.target = None;
+            }
+            if !leader.wave.is_empty() {
+                if leader.target.is_none() {
+                    to_retreat.push(id);
+                }
+                continue;
+            }
+            if leader.next_action_at > tick_counter {
+                continue;
+            }
+            match leader.target {
+                Some(target) if leader.waves_remaining > 0 => to_launch.push((id, target)),
+                None if leader.scouts_remaining > 0 && leader.waves_remaining > 0 => {
+                    leader.scouts_remaining -= 1;
+                    leader.next_action_at = tick_counter + SCOUT_EVERY;
+                    to_scout.push((id, leader_coords));
+                }
+                _ => {
+                    let scouts_out = game
+                        .monsters
+                        .wolf_system
+                        .scouts
+                        .iter()
+                        .any(|(_scout_id, scout)| scout.leader_id == id);
+                    if !scouts_out {
+                        to_disband.push(id);
+                    }
+                }
+            }
+        }
+        for (id, leader) in game.monsters.wolf_system.leaders.iter() {
+            if !leader.wave.is_empty()
+                && WolfLeader::get_wave_health(game, &leader.wave, leader.wave_size)
+                    < RETREAT_HEALTH_PROPORTION
+            {
+                to_retreat.push(id);
+            }
+        }
+        for id in to_forget {
+            game.monsters.wolf_system.leaders.remove(id);
+        }
+        for id in to_retreat {
+            WolfLeader::retreat_wave(game, id);
+        }
+        for (id, target) in to_launch {
+            WolfLeader::launch_wave(game, id, target);
+        }
+        for (id, coords) in to_scout {
+            let direction = Angle::enforce_range(rand::thread_rng().gen_range(0.0..2.0 * PI));
+            Scout::create(game, id, coords, direction);
+        }
+        // Nothing left to do, so the leader slinks off
+        for id in to_disband {
+            game.monsters.wolf_system.leaders.remove(id);
+            id.remove(game);
+        }
+    }
+}
+
+struct Scout {
+    leader_id: GameObjectId,
+    direction: Angle,
+    returning: bool,
+    found: Option<GameObjectId>,
+}
+
+impl Scout {
+    fn create(game: &mut Game, leader_id: GameObjectId, coords: PixelCoords, direction: Angle) {
+        let id = GameObject::create_game(game, coords);
+        add_wolf_body(game, id);
+        PerceiverComponent::add_to(game, id);
+        WalkerComponent::add_to(game, id, SCOUT_SPEED, SCOUT_SPEED);
+        game.monsters.wolf_system.scouts.insert(
+            id,
+            Scout {
+                leader_id,
+                direction,
+                returning: false,
+                found: None,
+            },
+        );
+    }
+    fn step(game: &mut Game) {
+        let mut to_forget = Vec::new();
+        let mut to_report = Vec::new();
+        let mut found_prey = Vec::new();
+        let mut to_move = Vec::new();
+        for (id, scout) in game.monsters.wolf_system.scouts.iter() {
+            let coords = id.get_coords_safe(&game.game_objects);
+            let leader_coords = scout.leader_id.get_coords_safe(&game.game_objects);
+            let (coords, leader_coords) = match (coords, leader_coords) {
+                (Some(coords), Some(leader_coords))
+                    if coords.get_plane() == leader_coords.get_plane() =>
+                {
+                    (coords, leader_coords)
+                }
+                _ => {
+                    to_forget.push(id);
+                    continue;
+                }
+            };
+            let distance_to_leader = coords.get_distance_to(&leader_coords);
+            if scout.returning {
+                if distance_to_leader < REJOIN_PACK_DISTANCE {
+                    to_report.push(id);
+                } else {
+                    id.intend_move_to_point(
+                        &mut game.movement_system.intend_move_system,
+                        leader_coords,
+                    );
+                }
+                continue;
+            }
+            if let Some(prey_id) =
+                PerceptionSystem::get_closest_perceived_prey(game, id, SCOUT_SIGHT_RANGE)
+            {
+                let prey = game.hunting_system.preys.get(prey_id).unwrap();
+                found_prey.push((id, Some(prey.game_object_id)));
+            } else if distance_to_leader > SCOUT_RANGE {
+                found_prey.push((id, None));
+            } else {
+                to_move.push((id, scout.direction));
+            }
+        }
+        for (id, direction) in to_move {
+            id.intend_move_in_direction_minimal(
+                &mut game.movement_system.intend_move_system,
+                direction,
+            );
+        }
+        for (id, found) in found_prey {
+            let scout = game.monsters.wolf_system.scouts.get_mut(id).unwrap();
+            scout.returning = true;
+            scout.found = found;
+        }
+        for id in to_report {
+            let scout = game.monsters.wolf_system.scouts.remove(id).unwrap();
+            if let Some(leader) = game.monsters.wolf_system.leaders.get_mut(scout.leader_id) {
+                match scout.found {
+                    Some(found) => leader.target = Some(found),
+                    // Nothing out there, they can go again
+                    None => leader.scouts_remaining += 1,
+                }
+            }
+            // Back in the pack
+            id.remove(game);
+        }
+        for id in to_forget {
+            game.monsters.wolf_system.scouts.remove(id);
+            if !id.is_deleted(&game.game_objects) {
+                id.remove(game);
+            }
+        }
+    }
+}
+
+fn add_wolf_body(game: &mut Game, id: GameObjectId) {
+    AllegianceComponent::add_to(
+        game,
+        id,
+        vec![game.allegiance_system.special_allegiances.wolf_allegiance],
+    );
+    if let Err(error) = ResistancesComponent::add_to(game, id, "wolf") {
+        tracing::warn!("Wolf has no resistances: {}", error);
+    }
+    BasicDrawingComponent::add_to(game, id, WOLF_SPRITE, DEFAULT_DEPTH);
+    DamageableComponent::add_to(game, id);
+    add_health_bar(game, id);
+    DieOnNoHealthComponent::add_to(game, id);
+    DeleteOnDeathComponent::add_to(game, id);
 }
 
 /* Wolf types:
@@ -15,12 +451,10 @@ Charges fast across screen
 Wizard (dark rifts?)
 */
 
-impl WolfLeader {
-    pub fn step(game: &mut Game) {
-        //
-    }
-}
 pub struct WolfSystem {
+    pack_definitions: Vec<PackDefinition>,
+    leaders: IdMap<GameObjectId, WolfLeader>,
+    scouts: IdMap<GameObjectId, Scout>,
     dashers: IdMap<GameObjectId, Dasher>,
     chargers: IdMap<GameObjectId, Charger>,
     chargers_by_target: IdMap<GameObjectId, WolfHashSet<GameObjectId>>,
@@ -28,12 +462,22 @@ pub struct WolfSystem {
 impl WolfSystem {
     pub fn new() -> Self {
         WolfSystem {
+            pack_definitions: load_pack_definitions(),
+            leaders: IdMap::new(),
+            scouts: IdMap::new(),
             dashers: IdMap::new(),
             chargers: IdMap::new(),
             chargers_by_target: IdMap::new(),
         }
     }
+    pub fn has_pack(&self, pack_name: &str) -> bool {
+        self.pack_definitions
+            .iter()
+            .any(|pack| pack.name == pack_name)
+    }
     pub fn step(game: &mut Game) {
+        WolfLeader::step(game);
+        Scout::step(game);
         Dasher::step(game);
         Charger::step(game);
     }
@@ -42,39 +486,74 @@ impl WolfSystem {
 struct Dasher {
     direction: Angle,
     die_at: u32,
+    // The leader whose wave this dasher is part of
+    leader_id: Option<GameObjectId>,
+    retreat_to: Option<PixelCoords>,
 }
 
 const SOLDIER_SPEED: f64 = 4.0;
 const SOLDIER_LIFETIME: u32 = 50;
 impl Dasher {
-    fn create(game: &mut Game, spawn_coords: PixelCoords, target_coords: PixelCoords) {
+    fn create(
+        game: &mut Game,
+        spawn_coords: PixelCoords,
+        target_coords: PixelCoords,
+        leader_id: Option<GameObjectId>,
+    ) -> GameObjectId {
         let game_object_id = GameObject::create_game(game, spawn_coords);
         let direction = spawn_coords.get_direction_to(&target_coords);
         game_object_id.set_rotation(game, direction);
-        BasicDrawingComponent::add_to(game, game_object_id, WOLF_SPRITE, DEFAULT_DEPTH);
-        DamageableComponent::add_to(game, game_object_id);
-        DieOnNoHealthComponent::add_to(game, game_object_id);
-        DeleteOnDeathComponent::add_to(game, game_object_id);
+        add_wolf_body(game, game_object_id);
+        DamagerComponent::add_to(
+            game,
+            game_object_id,
+            game_object_id,
+            Some(1),
+            DEFAULT_HEALTH.0 / 8,
+            DamageType::Physical,
+        );
         WalkerComponent::add_to(game, game_object_id, SOLDIER_SPEED, SOLDIER_SPEED);
-        add_health_bar(game, game_object_id);
         game.monsters.wolf_system.dashers.insert(
             game_object_id,
             Dasher {
                 direction,
                 die_at: game.tick_counter + SOLDIER_LIFETIME,
+                leader_id,
+                retreat_to: None,
             },
         );
+        game_object_id
+    }
+    fn retreat(game: &mut Game, id: GameObjectId, retreat_to: PixelCoords) {
+        if let Some(dasher) = game.monsters.wolf_system.dashers.get_mut(id) {
+            dasher.retreat_to = Some(retreat_to);
+        }
     }
     fn step(game: &mut Game) {
         let mut to_delete = Vec::new();
         let mut to_kill = Vec::new();
         for (id, wolf) in game.monsters.wolf_system.dashers.iter() {
-            if wolf.die_at <= game.tick_counter {
-                to_kill.push(id);
+            if id.is_deleted(&game.game_objects) {
                 to_delete.push(id);
                 continue;
             }
-            if id.is_deleted(&game.game_objects) {
+            if let Some(retreat_to) = wolf.retreat_to {
+                let coords = id.get_coords(&game.game_objects);
+                if retreat_to.get_plane() != coords.get_plane()
+                    || coords.get_distance_to(&retreat_to) < REJOIN_PACK_DISTANCE
+                {
+                    to_kill.push((id, wolf.leader_id));
+                    to_delete.push(id);
+                } else {
+                    id.intend_move_to_point(
+                        &mut game.movement_system.intend_move_system,
+                        retreat_to,
+                    );
+                }
+                continue;
+            }
+            if wolf.die_at <= game.tick_counter {
+                to_kill.push((id, wolf.leader_id));
                 to_delete.push(id);
                 continue;
             }
@@ -83,7 +562,16 @@ impl Dasher {
                 wolf.direction,
             );
         }
-        for id in to_kill {
+        for (id, leader_id) in to_kill {
+            // Finished its dash rather than being killed, so it doesn't count against the wave
+            if let Some(leader) =
+                leader_id.and_then(|leader_id| game.monsters.wolf_system.leaders.get_mut(leader_id))
+            {
+                if let Some(index) = leader.wave.iter().position(|member| *member == id) {
+                    leader.wave.remove(index);
+                    leader.wave_size -= 1;
+                }
+            }
             id.remove(game);
         }
         for id in to_delete {
@@ -97,6 +585,9 @@ const CHARGER_CHARGE_DISTANCE: f64 = 200.0;
 const CHARGER_SEPARATION_DISTANCE: f64 = 200.0;
 pub struct Charger {
     target: Option<GameObjectId>,
+    // Which side to come at the target from, when sent as part of a wave
+    approach_angle: Option<Angle>,
+    retreat_to: Option<PixelCoords>,
 }
 impl Charger {
     pub fn new(
@@ -105,32 +596,63 @@ impl Charger {
         target: GameObjectId,
     ) -> GameObjectId {
         let id = GameObject::create_game(game, starting_coords);
-        AllegianceComponent::add_to(
+        add_wolf_body(game, id);
+        DamagerComponent::add_to(
             game,
             id,
-            vec![game.allegiance_system.special_allegiances.wolf_allegiance],
+            id,
+            None,
+            DEFAULT_HEALTH.0 / 8,
+            DamageType::Physical,
         );
-        DamagerComponent::add_to(game, id, id, None, DEFAULT_HEALTH.0 / 8);
-        BasicDrawingComponent::add_to(game, id, WOLF_SPRITE, DEFAULT_DEPTH);
-        DamageableComponent::add_to(game, id);
-        add_health_bar(game, id);
+        ThreatTableComponent::add_to(game, id);
         WalkerComponent::add_to(game, id, CHARGER_SPEED, CHARGER_SPEED / 32.0);
-        DieOnNoHealthComponent::add_to(game, id);
-        DeleteOnDeathComponent::add_to(game, id);
         game.monsters.wolf_system.chargers.insert(
             id,
             Charger {
-                target: Some(target),
+                target: None,
+                approach_angle: None,
+                retreat_to: None,
             },
         );
-        game.monsters
-            .wolf_system
-            .chargers_by_target
-            .entry(target)
-            .or_insert_with(WolfHashSet::new)
-            .insert(id);
+        Charger::set_target(game, id, Some(target));
         id
     }
+    fn set_target(game: &mut Game, id: GameObjectId, target: Option<GameObjectId>) {
+        let wolf_system = &mut game.monsters.wolf_system;
+        let charger = match wolf_system.chargers.get_mut(id) {
+            Some(charger) => charger,
+            None => return,
+        };
+        if let Some(old_target) = charger.target {
+            match wolf_system.chargers_by_target.entry(old_target) {
+                hash_map::Entry::Occupied(mut occ) => {
+                    let set = occ.get_mut();
+                    set.remove(&id);
+                    if set.is_empty() {
+                        occ.remove();
+                    }
+                }
+                hash_map::Entry::Vacant(_) => {
+                    panic!("Wolf had target but was not registered on target")
+                }
+            };
+        }
+        charger.target = target;
+        if let Some(target) = target {
+            wolf_system
+                .chargers_by_target
+                .entry(target)
+                .or_insert_with(WolfHashSet::new)
+                .insert(id);
+        }
+    }
+    fn retreat(game: &mut Game, id: GameObjectId, retreat_to: PixelCoords) {
+        Charger::set_target(game, id, None);
+        if let Some(charger) = game.monsters.wolf_system.chargers.get_mut(id) {
+            charger.retreat_to = Some(retreat_to);
+        }
+    }
     pub fn step(game: &mut Game) {
         /* Based on wolf hunting techniques, hunt via:
         1) Get to hunt distance to prey
@@ -138,6 +660,8 @@ impl Charger {
         3) Attack using anime style magic moves (See Hagrid 2006)
         */
         let mut to_delete = Vec::new();
+        let mut to_retarget = Vec::new();
+        let mut rejoined_pack = Vec::new();
         for (id, charger) in game.monsters.wolf_system.chargers.iter() {
             let charger_coords = match id.get_coords_safe(&game.game_objects) {
                 Some(x) => x,
@@ -146,6 +670,26 @@ impl Charger {
                     continue;
                 }
             };
+            if let Some(retreat_to) = charger.retreat_to {
+                if retreat_to.get_plane() != charger_coords.get_plane()
+                    || charger_coords.get_distance_to(&retreat_to) < REJOIN_PACK_DISTANCE
+                {
+                    rejoined_pack.push(id);
+                } else {
+                    id.intend_move_to_point(
+                        &mut game.movement_system.intend_move_system,
+                        retreat_to,
+                    );
+                }
+                continue;
+            }
+            // Turn on whoever's hurting the pack most, rather than whoever we were sent after
+            if let Some(threat_target) = ThreatSystem::get_valid_target(game, id) {
+                if charger.target != Some(threat_target) {
+                    to_retarget.push((id, threat_target));
+                    continue;
+                }
+            }
             if let Some(target) = charger.target {
                 if let Some(target_coords) = target.get_coords_safe(&game.game_objects) {
                     if target_coords.get_plane() != charger_coords.get_plane() {
@@ -156,7 +700,18 @@ impl Charger {
                     if distance < CHARGER_CHARGE_DISTANCE {
                         id.intend_follow(&mut game.movement_system.intend_move_system, target);
                     } else if distance > CHARGER_HUNT_DISTANCE {
-                        id.intend_follow(&mut game.movement_system.intend_move_system, target);
+                        match charger.approach_angle {
+                            // Get into position on our side before closing in
+                            Some(approach_angle) => id.intend_move_to_point(
+                                &mut game.movement_system.intend_move_system,
+                                target_coords
+                                    .offset_direction(approach_angle, CHARGER_HUNT_DISTANCE * 0.9),
+                            ),
+                            None => id.intend_follow(
+                                &mut game.movement_system.intend_move_system,
+                                target,
+                            ),
+                        }
                     } else {
                         let mut dx: f64 = 0.0;
                         let mut dy: f64 = 0.0;
@@ -217,23 +772,180 @@ impl Charger {
                 }
             }
         }
+        for (id, target) in to_retarget {
+            Charger::set_target(game, id, Some(target));
+        }
         for id in to_delete {
-            let charger = game.monsters.wolf_system.chargers.remove(id).unwrap();
-            if let Some(target) = charger.target {
-                match game.monsters.wolf_system.chargers_by_target.entry(target) {
-                    hash_map::Entry::Occupied(mut occ) => {
-                        let set = occ.get_mut();
-                        set.remove(&id);
-                        if set.is_empty() {
-                            occ.remove();
-                        }
-                    }
-                    hash_map::Entry::Vacant(_) => {
-                        panic!("Wolf died with target but not registered on target")
-                    }
-                };
-            }
+            Charger::set_target(game, id, None);
+            game.monsters.wolf_system.chargers.remove(id);
+        }
+        for id in rejoined_pack {
+            game.monsters.wolf_system.chargers.remove(id);
+            id.remove(game);
         }
     }
 }
 struct Wolfzard {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_wolf_packs_load() {
+        let packs = load_pack_definitions();
+        let desert = packs.iter().find(|pack| pack.name == "desert").unwrap();
+        assert!(!desert.waves.is_empty());
+    }
+
+    #[test]
+    fn waves_list_each_wolf_type() {
+        let wave = WaveComposition::parse(&["charger", "3", "dasher", "2"]).unwrap();
+        assert_eq!(
+            wave,
+            WaveComposition {
+                chargers: 3,
+                dashers: 2
+            }
+        );
+        assert!(WaveComposition::parse(&["wizard", "1"]).is_err());
+    }
+
+    fn create_hunt(game: &mut Game, waves_done: u32) -> (GameObjectId, GameObjectId) {
+        let target = GameObject::create_game(game, PixelCoords::new_to_fixed(Plane(0), 0.0, 0.0));
+        let leader = WolfLeader::create_hunting(
+            game,
+            PixelCoords::new_to_fixed(Plane(0), 0.0, -800.0),
+            "desert",
+            target,
+        );
+        game.monsters
+            .wolf_system
+            .leaders
+            .get_mut(leader)
+            .unwrap()
+            .waves_remaining -= waves_done;
+        WolfLeader::step(game);
+        (leader, target)
+    }
+
+    #[test]
+    fn hunting_leaders_send_waves_at_their_target_without_scouting() {
+        let mut game = Game::new();
+        let (leader, target) = create_hunt(&mut game, 0);
+        assert_eq!(game.monsters.wolf_system.scouts.len(), 0);
+        let wave = &game.monsters.wolf_system.leaders.get(leader).unwrap().wave;
+        assert_eq!(wave.len(), 4);
+        for id in wave.iter() {
+            let charger = game.monsters.wolf_system.chargers.get(*id).unwrap();
+            assert_eq!(charger.target, Some(target));
+        }
+    }
+
+    #[test]
+    fn waves_spread_out_around_the_leaders_side_of_the_target() {
+        let mut game = Game::new();
+        let (leader, target) = create_hunt(&mut game, 0);
+        let towards_leader: f64 = target
+            .get_coords_game(&game)
+            .get_direction_to(&leader.get_coords_game(&game))
+            .into();
+        let mut offsets: Vec<f64> = game
+            .monsters
+            .wolf_system
+            .leaders
+            .get(leader)
+            .unwrap()
+            .wave
+            .iter()
+            .map(|id| {
+                let charger = game.monsters.wolf_system.chargers.get(*id).unwrap();
+                let angle: f64 = charger.approach_angle.unwrap().into();
+                let offset = (angle - towards_leader).rem_euclid(2.0 * PI);
+                if offset > PI {
+                    offset - 2.0 * PI
+                } else {
+                    offset
+                }
+            })
+            .collect();
+        offsets.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let spacing = FLANK_ARC / offsets.len() as f64;
+        for offset in offsets.iter() {
+            assert!(offset.abs() < FLANK_ARC / 2.0);
+        }
+        for pair in offsets.windows(2) {
+            assert!((pair[1] - pair[0] - spacing).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn badly_hurt_waves_retreat_dashers_included() {
+        let mut game = Game::new();
+        // The second wave has both chargers and dashers
+        let (leader, target) = create_hunt(&mut game, 1);
+        let wave = game
+            .monsters
+            .wolf_system
+            .leaders
+            .get(leader)
+            .unwrap()
+            .wave
+            .clone();
+        assert_eq!(wave.len(), 7);
+        WolfLeader::step(&mut game);
+        assert_eq!(
+            game.monsters
+                .wolf_system
+                .leaders
+                .get(leader)
+                .unwrap()
+                .wave
+                .len(),
+            7
+        );
+
+        let chargers: Vec<GameObjectId> = wave
+            .iter()
+            .copied()
+            .filter(|id| game.monsters.wolf_system.chargers.get(*id).is_some())
+            .collect();
+        for id in chargers.iter() {
+            deal_damage(
+                &mut game,
+                *id,
+                target,
+                Health(DEFAULT_HEALTH.0 * 10),
+                DamageType::Physical,
+            );
+        }
+        WolfLeader::step(&mut game);
+        let leader_state = game.monsters.wolf_system.leaders.get(leader).unwrap();
+        assert!(leader_state.wave.is_empty());
+        assert_eq!(
+            leader_state.next_action_at,
+            game.tick_counter + WAVE_COOLDOWN
+        );
+        let retreating_dashers = game
+            .monsters
+            .wolf_system
+            .dashers
+            .iter()
+            .filter(|(_id, dasher)| dasher.retreat_to.is_some())
+            .count();
+        assert_eq!(retreating_dashers, 2);
+    }
+
+    #[test]
+    fn dashers_finishing_their_dash_dont_count_as_losses() {
+        let mut game = Game::new();
+        let (leader, _target) = create_hunt(&mut game, 1);
+        game.tick_counter += SOLDIER_LIFETIME;
+        Dasher::step(&mut game);
+        assert_eq!(game.monsters.wolf_system.dashers.len(), 0);
+        WolfLeader::step(&mut game);
+        let leader_state = game.monsters.wolf_system.leaders.get(leader).unwrap();
+        assert_eq!(leader_state.wave.len(), 5);
+        assert_eq!(leader_state.wave_size, 5);
+    }
+}
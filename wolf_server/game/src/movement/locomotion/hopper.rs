@@ -1,4 +1,5 @@
 use super::*;
+use wolf_hash_map::WolfHashMap;
 
 const HOP_PROGRESS_PER_TICK: f64 = 0.02;
 const HOP_HEIGHT: f64 = 36.0;
@@ -44,14 +45,28 @@ impl Hopper {
         hopper_id
     }
     pub fn step(game: &mut Game) {
+        let speed_multipliers: WolfHashMap<GameObjectId, f64> = game
+            .movement_system
+            .locomotion_system
+            .hoppers
+            .iter()
+            .map(|(_id, hopper)| {
+                (
+                    hopper.game_object_id,
+                    get_speed_multiplier(game, hopper.game_object_id),
+                )
+            })
+            .collect();
         let mut to_move = Vec::new();
         let mut to_sticky_face = Vec::new();
         let mut to_stop_sticky_face = Vec::new();
         for (_hopper_id, hopper) in game.movement_system.locomotion_system.hoppers.iter_mut() {
             let game_object = game.game_objects.get(hopper.game_object_id).unwrap();
             let start_position = game_object.coords.clone();
+            let speed_multiplier = *speed_multipliers.get(&hopper.game_object_id).unwrap();
             let mut should_hop = false;
-            if hopper.current_hop.is_none() {
+            // Anything slowed to a standstill lands its current hop but doesn't start another
+            if hopper.current_hop.is_none() && speed_multiplier > 0.0 {
                 if let Some(locomotion_mode_selection) =
                     game_object.components.get_locomotion_mode_selection()
                 {
@@ -84,7 +99,11 @@ impl Hopper {
                     };
                     if let Some(angle) = angle {
                         to_sticky_face.push((hopper.game_object_id, angle));
-                        let hop = Hop::from_angle(start_position, angle, 40.0 * hopper.speed);
+                        let hop = Hop::from_angle(
+                            start_position,
+                            angle,
+                            40.0 * hopper.speed * speed_multiplier,
+                        );
                         hopper.current_hop = Some(hop);
                     }
                 }
@@ -160,3 +179,32 @@ impl Component for HopperComponent {
         self.component_id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statuses::{StatusKind, StatusSystem};
+
+    fn create_hopper(game: &mut Game) -> GameObjectId {
+        let id = GameObject::create_game(game, PixelCoords::new_at_zero());
+        id.add_sticky_facer(game);
+        HopperComponent::add_to(game, id, 1.0);
+        id.intend_move_in_direction(game, Angle::assume_in_range(0.0));
+        id
+    }
+
+    #[test]
+    fn stunned_hoppers_stay_put() {
+        let mut game = Game::new();
+        let free = create_hopper(&mut game);
+        let stunned = create_hopper(&mut game);
+        StatusSystem::apply_status(&mut game, stunned, stunned, StatusKind::Stun, 1000, 0);
+        let start = PixelCoords::new_at_zero();
+        for _ in 0..100 {
+            MovementSystem::pre_movement(&mut game);
+            MovementSystem::movement(&mut game);
+            assert_eq!(stunned.get_coords(&game.game_objects), start);
+        }
+        assert!(free.get_coords(&game.game_objects).get_x() > start.get_x());
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use wolf_hash_map::WolfHashMap;
 
 pub struct Walker {
     pub max_speed: f64,
@@ -36,6 +37,18 @@ impl Walker {
     pub fn step(game: &mut Game) {
         let mut update_facing = Vec::new();
         let mut to_update_velocity = Vec::new();
+        let speed_multipliers: WolfHashMap<GameObjectId, f64> = game
+            .movement_system
+            .locomotion_system
+            .walkers
+            .iter()
+            .map(|(_id, walker)| {
+                (
+                    walker.game_object_id,
+                    get_speed_multiplier(game, walker.game_object_id),
+                )
+            })
+            .collect();
         for (id, walker) in game.movement_system.locomotion_system.walkers.iter_mut() {
             let game_object = game.game_objects.get(walker.game_object_id).unwrap();
             let mut should_walk = false;
@@ -69,10 +82,9 @@ impl Walker {
                         None => None,
                     };
                     if let Some(angle) = angle {
-                        let desired_vector = (
-                            walker.max_speed * angle.cos(),
-                            walker.max_speed * angle.sin(),
-                        );
+                        let max_speed = walker.max_speed
+                            * speed_multipliers.get(&walker.game_object_id).unwrap();
+                        let desired_vector = (max_speed * angle.cos(), max_speed * angle.sin());
                         let acceleration_required =
                             (desired_vector.0 - walker.dx, desired_vector.1 - walker.dy);
                         let required_magnitude =
@@ -93,7 +105,7 @@ impl Walker {
                         update_facing.push((walker.game_object_id, new_angle));
                         walker.dx = new_velocity.0;
                         walker.dy = new_velocity.1;
-                        to_update_velocity.push((walker.game_object_id, new_velocity));
+                        to_update_velocity.push((id, walker.game_object_id, new_velocity));
                     } else {
                         let current_velocity = vector_magnitude(walker.dx, walker.dy);
                         if current_velocity <= walker.acceleration {
@@ -105,7 +117,7 @@ impl Walker {
                             let new_velocity = (walker.dx * scaling, walker.dy * scaling);
                             walker.dx = new_velocity.0;
                             walker.dy = new_velocity.1;
-                            to_update_velocity.push((walker.game_object_id, new_velocity));
+                            to_update_velocity.push((id, walker.game_object_id, new_velocity));
                         }
                     }
                 }
@@ -114,10 +126,47 @@ impl Walker {
         for (game_object_id, angle) in update_facing {
             game_object_id.set_facing_sticky(game, angle);
         }
-        for (game_object_id, (dx, dy)) in to_update_velocity {
-            game_object_id.move_by_game(game, dx, dy)
+        for (walker_id, game_object_id, (dx, dy)) in to_update_velocity {
+            if Walker::is_blocked_by_water(game, game_object_id, dx, dy) {
+                let walker = game
+                    .movement_system
+                    .locomotion_system
+                    .walkers
+                    .get_mut(walker_id)
+                    .unwrap();
+                walker.dx = 0.0;
+                walker.dy = 0.0;
+            } else {
+                game_object_id.move_by_game(game, dx, dy)
+            }
         }
     }
+    // Walkers only cross deep water on bridges, but can wade out if they're already in it.
+    // Players and anything they're riding go wherever they're steered.
+    fn is_blocked_by_water(game: &Game, game_object_id: GameObjectId, dx: f64, dy: f64) -> bool {
+        if game
+            .player_system
+            .players_by_game_object
+            .get(game_object_id)
+            .is_some()
+            || game
+                .movement_system
+                .intend_move_system
+                .mounts_by_mounted
+                .contains_key(&game_object_id)
+        {
+            return false;
+        }
+        let hit_box = game_object_id.get_hit_box(game);
+        let current_squares = hit_box.get_overlapping_squares();
+        hit_box
+            .move_to(hit_box.coords.translate(dx, dy))
+            .get_overlapping_squares()
+            .into_iter()
+            .any(|square| {
+                !current_squares.contains(&square) && !game.terrain.is_square_walkable(square)
+            })
+    }
     fn remove(game: &mut Game, walker_id: WalkerId) {
         let walker = game
             .movement_system
@@ -162,3 +211,64 @@ impl Component for WalkerComponent {
         self.component_id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::{Chunk, TerrainSprite, TerrainSpriteComponent};
+
+    const RIVER_X: i64 = 5;
+
+    // Dirt around the origin with a river running north to south, bridged at y = 5
+    fn create_game() -> Game {
+        let mut game = Game::new();
+        for cx in -2..3 {
+            for cy in -2..3 {
+                let square = SquareCoords::new(
+                    Plane(0),
+                    cx * TERRAIN_CHUNK_SIZE_SQUARES,
+                    cy * TERRAIN_CHUNK_SIZE_SQUARES,
+                );
+                Chunk::generate(&mut game, square.into(), DIRT_SPRITE);
+            }
+        }
+        for y in -2 * TERRAIN_CHUNK_SIZE_SQUARES..3 * TERRAIN_CHUNK_SIZE_SQUARES {
+            game.terrain.modify_chunk_square(
+                SquareCoords::new(Plane(0), RIVER_X, y),
+                |chunk_square| {
+                    chunk_square.base_sprite = WATER_SPRITE;
+                },
+            );
+        }
+        let bridge = SquareCoords::new(Plane(0), RIVER_X, 5);
+        let bridge_id = GameObject::create_game(&mut game, bridge.center_pixel());
+        TerrainSpriteComponent::add_to(&mut game, bridge_id, bridge, BRIDGE_SPRITE);
+        TerrainSprite::step(&mut game);
+        game
+    }
+
+    fn create_walker(game: &mut Game, square: SquareCoords) -> GameObjectId {
+        let id = GameObject::create_game(game, square.center_pixel());
+        WalkerComponent::add_to(game, id, 4.0, 4.0);
+        id.intend_move_in_direction(game, Angle::assume_in_range(0.0));
+        id
+    }
+
+    fn get_square(game: &Game, id: GameObjectId) -> SquareCoords {
+        id.get_coords(&game.game_objects).into()
+    }
+
+    #[test]
+    fn walkers_cross_water_only_on_bridges() {
+        let mut game = create_game();
+        let crosses_bridge = create_walker(&mut game, SquareCoords::new(Plane(0), 2, 5));
+        let meets_water = create_walker(&mut game, SquareCoords::new(Plane(0), 2, 6));
+        for _ in 0..100 {
+            MovementSystem::pre_movement(&mut game);
+            MovementSystem::movement(&mut game);
+        }
+        assert!(get_square(&game, crosses_bridge).get_x() > RIVER_X);
+        assert_eq!(get_square(&game, meets_water).get_x(), RIVER_X - 1);
+        assert_eq!(get_square(&game, meets_water).get_y(), 6);
+    }
+}
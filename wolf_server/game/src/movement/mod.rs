@@ -1,6 +1,6 @@
-use crate::combinable::OrBool;
+use crate::define_signal_listener;
 use crate::game::*;
-use signal_listener_macro::define_signal_listener;
+use signal_listeners::OrBool;
 use std::collections::hash_map::*;
 use wolf_hash_map::*;
 
@@ -93,6 +93,10 @@ impl MovementSystem {
                 (old_coords, player_ids)
             };
             id.send_move_signal(game, &old_coords);
+            game.event_bus.publish(GameEvent::Moved {
+                id: *id,
+                old_coords,
+            });
             for player_id in player_ids {
                 let entry = player_updates.entry(player_id);
                 let set = entry.or_insert_with(WolfHashSet::new);
@@ -1,9 +1,18 @@
 use super::*;
-use crate::combinable::Added;
-use signal_listener_macro::define_signal_listener;
+use crate::define_signal_listener;
+use signal_listeners::Added;
 
 define_signal_listener!(GetSpeedMod, &Game -> Added<f64>);
 
+// Mods add together, so 1.0 doubles speed and -0.5 halves it. Speed never goes below zero.
+pub fn get_speed_multiplier(game: &Game, game_object_id: GameObjectId) -> f64 {
+    let total = game_object_id
+        .send_get_speed_mod_signal(game)
+        .map(|x| x.extract())
+        .unwrap_or(0.0);
+    (1.0 + total).max(0.0)
+}
+
 pub struct SpeedModComponentId(ComponentId);
 
 #[derive(Clone)]
@@ -16,9 +25,6 @@ impl GetSpeedModSignalListener for SpeedModComponent {
     fn receive_get_speed_mod_signal(&self, _game: &Game, _owner_id: GameObjectId) -> Added<f64> {
         Added(self.speed_mod)
     }
-    fn clone_box(&self) -> Box<dyn GetSpeedModSignalListener> {
-        Box::new(self.clone())
-    }
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
@@ -35,7 +35,9 @@ impl Necrobolt {
             }
         }
         for coords in zombie_spawns {
-            add_zombie(game, coords);
+            if let Err(error) = add_zombie(game, coords) {
+                tracing::warn!(%error, "Couldn't raise zombie");
+            }
         }
         for game_object_id in to_remove {
             game_object_id.remove(game);
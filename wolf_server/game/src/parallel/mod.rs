@@ -0,0 +1,172 @@
+use crate::game::Game;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+
+/*
+Game isn't Sync (listeners are Rc'd trait objects, components live in AnyMaps), so systems
+can't be handed to other threads whole. Instead a system splits its work in two: whatever
+needs the Game (signals, components) is gathered on the main thread, then the heavy part runs
+on the pool on plain data.
+
+Inside one system, map_in_parallel can borrow from the Game while it runs. Systems that
+implement ParallelSystem go further: their jobs own their data and they declare which parts
+of the Game they read and write, so the scheduler can gather several of them, run all their
+jobs on the pool at once, and then apply the results one system at a time.
+
+Results always come back in the order the jobs went in, so merging them is deterministic no
+matter which thread finishes first.
+*/
+
+// Below this it's not worth splitting the work up
+const MIN_ITEMS_PER_THREAD: usize = 4;
+
+// Started once and kept for the life of the server, so a tick never pays for spawning threads
+static POOL: Lazy<rayon::ThreadPool> = Lazy::new(|| {
+    rayon::ThreadPoolBuilder::new()
+        .thread_name(|index| format!("game-worker-{}", index))
+        .build()
+        .expect("Couldn't start the game's thread pool")
+});
+
+pub fn map_in_parallel<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync + Send,
+{
+    if items.len() < MIN_ITEMS_PER_THREAD * 2 {
+        return items.into_iter().map(f).collect();
+    }
+    POOL.install(|| {
+        items
+            .into_par_iter()
+            .with_min_len(MIN_ITEMS_PER_THREAD)
+            .map(f)
+            .collect()
+    })
+}
+
+// The parts of the Game a parallel system can touch. Add to this as more systems declare access.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameField {
+    // Components, and anything reached through signals
+    GameObjects,
+    Ais,
+    Statuses,
+    Players,
+    Collisions,
+    Terrain,
+    Events,
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Access {
+    reads: Vec<GameField>,
+    writes: Vec<GameField>,
+}
+
+impl Access {
+    pub fn new() -> Self {
+        Access::default()
+    }
+    pub fn read(mut self, field: GameField) -> Self {
+        self.reads.push(field);
+        self
+    }
+    pub fn write(mut self, field: GameField) -> Self {
+        self.writes.push(field);
+        self
+    }
+    // Writing something conflicts with anything else reading or writing it. Reads never conflict.
+    pub fn conflicts_with(&self, other: &Access) -> bool {
+        self.writes
+            .iter()
+            .any(|field| other.reads.contains(field) || other.writes.contains(field))
+            || other.writes.iter().any(|field| self.reads.contains(field))
+    }
+}
+
+pub trait ParallelSystem: 'static {
+    type Job: Send;
+    type Output: Send;
+    // Everything gather reads and apply writes
+    fn get_access() -> Access;
+    // On the main thread, before any jobs run
+    fn gather(game: &mut Game) -> Vec<Self::Job>;
+    // On the pool, without the Game
+    fn run(job: Self::Job) -> Self::Output;
+    // On the main thread, with outputs in the same order as the jobs
+    fn apply(game: &mut Game, outputs: Vec<Self::Output>);
+}
+
+// A parallel system's jobs between being gathered and applied, with the system's type erased
+// so the scheduler can hold several systems' jobs at once
+pub trait PendingJobs: Send {
+    fn run(&mut self);
+    fn apply(self: Box<Self>, game: &mut Game);
+}
+
+struct Pending<S: ParallelSystem> {
+    jobs: Vec<S::Job>,
+    outputs: Vec<S::Output>,
+}
+
+impl<S: ParallelSystem> PendingJobs for Pending<S> {
+    fn run(&mut self) {
+        let jobs = std::mem::replace(&mut self.jobs, Vec::new());
+        self.outputs = map_in_parallel(jobs, S::run);
+    }
+    fn apply(self: Box<Self>, game: &mut Game) {
+        S::apply(game, self.outputs);
+    }
+}
+
+pub fn gather_jobs<S: ParallelSystem>(game: &mut Game) -> Box<dyn PendingJobs> {
+    Box::new(Pending::<S> {
+        jobs: S::gather(game),
+        outputs: Vec::new(),
+    })
+}
+
+// Every system's jobs share the pool, rather than each system waiting on the one before
+pub fn run_jobs(pending: &mut Vec<Box<dyn PendingJobs>>) {
+    if pending.len() == 1 {
+        pending[0].run();
+        return;
+    }
+    POOL.install(|| pending.par_iter_mut().for_each(|jobs| jobs.run()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_keep_the_order_of_the_items() {
+        let items: Vec<u64> = (0..1000).collect();
+        let results = map_in_parallel(items, |i| {
+            // Make early items slower so they'd finish last if order depended on timing
+            let mut total = 0;
+            for j in 0..(1000 - i) * 10 {
+                total += j % 7;
+            }
+            (i, total)
+        });
+        let order: Vec<u64> = results.iter().map(|(i, _total)| *i).collect();
+        assert_eq!(order, (0..1000).collect::<Vec<u64>>());
+        assert_eq!(map_in_parallel(vec![3], |i| i * 2), vec![6]);
+    }
+
+    #[test]
+    fn only_writes_conflict() {
+        let reads_ais = Access::new().read(GameField::Ais);
+        let writes_ais = Access::new().write(GameField::Ais);
+        let writes_players = Access::new().read(GameField::Ais).write(GameField::Players);
+        assert!(!reads_ais.conflicts_with(&reads_ais));
+        assert!(reads_ais.conflicts_with(&writes_ais));
+        assert!(writes_ais.conflicts_with(&reads_ais));
+        assert!(writes_ais.conflicts_with(&writes_ais));
+        assert!(!writes_players.conflicts_with(&reads_ais));
+        assert!(writes_players.conflicts_with(&writes_ais));
+    }
+}
@@ -0,0 +1,296 @@
+use crate::game::*;
+use crate::hunting::HuntingSystem;
+
+mod perceiver;
+pub use perceiver::*;
+mod visibility;
+pub use visibility::*;
+
+/// How often perceivers look around them
+const PERCEPTION_EVERY: u32 = 10;
+/// How long a perceiver remembers a target it can no longer sense
+pub const MEMORY_DURATION: u32 = 600;
+
+pub const DAMAGE_NOISE_RANGE: f64 = 400.0;
+pub const CAST_NOISE_RANGE: f64 = 250.0;
+
+/*
+Perception is how AIs find out about the world.
+A Perceiver sees preys that are inside its sight cone, in range, and not blocked by solid squares.
+Noises (damage, casting) are heard through walls by perceivers within range.
+Anything perceived is stored in the perceiver's memory as a last known position,
+which is forgotten after MEMORY_DURATION ticks without being perceived again.
+An object can have several perceivers (e.g. from a guard mind and a hunter), what it knows is
+whatever any of them knows.
+*/
+pub struct PerceptionSystem {
+    pub perceivers: IdMap<ComponentId, Perceiver>,
+    perceivers_by_owner: IdMap<GameObjectId, Vec<ComponentId>>,
+    noises: Vec<Noise>,
+}
+
+struct Noise {
+    source_id: GameObjectId,
+    coords: PixelCoords,
+    range: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Perception {
+    Seen(PixelCoords),
+    Remembered(PixelCoords),
+    Unknown,
+}
+
+impl PerceptionSystem {
+    pub fn new() -> Self {
+        PerceptionSystem {
+            perceivers: IdMap::new(),
+            perceivers_by_owner: IdMap::new(),
+            noises: Vec::new(),
+        }
+    }
+    pub fn step(game: &mut Game) {
+        Perceiver::hear(game);
+        if game.tick_counter % PERCEPTION_EVERY == 0 {
+            Perceiver::look(game);
+            Perceiver::forget(game);
+        }
+    }
+    /// Make a noise at the source's position, alerting perceivers that can hear it
+    pub fn make_noise(game: &mut Game, source_id: GameObjectId, range: f64) {
+        if let Some(coords) = source_id.get_coords_game_safe(game) {
+            game.perception_system.noises.push(Noise {
+                source_id,
+                coords,
+                range,
+            });
+        }
+    }
+    /// Objects without a perceiver know where everything is
+    pub fn perceive(
+        game: &Game,
+        perceiver_id: GameObjectId,
+        target_id: GameObjectId,
+    ) -> Perception {
+        let target_coords = match target_id.get_coords_game_safe(game) {
+            Some(x) => x,
+            None => return Perception::Unknown,
+        };
+        let perceiver_ids = match game.perception_system.perceivers_by_owner.get(perceiver_id) {
+            Some(x) => x,
+            None => return Perception::Seen(target_coords),
+        };
+        let mut remembered: Option<&PerceivedTarget> = None;
+        for memory in perceiver_ids.iter().filter_map(|id| {
+            game.perception_system
+                .perceivers
+                .get(*id)
+                .and_then(|perceiver| perceiver.memory.get(&target_id))
+        }) {
+            if memory.seen {
+                return Perception::Seen(target_coords);
+            }
+            // The most recent memory wins
+            if remembered.map_or(true, |other| {
+                memory.last_perceived_at > other.last_perceived_at
+            }) {
+                remembered = Some(memory);
+            }
+        }
+        match remembered {
+            Some(memory) => Perception::Remembered(memory.last_known_coords),
+            None => Perception::Unknown,
+        }
+    }
+    fn add_perceiver(game: &mut Game, perceiver_id: ComponentId, perceiver: Perceiver) {
+        let owner_id = perceiver.owner_id;
+        game.perception_system
+            .perceivers
+            .insert(perceiver_id, perceiver);
+        match game.perception_system.perceivers_by_owner.get_mut(owner_id) {
+            Some(perceiver_ids) => perceiver_ids.push(perceiver_id),
+            None => {
+                game.perception_system
+                    .perceivers_by_owner
+                    .insert(owner_id, vec![perceiver_id]);
+            }
+        }
+    }
+    fn remove_perceiver(game: &mut Game, perceiver_id: ComponentId) {
+        let owner_id = match game.perception_system.perceivers.remove(perceiver_id) {
+            Some(perceiver) => perceiver.owner_id,
+            None => return,
+        };
+        if let Some(perceiver_ids) = game.perception_system.perceivers_by_owner.get_mut(owner_id) {
+            perceiver_ids.retain(|id| *id != perceiver_id);
+            if perceiver_ids.is_empty() {
+                game.perception_system.perceivers_by_owner.remove(owner_id);
+            }
+        }
+    }
+    pub fn get_closest_perceived_prey(
+        game: &Game,
+        hunter: GameObjectId,
+        radius: f64,
+    ) -> Option<PreyId> {
+        HuntingSystem::get_closest_prey_matching(game, hunter, radius, |prey_object_id| {
+            PerceptionSystem::perceive(game, hunter, prey_object_id) != Perception::Unknown
+        })
+    }
+    /// Whether the straight line between two points crosses any solid square.
+    /// The squares the points themselves are in are not checked.
+    pub fn has_line_of_sight(game: &Game, from: PixelCoords, to: PixelCoords) -> bool {
+        if from.get_plane() != to.get_plane() {
+            return false;
+        }
+        let from_square: SquareCoords = from.into();
+        let to_square: SquareCoords = to.into();
+        let distance = from.get_distance_to(&to);
+        let step_size = SQUARE_SIZE_PIXELS as f64 / 4.0;
+        let steps = (distance / step_size).ceil() as i64;
+        let dx = to.get_x().to_num::<f64>() - from.get_x().to_num::<f64>();
+        let dy = to.get_y().to_num::<f64>() - from.get_y().to_num::<f64>();
+        let mut last_square = from_square;
+        for step in 1..steps {
+            let proportion = step as f64 / steps as f64;
+            let square: SquareCoords = from.translate(dx * proportion, dy * proportion).into();
+            if square == last_square {
+                continue;
+            }
+            last_square = square;
+            if square == to_square {
+                break;
+            }
+            if game.terrain.is_square_solid(square) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hunting::PreyComponent;
+    use crate::terrain::{get_chunk_index_from_relative_coords, Chunk};
+
+    fn generate_chunk(game: &mut Game) -> TerrainChunkCoords {
+        let chunk_coords = TerrainChunkCoords::new(Plane(0), 0, 0);
+        Chunk::generate(game, chunk_coords, 0);
+        chunk_coords
+    }
+
+    fn make_solid(game: &mut Game, chunk_coords: TerrainChunkCoords, square: SquareCoords) {
+        let relative = square.relative_to_chunk(chunk_coords).unwrap();
+        let index = get_chunk_index_from_relative_coords(relative);
+        game.terrain
+            .chunks
+            .get_mut(&chunk_coords)
+            .unwrap()
+            .chunk_squares[index]
+            .base_solid = true;
+    }
+
+    fn create_at(game: &mut Game, x: i64, y: i64) -> GameObjectId {
+        GameObject::create_game(game, SquareCoords::new(Plane(0), x, y).center_pixel())
+    }
+
+    #[test]
+    fn line_of_sight_blocked_by_solid_square() {
+        let mut game = Game::new();
+        let chunk_coords = generate_chunk(&mut game);
+        let from = SquareCoords::new(Plane(0), 1, 1).center_pixel();
+        let to = SquareCoords::new(Plane(0), 5, 1).center_pixel();
+        assert!(PerceptionSystem::has_line_of_sight(&game, from, to));
+
+        make_solid(&mut game, chunk_coords, SquareCoords::new(Plane(0), 3, 1));
+        assert!(!PerceptionSystem::has_line_of_sight(&game, from, to));
+        assert!(!PerceptionSystem::has_line_of_sight(&game, to, from));
+    }
+
+    #[test]
+    fn noises_are_heard_through_walls_until_forgotten() {
+        let mut game = Game::new();
+        let chunk_coords = generate_chunk(&mut game);
+        make_solid(&mut game, chunk_coords, SquareCoords::new(Plane(0), 3, 1));
+        let listener = create_at(&mut game, 1, 1);
+        let noisy = create_at(&mut game, 5, 1);
+        PerceiverComponent::add_to(&mut game, listener);
+        assert_eq!(
+            PerceptionSystem::perceive(&game, listener, noisy),
+            Perception::Unknown
+        );
+
+        PerceptionSystem::make_noise(&mut game, noisy, DAMAGE_NOISE_RANGE);
+        Perceiver::hear(&mut game);
+        let noise_coords = noisy.get_coords(&game.game_objects);
+        assert_eq!(
+            PerceptionSystem::perceive(&game, listener, noisy),
+            Perception::Remembered(noise_coords)
+        );
+
+        game.tick_counter += MEMORY_DURATION;
+        Perceiver::forget(&mut game);
+        assert_ne!(
+            PerceptionSystem::perceive(&game, listener, noisy),
+            Perception::Unknown
+        );
+        game.tick_counter += 1;
+        Perceiver::forget(&mut game);
+        assert_eq!(
+            PerceptionSystem::perceive(&game, listener, noisy),
+            Perception::Unknown
+        );
+    }
+
+    #[test]
+    fn only_targets_in_the_sight_cone_or_very_close_are_seen() {
+        let mut game = Game::new();
+        generate_chunk(&mut game);
+        let watcher = create_at(&mut game, 4, 1);
+        let ahead = create_at(&mut game, 7, 1);
+        let behind = create_at(&mut game, 1, 1);
+        let beside = create_at(&mut game, 4, 1);
+        for target in [ahead, behind, beside].iter() {
+            PreyComponent::add_to(&mut game, *target);
+        }
+        let perceiver_id = PerceiverComponent::add_to(&mut game, watcher);
+        // Facing along +x, towards ahead
+        game.perception_system
+            .perceivers
+            .get_mut(perceiver_id)
+            .unwrap()
+            .facing = Some(Angle::zero());
+
+        Perceiver::look(&mut game);
+        let perceive = |target| PerceptionSystem::perceive(&game, watcher, target);
+        assert!(matches!(perceive(ahead), Perception::Seen(_)));
+        assert_eq!(perceive(behind), Perception::Unknown);
+        assert!(matches!(perceive(beside), Perception::Seen(_)));
+    }
+
+    #[test]
+    fn perceivers_on_the_same_object_are_kept_apart() {
+        let mut game = Game::new();
+        generate_chunk(&mut game);
+        let owner = create_at(&mut game, 1, 1);
+        let target = create_at(&mut game, 3, 1);
+        PreyComponent::add_to(&mut game, target);
+        let first = PerceiverComponent::add_to(&mut game, owner);
+        let second = PerceiverComponent::add_to(&mut game, owner);
+        assert_eq!(game.perception_system.perceivers.len(), 2);
+
+        owner.remove_component(&mut game, first);
+        assert!(game.perception_system.perceivers.get(second).is_some());
+        Perceiver::look(&mut game);
+        assert!(matches!(
+            PerceptionSystem::perceive(&game, owner, target),
+            Perception::Seen(_)
+        ));
+
+        owner.remove_component(&mut game, second);
+        assert!(game.perception_system.perceivers.is_empty());
+    }
+}
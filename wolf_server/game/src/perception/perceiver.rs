@@ -0,0 +1,233 @@
+use super::*;
+use std::f64::consts::PI;
+use wolf_hash_map::WolfHashMap;
+
+/// Targets this close are noticed even when outside the sight cone
+const PERIPHERAL_RANGE: f64 = 60.0;
+/// Below this a perceiver is considered to be standing still, and keeps its old facing
+const MIN_FACING_MOVEMENT: f64 = 0.5;
+
+pub const DEFAULT_SIGHT_RANGE: f64 = 400.0;
+pub const DEFAULT_SIGHT_CONE: f64 = PI / 3.0;
+pub const DEFAULT_HEARING_RANGE: f64 = 500.0;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PerceivedTarget {
+    pub last_known_coords: PixelCoords,
+    pub last_perceived_at: u32,
+    /// Whether the target was in sight the last time the perceiver looked
+    pub seen: bool,
+}
+
+pub struct Perceiver {
+    pub owner_id: GameObjectId,
+    pub sight_range: f64,
+    /// Half-angle of the sight cone, in radians
+    pub sight_cone: f64,
+    pub hearing_range: f64,
+    /// Direction of the last movement, None if the perceiver hasn't moved yet (sees all around)
+    pub facing: Option<Angle>,
+    last_coords: Option<PixelCoords>,
+    pub memory: WolfHashMap<GameObjectId, PerceivedTarget>,
+}
+
+impl Perceiver {
+    fn new(owner_id: GameObjectId, sight_range: f64, sight_cone: f64, hearing_range: f64) -> Self {
+        Perceiver {
+            owner_id,
+            sight_range,
+            sight_cone,
+            hearing_range,
+            facing: None,
+            last_coords: None,
+            memory: WolfHashMap::new(),
+        }
+    }
+    fn can_see(
+        &self,
+        game: &Game,
+        perceiver_coords: PixelCoords,
+        facing: Option<Angle>,
+        target_id: GameObjectId,
+    ) -> Option<PixelCoords> {
+        let target_coords = target_id.get_coords_game_safe(game)?;
+        if target_coords.get_plane() != perceiver_coords.get_plane() {
+            return None;
+        }
+        let visibility = target_id.get_visibility(game);
+        let distance = perceiver_coords.get_distance_to(&target_coords);
+        if distance > self.sight_range * visibility {
+            return None;
+        }
+        if distance > PERIPHERAL_RANGE * visibility {
+            if let Some(facing) = facing {
+                let difference: f64 =
+                    (perceiver_coords.get_direction_to(&target_coords) - facing).into();
+                if difference.min(2.0 * PI - difference) > self.sight_cone {
+                    return None;
+                }
+            }
+        }
+        if !PerceptionSystem::has_line_of_sight(game, perceiver_coords, target_coords) {
+            return None;
+        }
+        Some(target_coords)
+    }
+    pub(super) fn look(game: &mut Game) {
+        let mut updates = Vec::new();
+        for (perceiver_id, perceiver) in game.perception_system.perceivers.iter() {
+            let owner_id = perceiver.owner_id;
+            let coords = match owner_id.get_coords_safe(&game.game_objects) {
+                Some(x) => x,
+                None => continue,
+            };
+            let facing = match perceiver.last_coords {
+                Some(last_coords)
+                    if last_coords.get_plane() == coords.get_plane()
+                        && last_coords.get_distance_to(&coords) > MIN_FACING_MOVEMENT =>
+                {
+                    Some(last_coords.get_direction_to(&coords))
+                }
+                _ => perceiver.facing,
+            };
+            let mut seen = Vec::new();
+            let chunk_coords: TerrainChunkCoords = coords.into();
+            let chunk_radius = perceiver.sight_range as i64 / TERRAIN_CHUNK_SIZE_PIXELS + 1;
+            for chunk_coords in square_of_coords_centered(chunk_coords, chunk_radius) {
+                if let Some(preys) = game.hunting_system.prey_chunk_map.get(chunk_coords) {
+                    for prey_id in preys.iter() {
+                        let target_id = game
+                            .hunting_system
+                            .preys
+                            .get(*prey_id)
+                            .unwrap()
+                            .game_object_id;
+                        if target_id == owner_id {
+                            continue;
+                        }
+                        if let Some(target_coords) =
+                            perceiver.can_see(game, coords, facing, target_id)
+                        {
+                            seen.push((target_id, target_coords));
+                        }
+                    }
+                }
+            }
+            updates.push((perceiver_id, coords, facing, seen));
+        }
+        for (perceiver_id, coords, facing, seen) in updates {
+            let perceiver = game
+                .perception_system
+                .perceivers
+                .get_mut(perceiver_id)
+                .unwrap();
+            perceiver.facing = facing;
+            perceiver.last_coords = Some(coords);
+            for (_target_id, memory) in perceiver.memory.iter_mut() {
+                memory.seen = false;
+            }
+            for (target_id, target_coords) in seen {
+                perceiver.memory.insert(
+                    target_id,
+                    PerceivedTarget {
+                        last_known_coords: target_coords,
+                        last_perceived_at: game.tick_counter,
+                        seen: true,
+                    },
+                );
+            }
+        }
+    }
+    pub(super) fn hear(game: &mut Game) {
+        if game.perception_system.noises.is_empty() {
+            return;
+        }
+        let noises = std::mem::replace(&mut game.perception_system.noises, Vec::new());
+        for (_perceiver_id, perceiver) in game.perception_system.perceivers.iter_mut() {
+            let owner_id = perceiver.owner_id;
+            let coords = match owner_id.get_coords_safe(&game.game_objects) {
+                Some(x) => x,
+                None => continue,
+            };
+            for noise in noises.iter() {
+                if noise.source_id == owner_id || noise.coords.get_plane() != coords.get_plane() {
+                    continue;
+                }
+                if coords.get_distance_to(&noise.coords) > noise.range.min(perceiver.hearing_range)
+                {
+                    continue;
+                }
+                let memory = perceiver
+                    .memory
+                    .entry(noise.source_id)
+                    .or_insert(PerceivedTarget {
+                        last_known_coords: noise.coords,
+                        last_perceived_at: game.tick_counter,
+                        seen: false,
+                    });
+                memory.last_known_coords = noise.coords;
+                memory.last_perceived_at = game.tick_counter;
+            }
+        }
+    }
+    pub(super) fn forget(game: &mut Game) {
+        let tick_counter = game.tick_counter;
+        let game_objects = &game.game_objects;
+        for (_perceiver_id, perceiver) in game.perception_system.perceivers.iter_mut() {
+            let forgotten: Vec<GameObjectId> = perceiver
+                .memory
+                .iter()
+                .filter(|(target_id, memory)| {
+                    memory.last_perceived_at + MEMORY_DURATION < tick_counter
+                        || target_id.is_deleted(game_objects)
+                })
+                .map(|(target_id, _)| *target_id)
+                .collect();
+            for target_id in forgotten {
+                perceiver.memory.remove(&target_id);
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PerceiverComponent {
+    component_id: ComponentId,
+}
+
+impl PerceiverComponent {
+    pub fn add_to(game: &mut Game, owner_id: GameObjectId) -> ComponentId {
+        Self::add_to_with_senses(
+            game,
+            owner_id,
+            DEFAULT_SIGHT_RANGE,
+            DEFAULT_SIGHT_CONE,
+            DEFAULT_HEARING_RANGE,
+        )
+    }
+    pub fn add_to_with_senses(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        sight_range: f64,
+        sight_cone: f64,
+        hearing_range: f64,
+    ) -> ComponentId {
+        let component_id = game.get_id();
+        PerceptionSystem::add_perceiver(
+            game,
+            component_id,
+            Perceiver::new(owner_id, sight_range, sight_cone, hearing_range),
+        );
+        owner_id.add_component(game, PerceiverComponent { component_id });
+        component_id
+    }
+}
+
+impl Component for PerceiverComponent {
+    fn get_component_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn on_remove(self: Box<Self>, game: &mut Game, _owner_id: GameObjectId) {
+        PerceptionSystem::remove_perceiver(game, self.component_id);
+    }
+}
@@ -0,0 +1,57 @@
+use super::*;
+use crate::define_signal_listener;
+use signal_listeners::Multiplied;
+
+// Scales how far away the owner can be seen from, 1.0 being fully visible
+define_signal_listener!(GetVisibility, &Game -> Multiplied<f64>);
+
+pub struct VisibilityModComponentId(ComponentId);
+
+#[derive(Clone)]
+pub struct VisibilityModComponent {
+    component_id: ComponentId,
+    visibility: f64,
+}
+
+impl GetVisibilitySignalListener for VisibilityModComponent {
+    fn receive_get_visibility_signal(
+        &self,
+        _game: &Game,
+        _owner_id: GameObjectId,
+    ) -> Multiplied<f64> {
+        Multiplied(self.visibility)
+    }
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+}
+
+impl VisibilityModComponent {
+    pub fn add_to(game: &mut Game, owner_id: GameObjectId, visibility: f64) -> ComponentId {
+        let component_id = game.get_id();
+        let comp = VisibilityModComponent {
+            component_id,
+            visibility,
+        };
+        owner_id.add_component(game, VisibilityModComponentId(component_id));
+        owner_id.add_get_visibility_signal_listener(game, comp);
+        component_id
+    }
+}
+
+impl Component for VisibilityModComponentId {
+    fn get_component_id(&self) -> ComponentId {
+        self.0
+    }
+    fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
+        owner_id.remove_get_visibility_signal_listener(game, self.0);
+    }
+}
+
+impl GameObjectId {
+    pub fn get_visibility(&self, game: &Game) -> f64 {
+        self.send_get_visibility_signal(game)
+            .map(Multiplied::extract)
+            .unwrap_or(1.0)
+    }
+}
@@ -1,4 +1,6 @@
 use super::*;
+use crate::inventory::InventorySystem;
+use crate::progression::ProgressionSystem;
 use crate::{abilities::CastAbilitySignalSender, villages::traverse_doors};
 use utilities::ret_opt;
 
@@ -29,8 +31,44 @@ impl PlayerId {
             Command::Move(mc) => self.process_move_command(game, mc),
             Command::Ability(ca) => self.process_ability_command(game, ca),
             Command::TraverseDoorsCommand => self.process_traverse_doors_command(game),
+            Command::MoveItem(mc) => self.process_move_item_command(game, mc),
+            Command::EquipItem(ec) => self.process_equip_item_command(game, ec),
+            Command::DropItem(dc) => self.process_drop_item_command(game, dc),
+            Command::UseItem(uc) => self.process_use_item_command(game, uc),
+            Command::LearnTalent(lc) => self.process_learn_talent_command(game, lc),
+            Command::Chat(cc) => ChatSystem::receive_chat(game, *self, cc),
+            Command::SetParty(pc) => ChatSystem::set_party(game, *self, pc.party_name),
+            Command::Login(lc) => Player::log_in(game, *self, lc.name),
         }
     }
+    fn process_move_item_command(&self, game: &mut Game, move_item_command: MoveItemCommand) {
+        let game_object_id = ret_opt!(self.get_game_object_id(game));
+        InventorySystem::move_item(
+            game,
+            game_object_id,
+            move_item_command.from,
+            move_item_command.to,
+        );
+    }
+    fn process_equip_item_command(&self, game: &mut Game, equip_item_command: EquipItemCommand) {
+        let game_object_id = ret_opt!(self.get_game_object_id(game));
+        InventorySystem::equip_item(game, game_object_id, equip_item_command.backpack_slot);
+    }
+    fn process_drop_item_command(&self, game: &mut Game, drop_item_command: DropItemCommand) {
+        let game_object_id = ret_opt!(self.get_game_object_id(game));
+        InventorySystem::drop_item(game, game_object_id, drop_item_command.from);
+    }
+    fn process_use_item_command(&self, game: &mut Game, use_item_command: UseItemCommand) {
+        let game_object_id = ret_opt!(self.get_game_object_id(game));
+        InventorySystem::use_item(game, game_object_id, use_item_command.backpack_slot);
+    }
+    fn process_learn_talent_command(
+        &self,
+        game: &mut Game,
+        learn_talent_command: LearnTalentCommand,
+    ) {
+        ProgressionSystem::learn_talent(game, *self, learn_talent_command.talent_index as usize);
+    }
     fn process_traverse_doors_command(&self, game: &mut Game) {
         if let Some(game_object_id) = self.get_game_object_id(game) {
             traverse_doors(game, game_object_id);
@@ -1,9 +1,10 @@
 use crate::abilities::SlotMapping;
 use crate::abilities::SlotMappingAssigner;
-use crate::combinable::CantCombine;
+use crate::define_signal_listener;
 use crate::game::*;
-use crate::time_system;
-use signal_listener_macro::define_signal_listener;
+use crate::parallel::map_in_parallel;
+use crate::progression::ProgressionSystem;
+use signal_listeners::CantCombine;
 use wolf_hash_map::WolfHashMap;
 use wolf_hash_map::WolfHashSet;
 use wolf_interface::*;
@@ -14,6 +15,8 @@ pub mod notifications;
 mod update;
 
 pub const CLIENT_SIDE_COMPONENT_RENDER_RANGE_SQUARES: i64 = 20;
+// Names are used as file names for saved progress, so they're kept short and plain
+const MAX_PLAYER_NAME_LENGTH: usize = 32;
 
 define_signal_listener!(BoundPlayer, &mut Game, player_id: PlayerId);
 define_signal_listener!(GetViewCoords, &Game -> CantCombine<PixelCoords>);
@@ -34,6 +37,8 @@ pub struct Player {
     pub bound_object_id: Option<GameObjectId>,
     pub last_view_coords: PixelCoords,
     pub notifications: IdMap<NotificationId, Notification>,
+    // Set once the player logs in, and what their progress is kept under
+    pub name: Option<String>,
 }
 
 impl PlayerSystem {
@@ -46,10 +51,6 @@ impl PlayerSystem {
             players_by_game_object: IdMap::new(),
         }
     }
-    pub fn end_step(game: &mut Game) {
-        time_system!(Player::end_step(game));
-        time_system!(Self::update_active_client_side_objects(game));
-    }
     pub fn update_active_client_side_objects(game: &mut Game) {
         let mut new_active_client_side_objects = WolfHashSet::new();
         for game_object_id in game
@@ -88,16 +89,68 @@ impl Player {
             bound_object_id: None,
             last_view_coords: PixelCoords::new_at_zero(),
             notifications: IdMap::new(),
+            name: None,
         };
         let player_id = game.get_id();
         game.player_system.players.insert(player_id, player);
         let game_object_id = game.create_basic_body();
         player_id.bind_to_object(game, game_object_id);
+        game.event_bus.publish(GameEvent::PlayerJoined {
+            player_id,
+            game_object_id,
+        });
         player_id
     }
-    pub fn end_step(game: &mut Game) {
-        time_system!(Player::handle_commands(game));
-        time_system!(Player::update_game_objects(game));
+    pub fn log_in(game: &mut Game, player_id: PlayerId, name: String) {
+        let valid = !name.is_empty()
+            && name.len() <= MAX_PLAYER_NAME_LENGTH
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            tracing::warn!("Player {} tried to log in as {:?}", player_id.0, name);
+            return;
+        }
+        let name_taken = game
+            .player_system
+            .players
+            .iter()
+            .any(|(_id, player)| player.name.as_ref() == Some(&name));
+        let player = match game.player_system.players.get_mut(player_id) {
+            Some(player) => player,
+            None => return,
+        };
+        if player.name.is_some() || name_taken {
+            return;
+        }
+        player.name = Some(name.clone());
+        ProgressionSystem::load_progress(game, player_id, &name);
+    }
+    // For when the player disconnects: keeps their progress and takes their body out of the world
+    pub fn remove(game: &mut Game, player_id: PlayerId) {
+        let (name, bound_object_id) = match game.player_system.players.get(player_id) {
+            Some(player) => (player.name.clone(), player.bound_object_id),
+            None => return,
+        };
+        ProgressionSystem::save_progress(game, player_id, name.as_deref());
+        ChatSystem::remove_player(game, player_id);
+        player_id.unbind(&mut game.player_system);
+        if let Some(bound_object_id) = bound_object_id {
+            if !bound_object_id.is_deleted(&game.game_objects) {
+                bound_object_id.remove(game);
+            }
+        }
+        let slot_mapping_keys: Vec<(PlayerId, GameObjectId)> = game
+            .player_system
+            .slot_mappings
+            .iter()
+            .map(|(key, _mapping)| *key)
+            .filter(|(owner_id, _game_object_id)| *owner_id == player_id)
+            .collect();
+        for key in slot_mapping_keys {
+            game.player_system.slot_mappings.remove(&key);
+        }
+        game.player_system.players.remove(player_id);
     }
     pub fn send_server_message_in_range(
         game: &mut Game,
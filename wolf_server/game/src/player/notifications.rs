@@ -3,6 +3,7 @@ use super::*;
 pub const DESERT_QUEST_START_NOTIFICATION_ID: NotificationId = NotificationId(0);
 pub const DESERT_QUEST_DISTANCE_NOTIFICATION_ID: NotificationId = NotificationId(1);
 pub const DESERT_QUEST_COMPLETE_NOTIFICATION_ID: NotificationId = NotificationId(2);
+pub const LEVEL_UP_NOTIFICATION_ID: NotificationId = NotificationId(3);
 
 impl PlayerId {
     pub fn send_notification(
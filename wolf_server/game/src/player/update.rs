@@ -2,67 +2,78 @@ use super::*;
 
 impl Player {
     pub fn update_game_objects(game: &mut Game) {
-        let mut updates: Vec<(
+        // Working out where each player is looking needs signals, so happens here
+        let mut views = Vec::new();
+        for (player_id, player) in game.player_system.players.iter() {
+            let view_coords = if let Some(game_object_id) = player.bound_object_id {
+                let view_coords_override = game_object_id.send_get_view_coords_signal(game);
+                if let Some(view_coords_override) = view_coords_override {
+                    view_coords_override.extract()
+                } else {
+                    if let Some(coords) = game_object_id.get_coords_game_safe(game) {
+                        coords
+                    } else {
+                        player.last_view_coords
+                    }
+                }
+            } else {
+                player.last_view_coords
+            };
+            views.push((
+                player_id,
+                player.bound_object_id,
+                view_coords,
+                &player.current_game_objects,
+                &player.game_objects_to_update,
+            ));
+        }
+        // Finding what each player can see only reads the collision map and the player's own
+        // sets, so players are done in parallel
+        let updates: Vec<(
             PlayerId,
             Option<GameObjectId>,
             PixelCoords,
             WolfHashSet<GameObjectId>,
             Vec<GameObjectId>,
             Vec<GameObjectId>,
-        )> = Vec::new();
-        {
+        )> = {
             let collision_map =
                 CollisionSystem::get_collision_group(game, CollisionGroupId::ClientSideComponent)
                     .map(|collision_group| collision_group.collision_map.borrow());
-
-            for (player_id, player) in game.player_system.players.iter() {
-                let view_coords = if let Some(game_object_id) = player.bound_object_id {
-                    let view_coords_override = game_object_id.send_get_view_coords_signal(game);
-                    if let Some(view_coords_override) = view_coords_override {
-                        view_coords_override.extract()
-                    } else {
-                        if let Some(coords) = game_object_id.get_coords_game_safe(game) {
-                            coords
-                        } else {
-                            player.last_view_coords
-                        }
-                    }
-                } else {
-                    player.last_view_coords
-                };
-                let center_coords: SquareCoords = view_coords.into();
-                let nearby_game_objects = match collision_map {
-                    Some(ref collision_map) => collision_map.0.get_within_box(
-                        center_coords,
-                        CLIENT_SIDE_COMPONENT_RENDER_RANGE_SQUARES,
-                        CLIENT_SIDE_COMPONENT_RENDER_RANGE_SQUARES,
-                    ),
-                    None => WolfHashSet::new(),
-                };
-                let new_game_objects = nearby_game_objects.difference(&player.current_game_objects);
-                let removed_game_objects = player
-                    .current_game_objects
-                    .difference(&nearby_game_objects)
-                    .map(|x| *x)
-                    .collect();
-                let moved_game_objects = player
-                    .game_objects_to_update
-                    .intersection(&nearby_game_objects);
-                let updated_game_objects = new_game_objects
-                    .chain(moved_game_objects)
-                    .map(|x| *x)
-                    .collect();
-
-                updates.push((
-                    player_id,
-                    player.bound_object_id,
-                    view_coords,
-                    nearby_game_objects,
-                    updated_game_objects,
-                    removed_game_objects,
-                ));
-            }
-        }
+            let spatial_map = collision_map.as_ref().map(|collision_map| &collision_map.0);
+            map_in_parallel(
+                views,
+                |(player_id, bound_object_id, view_coords, current_game_objects, to_update)| {
+                    let center_coords: SquareCoords = view_coords.into();
+                    let nearby_game_objects = match spatial_map {
+                        Some(spatial_map) => spatial_map.get_within_box(
+                            center_coords,
+                            CLIENT_SIDE_COMPONENT_RENDER_RANGE_SQUARES,
+                            CLIENT_SIDE_COMPONENT_RENDER_RANGE_SQUARES,
+                        ),
+                        None => WolfHashSet::new(),
+                    };
+                    let new_game_objects = nearby_game_objects.difference(current_game_objects);
+                    let removed_game_objects = current_game_objects
+                        .difference(&nearby_game_objects)
+                        .map(|x| *x)
+                        .collect();
+                    let moved_game_objects = to_update.intersection(&nearby_game_objects);
+                    let updated_game_objects = new_game_objects
+                        .chain(moved_game_objects)
+                        .map(|x| *x)
+                        .collect();
+                    (
+                        player_id,
+                        bound_object_id,
+                        view_coords,
+                        nearby_game_objects,
+                        updated_game_objects,
+                        removed_game_objects,
+                    )
+                },
+            )
+        };
         for (
             player_id,
             watching_object_id,
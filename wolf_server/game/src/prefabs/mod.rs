@@ -0,0 +1,253 @@
+use crate::component_registry::ComponentRegistry;
+use crate::game::*;
+use crate::meta::ComponentListComponent;
+use serde::Deserialize;
+
+/*
+Prefabs put an object together from a list of components read from a RON data file, rather
+than a function calling add_to on each of them. See the top of data/prefabs/creatures.ron for
+the format. The built in prefabs are compiled in, and more can be dropped in PREFABS_DIRECTORY
+without rebuilding the server.
+*/
+
+const PREFABS_FILE: &str = include_str!("../../data/prefabs/creatures.ron");
+const PREFABS_DIRECTORY: &str = "prefabs";
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PrefabComponent {
+    pub component: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Prefab {
+    pub name: String,
+    pub components: Vec<PrefabComponent>,
+}
+
+pub fn parse_prefabs(file: &str) -> Result<Vec<Prefab>, String> {
+    ron::from_str(file).map_err(|error| error.to_string())
+}
+
+// Only checks the components exist, their arguments are checked when they're added
+fn check_prefab(registry: &ComponentRegistry, prefab: &Prefab) -> Result<(), String> {
+    for prefab_component in prefab.components.iter() {
+        if registry.get_by_name(&prefab_component.component).is_none() {
+            return Err(format!(
+                "{} has unknown component {}",
+                prefab.name, prefab_component.component
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub struct PrefabSystem {
+    prefabs: Vec<Prefab>,
+}
+
+impl PrefabSystem {
+    pub fn new(registry: &ComponentRegistry) -> Self {
+        let mut prefab_system = PrefabSystem {
+            prefabs: Vec::new(),
+        };
+        for prefab in parse_prefabs(PREFABS_FILE).expect("Couldn't parse prefabs") {
+            check_prefab(registry, &prefab).expect("Bad prefab");
+            prefab_system.add(prefab);
+        }
+        prefab_system
+    }
+    pub fn load_prefabs_directory(game: &mut Game) {
+        PrefabSystem::load_directory(game, PREFABS_DIRECTORY);
+    }
+    /*
+    Bad files are skipped rather than stopping the server. Every prefab in a file is tried out on
+    a scratch object first, so a file can't replace a built in prefab with one that won't spawn.
+    */
+    fn load_directory(game: &mut Game, directory: &str) {
+        let entries = match std::fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        let mut paths: Vec<std::path::PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().map(|ext| ext == "ron").unwrap_or(false))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let prefabs = std::fs::read_to_string(&path)
+                .map_err(|error| error.to_string())
+                .and_then(|file| parse_prefabs(&file))
+                .and_then(|prefabs| {
+                    for prefab in prefabs.iter() {
+                        check_prefab(&game.component_registry, prefab)?;
+                        PrefabSystem::try_out(game, prefab)?;
+                    }
+                    Ok(prefabs)
+                });
+            match prefabs {
+                Ok(prefabs) => {
+                    for prefab in prefabs {
+                        game.prefab_system.add(prefab);
+                    }
+                }
+                Err(error) => {
+                    tracing::warn!(path = %path.display(), %error, "Skipping prefabs")
+                }
+            }
+        }
+    }
+    fn try_out(game: &mut Game, prefab: &Prefab) -> Result<(), String> {
+        let scratch_id = GameObject::create_game(game, PixelCoords::new_at_zero());
+        let result = PrefabSystem::add_components(game, scratch_id, prefab);
+        GameObject::_remove(game, scratch_id);
+        result
+    }
+    // Replaces any prefab with the same name
+    fn add(&mut self, prefab: Prefab) {
+        self.prefabs.retain(|other| other.name != prefab.name);
+        self.prefabs.push(prefab);
+    }
+    pub fn get(&self, name: &str) -> Option<&Prefab> {
+        self.prefabs.iter().find(|prefab| prefab.name == name)
+    }
+    pub fn get_names(&self) -> Vec<&str> {
+        self.prefabs
+            .iter()
+            .map(|prefab| prefab.name.as_str())
+            .collect()
+    }
+    pub fn spawn(game: &mut Game, name: &str, coords: PixelCoords) -> Result<GameObjectId, String> {
+        let game_object_id = GameObject::create_game(game, coords);
+        match PrefabSystem::apply_to(game, game_object_id, name) {
+            Ok(_bundle_id) => Ok(game_object_id),
+            Err(error) => {
+                GameObject::_remove(game, game_object_id);
+                Err(error)
+            }
+        }
+    }
+    // Everything the prefab adds is bundled under one component, so removing that takes the
+    // whole prefab back off again
+    pub fn apply_to(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        name: &str,
+    ) -> Result<ComponentId, String> {
+        let prefab = match game.prefab_system.get(name) {
+            Some(prefab) => prefab.clone(),
+            None => return Err(format!("Unknown prefab {}", name)),
+        };
+        let existing = get_component_ids(game, owner_id);
+        let result = PrefabSystem::add_components(game, owner_id, &prefab);
+        let added = get_component_ids(game, owner_id)
+            .into_iter()
+            .filter(|component_id| !existing.contains(component_id))
+            .collect();
+        let bundle_id = ComponentListComponent::add_managing(game, owner_id, added);
+        match result {
+            Ok(()) => Ok(bundle_id),
+            Err(error) => {
+                owner_id.remove_component(game, bundle_id);
+                Err(error)
+            }
+        }
+    }
+    // Stops at the first component that can't be added, leaving the ones before it on
+    fn add_components(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        prefab: &Prefab,
+    ) -> Result<(), String> {
+        for prefab_component in prefab.components.iter() {
+            let args: Vec<&str> = prefab_component.args.iter().map(String::as_str).collect();
+            ComponentRegistry::add_component(game, owner_id, &prefab_component.component, &args)
+                .map_err(|error| {
+                    format!(
+                        "{} in {}: {}",
+                        prefab_component.component, prefab.name, error
+                    )
+                })?;
+        }
+        Ok(())
+    }
+}
+
+fn get_component_ids(game: &Game, owner_id: GameObjectId) -> Vec<ComponentId> {
+    match game.game_objects.get(owner_id) {
+        Some(game_object) => game_object
+            .components
+            .components
+            .iter()
+            .map(|(component_id, _component)| component_id)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefabs_spawn_and_come_off_as_a_bundle() {
+        let mut game = Game::new();
+        let zombie = PrefabSystem::spawn(&mut game, "zombie", PixelCoords::new_at_zero()).unwrap();
+        let names = zombie.get_component_names(&game);
+        assert!(names.contains(&"resistances"));
+        assert!(names.contains(&"loot"));
+
+        assert!(parse_prefabs("[(components: [])]").is_err());
+        let broken = parse_prefabs(
+            r#"[(
+                name: "broken",
+                components: [
+                    (component: "damageable"),
+                    (component: "resistances", args: ["nope"]),
+                ],
+            )]"#,
+        )
+        .unwrap();
+        game.prefab_system.add(broken[0].clone());
+        let target = GameObject::create_game(&mut game, PixelCoords::new_at_zero());
+        assert!(PrefabSystem::apply_to(&mut game, target, "broken").is_err());
+        // The damageable that did get added is taken off again
+        assert!(target.get_component_names(&game).is_empty());
+        // Sprites go by name, not number
+        assert!(
+            ComponentRegistry::add_component(&mut game, target, "drawing", &["8", "0"]).is_err()
+        );
+        ComponentRegistry::add_component(&mut game, target, "drawing", &["zombie", "0"]).unwrap();
+    }
+
+    #[test]
+    fn prefab_files_that_wont_spawn_are_skipped() {
+        let mut game = Game::new();
+        let directory = std::env::temp_dir().join("wolf_prefabs_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        // Every component exists, but the walker's speed isn't a number
+        std::fs::write(
+            directory.join("bad.ron"),
+            r#"[
+                (name: "runner", components: [(component: "damageable")]),
+                (name: "zombie", components: [(component: "walker", args: ["fast", "0.5"])]),
+            ]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            directory.join("good.ron"),
+            r#"[(name: "lump", components: [(component: "damageable")])]"#,
+        )
+        .unwrap();
+        let objects_before = game.game_objects.len();
+        PrefabSystem::load_directory(&mut game, directory.to_str().unwrap());
+        std::fs::remove_dir_all(&directory).unwrap();
+        // Nothing's left behind from trying them out
+        assert_eq!(game.game_objects.len(), objects_before);
+        assert!(game.prefab_system.get("lump").is_some());
+        assert!(game.prefab_system.get("runner").is_none());
+        let zombie = PrefabSystem::spawn(&mut game, "zombie", PixelCoords::new_at_zero()).unwrap();
+        assert!(zombie.get_component_names(&game).contains(&"loot"));
+    }
+}
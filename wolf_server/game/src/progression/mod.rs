@@ -0,0 +1,569 @@
+use crate::abilities::{AbilityTypeId, AbilityUpgrade, BasicAbilityUser};
+use crate::game::*;
+use crate::player::notifications::LEVEL_UP_NOTIFICATION_ID;
+use std::path::PathBuf;
+use wolf_hash_map::WolfHashMap;
+use wolf_interface::*;
+
+mod talents;
+pub use talents::*;
+
+/*
+Experience belongs to the player rather than their body, so it survives switching character
+or losing the body. Levels raise max health and give talent points, which are spent in the
+tree of whichever class the player is currently playing.
+Players who log in with a name have their progress kept under it when they leave, and written
+to the save directory if there is one, so it's there again when they come back.
+*/
+
+pub const MAX_LEVEL: u32 = 20;
+const EXPERIENCE_PER_LEVEL: u32 = 100;
+const HEALTH_PER_LEVEL: i32 = 500;
+
+pub const KILL_EXPERIENCE: u32 = 40;
+pub const HARVEST_EXPERIENCE: u32 = 5;
+pub const BUILD_EXPERIENCE: u32 = 10;
+pub const QUEST_EXPERIENCE: u32 = 500;
+
+pub struct Progress {
+    pub level: u32,
+    // Towards the next level
+    pub experience: u32,
+    pub talent_points: u32,
+    pub learned_talents: WolfHashMap<CharacterClass, Vec<usize>>,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Progress {
+            level: 1,
+            experience: 0,
+            talent_points: 0,
+            learned_talents: WolfHashMap::new(),
+        }
+    }
+    pub fn get_experience_for_next_level(level: u32) -> u32 {
+        level * EXPERIENCE_PER_LEVEL
+    }
+    // Returns how many levels were gained
+    pub fn add_experience(&mut self, amount: u32) -> u32 {
+        if self.level >= MAX_LEVEL {
+            return 0;
+        }
+        self.experience += amount;
+        let mut levels_gained = 0;
+        while self.level < MAX_LEVEL
+            && self.experience >= Progress::get_experience_for_next_level(self.level)
+        {
+            self.experience -= Progress::get_experience_for_next_level(self.level);
+            self.level += 1;
+            self.talent_points += 1;
+            levels_gained += 1;
+        }
+        if self.level >= MAX_LEVEL {
+            self.experience = 0;
+        }
+        levels_gained
+    }
+    pub fn get_max_health(&self) -> Health {
+        DEFAULT_HEALTH + Health((self.level - 1) as i32 * HEALTH_PER_LEVEL)
+    }
+    pub fn has_learned(&self, class: CharacterClass, talent_index: usize) -> bool {
+        self.learned_talents
+            .get(&class)
+            .map(|learned| learned.contains(&talent_index))
+            .unwrap_or(false)
+    }
+    pub fn can_learn(&self, class: CharacterClass, talent_index: usize) -> bool {
+        let tree = class.get_talent_tree();
+        let talent = match tree.get(talent_index) {
+            Some(talent) => talent,
+            None => return false,
+        };
+        self.talent_points > 0
+            && !self.has_learned(class, talent_index)
+            && talent
+                .requires
+                .map(|required| self.has_learned(class, required))
+                .unwrap_or(true)
+    }
+    pub fn learn(&mut self, class: CharacterClass, talent_index: usize) -> bool {
+        if !self.can_learn(class, talent_index) {
+            return false;
+        }
+        self.talent_points -= 1;
+        self.learned_talents
+            .entry(class)
+            .or_insert(Vec::new())
+            .push(talent_index);
+        true
+    }
+    fn get_learned_effects(&self, class: CharacterClass) -> Vec<TalentEffect> {
+        let tree = class.get_talent_tree();
+        self.learned_talents
+            .get(&class)
+            .map(|learned| learned.iter().map(|index| tree[*index].effect).collect())
+            .unwrap_or(Vec::new())
+    }
+    // One "<field> <value>" line each, then a "talents <class> <indices...>" line per class
+    pub fn serialise(&self) -> String {
+        let mut lines = vec![
+            format!("level {}", self.level),
+            format!("experience {}", self.experience),
+            format!("talent_points {}", self.talent_points),
+        ];
+        let mut learned_talents: Vec<(&CharacterClass, &Vec<usize>)> =
+            self.learned_talents.iter().collect();
+        learned_talents.sort_by_key(|(class, _learned)| class.get_name());
+        for (class, learned) in learned_talents {
+            let indices: Vec<String> = learned.iter().map(|index| index.to_string()).collect();
+            lines.push(format!(
+                "talents {} {}",
+                class.get_name(),
+                indices.join(" ")
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut progress = Progress::new();
+        for line in text
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+        {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let parse_number = |word: &str| {
+                word.parse::<u32>()
+                    .map_err(|_| format!("Bad number in progress: {}", line))
+            };
+            match words[..] {
+                ["level", level] => progress.level = parse_number(level)?.max(1).min(MAX_LEVEL),
+                ["experience", experience] => progress.experience = parse_number(experience)?,
+                ["talent_points", points] => progress.talent_points = parse_number(points)?,
+                ["talents", class, ..] => {
+                    let class = CharacterClass::from_name(class)
+                        .ok_or(format!("Unknown class in progress: {}", line))?;
+                    let tree_size = class.get_talent_tree().len();
+                    let mut learned = Vec::new();
+                    for word in words[2..].iter() {
+                        let index = parse_number(word)? as usize;
+                        if index >= tree_size {
+                            return Err(format!("No such talent in progress: {}", line));
+                        }
+                        learned.push(index);
+                    }
+                    progress.learned_talents.insert(class, learned);
+                }
+                _ => return Err(format!("Unknown line in progress: {}", line)),
+            }
+        }
+        Ok(progress)
+    }
+    fn get_message(&self, class: Option<CharacterClass>) -> ProgressionMessage {
+        let talents = match class {
+            Some(class) => class
+                .get_talent_tree()
+                .iter()
+                .enumerate()
+                .map(|(index, talent)| TalentMessage {
+                    name: talent.name.to_string(),
+                    learned: self.has_learned(class, index),
+                    can_learn: self.can_learn(class, index),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        ProgressionMessage {
+            level: self.level,
+            experience: self.experience,
+            experience_for_next_level: Progress::get_experience_for_next_level(self.level),
+            talent_points: self.talent_points,
+            talents,
+        }
+    }
+}
+
+pub struct ProgressionSystem {
+    pub progress: IdMap<PlayerId, Progress>,
+    pub character_classes: IdMap<GameObjectId, CharacterClass>,
+    // Abilities unlocked by talents, so they can be taken back before being granted again
+    pub unlocked_abilities: IdMap<GameObjectId, Vec<AbilityId>>,
+    // Progress of players who have left, by the name they logged in with
+    pub saved_progress: WolfHashMap<String, Progress>,
+    // Where progress is written when players leave, one <name>.txt each
+    pub save_directory: Option<PathBuf>,
+}
+
+impl ProgressionSystem {
+    pub fn new() -> Self {
+        ProgressionSystem {
+            progress: IdMap::new(),
+            character_classes: IdMap::new(),
+            unlocked_abilities: IdMap::new(),
+            saved_progress: WolfHashMap::new(),
+            save_directory: None,
+        }
+    }
+    // Picks up where the player left off, replacing anything earned before logging in
+    pub fn load_progress(game: &mut Game, player_id: PlayerId, name: &str) {
+        let saved = match game.progression_system.saved_progress.remove(name) {
+            Some(saved) => Some(saved),
+            None => ProgressionSystem::read_progress(game, name),
+        };
+        let saved = match saved {
+            Some(saved) => saved,
+            None => return,
+        };
+        game.progression_system.progress.insert(player_id, saved);
+        if let Some(bound_object_id) = game
+            .player_system
+            .players
+            .get(player_id)
+            .and_then(|player| player.bound_object_id)
+        {
+            ProgressionSystem::apply_progress(game, player_id, bound_object_id);
+        }
+    }
+    // Called as the player leaves. Without a name there's nothing to keep it under
+    pub fn save_progress(game: &mut Game, player_id: PlayerId, name: Option<&str>) {
+        let progress = game.progression_system.progress.remove(player_id);
+        if let (Some(progress), Some(name)) = (progress, name) {
+            if let Some(save_directory) = &game.progression_system.save_directory {
+                let path = save_directory.join(format!("{}.txt", name));
+                let written = std::fs::create_dir_all(save_directory)
+                    .and_then(|_| std::fs::write(&path, progress.serialise()));
+                if let Err(error) = written {
+                    tracing::warn!("Unable to save progress to {:?}: {}", path, error);
+                }
+            }
+            game.progression_system
+                .saved_progress
+                .insert(name.to_string(), progress);
+        }
+    }
+    fn read_progress(game: &Game, name: &str) -> Option<Progress> {
+        let path = game
+            .progression_system
+            .save_directory
+            .as_ref()?
+            .join(format!("{}.txt", name));
+        // Not having a file just means they're new
+        let text = std::fs::read_to_string(&path).ok()?;
+        match Progress::parse(&text) {
+            Ok(progress) => Some(progress),
+            Err(error) => {
+                tracing::warn!("Unable to load progress from {:?}: {}", path, error);
+                None
+            }
+        }
+    }
+    fn get_progress_mut(game: &mut Game, player_id: PlayerId) -> &mut Progress {
+        game.progression_system
+            .progress
+            .entry(player_id)
+            .or_insert_with(Progress::new)
+    }
+    fn send_progression_message(game: &mut Game, player_id: PlayerId, owner_id: GameObjectId) {
+        let class = game
+            .progression_system
+            .character_classes
+            .get(owner_id)
+            .map(|class| *class);
+        let message = ProgressionSystem::get_progress_mut(game, player_id).get_message(class);
+        if let Some(player) = game.player_system.players.get_mut(player_id) {
+            player
+                .server_messages
+                .push(ServerMessage::Progression(message));
+        }
+    }
+    // Does nothing unless a player is controlling the game object
+    pub fn grant_experience(game: &mut Game, game_object_id: GameObjectId, amount: u32) {
+        if let Some(player_id) = game
+            .player_system
+            .players_by_game_object
+            .get(game_object_id)
+            .map(|x| *x)
+        {
+            ProgressionSystem::grant_player_experience(game, player_id, amount);
+        }
+    }
+    pub fn grant_player_experience(game: &mut Game, player_id: PlayerId, amount: u32) {
+        let levels_gained =
+            ProgressionSystem::get_progress_mut(game, player_id).add_experience(amount);
+        let bound_object_id = game
+            .player_system
+            .players
+            .get(player_id)
+            .and_then(|player| player.bound_object_id);
+        if levels_gained > 0 {
+            let level = ProgressionSystem::get_progress_mut(game, player_id).level;
+            player_id.send_notification(
+                game.tick_counter,
+                &mut game.player_system,
+                LEVEL_UP_NOTIFICATION_ID,
+                format!("You have reached level {}!", level),
+            );
+            if let Some(bound_object_id) = bound_object_id {
+                let max_health =
+                    ProgressionSystem::get_progress_mut(game, player_id).get_max_health();
+                bound_object_id.send_set_max_health_signal(game, max_health);
+            }
+        }
+        if let Some(bound_object_id) = bound_object_id {
+            ProgressionSystem::send_progression_message(game, player_id, bound_object_id);
+        }
+    }
+    pub fn learn_talent(game: &mut Game, player_id: PlayerId, talent_index: usize) {
+        let owner_id = match game
+            .player_system
+            .players
+            .get(player_id)
+            .and_then(|player| player.bound_object_id)
+        {
+            Some(owner_id) => owner_id,
+            None => return,
+        };
+        let class = match game.progression_system.character_classes.get(owner_id) {
+            Some(class) => *class,
+            None => return,
+        };
+        if ProgressionSystem::get_progress_mut(game, player_id).learn(class, talent_index) {
+            ProgressionSystem::apply_talents(game, player_id, owner_id, class);
+            ProgressionSystem::send_progression_message(game, player_id, owner_id);
+        }
+    }
+    fn apply_talents(
+        game: &mut Game,
+        player_id: PlayerId,
+        owner_id: GameObjectId,
+        class: CharacterClass,
+    ) {
+        let effects =
+            ProgressionSystem::get_progress_mut(game, player_id).get_learned_effects(class);
+        let mut unlocks: Vec<AbilityTypeId> = Vec::new();
+        let mut upgrades: Vec<(AbilityTypeId, AbilityUpgrade)> = Vec::new();
+        for effect in effects {
+            match effect {
+                TalentEffect::UnlockAbility(ability_type) => unlocks.push(ability_type),
+                TalentEffect::UpgradeAbility(ability_type, upgrade) => {
+                    upgrades.push((ability_type, upgrade))
+                }
+            }
+        }
+        if let Some(previously_unlocked) =
+            game.progression_system.unlocked_abilities.remove(owner_id)
+        {
+            BasicAbilityUser::revoke_abilities(game, owner_id, &previously_unlocked);
+        }
+        let unlocked = BasicAbilityUser::grant_abilities(game, owner_id, &unlocks);
+        game.progression_system
+            .unlocked_abilities
+            .insert(owner_id, unlocked);
+        BasicAbilityUser::set_upgrades(game, owner_id, upgrades);
+    }
+    // Brings a body up to date with the player's level and talents
+    fn apply_progress(game: &mut Game, player_id: PlayerId, owner_id: GameObjectId) {
+        let max_health = ProgressionSystem::get_progress_mut(game, player_id).get_max_health();
+        owner_id.send_set_max_health_signal(game, max_health);
+        if let Some(class) = game
+            .progression_system
+            .character_classes
+            .get(owner_id)
+            .map(|class| *class)
+        {
+            ProgressionSystem::apply_talents(game, player_id, owner_id, class);
+        }
+        ProgressionSystem::send_progression_message(game, player_id, owner_id);
+    }
+}
+
+#[derive(Clone)]
+pub struct ProgressionComponent {
+    component_id: ComponentId,
+}
+
+impl Component for ProgressionComponent {
+    fn get_component_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
+        owner_id.remove_bound_player_signal_listener(game, self.component_id);
+        game.progression_system.character_classes.remove(owner_id);
+        game.progression_system.unlocked_abilities.remove(owner_id);
+    }
+}
+
+impl BoundPlayerSignalListener for ProgressionComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_bound_player_signal(
+        &self,
+        game: &mut Game,
+        owner_id: GameObjectId,
+        player_id: PlayerId,
+    ) {
+        ProgressionSystem::apply_progress(game, player_id, owner_id);
+    }
+}
+
+impl ProgressionComponent {
+    // Added after the character's abilities, so talents have something to unlock into
+    pub fn add_to(game: &mut Game, owner_id: GameObjectId, class: CharacterClass) -> ComponentId {
+        let component_id = game.get_id();
+        let comp = ProgressionComponent { component_id };
+        game.progression_system
+            .character_classes
+            .insert(owner_id, class);
+        owner_id.add_bound_player_signal_listener(game, comp.clone());
+        owner_id.add_component(game, comp);
+        if let Some(player_id) = game
+            .player_system
+            .players_by_game_object
+            .get(owner_id)
+            .map(|x| *x)
+        {
+            ProgressionSystem::apply_progress(game, player_id, owner_id);
+        }
+        component_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Player;
+
+    #[test]
+    fn levelling_carries_over_experience_and_gives_points() {
+        let mut progress = Progress::new();
+        assert_eq!(progress.add_experience(99), 0);
+        assert_eq!(progress.add_experience(251), 2);
+        assert_eq!(progress.level, 3);
+        assert_eq!(progress.experience, 50);
+        assert_eq!(progress.talent_points, 2);
+        assert_eq!(
+            progress.get_max_health(),
+            DEFAULT_HEALTH + Health(2 * HEALTH_PER_LEVEL)
+        );
+        progress.add_experience(1000000);
+        assert_eq!(progress.level, MAX_LEVEL);
+    }
+
+    #[test]
+    fn talents_need_points_and_their_prerequisites() {
+        let mut progress = Progress::new();
+        assert!(!progress.learn(CharacterClass::Knight, 0));
+        progress.talent_points = 2;
+        assert!(!progress.learn(CharacterClass::Knight, 1));
+        assert!(progress.learn(CharacterClass::Knight, 0));
+        assert!(!progress.learn(CharacterClass::Knight, 0));
+        assert!(progress.learn(CharacterClass::Knight, 1));
+        assert!(!progress.has_learned(CharacterClass::Lich, 0));
+        for class in [
+            CharacterClass::Paladin,
+            CharacterClass::Lich,
+            CharacterClass::Knight,
+        ]
+        .iter()
+        {
+            for (index, talent) in class.get_talent_tree().iter().enumerate() {
+                assert!(talent
+                    .requires
+                    .map(|required| required < index)
+                    .unwrap_or(true));
+            }
+        }
+    }
+
+    #[test]
+    fn progress_is_written_out_and_read_back() {
+        let mut progress = Progress::new();
+        progress.add_experience(250);
+        progress.learn(CharacterClass::Knight, 0);
+        let read = Progress::parse(&progress.serialise()).unwrap();
+        assert_eq!(read.level, progress.level);
+        assert_eq!(read.experience, progress.experience);
+        assert_eq!(read.talent_points, progress.talent_points);
+        assert!(read.has_learned(CharacterClass::Knight, 0));
+        assert!(Progress::parse("talents knight 99").is_err());
+        assert!(Progress::parse("talents bard 0").is_err());
+        assert!(Progress::parse("level lots").is_err());
+    }
+
+    #[test]
+    fn progress_is_kept_under_the_players_name_when_they_leave() {
+        let save_directory =
+            std::env::temp_dir().join(format!("wolf_progress_{}", std::process::id()));
+        let mut game = Game::new();
+        game.progression_system.save_directory = Some(save_directory.clone());
+        let player_id = Player::create(&mut game);
+        Player::log_in(&mut game, player_id, "wanderer".to_string());
+        let body_id = game
+            .player_system
+            .players
+            .get(player_id)
+            .unwrap()
+            .bound_object_id
+            .unwrap();
+        ProgressionSystem::grant_player_experience(&mut game, player_id, 350);
+        Player::remove(&mut game, player_id);
+        assert!(game.player_system.players.get(player_id).is_none());
+        assert!(game.progression_system.progress.get(player_id).is_none());
+        assert!(game.to_delete.contains(&body_id));
+
+        // Same name again, same game
+        let returning_id = Player::create(&mut game);
+        Player::log_in(&mut game, returning_id, "wanderer".to_string());
+        assert_eq!(
+            game.progression_system
+                .progress
+                .get(returning_id)
+                .unwrap()
+                .level,
+            3
+        );
+        // The name's in use now, so nobody else gets it
+        let impostor_id = Player::create(&mut game);
+        Player::log_in(&mut game, impostor_id, "wanderer".to_string());
+        assert!(game
+            .player_system
+            .players
+            .get(impostor_id)
+            .unwrap()
+            .name
+            .is_none());
+        assert_eq!(
+            game.progression_system
+                .progress
+                .get(impostor_id)
+                .unwrap()
+                .level,
+            1
+        );
+        // Names end up as file names
+        Player::log_in(&mut game, impostor_id, "../wanderer".to_string());
+        assert!(game
+            .player_system
+            .players
+            .get(impostor_id)
+            .unwrap()
+            .name
+            .is_none());
+
+        // After a restart it comes from the save directory
+        let mut restarted = Game::new();
+        restarted.progression_system.save_directory = Some(save_directory.clone());
+        let restarted_id = Player::create(&mut restarted);
+        Player::log_in(&mut restarted, restarted_id, "wanderer".to_string());
+        let progress = restarted
+            .progression_system
+            .progress
+            .get(restarted_id)
+            .unwrap();
+        assert_eq!(progress.level, 3);
+        assert_eq!(progress.experience, 50);
+        std::fs::remove_dir_all(save_directory).unwrap();
+    }
+}
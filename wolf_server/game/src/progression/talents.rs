@@ -0,0 +1,130 @@
+use crate::abilities::{AbilityTypeId, AbilityUpgrade};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CharacterClass {
+    Paladin,
+    Lich,
+    Knight,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TalentEffect {
+    UnlockAbility(AbilityTypeId),
+    UpgradeAbility(AbilityTypeId, AbilityUpgrade),
+}
+
+pub struct Talent {
+    pub name: &'static str,
+    // Index of the talent that has to be learned first
+    pub requires: Option<usize>,
+    pub effect: TalentEffect,
+}
+
+impl CharacterClass {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "paladin" => Some(CharacterClass::Paladin),
+            "lich" => Some(CharacterClass::Lich),
+            "knight" => Some(CharacterClass::Knight),
+            _ => None,
+        }
+    }
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            CharacterClass::Paladin => "paladin",
+            CharacterClass::Lich => "lich",
+            CharacterClass::Knight => "knight",
+        }
+    }
+    pub fn get_talent_tree(&self) -> Vec<Talent> {
+        match self {
+            CharacterClass::Paladin => vec![
+                Talent {
+                    name: "Swift Judgement",
+                    requires: None,
+                    effect: TalentEffect::UpgradeAbility(
+                        AbilityTypeId::HolySlashId,
+                        AbilityUpgrade::cooldown(0.75),
+                    ),
+                },
+                Talent {
+                    name: "Steadfast Shield",
+                    requires: Some(0),
+                    effect: TalentEffect::UpgradeAbility(
+                        AbilityTypeId::HolyShieldId,
+                        AbilityUpgrade::cooldown(0.7),
+                    ),
+                },
+                Talent {
+                    name: "Holy Steed",
+                    requires: None,
+                    effect: TalentEffect::UnlockAbility(AbilityTypeId::HolySteedId),
+                },
+                Talent {
+                    name: "Tireless Steed",
+                    requires: Some(2),
+                    effect: TalentEffect::UpgradeAbility(
+                        AbilityTypeId::HolySteedId,
+                        AbilityUpgrade::cost(0.5),
+                    ),
+                },
+            ],
+            CharacterClass::Lich => vec![
+                Talent {
+                    name: "Frugal Necromancy",
+                    requires: None,
+                    effect: TalentEffect::UpgradeAbility(
+                        AbilityTypeId::NecroboltId,
+                        AbilityUpgrade::cost(0.7),
+                    ),
+                },
+                Talent {
+                    name: "Shroud",
+                    requires: Some(0),
+                    effect: TalentEffect::UnlockAbility(AbilityTypeId::CloakId),
+                },
+                Talent {
+                    name: "Hurried Burial",
+                    requires: None,
+                    effect: TalentEffect::UpgradeAbility(
+                        AbilityTypeId::CorpseTossId,
+                        AbilityUpgrade::cooldown(0.6),
+                    ),
+                },
+                Talent {
+                    name: "Hellfire",
+                    requires: Some(2),
+                    effect: TalentEffect::UnlockAbility(AbilityTypeId::FireballId),
+                },
+            ],
+            CharacterClass::Knight => vec![
+                Talent {
+                    name: "Quick Reload",
+                    requires: None,
+                    effect: TalentEffect::UpgradeAbility(
+                        AbilityTypeId::RailgunId,
+                        AbilityUpgrade::cooldown(0.75),
+                    ),
+                },
+                Talent {
+                    name: "Second Wind",
+                    requires: Some(0),
+                    effect: TalentEffect::UpgradeAbility(
+                        AbilityTypeId::SprintId,
+                        AbilityUpgrade::cost(0.5),
+                    ),
+                },
+                Talent {
+                    name: "Forager",
+                    requires: None,
+                    effect: TalentEffect::UnlockAbility(AbilityTypeId::HarvestId),
+                },
+                Talent {
+                    name: "Vanish",
+                    requires: Some(2),
+                    effect: TalentEffect::UnlockAbility(AbilityTypeId::CloakId),
+                },
+            ],
+        }
+    }
+}
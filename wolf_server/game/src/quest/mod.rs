@@ -1,21 +1,24 @@
 use crate::{
-    combinable::OrBool,
     game::*,
-    monsters::Charger,
+    monsters::WolfLeader,
     notifications::{
         DESERT_QUEST_COMPLETE_NOTIFICATION_ID, DESERT_QUEST_DISTANCE_NOTIFICATION_ID,
         DESERT_QUEST_START_NOTIFICATION_ID,
     },
+    progression::{ProgressionSystem, QUEST_EXPERIENCE},
     wildlife::WanderingHerbivoreComponent,
 };
+use signal_listeners::{OrBool, Stoppable};
 use std::f64::consts::PI;
 
 use coords::Angle;
 use id::IdMap;
 use wolf_hash_map::WolfHashSet;
 
-const SPAWN_CHARGERS_Y: PixelNum = PixelNum::const_from_int(0);
-const SPAWN_CHARGERS_STARTING_DISTANCE: PixelNum = PixelNum::const_from_int(200);
+const SPAWN_WOLVES_Y: PixelNum = PixelNum::const_from_int(0);
+const SPAWN_WOLF_LEADER_DISTANCE: PixelNum = PixelNum::const_from_int(800);
+const DESERT_WOLF_PACK: &str = "desert";
+const INVINCIBILITY_PRIORITY: i32 = 100;
 const CROSS_DESERT_QUEST_VICTORY_Y: PixelNum = PixelNum::const_from_int(6000);
 
 pub struct QuestSystem {
@@ -54,16 +57,16 @@ impl AboutToBeDamagedSignalListener for PostQuestInvincibleComponent {
         owner: GameObjectId,
         damager_id: GameObjectId,
         firer_id: GameObjectId,
-    ) -> OrBool {
-        OrBool(true)
+    ) -> Stoppable<OrBool> {
+        // Nothing else needs to hear about attacks that can't land
+        Stoppable::Stop(OrBool(true))
     }
 
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-
-    fn clone_box(&self) -> Box<dyn AboutToBeDamagedSignalListener> {
-        Box::new(self.clone())
+    fn get_listener_priority(&self) -> i32 {
+        INVINCIBILITY_PRIORITY
     }
 }
 impl PostQuestInvincibleComponent {
@@ -85,7 +88,7 @@ impl Component for PostQuestInvincibleComponent {
     }
 }
 pub struct CrossDesertQuest {
-    pub spawned_chargers: bool,
+    pub spawned_wolves: bool,
     pub y_started_at: PixelNum,
 }
 
@@ -93,13 +96,14 @@ impl CrossDesertQuest {
     fn step(game: &mut Game) {
         let mut quests_to_end = Vec::new();
         let mut to_make_invincible = Vec::new();
-        let mut chargers_to_spawn = Vec::new();
+        let mut wolves_to_spawn = Vec::new();
+        let mut players_to_reward = Vec::new();
         for (id, quest) in game.quest_system.cross_desert_quests.iter_mut() {
             if let Some(coords) = id.get_coords_safe(&game.game_objects) {
                 let distance_travelled = coords.get_y() - quest.y_started_at;
-                if !quest.spawned_chargers && distance_travelled > SPAWN_CHARGERS_Y {
-                    chargers_to_spawn.push(id);
-                    quest.spawned_chargers = true;
+                if !quest.spawned_wolves && distance_travelled > SPAWN_WOLVES_Y {
+                    wolves_to_spawn.push(id);
+                    quest.spawned_wolves = true;
                 }
                 if distance_travelled > CROSS_DESERT_QUEST_VICTORY_Y {
                     // Victory!
@@ -124,6 +128,7 @@ impl CrossDesertQuest {
                         .map(|x| *x)
                     {
                         player_id.unbind(&mut game.player_system);
+                        players_to_reward.push(player_id);
                     }
                     game.quest_system.walking_upwards.insert(id);
                     to_make_invincible.push(id);
@@ -144,15 +149,14 @@ impl CrossDesertQuest {
             } else {
             }
         }
-        for id in chargers_to_spawn {
+        for id in wolves_to_spawn {
             let coords = id.get_coords_game(&game);
-            let target_coords =
-                coords.translate_fixed(PixelNum::from_num(0), -SPAWN_CHARGERS_STARTING_DISTANCE);
-            for i in 0..10 {
-                let direction = Angle::enforce_range(rand::thread_rng().gen_range(0.0..2.0 * PI));
-                let spawn_coords = target_coords.offset_direction(direction, 50.0);
-                Charger::new(game, spawn_coords, id);
-            }
+            let leader_coords =
+                coords.translate_fixed(PixelNum::from_num(0), -SPAWN_WOLF_LEADER_DISTANCE);
+            WolfLeader::create_hunting(game, leader_coords, DESERT_WOLF_PACK, id);
+        }
+        for player_id in players_to_reward {
+            ProgressionSystem::grant_player_experience(game, player_id, QUEST_EXPERIENCE);
         }
         for id in to_make_invincible {
             PostQuestInvincibleComponent::add_to(game, id);
@@ -172,7 +176,7 @@ impl CrossDesertQuest {
         game.quest_system.cross_desert_quests.insert(
             id,
             CrossDesertQuest {
-                spawned_chargers: false,
+                spawned_wolves: false,
                 y_started_at,
             },
         );
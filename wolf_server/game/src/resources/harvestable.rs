@@ -1,20 +1,30 @@
 use super::*;
 use crate::damage::DeathSignalListener;
-use signal_listener_macro::define_signal_listener;
+use crate::define_signal_listener;
+use crate::loot::{Loot, LootSystem};
+use crate::progression::{ProgressionSystem, HARVEST_EXPERIENCE};
 
 define_signal_listener!(Harvest, &mut Game, harvester_id: GameObjectId);
 
 pub struct Harvestable {
     pub game_object_id: GameObjectId,
     pub resources: Resources,
+    // Rolled on top of the resources when harvested or destroyed
+    pub loot_table: Option<usize>,
 }
 
 impl Harvestable {
-    fn new(game: &mut Game, game_object_id: GameObjectId, resources: Resources) -> HarvestableId {
+    fn new(
+        game: &mut Game,
+        game_object_id: GameObjectId,
+        resources: Resources,
+        loot_table: Option<usize>,
+    ) -> HarvestableId {
         let id = game.get_id();
         let harvestable = Harvestable {
             game_object_id,
             resources,
+            loot_table,
         };
         game.resource_system.harvestables.insert(id, harvestable);
         game_object_id.add_collision_group(game, CollisionGroupId::Harvestable);
@@ -30,6 +40,17 @@ impl Harvestable {
             None
         }
     }
+    // Takes everything this harvestable has to give, leaving it empty
+    fn take_loot(game: &mut Game, id: HarvestableId) -> Loot {
+        let harvestable = game.resource_system.harvestables.get_mut(id).unwrap();
+        let resources = std::mem::replace(&mut harvestable.resources, Resources::new());
+        let mut loot = match harvestable.loot_table.take() {
+            Some(table_index) => LootSystem::roll(game, table_index),
+            None => Loot::new(),
+        };
+        loot.resources += &resources;
+        loot
+    }
 }
 
 #[derive(Clone)]
@@ -51,21 +72,12 @@ impl HitBoxed for HarvestableId {
 }
 
 impl DeathSignalListener for HarvestableComponent {
-    fn clone_box(&self) -> Box<(dyn DeathSignalListener + 'static)> {
-        Box::new(self.clone())
-    }
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
     fn receive_death_signal(&self, game: &mut Game, owner_id: GameObjectId) {
-        let harvestable = game
-            .resource_system
-            .harvestables
-            .get_mut(self.harvestable_id)
-            .unwrap();
-        let coords = harvestable.game_object_id.get_coords(&game.game_objects);
-        let resources = std::mem::replace(&mut harvestable.resources, Resources::new());
-        create_resource_pickup(game, coords, resources);
+        let coords = owner_id.get_coords_game(game);
+        Harvestable::take_loot(game, self.harvestable_id).drop_at(game, coords);
     }
 }
 
@@ -84,30 +96,39 @@ impl HarvestSignalListener for HarvestableComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn HarvestSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_harvest_signal(
         &self,
         game: &mut Game,
         owner_id: GameObjectId,
         harvester_id: GameObjectId,
     ) {
-        let harvestable = game
-            .resource_system
-            .harvestables
-            .get_mut(self.harvestable_id)
-            .unwrap();
-        let mut resources = std::mem::replace(&mut harvestable.resources, Resources::new());
-        harvester_id.send_add_resources_signal(game, &mut resources);
+        let mut loot = Harvestable::take_loot(game, self.harvestable_id);
+        harvester_id.send_add_resources_signal(game, &mut loot.resources);
+        ProgressionSystem::grant_experience(game, harvester_id, HARVEST_EXPERIENCE);
+        // Anything that isn't resources gets left on the ground
+        loot.resources = Resources::new();
+        let coords = owner_id.get_coords_game(game);
+        loot.drop_at(game, coords);
         owner_id.remove(game);
     }
 }
 
 impl HarvestableComponent {
     pub fn add_to(game: &mut Game, owner_id: GameObjectId, resources: Resources) {
+        HarvestableComponent::add(game, owner_id, resources, None);
+    }
+    pub fn add_with_loot_table(game: &mut Game, owner_id: GameObjectId, table_name: &str) {
+        let table_index = game.loot_system.get_table_index(table_name);
+        HarvestableComponent::add(game, owner_id, Resources::new(), Some(table_index));
+    }
+    fn add(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        resources: Resources,
+        loot_table: Option<usize>,
+    ) {
         let component_id = game.get_id();
-        let harvestable_id = Harvestable::new(game, owner_id, resources);
+        let harvestable_id = Harvestable::new(game, owner_id, resources, loot_table);
         let comp = HarvestableComponent {
             component_id,
             harvestable_id,
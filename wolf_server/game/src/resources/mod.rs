@@ -15,9 +15,6 @@ pub use resource_holder::*;
 mod resource_collector;
 pub use resource_collector::*;
 
-mod resource_dropper;
-pub use resource_dropper::*;
-
 pub struct ResourceSystem {
     pub harvestables: IdMap<HarvestableId, Harvestable>,
 
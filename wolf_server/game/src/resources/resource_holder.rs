@@ -1,6 +1,6 @@
 use super::*;
-use crate::combinable::*;
-use signal_listener_macro::define_signal_listener;
+use crate::define_signal_listener;
+use signal_listeners::*;
 
 define_signal_listener!(DropResources, &mut Game);
 define_signal_listener!(TransferResources, &mut Game, transfer_to: GameObjectId);
@@ -76,9 +76,6 @@ impl DropResourcesSignalListener for ResourceHolderComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn DropResourcesSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_drop_resources_signal(&self, game: &mut Game, owner_id: GameObjectId) {
         let coords = owner_id.get_coords_game(game);
         let resources = {
@@ -97,9 +94,6 @@ impl AddResourcesSignalListener for ResourceHolderComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn AddResourcesSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_add_resources_signal(
         &self,
         game: &mut Game,
@@ -119,9 +113,6 @@ impl TransferResourcesSignalListener for ResourceHolderComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn TransferResourcesSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_transfer_resources_signal(
         &self,
         game: &mut Game,
@@ -145,9 +136,6 @@ impl HasResourcesSignalListener for ResourceHolderComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn HasResourcesSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_has_resources_signal(
         &self,
         game: &Game,
@@ -177,9 +165,6 @@ impl HasAnyResourcesSignalListener for ResourceHolderComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn HasAnyResourcesSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_has_any_resources_signal(&self, game: &Game, _owner_id: GameObjectId) -> OrBool {
         let resource_holder = game
             .resource_system
@@ -194,9 +179,6 @@ impl SpendResourcesSignalListener for ResourceHolderComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn SpendResourcesSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_spend_resources_signal(
         &self,
         game: &mut Game,
@@ -216,9 +198,6 @@ impl GetResourcesSignalListener for ResourceHolderComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn GetResourcesSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_get_resources_signal(
         &self,
         game: &Game,
@@ -237,6 +216,12 @@ impl GameObjectId {
     pub fn drop_resources(&self, game: &mut Game) {
         self.send_drop_resources_signal(game);
     }
+    pub fn add_resources(&self, game: &mut Game, mut resources: Resources) {
+        self.send_add_resources_signal(game, &mut resources);
+    }
+    pub fn transfer_resources(&self, game: &mut Game, transfer_to: GameObjectId) {
+        self.send_transfer_resources_signal(game, transfer_to);
+    }
     pub fn spend_resources(&self, game: &mut Game, mut resources: Resources) -> bool {
         let has_resources = self
             .send_has_resources_signal(game, &resources)
@@ -20,6 +20,22 @@ impl std::ops::Sub for ResourceAmount {
 pub enum ResourceType {
     Wood,
     Food,
+    Stone,
+    Gems,
+    Tools,
+}
+
+impl ResourceType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "wood" => Some(ResourceType::Wood),
+            "food" => Some(ResourceType::Food),
+            "stone" => Some(ResourceType::Stone),
+            "gems" => Some(ResourceType::Gems),
+            "tools" => Some(ResourceType::Tools),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -97,6 +113,11 @@ impl Resources {
             self.resource_amounts.insert(resource_type, amount);
         }
     }
+    pub fn of(resource_type: ResourceType, amount: ResourceAmount) -> Self {
+        let mut resources = Resources::new();
+        resources.set_resource_amount(resource_type, amount);
+        resources
+    }
     pub fn wood(wood_amount: ResourceAmount) -> Self {
         let mut resource_amounts = BTreeMap::new();
         resource_amounts.insert(ResourceType::Wood, wood_amount);
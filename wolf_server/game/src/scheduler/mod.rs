@@ -0,0 +1,453 @@
+use crate::game::Game;
+use crate::parallel::{gather_jobs, run_jobs, Access, ParallelSystem, PendingJobs};
+use std::time::{Duration, Instant};
+
+/*
+Every system that runs each tick is registered here with the phase it belongs to, and
+optionally which systems it has to run before or after. Within a phase, systems run in the
+order they were registered unless a constraint says otherwise. Constraints on systems in
+other phases have to agree with the phase order, since phases always run in order.
+
+Parallel systems that end up next to each other in the order, in the same phase, run as a
+batch if their declared access doesn't conflict and neither is constrained against the other:
+all of them gather, then all their jobs share the thread pool, then each applies its results
+in order.
+*/
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Phase {
+    // Normal things
+    Step,
+    // Things that need to happen after step and just before the movement phase
+    PreMovement,
+    Movement,
+    // Things that need to happen just after the movement phase
+    PostMovement,
+    // Things that must happen last
+    EndStep,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SystemLabel {
+    Behaviour,
+    Timers,
+    Generic,
+    Perception,
+    AiPlanning,
+    Ai,
+    Ants,
+    Projectiles,
+    Damage,
+    Statuses,
+    Threat,
+    Monsters,
+    Wildlife,
+    BasicChunkLoaders,
+    ChunkLoaders,
+    ChunkWatchers,
+    TerrainSprites,
+    Resources,
+    Inventory,
+    Abilities,
+    Necromancy,
+    Speech,
+    Villages,
+    Quests,
+    Scripts,
+    IntendedMovement,
+    SolidsPreMovement,
+    Movement,
+    Collisions,
+    SolidsPostMovement,
+    PlayerCommands,
+    PlayerUpdates,
+    ClientSideObjects,
+    DeleteObjects,
+    Events,
+    Metrics,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Constraint {
+    Before(SystemLabel),
+    After(SystemLabel),
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct SystemTiming {
+    pub last: Duration,
+    pub total: Duration,
+    pub runs: u64,
+}
+
+enum SystemRun {
+    Main(fn(&mut Game)),
+    Parallel(fn(&mut Game) -> Box<dyn PendingJobs>, Access),
+}
+
+struct ScheduledSystem {
+    label: SystemLabel,
+    phase: Phase,
+    run: SystemRun,
+    constraints: Vec<Constraint>,
+    enabled: bool,
+    timing: SystemTiming,
+}
+
+pub struct Scheduler {
+    // In registration order
+    systems: Vec<ScheduledSystem>,
+    // Indices into systems, in the order they run
+    order: Vec<usize>,
+    // The same indices, grouped into systems that run together
+    batches: Vec<Vec<usize>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Scheduler {
+            systems: Vec::new(),
+            order: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+    pub fn register(
+        &mut self,
+        label: SystemLabel,
+        phase: Phase,
+        run: fn(&mut Game),
+        constraints: Vec<Constraint>,
+    ) {
+        self.add_system(label, phase, SystemRun::Main(run), constraints);
+    }
+    pub fn register_parallel<S: ParallelSystem>(
+        &mut self,
+        label: SystemLabel,
+        phase: Phase,
+        constraints: Vec<Constraint>,
+    ) {
+        self.add_system(
+            label,
+            phase,
+            SystemRun::Parallel(gather_jobs::<S>, S::get_access()),
+            constraints,
+        );
+    }
+    fn add_system(
+        &mut self,
+        label: SystemLabel,
+        phase: Phase,
+        run: SystemRun,
+        constraints: Vec<Constraint>,
+    ) {
+        if self.get_index(label).is_some() {
+            panic!("{:?} registered twice", label);
+        }
+        self.systems.push(ScheduledSystem {
+            label,
+            phase,
+            run,
+            constraints,
+            enabled: true,
+            timing: SystemTiming::default(),
+        });
+        self.order = self.get_order();
+        self.batches = self.get_batches();
+    }
+    fn get_index(&self, label: SystemLabel) -> Option<usize> {
+        self.systems.iter().position(|system| system.label == label)
+    }
+    // Pairs of (earlier, later) indices. Constraints on systems that aren't registered yet are
+    // ignored until they are.
+    fn get_edges(&self) -> Vec<(usize, usize)> {
+        let mut edges = Vec::new();
+        for (i, system) in self.systems.iter().enumerate() {
+            for constraint in system.constraints.iter() {
+                let edge = match constraint {
+                    Constraint::Before(other) => self.get_index(*other).map(|other| (i, other)),
+                    Constraint::After(other) => self.get_index(*other).map(|other| (other, i)),
+                };
+                if let Some((earlier, later)) = edge {
+                    if self.systems[earlier].phase > self.systems[later].phase {
+                        panic!(
+                            "{:?} can't run before {:?}, it's in a later phase",
+                            self.systems[earlier].label, self.systems[later].label
+                        );
+                    }
+                    edges.push((earlier, later));
+                }
+            }
+        }
+        edges
+    }
+    // Always picks the earliest phase, then the earliest registered, of the systems that are free
+    // to run, so the order only changes from registration order where a constraint demands it
+    fn get_order(&self) -> Vec<usize> {
+        let edges = self.get_edges();
+        let mut remaining_before = vec![0; self.systems.len()];
+        for (_earlier, later) in edges.iter() {
+            remaining_before[*later] += 1;
+        }
+        let mut placed = vec![false; self.systems.len()];
+        let mut order = Vec::new();
+        while order.len() < self.systems.len() {
+            let next = (0..self.systems.len())
+                .filter(|i| !placed[*i] && remaining_before[*i] == 0)
+                .min_by_key(|i| (self.systems[*i].phase, *i));
+            let next = match next {
+                Some(next) => next,
+                None => panic!("Systems have a circular ordering constraint"),
+            };
+            placed[next] = true;
+            order.push(next);
+            for (earlier, later) in edges.iter() {
+                if *earlier == next {
+                    remaining_before[*later] -= 1;
+                }
+            }
+        }
+        order
+    }
+    fn can_batch(&self, i: usize, j: usize, edges: &[(usize, usize)]) -> bool {
+        let (first, second) = (&self.systems[i], &self.systems[j]);
+        match (&first.run, &second.run) {
+            (SystemRun::Parallel(_, first_access), SystemRun::Parallel(_, second_access)) => {
+                first.phase == second.phase
+                    && !first_access.conflicts_with(second_access)
+                    && !edges.contains(&(i, j))
+                    && !edges.contains(&(j, i))
+            }
+            _ => false,
+        }
+    }
+    fn get_batches(&self) -> Vec<Vec<usize>> {
+        let edges = self.get_edges();
+        let mut batches: Vec<Vec<usize>> = Vec::new();
+        for i in self.order.iter() {
+            if let Some(batch) = batches.last_mut() {
+                if batch.iter().all(|j| self.can_batch(*j, *i, &edges)) {
+                    batch.push(*i);
+                    continue;
+                }
+            }
+            batches.push(vec![*i]);
+        }
+        batches
+    }
+    pub fn get_batched_labels(&self) -> Vec<Vec<SystemLabel>> {
+        self.batches
+            .iter()
+            .map(|batch| batch.iter().map(|i| self.systems[*i].label).collect())
+            .collect()
+    }
+    pub fn get_labels_in_order(&self) -> Vec<SystemLabel> {
+        self.order.iter().map(|i| self.systems[*i].label).collect()
+    }
+    pub fn set_enabled(&mut self, label: SystemLabel, enabled: bool) {
+        if let Some(i) = self.get_index(label) {
+            self.systems[i].enabled = enabled;
+        }
+    }
+    pub fn is_enabled(&self, label: SystemLabel) -> bool {
+        self.get_index(label)
+            .map(|i| self.systems[i].enabled)
+            .unwrap_or(false)
+    }
+    pub fn get_timings(&self) -> Vec<(SystemLabel, SystemTiming)> {
+        self.order
+            .iter()
+            .map(|i| (self.systems[*i].label, self.systems[*i].timing))
+            .collect()
+    }
+    // Systems can be enabled or disabled by the systems before them in the same tick. In a
+    // batch, whether a system runs is decided before the others in it apply their results.
+    pub fn run(game: &mut Game) {
+        for position in 0..game.scheduler.batches.len() {
+            let batch = game.scheduler.batches[position].clone();
+            if let [i] = batch[..] {
+                if let SystemRun::Main(run) = game.scheduler.systems[i].run {
+                    Scheduler::run_main(game, i, run);
+                    continue;
+                }
+            }
+            Scheduler::run_batch(game, batch);
+        }
+    }
+    fn run_main(game: &mut Game, i: usize, run: fn(&mut Game)) {
+        let (label, enabled) = {
+            let system = &game.scheduler.systems[i];
+            (system.label, system.enabled)
+        };
+        if !enabled {
+            return;
+        }
+        let _span = tracing::debug_span!("system", system = ?label).entered();
+        let start_time = Instant::now();
+        run(game);
+        game.scheduler.systems[i].record(Instant::now() - start_time);
+    }
+    // Each system in a batch is timed for its own gathering and applying, plus the time all
+    // their jobs spent on the pool together
+    fn run_batch(game: &mut Game, batch: Vec<usize>) {
+        let mut gathered = Vec::new();
+        let mut pending = Vec::new();
+        for i in batch {
+            let (label, enabled, gather) = {
+                let system = &game.scheduler.systems[i];
+                match system.run {
+                    SystemRun::Parallel(gather, _) => (system.label, system.enabled, gather),
+                    SystemRun::Main(_) => unreachable!("Only parallel systems are batched"),
+                }
+            };
+            if !enabled {
+                continue;
+            }
+            let _span = tracing::debug_span!("gather", system = ?label).entered();
+            let start_time = Instant::now();
+            pending.push(gather(game));
+            gathered.push((i, label, Instant::now() - start_time));
+        }
+        let start_time = Instant::now();
+        run_jobs(&mut pending);
+        let pool_time = Instant::now() - start_time;
+        for ((i, label, gather_time), jobs) in gathered.into_iter().zip(pending) {
+            let _span = tracing::debug_span!("apply", system = ?label).entered();
+            let start_time = Instant::now();
+            jobs.apply(game);
+            let apply_time = Instant::now() - start_time;
+            game.scheduler.systems[i].record(gather_time + pool_time + apply_time);
+        }
+    }
+}
+
+impl ScheduledSystem {
+    fn record(&mut self, time_taken: Duration) {
+        self.timing.last = time_taken;
+        self.timing.total += time_taken;
+        self.timing.runs += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parallel::GameField;
+
+    fn do_nothing(_game: &mut Game) {}
+
+    struct ReadsAis;
+    impl ParallelSystem for ReadsAis {
+        type Job = ();
+        type Output = ();
+        fn get_access() -> Access {
+            Access::new().read(GameField::Ais)
+        }
+        fn gather(_game: &mut Game) -> Vec<()> {
+            Vec::new()
+        }
+        fn run(_job: ()) {}
+        fn apply(_game: &mut Game, _outputs: Vec<()>) {}
+    }
+
+    struct WritesAis;
+    impl ParallelSystem for WritesAis {
+        type Job = ();
+        type Output = ();
+        fn get_access() -> Access {
+            Access::new().write(GameField::Ais)
+        }
+        fn gather(_game: &mut Game) -> Vec<()> {
+            Vec::new()
+        }
+        fn run(_job: ()) {}
+        fn apply(_game: &mut Game, _outputs: Vec<()>) {}
+    }
+
+    #[test]
+    fn systems_run_by_phase_then_constraints_then_registration() {
+        let mut scheduler = Scheduler::new();
+        scheduler.register(
+            SystemLabel::PlayerCommands,
+            Phase::EndStep,
+            do_nothing,
+            Vec::new(),
+        );
+        scheduler.register(
+            SystemLabel::Movement,
+            Phase::Movement,
+            do_nothing,
+            Vec::new(),
+        );
+        scheduler.register(SystemLabel::Ai, Phase::Step, do_nothing, Vec::new());
+        scheduler.register(
+            SystemLabel::Damage,
+            Phase::Step,
+            do_nothing,
+            vec![Constraint::Before(SystemLabel::Ai)],
+        );
+        scheduler.register(
+            SystemLabel::Threat,
+            Phase::Step,
+            do_nothing,
+            vec![Constraint::After(SystemLabel::Damage)],
+        );
+        assert_eq!(
+            scheduler.get_labels_in_order(),
+            vec![
+                SystemLabel::Damage,
+                SystemLabel::Ai,
+                SystemLabel::Threat,
+                SystemLabel::Movement,
+                SystemLabel::PlayerCommands
+            ]
+        );
+        scheduler.set_enabled(SystemLabel::Ai, false);
+        assert!(!scheduler.is_enabled(SystemLabel::Ai));
+        assert!(scheduler.is_enabled(SystemLabel::Damage));
+    }
+
+    #[test]
+    fn parallel_systems_only_batch_without_conflicts() {
+        let mut scheduler = Scheduler::new();
+        scheduler.register_parallel::<ReadsAis>(SystemLabel::Perception, Phase::Step, Vec::new());
+        scheduler.register_parallel::<ReadsAis>(SystemLabel::AiPlanning, Phase::Step, Vec::new());
+        scheduler.register_parallel::<WritesAis>(SystemLabel::Ai, Phase::Step, Vec::new());
+        scheduler.register_parallel::<ReadsAis>(
+            SystemLabel::Threat,
+            Phase::Step,
+            vec![Constraint::After(SystemLabel::Damage)],
+        );
+        scheduler.register_parallel::<ReadsAis>(SystemLabel::Damage, Phase::Step, Vec::new());
+        scheduler.register(SystemLabel::Timers, Phase::Step, do_nothing, Vec::new());
+        scheduler.register_parallel::<ReadsAis>(SystemLabel::Movement, Phase::Movement, Vec::new());
+        assert_eq!(
+            scheduler.get_batched_labels(),
+            vec![
+                vec![SystemLabel::Perception, SystemLabel::AiPlanning],
+                vec![SystemLabel::Ai],
+                vec![SystemLabel::Damage],
+                vec![SystemLabel::Threat],
+                vec![SystemLabel::Timers],
+                vec![SystemLabel::Movement],
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn constraints_cant_go_against_phases() {
+        let mut scheduler = Scheduler::new();
+        scheduler.register(
+            SystemLabel::PlayerCommands,
+            Phase::EndStep,
+            do_nothing,
+            Vec::new(),
+        );
+        scheduler.register(
+            SystemLabel::Ai,
+            Phase::Step,
+            do_nothing,
+            vec![Constraint::After(SystemLabel::PlayerCommands)],
+        );
+    }
+}
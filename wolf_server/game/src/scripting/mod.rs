@@ -0,0 +1,641 @@
+use crate::game::*;
+use crate::prefabs::PrefabSystem;
+use crate::progression::ProgressionSystem;
+use crate::timers::TimerSystem;
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+/*
+Abilities and quests written in Rhai. A script is a file of functions, named after the file:
+abilities define activate, quests define begin and step (see data/scripts).
+
+Scripts never get hold of the Game. They're given a snapshot of the objects they're about
+(a GameObject with its id and coordinates), and the functions they can call only queue up
+commands, which are carried out in order once the script returns. If the script fails, none of
+its commands are carried out.
+
+The built in scripts are compiled in. Scripts in SCRIPTS_DIRECTORY replace any with the same
+name, and are reloaded whenever their file changes, so they can be edited on a running server.
+A reload swaps the new version in where the old one was, so abilities already handed out (which
+hold a ScriptId) still point at the right script. Active quests and waiting timers look their
+script up by name each time they run. Either way they all pick up the new version.
+*/
+
+const BUILT_IN_SCRIPTS: [(&str, &str); 3] = [
+    ("blink", include_str!("../../data/scripts/blink.rhai")),
+    ("hold_out", include_str!("../../data/scripts/hold_out.rhai")),
+    (
+        "last_stand",
+        include_str!("../../data/scripts/last_stand.rhai"),
+    ),
+];
+const SCRIPTS_DIRECTORY: &str = "scripts";
+const RELOAD_CHECK_TICKS: u32 = 60;
+// So a script stuck in a loop can't hang the server
+const MAX_OPERATIONS: u64 = 100_000;
+
+// What a script sees of an object, as it was when the script was called
+#[derive(Clone, Debug)]
+pub struct ScriptObject {
+    id: GameObjectId,
+    x: f64,
+    y: f64,
+}
+
+impl ScriptObject {
+    fn get(game: &Game, id: GameObjectId) -> Option<Self> {
+        id.get_coords_safe(&game.game_objects)
+            .map(|coords| ScriptObject {
+                id,
+                x: coords.get_x().to_num(),
+                y: coords.get_y().to_num(),
+            })
+    }
+}
+
+#[derive(Clone, Debug)]
+enum ScriptCommand {
+    // Spawns on the same plane as whatever the script is running for
+    Spawn {
+        prefab: String,
+        x: f64,
+        y: f64,
+    },
+    MoveTo {
+        id: GameObjectId,
+        x: f64,
+        y: f64,
+    },
+    Damage {
+        target_id: GameObjectId,
+        amount: i64,
+        damage_type: String,
+    },
+    // Calls function after a number of ticks, with value as a second argument if there is one
+    After {
+        ticks: i64,
+        function: String,
+        value: Option<Dynamic>,
+    },
+    Say {
+        id: GameObjectId,
+        text: String,
+    },
+    Notify {
+        id: GameObjectId,
+        text: String,
+    },
+    GrantExperience {
+        id: GameObjectId,
+        amount: i64,
+    },
+    BeginQuest {
+        id: GameObjectId,
+        script_name: String,
+    },
+    EndQuest,
+}
+
+type CommandQueue = Rc<RefCell<Vec<ScriptCommand>>>;
+
+struct Script {
+    name: String,
+    ast: AST,
+    // None for built in scripts
+    modified: Option<SystemTime>,
+}
+
+// Where a script is in the loaded scripts. Scripts are only ever replaced, never removed, so
+// this stays valid while the server runs.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct ScriptId(usize);
+
+struct ScriptedQuest {
+    script_name: String,
+    owner_id: GameObjectId,
+    state: Dynamic,
+}
+
+pub struct ScriptSystem {
+    engine: Engine,
+    commands: CommandQueue,
+    scripts: Vec<Script>,
+    quests: Vec<ScriptedQuest>,
+    // Each script gets its own notification, so scripts don't overwrite each other's
+    notification_ids: Vec<(String, NotificationId)>,
+}
+
+fn get_engine(commands: &CommandQueue) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine
+        .register_type_with_name::<ScriptObject>("GameObject")
+        .register_get("x", |object: &mut ScriptObject| object.x)
+        .register_get("y", |object: &mut ScriptObject| object.y);
+
+    let queue = commands.clone();
+    engine.register_fn("spawn_prefab", move |prefab: &str, x: f64, y: f64| {
+        queue.borrow_mut().push(ScriptCommand::Spawn {
+            prefab: prefab.to_string(),
+            x,
+            y,
+        })
+    });
+    let queue = commands.clone();
+    engine.register_fn("move_to", move |object: ScriptObject, x: f64, y: f64| {
+        queue.borrow_mut().push(ScriptCommand::MoveTo {
+            id: object.id,
+            x,
+            y,
+        })
+    });
+    let queue = commands.clone();
+    engine.register_fn(
+        "damage",
+        move |target: ScriptObject, amount: i64, damage_type: &str| {
+            queue.borrow_mut().push(ScriptCommand::Damage {
+                target_id: target.id,
+                amount,
+                damage_type: damage_type.to_string(),
+            })
+        },
+    );
+    let queue = commands.clone();
+    engine.register_fn("after", move |ticks: i64, function: &str| {
+        queue.borrow_mut().push(ScriptCommand::After {
+            ticks,
+            function: function.to_string(),
+            value: None,
+        })
+    });
+    let queue = commands.clone();
+    engine.register_fn(
+        "after",
+        move |ticks: i64, function: &str, value: Dynamic| {
+            queue.borrow_mut().push(ScriptCommand::After {
+                ticks,
+                function: function.to_string(),
+                value: Some(value),
+            })
+        },
+    );
+    let queue = commands.clone();
+    engine.register_fn("say", move |object: ScriptObject, text: &str| {
+        queue.borrow_mut().push(ScriptCommand::Say {
+            id: object.id,
+            text: text.to_string(),
+        })
+    });
+    let queue = commands.clone();
+    engine.register_fn("notify", move |object: ScriptObject, text: &str| {
+        queue.borrow_mut().push(ScriptCommand::Notify {
+            id: object.id,
+            text: text.to_string(),
+        })
+    });
+    let queue = commands.clone();
+    engine.register_fn(
+        "grant_experience",
+        move |object: ScriptObject, amount: i64| {
+            queue.borrow_mut().push(ScriptCommand::GrantExperience {
+                id: object.id,
+                amount,
+            })
+        },
+    );
+    let queue = commands.clone();
+    engine.register_fn(
+        "begin_quest",
+        move |object: ScriptObject, script_name: &str| {
+            queue.borrow_mut().push(ScriptCommand::BeginQuest {
+                id: object.id,
+                script_name: script_name.to_string(),
+            })
+        },
+    );
+    let queue = commands.clone();
+    engine.register_fn("end_quest", move || {
+        queue.borrow_mut().push(ScriptCommand::EndQuest)
+    });
+    engine
+}
+
+impl ScriptSystem {
+    pub fn new() -> Self {
+        let commands = Rc::new(RefCell::new(Vec::new()));
+        let mut script_system = ScriptSystem {
+            engine: get_engine(&commands),
+            commands,
+            scripts: Vec::new(),
+            quests: Vec::new(),
+            notification_ids: Vec::new(),
+        };
+        for (name, source) in BUILT_IN_SCRIPTS.iter() {
+            script_system
+                .load(name, source, None)
+                .expect("Couldn't compile built in script");
+        }
+        script_system.reload_changed();
+        script_system
+    }
+    // Replaces any script with the same name. If it doesn't compile the old one is kept.
+    pub fn load(
+        &mut self,
+        name: &str,
+        source: &str,
+        modified: Option<SystemTime>,
+    ) -> Result<(), String> {
+        let ast = self
+            .engine
+            .compile(source)
+            .map_err(|error| format!("{} doesn't compile: {}", name, error))?;
+        match self.scripts.iter_mut().find(|script| script.name == name) {
+            Some(script) => {
+                script.ast = ast;
+                script.modified = modified;
+            }
+            None => self.scripts.push(Script {
+                name: name.to_string(),
+                ast,
+                modified,
+            }),
+        }
+        Ok(())
+    }
+    pub fn get_id(&self, name: &str) -> Option<ScriptId> {
+        self.scripts
+            .iter()
+            .position(|script| script.name == name)
+            .map(ScriptId)
+    }
+    pub fn get_name(&self, script_id: ScriptId) -> &str {
+        &self.scripts[script_id.0].name
+    }
+    pub fn get_names(&self) -> Vec<&str> {
+        self.scripts
+            .iter()
+            .map(|script| script.name.as_str())
+            .collect()
+    }
+    fn get(&self, name: &str) -> Option<&Script> {
+        self.scripts.iter().find(|script| script.name == name)
+    }
+    // Deleting a file doesn't unload its script, it stays as it was until the server restarts
+    fn reload_changed(&mut self) {
+        let entries = match std::fs::read_dir(SCRIPTS_DIRECTORY) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        let mut paths: Vec<std::path::PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().map(|ext| ext == "rhai").unwrap_or(false))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            let modified = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            let unchanged = self
+                .get(&name)
+                .map(|script| script.modified.is_some() && script.modified == modified)
+                .unwrap_or(false);
+            if unchanged {
+                continue;
+            }
+            let result = std::fs::read_to_string(&path)
+                .map_err(|error| error.to_string())
+                .and_then(|source| self.load(&name, &source, modified));
+            match result {
+                Ok(()) => tracing::info!(path = %path.display(), "Loaded script"),
+                Err(error) => {
+                    tracing::warn!(path = %path.display(), %error, "Skipping script");
+                    // Don't try again until the file changes
+                    if let Some(script) = self.scripts.iter_mut().find(|script| script.name == name)
+                    {
+                        script.modified = modified;
+                    }
+                }
+            }
+        }
+    }
+    pub fn step(game: &mut Game) {
+        if game.tick_counter % RELOAD_CHECK_TICKS == 0 {
+            game.script_system.reload_changed();
+        }
+        let quest_count = game.script_system.quests.len();
+        let mut ended = Vec::new();
+        for i in 0..quest_count {
+            let (script_name, owner_id) = {
+                let quest = &game.script_system.quests[i];
+                (quest.script_name.clone(), quest.owner_id)
+            };
+            let owner = match ScriptObject::get(game, owner_id) {
+                Some(owner) => owner,
+                None => {
+                    ended.push(i);
+                    continue;
+                }
+            };
+            let mut state =
+                std::mem::replace(&mut game.script_system.quests[i].state, Dynamic::UNIT);
+            let result = ScriptSystem::call(
+                game,
+                &script_name,
+                "step",
+                vec![Dynamic::from(owner)],
+                Some(&mut state),
+            );
+            game.script_system.quests[i].state = state;
+            if ScriptSystem::carry_out(game, &script_name, owner_id, result) {
+                ended.push(i);
+            }
+        }
+        // Quests begun this tick went on the end, so these indices are still right
+        for i in ended.into_iter().rev() {
+            game.script_system.quests.remove(i);
+        }
+    }
+    pub fn activate_ability(
+        game: &mut Game,
+        script_name: &str,
+        caster_id: GameObjectId,
+        target_coords: PixelCoords,
+    ) {
+        let caster = match ScriptObject::get(game, caster_id) {
+            Some(caster) => caster,
+            None => return,
+        };
+        let result = ScriptSystem::call(
+            game,
+            script_name,
+            "activate",
+            vec![
+                Dynamic::from(caster),
+                Dynamic::from(target_coords.get_x().to_num::<f64>()),
+                Dynamic::from(target_coords.get_y().to_num::<f64>()),
+            ],
+            None,
+        );
+        ScriptSystem::carry_out(game, script_name, caster_id, result);
+    }
+    pub fn begin_quest(
+        game: &mut Game,
+        script_name: &str,
+        owner_id: GameObjectId,
+    ) -> Result<(), String> {
+        let owner = ScriptObject::get(game, owner_id)
+            .ok_or_else(|| "Can't begin a quest for a deleted object".to_string())?;
+        let mut state = Dynamic::from(Map::new());
+        let commands = ScriptSystem::call(
+            game,
+            script_name,
+            "begin",
+            vec![Dynamic::from(owner)],
+            Some(&mut state),
+        )?;
+        let ended = ScriptSystem::carry_out(game, script_name, owner_id, Ok(commands));
+        if !ended {
+            game.script_system.quests.push(ScriptedQuest {
+                script_name: script_name.to_string(),
+                owner_id,
+                state,
+            });
+        }
+        Ok(())
+    }
+    pub fn is_on_quest(game: &Game, script_name: &str, owner_id: GameObjectId) -> bool {
+        game.script_system
+            .quests
+            .iter()
+            .any(|quest| quest.script_name == script_name && quest.owner_id == owner_id)
+    }
+    fn call_later(
+        game: &mut Game,
+        script_name: &str,
+        function: &str,
+        owner_id: GameObjectId,
+        value: Option<Dynamic>,
+    ) {
+        let owner = match ScriptObject::get(game, owner_id) {
+            Some(owner) => owner,
+            None => return,
+        };
+        let mut args = vec![Dynamic::from(owner)];
+        args.extend(value);
+        let result = ScriptSystem::call(game, script_name, function, args, None);
+        ScriptSystem::carry_out(game, script_name, owner_id, result);
+    }
+    // Returns the commands the script queued
+    fn call(
+        game: &mut Game,
+        script_name: &str,
+        function: &str,
+        args: Vec<Dynamic>,
+        this: Option<&mut Dynamic>,
+    ) -> Result<Vec<ScriptCommand>, String> {
+        let script_system = &game.script_system;
+        let script = script_system
+            .get(script_name)
+            .ok_or_else(|| format!("Unknown script {}", script_name))?;
+        let mut options = CallFnOptions::new().eval_ast(false);
+        if let Some(this) = this {
+            options = options.bind_this_ptr(this);
+        }
+        let result = script_system.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            &script.ast,
+            function,
+            args,
+        );
+        let commands: Vec<ScriptCommand> = script_system.commands.borrow_mut().drain(..).collect();
+        match result {
+            Ok(_) => Ok(commands),
+            Err(error) => Err(format!("{} in {}: {}", function, script_name, error)),
+        }
+    }
+    // Returns whether the script ended its quest
+    fn carry_out(
+        game: &mut Game,
+        script_name: &str,
+        owner_id: GameObjectId,
+        result: Result<Vec<ScriptCommand>, String>,
+    ) -> bool {
+        let commands = match result {
+            Ok(commands) => commands,
+            Err(error) => {
+                tracing::warn!(%error, "Script failed");
+                return false;
+            }
+        };
+        let mut ended_quest = false;
+        for command in commands {
+            if let ScriptCommand::EndQuest = command {
+                ended_quest = true;
+            }
+            if let Err(error) =
+                ScriptSystem::carry_out_command(game, script_name, owner_id, command)
+            {
+                tracing::warn!(script = script_name, %error, "Script failed");
+            }
+        }
+        ended_quest
+    }
+    fn carry_out_command(
+        game: &mut Game,
+        script_name: &str,
+        owner_id: GameObjectId,
+        command: ScriptCommand,
+    ) -> Result<(), String> {
+        match command {
+            ScriptCommand::Spawn { prefab, x, y } => {
+                let plane = owner_id
+                    .get_coords_safe(&game.game_objects)
+                    .map(|coords| coords.get_plane())
+                    .unwrap_or(Plane(0));
+                PrefabSystem::spawn(game, &prefab, PixelCoords::new_to_fixed(plane, x, y))?;
+            }
+            ScriptCommand::MoveTo { id, x, y } => {
+                if let Some(coords) = id.get_coords_safe(&game.game_objects) {
+                    id.move_to_game(game, PixelCoords::new_to_fixed(coords.get_plane(), x, y));
+                }
+            }
+            ScriptCommand::Damage {
+                target_id,
+                amount,
+                damage_type,
+            } => {
+                let damage_type = DamageType::from_name(&damage_type)
+                    .ok_or_else(|| format!("Unknown damage type {}", damage_type))?;
+                if !target_id.is_deleted(&game.game_objects) {
+                    deal_damage(
+                        game,
+                        target_id,
+                        owner_id,
+                        Health(amount as i32),
+                        damage_type,
+                    );
+                }
+            }
+            ScriptCommand::After {
+                ticks,
+                function,
+                value,
+            } => {
+                let script_name = script_name.to_string();
+                TimerSystem::add_timer(
+                    game,
+                    Box::new(move |game| {
+                        ScriptSystem::call_later(game, &script_name, &function, owner_id, value)
+                    }),
+                    ticks.max(0) as u32,
+                );
+            }
+            ScriptCommand::Say { id, text } => id.speak_safe(game, text, None),
+            ScriptCommand::Notify { id, text } => {
+                let notification_id = ScriptSystem::get_notification_id(game, script_name);
+                id.send_notification(
+                    game.tick_counter,
+                    &mut game.player_system,
+                    notification_id,
+                    text,
+                );
+            }
+            ScriptCommand::GrantExperience { id, amount } => {
+                if let Some(player_id) = game.player_system.players_by_game_object.get(id) {
+                    let player_id = *player_id;
+                    ProgressionSystem::grant_player_experience(
+                        game,
+                        player_id,
+                        amount.max(0) as u32,
+                    );
+                }
+            }
+            ScriptCommand::BeginQuest { id, script_name } => {
+                ScriptSystem::begin_quest(game, &script_name, id)?;
+            }
+            ScriptCommand::EndQuest => {}
+        }
+        Ok(())
+    }
+    fn get_notification_id(game: &mut Game, script_name: &str) -> NotificationId {
+        if let Some((_name, notification_id)) = game
+            .script_system
+            .notification_ids
+            .iter()
+            .find(|(name, _notification_id)| name == script_name)
+        {
+            return *notification_id;
+        }
+        let notification_id = game.get_id();
+        game.script_system
+            .notification_ids
+            .push((script_name.to_string(), notification_id));
+        notification_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SCRIPT: &str = "
+        fn activate(caster, x, y) {
+            say(caster, `Aimed at ${x}`);
+            after(2, \"hurt\", 500);
+        }
+        fn hurt(caster, amount) {
+            damage(caster, amount, \"physical\");
+        }
+    ";
+
+    #[test]
+    fn scripted_abilities_queue_commands_and_timers() {
+        let mut game = Game::new();
+        game.script_system.load("test", TEST_SCRIPT, None).unwrap();
+        let zombie = PrefabSystem::spawn(&mut game, "zombie", PixelCoords::new_at_zero()).unwrap();
+        let health = |game: &Game| {
+            let component = zombie.get_components_of_type::<Rc<DamageableComponent>>(game)[0];
+            game.damage_system
+                .damageables
+                .get(component.damageable_id)
+                .unwrap()
+                .health
+                .0
+        };
+        let starting_health = health(&game);
+
+        ScriptSystem::activate_ability(&mut game, "test", zombie, PixelCoords::new_at_zero());
+        assert!(game.speeches.get(zombie).is_some());
+        assert_eq!(health(&game), starting_health);
+        for _ in 0..3 {
+            game.tick_counter += 1;
+            TimerSystem::step(&mut game);
+        }
+        assert_eq!(health(&game), starting_health - 500);
+
+        // A broken reload keeps the old version
+        assert!(game
+            .script_system
+            .load("test", "fn activate(", None)
+            .is_err());
+        assert!(game.script_system.get("test").is_some());
+        // Reloading keeps the script where it was, so ids handed out still point at it
+        let script_id = game.script_system.get_id("test").unwrap();
+        game.script_system.load("test", TEST_SCRIPT, None).unwrap();
+        assert_eq!(game.script_system.get_id("test"), Some(script_id));
+        assert_eq!(game.script_system.get_name(script_id), "test");
+
+        ScriptSystem::begin_quest(&mut game, "hold_out", zombie).unwrap();
+        assert!(ScriptSystem::is_on_quest(&game, "hold_out", zombie));
+        ScriptSystem::step(&mut game);
+        let state = game.script_system.quests[0].state.clone_cast::<Map>();
+        assert_eq!(state["ticks_left"].as_int(), Ok(1199));
+    }
+}
@@ -1,6 +1,6 @@
-use crate::combinable::OrBool;
 use crate::game::*;
 use crate::terrain::get_chunk_index_from_relative_coords;
+use signal_listeners::OrBool;
 
 pub struct SolidSystem {
     blockable_movers: IdMap<BlockableMoverId, BlockableMover>,
@@ -158,9 +158,6 @@ impl GetBlockMovementSignalListener for BlockableMoverComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn GetBlockMovementSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_get_block_movement_signal(&self, game: &Game, _owner_id: GameObjectId) -> OrBool {
         let blockable_mover = game
             .solid_system
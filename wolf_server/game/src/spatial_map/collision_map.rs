@@ -74,6 +74,52 @@ impl HitBox {
         ret.coords = location;
         ret
     }
+    // The box covering everywhere this box goes while moving by (dx, dy)
+    pub fn get_swept_bounds(&self, dx: f64, dy: f64) -> HitBox {
+        HitBox::new(
+            self.coords.translate(dx / 2.0, dy / 2.0),
+            self.width.0.to_num::<f64>() + dx.abs() / 2.0,
+            self.height.0.to_num::<f64>() + dy.abs() / 2.0,
+        )
+    }
+    /*
+    Swept AABB: how far through the move (dx, dy), from 0 to 1, this box first touches other.
+    Growing other by our size lets us sweep our center as a point through it.
+    Boxes that already overlap touch at 0.
+    */
+    pub fn get_sweep_contact(&self, dx: f64, dy: f64, other: &HitBox) -> Option<f64> {
+        let axes = [
+            (
+                other.coords.get_x().0.to_num::<f64>() - self.coords.get_x().0.to_num::<f64>(),
+                (self.width + other.width).0.to_num::<f64>(),
+                dx,
+            ),
+            (
+                other.coords.get_y().0.to_num::<f64>() - self.coords.get_y().0.to_num::<f64>(),
+                (self.height + other.height).0.to_num::<f64>(),
+                dy,
+            ),
+        ];
+        let mut entry: f64 = 0.0;
+        let mut exit: f64 = 1.0;
+        for (offset, half_size, delta) in axes {
+            if delta == 0.0 {
+                if offset.abs() > half_size {
+                    return None;
+                }
+            } else {
+                let near = (offset - half_size) / delta;
+                let far = (offset + half_size) / delta;
+                entry = entry.max(near.min(far));
+                exit = exit.min(near.max(far));
+            }
+        }
+        if entry <= exit {
+            Some(entry)
+        } else {
+            None
+        }
+    }
     pub fn get_overlapping_squares(&self) -> WolfHashSet<SquareCoords> {
         let bottom_left = self.coords.translate_fixed(-self.width, -self.height);
         let top_right = self.coords.translate_fixed(self.width, self.height);
@@ -127,4 +173,47 @@ impl<ItemType: HitBoxed + Eq + Hash + Clone> CollisionMap<ItemType> {
             .filter(|candidate| candidate.get_hit_box(game).collides_with(&hit_box))
             .collect::<Vec<ItemType>>()
     }
+    // Everything the box touches while moving by (dx, dy), nearest first, with when it's touched
+    pub fn get_swept_colliding_game(
+        &self,
+        game: &Game,
+        hit_box: HitBox,
+        dx: f64,
+        dy: f64,
+    ) -> Vec<(f64, ItemType)> {
+        let bounds = hit_box.get_swept_bounds(dx, dy);
+        let candidates = self.0.get_within_box(
+            bounds.coords.into(),
+            bounds.width.0.to_num::<i64>(),
+            bounds.height.0.to_num::<i64>(),
+        );
+        let mut contacts: Vec<(f64, ItemType)> = candidates
+            .into_iter()
+            .filter_map(|candidate| {
+                hit_box
+                    .get_sweep_contact(dx, dy, &candidate.get_hit_box(game))
+                    .map(|contact| (contact, candidate))
+            })
+            .collect();
+        contacts.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+        contacts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fast_boxes_hit_thin_targets_they_pass_through() {
+        let bullet = HitBox::new_at_zero(5, 5);
+        let wall = HitBox::new(PixelCoords::new_to_fixed(Plane(0), 30, 0), 2, 20);
+        assert!(!bullet
+            .translate(PixelCoords::new_to_fixed(Plane(0), 60, 0))
+            .collides_with(&wall));
+        let contact = bullet.get_sweep_contact(60.0, 0.0, &wall).unwrap();
+        assert!((contact - 23.0 / 60.0).abs() < 0.001);
+        assert_eq!(bullet.get_sweep_contact(-60.0, 0.0, &wall), None);
+        assert_eq!(bullet.get_sweep_contact(60.0, 100.0, &wall), None);
+    }
 }
@@ -1,6 +1,6 @@
 use crate::game::*;
 
-const SPEECH_TIME: u32 = 100;
+pub const SPEECH_TIME: u32 = 100;
 
 pub struct Speech {
     say_until: Option<u32>,
@@ -11,7 +11,7 @@ impl Speech {
         let mut to_remove = Vec::new();
         for (id, speech) in game.speeches.iter() {
             if let Some(say_until) = speech.say_until {
-                if say_until > game.tick_counter {
+                if say_until <= game.tick_counter {
                     to_remove.push((id, speech.client_side_component_id));
                 }
             }
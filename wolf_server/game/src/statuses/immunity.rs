@@ -0,0 +1,82 @@
+use super::*;
+use signal_listeners::CombinedVecs;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StatusTag {
+    Harmful,
+    Beneficial,
+    Blood,
+    Fire,
+    Movement,
+    Mind,
+}
+
+impl StatusTag {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "harmful" => Some(StatusTag::Harmful),
+            "beneficial" => Some(StatusTag::Beneficial),
+            "blood" => Some(StatusTag::Blood),
+            "fire" => Some(StatusTag::Fire),
+            "movement" => Some(StatusTag::Movement),
+            "mind" => Some(StatusTag::Mind),
+            _ => None,
+        }
+    }
+}
+
+define_signal_listener!(GetStatusImmunities, &Game -> CombinedVecs<StatusTag>);
+
+pub fn is_immune(game: &Game, target_id: GameObjectId, tags: &Vec<StatusTag>) -> bool {
+    target_id
+        .send_get_status_immunities_signal(game)
+        .map(|x| x.0)
+        .unwrap_or(Vec::new())
+        .iter()
+        .any(|immunity| tags.contains(immunity))
+}
+
+#[derive(Clone)]
+pub struct StatusImmunityComponent {
+    component_id: ComponentId,
+    immunities: Vec<StatusTag>,
+}
+
+impl StatusImmunityComponent {
+    pub fn add_to(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        immunities: Vec<StatusTag>,
+    ) -> ComponentId {
+        let component_id = game.get_id();
+        let comp = StatusImmunityComponent {
+            component_id,
+            immunities,
+        };
+        owner_id.add_get_status_immunities_signal_listener(game, comp.clone());
+        owner_id.add_component(game, comp);
+        component_id
+    }
+}
+
+impl Component for StatusImmunityComponent {
+    fn get_component_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
+        owner_id.remove_get_status_immunities_signal_listener(game, self.component_id);
+    }
+}
+
+impl GetStatusImmunitiesSignalListener for StatusImmunityComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_get_status_immunities_signal(
+        &self,
+        _game: &Game,
+        _owner_id: GameObjectId,
+    ) -> CombinedVecs<StatusTag> {
+        CombinedVecs(self.immunities.clone())
+    }
+}
@@ -0,0 +1,483 @@
+use super::*;
+use crate::abilities::BasicAbilityUser;
+use crate::threat::ThreatSystem;
+use signal_listeners::Added;
+use wolf_interface::{CreateComponentData, StatusData};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StatusKind {
+    Bleed,
+    Slow,
+    Stun,
+    Confusion,
+    Regeneration,
+    Burning,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StackingRule {
+    // Reapplying resets the duration and keeps the strongest potency
+    Refresh,
+    // Reapplying adds a stack, up to the maximum, and resets the duration
+    StackIntensity(u32),
+    // Every application runs separately
+    Independent,
+}
+
+pub struct StatusDefinition {
+    pub icon: u32,
+    pub stacking: StackingRule,
+    pub tick_interval: Option<u32>,
+    pub tags: Vec<StatusTag>,
+    pub dispellable: bool,
+}
+
+impl StatusKind {
+    /*
+    Potency means different things for different statuses:
+    damage or healing per tick for Bleed, Burning and Regeneration,
+    percent slowed for Slow, and nothing for Stun and Confusion
+    */
+    pub fn get_definition(&self) -> StatusDefinition {
+        match self {
+            StatusKind::Bleed => StatusDefinition {
+                icon: BLEED_STATUS_ICON,
+                stacking: StackingRule::StackIntensity(5),
+                tick_interval: Some(10),
+                tags: vec![StatusTag::Harmful, StatusTag::Blood],
+                dispellable: true,
+            },
+            StatusKind::Slow => StatusDefinition {
+                icon: SLOW_STATUS_ICON,
+                stacking: StackingRule::Refresh,
+                tick_interval: None,
+                tags: vec![StatusTag::Harmful, StatusTag::Movement],
+                dispellable: true,
+            },
+            StatusKind::Stun => StatusDefinition {
+                icon: STUN_STATUS_ICON,
+                stacking: StackingRule::Refresh,
+                tick_interval: None,
+                tags: vec![StatusTag::Harmful, StatusTag::Movement],
+                dispellable: true,
+            },
+            StatusKind::Confusion => StatusDefinition {
+                icon: CONFUSED_STATUS_ICON,
+                stacking: StackingRule::Refresh,
+                tick_interval: None,
+                tags: vec![StatusTag::Mind],
+                dispellable: false,
+            },
+            StatusKind::Regeneration => StatusDefinition {
+                icon: REGENERATION_STATUS_ICON,
+                stacking: StackingRule::Independent,
+                tick_interval: Some(10),
+                tags: vec![StatusTag::Beneficial],
+                dispellable: true,
+            },
+            StatusKind::Burning => StatusDefinition {
+                icon: BURNING_STATUS_ICON,
+                stacking: StackingRule::Refresh,
+                tick_interval: Some(10),
+                tags: vec![StatusTag::Harmful, StatusTag::Fire],
+                dispellable: true,
+            },
+        }
+    }
+}
+
+pub struct Status {
+    pub target_id: GameObjectId,
+    pub source_id: GameObjectId,
+    pub kind: StatusKind,
+    pub potency: i32,
+    pub stacks: u32,
+    pub expires_at: u32,
+    pub next_tick_at: Option<u32>,
+    pub component_id: ComponentId,
+    pub client_side_component_id: ClientSideComponentId,
+}
+
+impl Status {
+    fn get_status_data(&self) -> CreateComponentData {
+        CreateComponentData::Status(StatusData {
+            icon: self.kind.get_definition().icon,
+            stacks: self.stacks,
+            expires_at: self.expires_at,
+        })
+    }
+}
+
+impl StatusSystem {
+    pub fn get_statuses_on(
+        &self,
+        target_id: GameObjectId,
+    ) -> impl Iterator<Item = (StatusId, &Status)> {
+        self.statuses
+            .iter()
+            .filter(move |(_id, status)| status.target_id == target_id)
+    }
+    pub fn has_status(&self, target_id: GameObjectId, kind: StatusKind) -> bool {
+        self.get_statuses_on(target_id)
+            .any(|(_id, status)| status.kind == kind)
+    }
+    // Returns the status that was created or changed, if the target wasn't immune
+    pub fn apply_status(
+        game: &mut Game,
+        target_id: GameObjectId,
+        source_id: GameObjectId,
+        kind: StatusKind,
+        duration: u32,
+        potency: i32,
+    ) -> Option<StatusId> {
+        let definition = kind.get_definition();
+        if is_immune(game, target_id, &definition.tags) {
+            return None;
+        }
+        let tick_counter = game.tick_counter;
+        let existing = game
+            .status_system
+            .get_statuses_on(target_id)
+            .find(|(_id, status)| status.kind == kind)
+            .map(|(id, _status)| id);
+        if let Some(existing_id) = existing {
+            let status = game.status_system.statuses.get_mut(existing_id).unwrap();
+            let updated = match definition.stacking {
+                StackingRule::Refresh => {
+                    status.expires_at = status.expires_at.max(tick_counter + duration);
+                    status.potency = status.potency.max(potency);
+                    true
+                }
+                StackingRule::StackIntensity(max_stacks) => {
+                    status.stacks = (status.stacks + 1).min(max_stacks);
+                    status.expires_at = tick_counter + duration;
+                    status.potency = status.potency.max(potency);
+                    true
+                }
+                StackingRule::Independent => false,
+            };
+            if updated {
+                let data = status.get_status_data();
+                let client_side_component_id = status.client_side_component_id;
+                target_id.refresh_client_side_component(game, client_side_component_id, data);
+                return Some(existing_id);
+            }
+        }
+        let status_id = game.get_id();
+        let component_id = game.get_id();
+        let expires_at = tick_counter + duration;
+        let client_side_component_id = target_id.add_client_side_component(
+            game,
+            CreateComponentData::Status(StatusData {
+                icon: definition.icon,
+                stacks: 1,
+                expires_at,
+            }),
+        );
+        let status = Status {
+            target_id,
+            source_id,
+            kind,
+            potency,
+            stacks: 1,
+            expires_at,
+            next_tick_at: definition
+                .tick_interval
+                .map(|interval| tick_counter + interval),
+            component_id,
+            client_side_component_id,
+        };
+        game.status_system.statuses.insert(status_id, status);
+        let comp = StatusComponent {
+            component_id,
+            status_id,
+        };
+        if let StatusKind::Slow | StatusKind::Stun = kind {
+            target_id.add_get_speed_mod_signal_listener(game, comp.clone());
+        }
+        target_id.add_component(game, comp);
+        if kind == StatusKind::Stun {
+            BasicAbilityUser::interrupt_all(game, target_id);
+        }
+        Some(status_id)
+    }
+    pub fn remove_status(game: &mut Game, status_id: StatusId) {
+        let (target_id, component_id) = match game.status_system.statuses.get(status_id) {
+            Some(status) => (status.target_id, status.component_id),
+            None => return,
+        };
+        target_id.remove_component(game, component_id);
+    }
+    // Removes dispellable statuses with the given tag, returning how many went
+    pub fn dispel(game: &mut Game, target_id: GameObjectId, tag: StatusTag) -> usize {
+        let to_dispel: Vec<StatusId> = game
+            .status_system
+            .get_statuses_on(target_id)
+            .filter(|(_id, status)| {
+                let definition = status.kind.get_definition();
+                definition.dispellable && definition.tags.contains(&tag)
+            })
+            .map(|(id, _status)| id)
+            .collect();
+        for status_id in to_dispel.iter() {
+            StatusSystem::remove_status(game, *status_id);
+        }
+        to_dispel.len()
+    }
+    pub fn step(game: &mut Game) {
+        let tick_counter = game.tick_counter;
+        let mut to_expire = Vec::new();
+        let mut to_tick = Vec::new();
+        for (id, status) in game.status_system.statuses.iter_mut() {
+            if let Some(next_tick_at) = status.next_tick_at {
+                if next_tick_at <= tick_counter {
+                    let interval = status.kind.get_definition().tick_interval.unwrap();
+                    status.next_tick_at = Some(next_tick_at + interval);
+                    to_tick.push((
+                        status.target_id,
+                        status.source_id,
+                        status.kind,
+                        Health(status.potency * status.stacks as i32),
+                    ));
+                }
+            }
+            if status.expires_at <= tick_counter {
+                to_expire.push(id);
+            }
+        }
+        for (target_id, source_id, kind, amount) in to_tick {
+            match kind {
+                StatusKind::Bleed => {
+                    deal_damage(game, target_id, source_id, amount, DamageType::Bleed);
+                }
+                StatusKind::Burning => {
+                    deal_damage(game, target_id, source_id, amount, DamageType::Fire);
+                }
+                StatusKind::Regeneration => {
+                    target_id.send_heal_signal(game, amount);
+                    ThreatSystem::add_healing_threat(game, source_id, target_id, amount.0 as f64);
+                }
+                _ => {}
+            }
+        }
+        for status_id in to_expire {
+            StatusSystem::remove_status(game, status_id);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct StatusComponent {
+    component_id: ComponentId,
+    status_id: StatusId,
+}
+
+impl Component for StatusComponent {
+    fn get_component_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
+        if let Some(status) = game.status_system.statuses.remove(self.status_id) {
+            if let StatusKind::Slow | StatusKind::Stun = status.kind {
+                owner_id.remove_get_speed_mod_signal_listener(game, self.component_id);
+            }
+            owner_id.remove_client_side_component(game, status.client_side_component_id);
+        }
+    }
+}
+
+impl GetSpeedModSignalListener for StatusComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_get_speed_mod_signal(&self, game: &Game, _owner_id: GameObjectId) -> Added<f64> {
+        match game.status_system.statuses.get(self.status_id) {
+            Some(Status {
+                kind: StatusKind::Slow,
+                potency,
+                ..
+            }) => Added(-(*potency as f64) / 100.0),
+            // Nothing can outrun a stun
+            Some(Status {
+                kind: StatusKind::Stun,
+                ..
+            }) => Added(f64::NEG_INFINITY),
+            _ => Added(0.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::threat::ThreatTable;
+    use std::rc::Rc;
+
+    fn create_target(game: &mut Game) -> GameObjectId {
+        let id = GameObject::create_game(game, PixelCoords::new_at_zero());
+        DamageableComponent::add_to(game, id);
+        id
+    }
+
+    fn get_damage_taken(game: &Game, id: GameObjectId) -> i32 {
+        let damageable_id = id
+            .get_components_of_type::<Rc<DamageableComponent>>(game)
+            .first()
+            .unwrap()
+            .damageable_id;
+        let damageable = game.damage_system.damageables.get(damageable_id).unwrap();
+        damageable.max_health.0 - damageable.health.0
+    }
+
+    fn step_to(game: &mut Game, tick_counter: u32) {
+        while game.tick_counter < tick_counter {
+            game.tick_counter += 1;
+            StatusSystem::step(game);
+        }
+    }
+
+    fn apply(
+        game: &mut Game,
+        target_id: GameObjectId,
+        kind: StatusKind,
+        duration: u32,
+        potency: i32,
+    ) -> Option<StatusId> {
+        StatusSystem::apply_status(game, target_id, target_id, kind, duration, potency)
+    }
+
+    #[test]
+    fn refreshed_statuses_keep_the_longest_duration_and_strongest_potency() {
+        let mut game = Game::new();
+        let target_id = create_target(&mut game);
+        let first = apply(&mut game, target_id, StatusKind::Slow, 50, 40);
+        step_to(&mut game, 10);
+        let second = apply(&mut game, target_id, StatusKind::Slow, 20, 20);
+        assert_eq!(first, second);
+        let status = game.status_system.statuses.get(first.unwrap()).unwrap();
+        assert_eq!(
+            (status.expires_at, status.potency, status.stacks),
+            (50, 40, 1)
+        );
+        apply(&mut game, target_id, StatusKind::Slow, 60, 10);
+        let status = game.status_system.statuses.get(first.unwrap()).unwrap();
+        assert_eq!((status.expires_at, status.potency), (70, 40));
+    }
+
+    #[test]
+    fn intensity_stacks_up_to_the_maximum_and_resets_the_duration() {
+        let mut game = Game::new();
+        let target_id = create_target(&mut game);
+        let status_id = apply(&mut game, target_id, StatusKind::Bleed, 100, 1).unwrap();
+        for tick in 1..=10 {
+            step_to(&mut game, tick);
+            apply(&mut game, target_id, StatusKind::Bleed, 20, 1);
+        }
+        let status = game.status_system.statuses.get(status_id).unwrap();
+        assert_eq!(status.stacks, 5);
+        // Shorter than it was first applied for, as every stack resets it
+        assert_eq!(status.expires_at, 30);
+        assert_eq!(game.status_system.get_statuses_on(target_id).count(), 1);
+    }
+
+    #[test]
+    fn independent_statuses_run_separately() {
+        let mut game = Game::new();
+        let target_id = create_target(&mut game);
+        let first = apply(&mut game, target_id, StatusKind::Regeneration, 20, 1);
+        let second = apply(&mut game, target_id, StatusKind::Regeneration, 40, 1);
+        assert_ne!(first, second);
+        assert_eq!(game.status_system.get_statuses_on(target_id).count(), 2);
+        step_to(&mut game, 20);
+        assert_eq!(game.status_system.get_statuses_on(target_id).count(), 1);
+        assert!(game.status_system.statuses.get(second.unwrap()).is_some());
+    }
+
+    #[test]
+    fn immunities_block_statuses_with_those_tags() {
+        let mut game = Game::new();
+        let target_id = create_target(&mut game);
+        StatusImmunityComponent::add_to(&mut game, target_id, vec![StatusTag::Blood]);
+        assert!(apply(&mut game, target_id, StatusKind::Bleed, 20, 1).is_none());
+        assert!(!game.status_system.has_status(target_id, StatusKind::Bleed));
+        assert!(apply(&mut game, target_id, StatusKind::Slow, 20, 1).is_some());
+    }
+
+    #[test]
+    fn only_dispellable_statuses_are_dispelled() {
+        let mut game = Game::new();
+        let target_id = create_target(&mut game);
+        apply(&mut game, target_id, StatusKind::Confusion, 100, 0);
+        apply(&mut game, target_id, StatusKind::Slow, 100, 20);
+        apply(&mut game, target_id, StatusKind::Bleed, 100, 1);
+        assert_eq!(
+            StatusSystem::dispel(&mut game, target_id, StatusTag::Mind),
+            0
+        );
+        assert!(game
+            .status_system
+            .has_status(target_id, StatusKind::Confusion));
+        assert_eq!(
+            StatusSystem::dispel(&mut game, target_id, StatusTag::Harmful),
+            2
+        );
+        assert!(!game.status_system.has_status(target_id, StatusKind::Slow));
+        assert!(!game.status_system.has_status(target_id, StatusKind::Bleed));
+        assert!(game
+            .status_system
+            .has_status(target_id, StatusKind::Confusion));
+    }
+
+    #[test]
+    fn statuses_tick_on_their_interval_until_they_expire() {
+        let mut game = Game::new();
+        let target_id = create_target(&mut game);
+        apply(&mut game, target_id, StatusKind::Burning, 35, 3);
+        step_to(&mut game, 9);
+        assert_eq!(get_damage_taken(&game, target_id), 0);
+        step_to(&mut game, 10);
+        assert_eq!(get_damage_taken(&game, target_id), 3);
+        step_to(&mut game, 34);
+        assert_eq!(get_damage_taken(&game, target_id), 9);
+        assert!(game
+            .status_system
+            .has_status(target_id, StatusKind::Burning));
+        step_to(&mut game, 35);
+        assert!(!game
+            .status_system
+            .has_status(target_id, StatusKind::Burning));
+        step_to(&mut game, 60);
+        assert_eq!(get_damage_taken(&game, target_id), 9);
+    }
+
+    #[test]
+    fn bleeding_ignores_armour_but_counts_as_damage_from_its_source() {
+        let mut game = Game::new();
+        let target_id = create_target(&mut game);
+        ResistancesComponent::add_to(&mut game, target_id, "plate").unwrap();
+        game.threat_system
+            .threat_tables
+            .insert(target_id, ThreatTable::new());
+        let source_id = create_target(&mut game);
+        StatusSystem::apply_status(&mut game, target_id, source_id, StatusKind::Bleed, 20, 4);
+        step_to(&mut game, 10);
+        assert_eq!(get_damage_taken(&game, target_id), 4);
+        assert_eq!(game.threat_system.get_target(target_id), Some(source_id));
+    }
+
+    #[test]
+    fn slows_stop_slowing_when_they_expire() {
+        let mut game = Game::new();
+        let target_id = create_target(&mut game);
+        apply(&mut game, target_id, StatusKind::Slow, 10, 50);
+        assert_eq!(
+            target_id.send_get_speed_mod_signal(&game).map(|x| x.0),
+            Some(-0.5)
+        );
+        step_to(&mut game, 10);
+        assert_eq!(
+            target_id.send_get_speed_mod_signal(&game).map(|x| x.0),
+            None
+        );
+    }
+}
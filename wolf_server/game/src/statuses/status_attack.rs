@@ -0,0 +1,68 @@
+use super::*;
+
+// Applies a status to anything the owner damages
+#[derive(Clone)]
+pub struct StatusAttackComponent {
+    component_id: ComponentId,
+    kind: StatusKind,
+    duration: u32,
+    potency: i32,
+}
+
+impl StatusAttackComponent {
+    pub fn add_to(
+        game: &mut Game,
+        owner_id: GameObjectId,
+        kind: StatusKind,
+        duration: u32,
+        potency: i32,
+    ) -> ComponentId {
+        let component_id = game.get_id();
+        let comp = StatusAttackComponent {
+            component_id,
+            kind,
+            duration,
+            potency,
+        };
+        owner_id.add_dealt_damage_signal_listener(game, comp.clone());
+        owner_id.add_component(game, comp);
+        component_id
+    }
+}
+
+impl DealtDamageSignalListener for StatusAttackComponent {
+    fn receive_dealt_damage_signal(
+        &self,
+        game: &mut Game,
+        owner_id: GameObjectId,
+        target_id: GameObjectId,
+    ) {
+        let source_id = game
+            .damage_system
+            .damagers
+            .iter()
+            .find(|(_id, damager)| damager.game_object_id == owner_id)
+            .map(|(_id, damager)| damager.firer_id)
+            .unwrap_or(owner_id);
+        StatusSystem::apply_status(
+            game,
+            target_id,
+            source_id,
+            self.kind,
+            self.duration,
+            self.potency,
+        );
+    }
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+}
+
+impl Component for StatusAttackComponent {
+    fn get_component_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
+        owner_id.remove_dealt_damage_signal_listener(game, self.component_id);
+    }
+}
@@ -1,3 +1,4 @@
+use super::get_chunk_index_from_relative_coords;
 use crate::game::*;
 use wolf_hash_map::WolfHashSet;
 use wolf_interface::*;
@@ -23,9 +24,27 @@ impl ChunkSquare {
             .chain(self.sprites.iter().map(|(_, s)| *s)))
         .collect()
     }
+    // The client draws the chunk's base sprite under every square, so a square only needs to
+    // send its own base sprite when it's different
+    pub fn to_sprites_vec_over(&self, chunk_base_sprite: u32) -> Vec<u32> {
+        let mut sprites_vec = self.to_sprites_vec();
+        if self.base_sprite != chunk_base_sprite {
+            sprites_vec.insert(0, self.base_sprite);
+        }
+        sprites_vec
+    }
     pub fn is_solid(&self) -> bool {
         self.base_solid || !self.solids.is_empty()
     }
+    // Deep water can't be walked through unless it's been bridged
+    pub fn is_walkable(&self) -> bool {
+        !self.is_solid()
+            && (self.base_sprite != WATER_SPRITE
+                || self
+                    .sprites
+                    .iter()
+                    .any(|(_, sprite)| *sprite == BRIDGE_SPRITE))
+    }
 }
 
 pub struct Chunk {
@@ -73,15 +92,16 @@ impl Chunk {
         let chunk = Self::new(base_sprite);
         game.terrain.chunks.insert(coords, chunk);
     }
+    pub fn get_square_sprites(&self, relative: ChunkRelativeSquareCoords) -> Vec<u32> {
+        self.chunk_squares[get_chunk_index_from_relative_coords(relative)]
+            .to_sprites_vec_over(self.base_sprite)
+    }
     pub fn get_base_info_message(&self) -> BaseChunkMessage {
-        let mut terrain: Vec<Vec<u32>> = Vec::new();
-        for square_sprites in self.chunk_squares.iter() {
-            let mut sprites_vec = square_sprites.to_sprites_vec();
-            if square_sprites.base_sprite != self.base_sprite {
-                sprites_vec.insert(0, square_sprites.base_sprite);
-            }
-            terrain.push(sprites_vec);
-        }
+        let terrain: Vec<Vec<u32>> = self
+            .chunk_squares
+            .iter()
+            .map(|square_sprites| square_sprites.to_sprites_vec_over(self.base_sprite))
+            .collect();
         BaseChunkMessage {
             base_sprite: self.base_sprite,
             terrain,
@@ -1,8 +1,8 @@
 use wolf_hash_map::WolfHashSet;
 
 use crate::biomes::generate_biome;
+use crate::game::*;
 use crate::loading::LoadingSystem;
-use crate::{game::*, time_system};
 
 pub const LOAD_CHUNKS_WITHIN: i64 = 4;
 
@@ -43,18 +43,19 @@ pub fn step_chunk_loaders(game: &mut Game) {
         }
     }
     for coords in all_loaded_chunks {
-        time_system!(if game.terrain.chunks.contains_key(&coords) {
+        if game.terrain.chunks.contains_key(&coords) {
             continue;
-        });
+        }
         new_chunks_to_load.push(coords);
     }
     for coords in chunks_to_remove {
-        time_system!(unload_chunk(game, coords));
+        unload_chunk(game, coords);
         game.terrain.chunks.remove(&coords);
     }
     for coords in new_chunks_to_load {
         generate_biome(game, coords);
         notify_new_chunk(game, coords);
+        game.event_bus.publish(GameEvent::ChunkLoaded { coords });
     }
 }
 
@@ -4,8 +4,6 @@ use crate::terrain::LOAD_CHUNKS_WITHIN;
 use wolf_hash_map::WolfHashSet;
 use wolf_interface::{ChunkUnloadMessage, ChunkUpdateMessage, ServerMessage};
 
-use super::get_chunk_index_from_relative_coords;
-
 /// Sends messages to a player to keep them up to date on terrain
 pub struct ChunkWatcher {
     player_id: Option<PlayerId>,
@@ -34,30 +32,21 @@ impl ChunkWatcher {
                 Some(x) => x,
                 None => continue,
             };
-            let watching = crate::time_system!(square_of_coords_centered(
+            let watching = square_of_coords_centered(
                 chunk_watcher.game_object_id.get_chunk_coords(game),
                 LOAD_CHUNKS_WITHIN,
-            ));
+            );
             let mut update_messages = Vec::new();
             let mut to_unload = Vec::new();
-            let mut chunks_redrawn_debug = 0;
-            let mut squares_redrawn_debug = 0;
             for coords in chunk_watcher.currently_loaded.iter() {
-                chunks_redrawn_debug += 1;
                 if let Some(chunk) = game.terrain.chunks.get(coords) {
-                    let square_updates = crate::time_system!(chunk
+                    let square_updates = chunk
                         .squares_to_redraw
                         .iter()
                         .map(|relative_coords| {
-                            squares_redrawn_debug += 1;
-                            (
-                                *relative_coords,
-                                chunk.chunk_squares
-                                    [get_chunk_index_from_relative_coords(*relative_coords)]
-                                .to_sprites_vec(),
-                            )
+                            (*relative_coords, chunk.get_square_sprites(*relative_coords))
                         })
-                        .collect());
+                        .collect();
                     let message = ChunkUpdateMessage {
                         coords: *coords,
                         square_updates,
@@ -67,17 +56,10 @@ impl ChunkWatcher {
                     to_unload.push(*coords);
                 }
             }
-            #[cfg(feature = "timing")]
-            if game.tick_counter % TIME_EVERY == 0 {
-                println!(
-                    "Redrawing {} squares for {} chunks",
-                    squares_redrawn_debug, chunks_redrawn_debug
-                );
-            }
-            let needs_sending: Vec<TerrainChunkCoords> = crate::time_system!(watching
+            let needs_sending: Vec<TerrainChunkCoords> = watching
                 .difference(&chunk_watcher.currently_loaded)
                 .map(|x| x.clone())
-                .collect());
+                .collect();
             to_send.push((id, player_id, needs_sending, update_messages, to_unload))
         }
         for (chunk_watcher_id, player_id, chunk_coords_to_send, update_messages, to_unload) in
@@ -89,12 +71,12 @@ impl ChunkWatcher {
                     .chunk_watchers
                     .get_mut(chunk_watcher_id)
                     .unwrap();
-                crate::time_system!(chunk_watcher
+                chunk_watcher
                     .currently_loaded
-                    .extend(chunk_coords_to_send.clone().into_iter()));
-                crate::time_system!(for coords in to_unload.iter() {
+                    .extend(chunk_coords_to_send.clone().into_iter());
+                for coords in to_unload.iter() {
                     chunk_watcher.currently_loaded.remove(coords);
-                });
+                }
             }
             let unload_message = if to_unload.is_empty() {
                 None
@@ -103,7 +85,7 @@ impl ChunkWatcher {
                     coords: to_unload,
                 }))
             };
-            let mut messages: Vec<ServerMessage> = crate::time_system!(chunk_coords_to_send
+            let mut messages: Vec<ServerMessage> = chunk_coords_to_send
                 .into_iter()
                 .filter_map(|coords| {
                     game.terrain
@@ -113,9 +95,9 @@ impl ChunkWatcher {
                 })
                 .chain(update_messages.into_iter())
                 .chain(unload_message.into_iter())
-                .collect());
+                .collect();
             let player = game.player_system.players.get_mut(player_id).unwrap();
-            crate::time_system!(player.server_messages.append(&mut messages));
+            player.server_messages.append(&mut messages);
         }
         for (_coords, chunk) in game.terrain.chunks.iter_mut() {
             chunk.squares_to_redraw = Vec::new();
@@ -153,9 +135,6 @@ impl BoundPlayerSignalListener for ChunkWatcherComponent {
     fn get_listener_id(&self) -> ComponentId {
         self.component_id
     }
-    fn clone_box(&self) -> Box<dyn BoundPlayerSignalListener> {
-        Box::new(self.clone())
-    }
     fn receive_bound_player_signal(
         &self,
         game: &mut Game,
@@ -1,4 +1,4 @@
-use crate::{game::*, time_system};
+use crate::game::*;
 
 mod chunk_loader;
 pub use chunk_loader::*;
@@ -35,13 +35,40 @@ impl Terrain {
             basic_chunk_loaders: IdMap::new(),
         }
     }
-}
-
-impl Terrain {
-    pub fn step(game: &mut Game) {
-        time_system!(BasicChunkLoader::step(game));
-        time_system!(step_chunk_loaders(game));
-        time_system!(ChunkWatcher::step(game));
-        time_system!(TerrainSprite::step(game));
+    pub fn get_chunk_square(&self, square: SquareCoords) -> Option<&ChunkSquare> {
+        let chunk_coords: TerrainChunkCoords = square.into();
+        let relative = square.relative_to_chunk(chunk_coords).unwrap();
+        self.chunks
+            .get(&chunk_coords)
+            .map(|chunk| &chunk.chunk_squares[get_chunk_index_from_relative_coords(relative)])
+    }
+    /// Unloaded squares are never solid
+    pub fn is_square_solid(&self, square: SquareCoords) -> bool {
+        self.get_chunk_square(square)
+            .map(|chunk_square| chunk_square.is_solid())
+            .unwrap_or(false)
+    }
+    /// Unloaded squares are always walkable
+    pub fn is_square_walkable(&self, square: SquareCoords) -> bool {
+        self.get_chunk_square(square)
+            .map(|chunk_square| chunk_square.is_walkable())
+            .unwrap_or(true)
+    }
+    /// Changes a loaded square and queues it to be redrawn. Returns false if the square is unloaded.
+    pub fn modify_chunk_square<F: FnOnce(&mut ChunkSquare)>(
+        &mut self,
+        square: SquareCoords,
+        modify: F,
+    ) -> bool {
+        let chunk_coords: TerrainChunkCoords = square.into();
+        let relative = square.relative_to_chunk(chunk_coords).unwrap();
+        match self.chunks.get_mut(&chunk_coords) {
+            Some(chunk) => {
+                modify(&mut chunk.chunk_squares[get_chunk_index_from_relative_coords(relative)]);
+                chunk.squares_to_redraw.push(relative);
+                true
+            }
+            None => false,
+        }
     }
 }
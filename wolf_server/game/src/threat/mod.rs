@@ -0,0 +1,386 @@
+use crate::game::*;
+use crate::perception::{Perception, PerceptionSystem};
+use signal_listeners::{OrBool, Stoppable};
+use wolf_hash_map::WolfHashMap;
+
+/*
+Hostiles remember how much trouble everyone has caused them and go after whoever has caused the most.
+Damage taken, attacks attempted, healing their enemies and taunts all add threat, and it fades over time.
+*/
+
+const THREAT_DECAY_INTERVAL: u32 = 10;
+// Fraction of threat kept each time it decays
+const THREAT_DECAY: f64 = 0.95;
+// Threat below this is forgotten
+const MINIMUM_THREAT: f64 = 10.0;
+// A new target needs this much more threat than the current one before we switch to it
+const THREAT_SWITCH_THRESHOLD: f64 = 1.1;
+// Even attacks that are blocked or absorbed are worth noticing
+const ATTACKED_THREAT: f64 = 100.0;
+// Whoever we're already hitting gets a little more, so we don't flip between targets
+const DEALT_DAMAGE_THREAT: f64 = 50.0;
+const HEALING_THREAT_MULTIPLIER: f64 = 0.5;
+const TAUNT_DURATION: u32 = 100;
+
+pub struct ThreatTable {
+    pub threat: WolfHashMap<GameObjectId, f64>,
+    pub current_target: Option<GameObjectId>,
+    // Who taunted us, and until when
+    pub taunted_by: Option<(GameObjectId, u32)>,
+}
+
+impl ThreatTable {
+    pub fn new() -> Self {
+        ThreatTable {
+            threat: WolfHashMap::new(),
+            current_target: None,
+            taunted_by: None,
+        }
+    }
+    fn get_threat(&self, id: GameObjectId) -> f64 {
+        self.threat.get(&id).map(|x| *x).unwrap_or(0.0)
+    }
+    fn choose_target(&mut self, tick_counter: u32) {
+        if let Some((taunter_id, until)) = self.taunted_by {
+            if until > tick_counter && self.threat.contains_key(&taunter_id) {
+                self.current_target = Some(taunter_id);
+                return;
+            }
+            self.taunted_by = None;
+        }
+        let highest = self
+            .threat
+            .iter()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(id, threat)| (*id, *threat));
+        self.current_target = match (self.current_target, highest) {
+            (_, None) => None,
+            (Some(current), Some((_, highest_threat)))
+                if self.threat.contains_key(&current)
+                    && highest_threat < self.get_threat(current) * THREAT_SWITCH_THRESHOLD =>
+            {
+                Some(current)
+            }
+            (_, Some((highest_id, _))) => Some(highest_id),
+        };
+    }
+}
+
+pub struct ThreatSystem {
+    pub threat_tables: IdMap<GameObjectId, ThreatTable>,
+}
+
+impl ThreatSystem {
+    pub fn new() -> Self {
+        ThreatSystem {
+            threat_tables: IdMap::new(),
+        }
+    }
+    pub fn get_target(&self, holder_id: GameObjectId) -> Option<GameObjectId> {
+        self.threat_tables
+            .get(holder_id)
+            .and_then(|table| table.current_target)
+    }
+    // The current target, as long as we still know about them and can hurt them
+    pub fn get_valid_target(game: &Game, holder_id: GameObjectId) -> Option<GameObjectId> {
+        game.threat_system
+            .get_target(holder_id)
+            .filter(|target_id| {
+                holder_id.can_hurt(game, *target_id)
+                    && PerceptionSystem::perceive(game, holder_id, *target_id)
+                        != Perception::Unknown
+            })
+    }
+    pub fn add_threat(
+        game: &mut Game,
+        holder_id: GameObjectId,
+        source_id: GameObjectId,
+        amount: f64,
+    ) {
+        if holder_id == source_id || amount <= 0.0 {
+            return;
+        }
+        let tick_counter = game.tick_counter;
+        if let Some(table) = game.threat_system.threat_tables.get_mut(holder_id) {
+            *table.threat.entry(source_id).or_insert(0.0) += amount;
+            table.choose_target(tick_counter);
+        }
+    }
+    // Healing someone makes everyone who's after them angry at the healer too
+    pub fn add_healing_threat(
+        game: &mut Game,
+        healer_id: GameObjectId,
+        healed_id: GameObjectId,
+        amount: f64,
+    ) {
+        let holders: Vec<GameObjectId> = game
+            .threat_system
+            .threat_tables
+            .iter()
+            .filter(|(_holder_id, table)| table.threat.contains_key(&healed_id))
+            .map(|(holder_id, _table)| holder_id)
+            .collect();
+        for holder_id in holders {
+            ThreatSystem::add_threat(
+                game,
+                holder_id,
+                healer_id,
+                amount * HEALING_THREAT_MULTIPLIER,
+            );
+        }
+    }
+    // Forces the holder onto the taunter for a while, and leaves the taunter on top of the table after
+    pub fn taunt(game: &mut Game, holder_id: GameObjectId, taunter_id: GameObjectId) {
+        let tick_counter = game.tick_counter;
+        if let Some(table) = game.threat_system.threat_tables.get_mut(holder_id) {
+            let highest = table
+                .threat
+                .iter()
+                .fold(MINIMUM_THREAT, |highest, (_, threat)| highest.max(*threat));
+            let taunter_threat = table.threat.entry(taunter_id).or_insert(0.0);
+            *taunter_threat = taunter_threat.max(highest * THREAT_SWITCH_THRESHOLD);
+            table.taunted_by = Some((taunter_id, tick_counter + TAUNT_DURATION));
+            table.choose_target(tick_counter);
+        }
+    }
+    // Taunts every hostile nearby that could hurt the taunter
+    pub fn taunt_nearby(game: &mut Game, taunter_id: GameObjectId, radius: f64) {
+        let taunter_coords = taunter_id.get_coords_game(game);
+        let holders: Vec<GameObjectId> = game
+            .threat_system
+            .threat_tables
+            .iter()
+            .map(|(holder_id, _table)| holder_id)
+            .filter(|holder_id| {
+                holder_id
+                    .get_coords_game_safe(game)
+                    .map(|coords| {
+                        coords.get_plane() == taunter_coords.get_plane()
+                            && coords.get_distance_to(&taunter_coords) <= radius
+                    })
+                    .unwrap_or(false)
+                    && holder_id.can_hurt(game, taunter_id)
+            })
+            .collect();
+        for holder_id in holders {
+            ThreatSystem::taunt(game, holder_id, taunter_id);
+        }
+    }
+    pub fn step(game: &mut Game) {
+        let tick_counter = game.tick_counter;
+        if tick_counter % THREAT_DECAY_INTERVAL != 0 {
+            return;
+        }
+        let game_objects = &game.game_objects;
+        for (_holder_id, table) in game.threat_system.threat_tables.iter_mut() {
+            for (_source_id, threat) in table.threat.iter_mut() {
+                *threat *= THREAT_DECAY;
+            }
+            let forgotten: Vec<GameObjectId> = table
+                .threat
+                .iter()
+                .filter(|(source_id, threat)| {
+                    **threat < MINIMUM_THREAT || source_id.is_deleted(game_objects)
+                })
+                .map(|(source_id, _threat)| *source_id)
+                .collect();
+            for source_id in forgotten {
+                table.threat.remove(&source_id);
+            }
+            table.choose_target(tick_counter);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ThreatTableComponent {
+    component_id: ComponentId,
+}
+
+impl ThreatTableComponent {
+    pub fn add_to(game: &mut Game, owner_id: GameObjectId) -> ComponentId {
+        let component_id = game.get_id();
+        let comp = ThreatTableComponent { component_id };
+        game.threat_system
+            .threat_tables
+            .insert(owner_id, ThreatTable::new());
+        owner_id.add_about_to_be_damaged_signal_listener(game, comp.clone());
+        owner_id.add_dealt_damage_signal_listener(game, comp.clone());
+        owner_id.add_component(game, comp);
+        component_id
+    }
+}
+
+impl Component for ThreatTableComponent {
+    fn get_component_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn on_remove(self: Box<Self>, game: &mut Game, owner_id: GameObjectId) {
+        owner_id.remove_about_to_be_damaged_signal_listener(game, self.component_id);
+        owner_id.remove_dealt_damage_signal_listener(game, self.component_id);
+        game.threat_system.threat_tables.remove(owner_id);
+    }
+}
+
+impl AboutToBeDamagedSignalListener for ThreatTableComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_about_to_be_damaged_signal(
+        &self,
+        game: &mut Game,
+        owner_id: GameObjectId,
+        _damager_id: GameObjectId,
+        firer_id: GameObjectId,
+    ) -> Stoppable<OrBool> {
+        ThreatSystem::add_threat(game, owner_id, firer_id, ATTACKED_THREAT);
+        Stoppable::Continue(OrBool(false))
+    }
+}
+
+impl DealtDamageSignalListener for ThreatTableComponent {
+    fn get_listener_id(&self) -> ComponentId {
+        self.component_id
+    }
+    fn receive_dealt_damage_signal(
+        &self,
+        game: &mut Game,
+        owner_id: GameObjectId,
+        target_id: GameObjectId,
+    ) {
+        ThreatSystem::add_threat(game, owner_id, target_id, DEALT_DAMAGE_THREAT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allegiance::AllegianceComponent;
+
+    #[test]
+    fn targets_only_switch_past_the_threshold() {
+        let first = GameObjectId::from(1);
+        let second = GameObjectId::from(2);
+        let mut table = ThreatTable::new();
+        table.threat.insert(first, 100.0);
+        table.choose_target(0);
+        assert_eq!(table.current_target, Some(first));
+        table.threat.insert(second, 105.0);
+        table.choose_target(0);
+        assert_eq!(table.current_target, Some(first));
+        table.threat.insert(second, 120.0);
+        table.choose_target(0);
+        assert_eq!(table.current_target, Some(second));
+    }
+
+    fn create_object(game: &mut Game, x: f64) -> GameObjectId {
+        GameObject::create_game(game, PixelCoords::new_at_zero().translate(x, 0.0))
+    }
+
+    fn create_holder(game: &mut Game, x: f64) -> GameObjectId {
+        let id = create_object(game, x);
+        ThreatTableComponent::add_to(game, id);
+        id
+    }
+
+    fn get_threat(game: &Game, holder_id: GameObjectId, source_id: GameObjectId) -> f64 {
+        game.threat_system
+            .threat_tables
+            .get(holder_id)
+            .unwrap()
+            .get_threat(source_id)
+    }
+
+    fn step_to(game: &mut Game, tick_counter: u32) {
+        while game.tick_counter < tick_counter {
+            game.tick_counter += 1;
+            ThreatSystem::step(game);
+        }
+    }
+
+    #[test]
+    fn threat_decays_until_its_forgotten() {
+        let mut game = Game::new();
+        let holder_id = create_holder(&mut game, 0.0);
+        let source_id = create_object(&mut game, 0.0);
+        ThreatSystem::add_threat(&mut game, holder_id, source_id, 20.0);
+        step_to(&mut game, THREAT_DECAY_INTERVAL - 1);
+        assert_eq!(get_threat(&game, holder_id, source_id), 20.0);
+        step_to(&mut game, THREAT_DECAY_INTERVAL);
+        assert_eq!(get_threat(&game, holder_id, source_id), 20.0 * THREAT_DECAY);
+        assert_eq!(game.threat_system.get_target(holder_id), Some(source_id));
+        // 20 * 0.95^14 is just under 10
+        step_to(&mut game, THREAT_DECAY_INTERVAL * 14);
+        assert_eq!(get_threat(&game, holder_id, source_id), 0.0);
+        assert_eq!(game.threat_system.get_target(holder_id), None);
+    }
+
+    #[test]
+    fn deleted_sources_are_forgotten() {
+        let mut game = Game::new();
+        let holder_id = create_holder(&mut game, 0.0);
+        let deleted_id = create_object(&mut game, 0.0);
+        let remaining_id = create_object(&mut game, 0.0);
+        ThreatSystem::add_threat(&mut game, holder_id, deleted_id, 1000.0);
+        ThreatSystem::add_threat(&mut game, holder_id, remaining_id, 100.0);
+        assert_eq!(game.threat_system.get_target(holder_id), Some(deleted_id));
+        GameObject::_remove(&mut game, deleted_id);
+        step_to(&mut game, THREAT_DECAY_INTERVAL);
+        assert_eq!(get_threat(&game, holder_id, deleted_id), 0.0);
+        assert_eq!(game.threat_system.get_target(holder_id), Some(remaining_id));
+    }
+
+    #[test]
+    fn taunts_hold_the_target_until_they_wear_off() {
+        let mut game = Game::new();
+        let holder_id = create_holder(&mut game, 0.0);
+        let attacker_id = create_object(&mut game, 0.0);
+        let taunter_id = create_object(&mut game, 0.0);
+        ThreatSystem::add_threat(&mut game, holder_id, attacker_id, 1000.0);
+        ThreatSystem::taunt(&mut game, holder_id, taunter_id);
+        assert_eq!(game.threat_system.get_target(holder_id), Some(taunter_id));
+        assert!(get_threat(&game, holder_id, taunter_id) > 1000.0);
+        // Far more than the taunt left the taunter with, but it can't break the taunt
+        ThreatSystem::add_threat(&mut game, holder_id, attacker_id, 5000.0);
+        assert_eq!(game.threat_system.get_target(holder_id), Some(taunter_id));
+        game.tick_counter = TAUNT_DURATION;
+        ThreatSystem::add_threat(&mut game, holder_id, attacker_id, 1.0);
+        assert_eq!(game.threat_system.get_target(holder_id), Some(attacker_id));
+    }
+
+    #[test]
+    fn taunting_nearby_only_reaches_enemies_in_range() {
+        let mut game = Game::new();
+        let taunter_id = create_object(&mut game, 0.0);
+        let villager_allegiance = game
+            .allegiance_system
+            .special_allegiances
+            .villager_allegiance;
+        AllegianceComponent::add_to(&mut game, taunter_id, vec![villager_allegiance]);
+        let near_id = create_holder(&mut game, 50.0);
+        let far_id = create_holder(&mut game, 500.0);
+        let friend_id = create_holder(&mut game, 50.0);
+        AllegianceComponent::add_to(&mut game, friend_id, vec![villager_allegiance]);
+        ThreatSystem::taunt_nearby(&mut game, taunter_id, 100.0);
+        assert_eq!(game.threat_system.get_target(near_id), Some(taunter_id));
+        assert_eq!(game.threat_system.get_target(far_id), None);
+        assert_eq!(game.threat_system.get_target(friend_id), None);
+    }
+
+    #[test]
+    fn healing_angers_whoever_is_after_the_healed() {
+        let mut game = Game::new();
+        let holder_id = create_holder(&mut game, 0.0);
+        let bystander_id = create_holder(&mut game, 0.0);
+        let healed_id = create_object(&mut game, 0.0);
+        let healer_id = create_object(&mut game, 0.0);
+        ThreatSystem::add_threat(&mut game, holder_id, healed_id, 100.0);
+        ThreatSystem::add_healing_threat(&mut game, healer_id, healed_id, 400.0);
+        assert_eq!(
+            get_threat(&game, holder_id, healer_id),
+            400.0 * HEALING_THREAT_MULTIPLIER
+        );
+        assert_eq!(game.threat_system.get_target(holder_id), Some(healer_id));
+        assert_eq!(get_threat(&game, bystander_id, healer_id), 0.0);
+    }
+}
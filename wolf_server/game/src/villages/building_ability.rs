@@ -1,5 +1,7 @@
 use crate::abilities::*;
 use crate::game::*;
+use crate::progression::{ProgressionSystem, BUILD_EXPERIENCE};
+use crate::resources::{HarvestableComponent, ResourceAmount, Resources};
 use crate::solids::SolidComponent;
 use crate::terrain::TerrainSpriteComponent;
 
@@ -15,6 +17,20 @@ pub fn create_wall(game: &mut Game, coords: SquareCoords) {
     DeleteOnDeathComponent::add_to(game, owner_id);
     DieOnNoHealthComponent::add_to(game, owner_id);
 }
+pub fn create_floor(game: &mut Game, coords: SquareCoords) {
+    let owner_id = GameObject::create_game(game, coords.center_pixel());
+    TerrainSpriteComponent::add_to(game, owner_id, coords, FLOOR_SPRITE);
+}
+// Crops that can be harvested for food, like trees for wood
+pub fn create_field(game: &mut Game, coords: SquareCoords) {
+    let owner_id = GameObject::create_game(game, coords.center_pixel());
+    TerrainSpriteComponent::add_to(game, owner_id, coords, DIRT_SPRITE);
+    BasicDrawingComponent::add_to(game, owner_id, APPLE_SPRITE, DEFAULT_DEPTH);
+    HarvestableComponent::add_to(game, owner_id, Resources::food(ResourceAmount(1)));
+    DamageableComponent::add_to(game, owner_id);
+    DeleteOnDeathComponent::add_to(game, owner_id);
+    DieOnNoHealthComponent::add_to(game, owner_id);
+}
 pub struct Building {
     pub damageable_id: DamageableId,
 }
@@ -37,9 +53,10 @@ impl Ability for BuildingAbility {
     fn activate(
         &mut self,
         game: &mut crate::Game,
-        _caster: crate::GameObjectId,
+        caster: crate::GameObjectId,
         target_coords: coords::PixelCoords,
     ) {
         create_wall(game, target_coords.into());
+        ProgressionSystem::grant_experience(game, caster, BUILD_EXPERIENCE);
     }
 }
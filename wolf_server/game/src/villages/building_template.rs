@@ -0,0 +1,309 @@
+use crate::resources::{ResourceAmount, Resources};
+
+use super::*;
+
+/*
+Building templates are read from data/buildings. Each file is a list of "key value" lines,
+followed by grids of squares. Grids are drawn as seen from above, with the top line furthest north.
+  name <name>
+  weight <how often villagers pick it>
+  cost <resource> <amount> ...
+  recipe <recipe name> (for stations)
+  footprint: grid of # wall, . floor, D door, S station, F field, _ nothing
+  interior: grid of # wall, . floor, D exit door, laid out on a new plane
+Lines starting with // are comments.
+*/
+
+const BUILDING_TEMPLATE_FILES: [(&str, &str); 5] = [
+    (
+        "tavern.txt",
+        include_str!("../../data/buildings/tavern.txt"),
+    ),
+    ("house.txt", include_str!("../../data/buildings/house.txt")),
+    (
+        "workshop.txt",
+        include_str!("../../data/buildings/workshop.txt"),
+    ),
+    ("farm.txt", include_str!("../../data/buildings/farm.txt")),
+    ("tower.txt", include_str!("../../data/buildings/tower.txt")),
+];
+
+// Templates must fit in a reservation, with space for the builder to be off by one
+const MAX_FOOTPRINT_SIZE: i64 = RESERVATION_CHUNK_SIZE - 2;
+
+pub type BuildingTemplateIndex = usize;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FootprintSquare {
+    Wall,
+    Floor,
+    Door,
+    Station,
+    Field,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InteriorSquare {
+    Wall,
+    Floor,
+    Exit,
+}
+
+#[derive(Clone, Debug)]
+pub struct BuildingTemplate {
+    pub name: String,
+    pub weight: u32,
+    pub cost: Resources,
+    pub recipe: Option<Recipe>,
+    // Offsets from the center of the building
+    pub footprint: Vec<(i64, i64, FootprintSquare)>,
+    // Offsets from the bottom left of the interior
+    pub interior: Option<Vec<(u8, u8, InteriorSquare)>>,
+}
+
+fn parse_grid<T, F: Fn(char) -> Result<Option<T>, String>>(
+    lines: &[&str],
+    parse_square: F,
+) -> Result<(Vec<(i64, i64, T)>, i64, i64), String> {
+    let height = lines.len() as i64;
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0) as i64;
+    let mut squares = Vec::new();
+    for (row, line) in lines.iter().enumerate() {
+        for (column, c) in line.chars().enumerate() {
+            if let Some(square) = parse_square(c)? {
+                squares.push((column as i64, height - 1 - row as i64, square));
+            }
+        }
+    }
+    Ok((squares, width, height))
+}
+
+impl BuildingTemplate {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut name = None;
+        let mut weight = 1;
+        let mut cost = Resources::new();
+        let mut recipe = None;
+        let mut footprint_lines = Vec::new();
+        let mut interior_lines = Vec::new();
+        let mut current_grid = None;
+        for line in text.lines() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            match line {
+                "footprint" => {
+                    current_grid = Some(&mut footprint_lines);
+                    continue;
+                }
+                "interior" => {
+                    current_grid = Some(&mut interior_lines);
+                    continue;
+                }
+                _ => {}
+            }
+            if let Some(grid) = current_grid.as_mut() {
+                grid.push(line);
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let key = words.next().unwrap();
+            let values: Vec<&str> = words.collect();
+            match key {
+                "name" => name = Some(values.join(" ")),
+                "weight" => {
+                    weight = values
+                        .get(0)
+                        .and_then(|x| x.parse().ok())
+                        .ok_or(format!("Bad weight: {}", line))?;
+                }
+                "cost" => {
+                    for pair in values.chunks(2) {
+                        let resource_type = ResourceType::from_name(pair[0])
+                            .ok_or(format!("Unknown resource: {}", pair[0]))?;
+                        let amount = pair
+                            .get(1)
+                            .and_then(|x| x.parse().ok())
+                            .ok_or(format!("Bad cost: {}", line))?;
+                        cost.set_resource_amount(resource_type, ResourceAmount(amount));
+                    }
+                }
+                "recipe" => {
+                    let recipe_name = values.get(0).map(|x| *x).unwrap_or("");
+                    recipe = Some(
+                        Recipe::from_name(recipe_name)
+                            .ok_or(format!("Unknown recipe: {}", recipe_name))?,
+                    );
+                }
+                _ => return Err(format!("Unknown key: {}", key)),
+            }
+        }
+        let name = name.ok_or("Template has no name")?;
+        let (squares, width, height) = parse_grid(&footprint_lines, |c| match c {
+            '#' => Ok(Some(FootprintSquare::Wall)),
+            '.' => Ok(Some(FootprintSquare::Floor)),
+            'D' => Ok(Some(FootprintSquare::Door)),
+            'S' => Ok(Some(FootprintSquare::Station)),
+            'F' => Ok(Some(FootprintSquare::Field)),
+            '_' => Ok(None),
+            _ => Err(format!("Unknown footprint square: {}", c)),
+        })?;
+        if squares.is_empty() {
+            return Err("Template has no footprint".to_string());
+        }
+        if width > MAX_FOOTPRINT_SIZE || height > MAX_FOOTPRINT_SIZE {
+            return Err(format!("Footprint of {} is too big", name));
+        }
+        let footprint = squares
+            .into_iter()
+            .map(|(x, y, square)| (x - width / 2, y - height / 2, square))
+            .collect();
+        let interior = if interior_lines.is_empty() {
+            None
+        } else {
+            let (squares, width, height) = parse_grid(&interior_lines, |c| match c {
+                '#' => Ok(Some(InteriorSquare::Wall)),
+                '.' => Ok(Some(InteriorSquare::Floor)),
+                'D' => Ok(Some(InteriorSquare::Exit)),
+                _ => Err(format!("Unknown interior square: {}", c)),
+            })?;
+            if width > TERRAIN_CHUNK_SIZE_SQUARES || height > TERRAIN_CHUNK_SIZE_SQUARES {
+                return Err(format!("Interior of {} doesn't fit in a chunk", name));
+            }
+            if !squares
+                .iter()
+                .any(|(_, _, square)| *square == InteriorSquare::Exit)
+            {
+                return Err(format!("Interior of {} has no exit", name));
+            }
+            Some(
+                squares
+                    .into_iter()
+                    .map(|(x, y, square)| (x as u8, y as u8, square))
+                    .collect(),
+            )
+        };
+        Ok(BuildingTemplate {
+            name,
+            weight,
+            cost,
+            recipe,
+            footprint,
+            interior,
+        })
+    }
+}
+
+pub fn load_building_templates() -> Vec<BuildingTemplate> {
+    BUILDING_TEMPLATE_FILES
+        .iter()
+        .map(|(file_name, text)| match BuildingTemplate::parse(text) {
+            Ok(template) => template,
+            Err(error) => panic!("Failed to load building template {}: {}", file_name, error),
+        })
+        .collect()
+}
+
+impl VillagesSystem {
+    pub fn get_building_template_index(&self, name: &str) -> Option<BuildingTemplateIndex> {
+        self.building_templates
+            .iter()
+            .position(|template| template.name == name)
+    }
+    // Picks a template the village can pay for, weighted by how common each should be
+    pub fn choose_building_template(
+        game: &Game,
+        village_id: VillageId,
+    ) -> Option<BuildingTemplateIndex> {
+        let stockpile = game.villages_system.get_stockpile(village_id);
+        let affordable: Vec<(BuildingTemplateIndex, u32)> = game
+            .villages_system
+            .building_templates
+            .iter()
+            .enumerate()
+            .filter(|(_, template)| match stockpile {
+                Some(stockpile) => {
+                    template.cost.is_empty() || stockpile.has_resources(game, &template.cost)
+                }
+                // A village's first building comes before its stockpile, so it's free
+                None => true,
+            })
+            .map(|(index, template)| (index, template.weight))
+            .collect();
+        let total_weight: u32 = affordable.iter().map(|(_, weight)| weight).sum();
+        if total_weight == 0 {
+            return None;
+        }
+        let mut roll = rand::thread_rng().gen_range(0..total_weight);
+        for (index, weight) in affordable {
+            if roll < weight {
+                return Some(index);
+            }
+            roll -= weight;
+        }
+        None
+    }
+    // Pays for a building and lays out its scaffolds for villagers to build
+    pub fn place_building(
+        game: &mut Game,
+        village_id: VillageId,
+        template_index: BuildingTemplateIndex,
+        center: SquareCoords,
+    ) -> bool {
+        let template = game.villages_system.building_templates[template_index].clone();
+        if let Some(stockpile) = game.villages_system.get_stockpile(village_id) {
+            if !template.cost.is_empty() && !stockpile.spend_resources(game, template.cost.clone())
+            {
+                return false;
+            }
+        }
+        // Destroy anything present
+        let destroy_width = (MAX_FOOTPRINT_SIZE * SQUARE_SIZE_PIXELS) / 2;
+        let hit_box = HitBox::new(center.center_pixel(), destroy_width, destroy_width);
+        let colliding = CollisionSystem::get_colliding(game, CollisionGroupId::Damageable, hit_box);
+        for id in colliding {
+            kill(game, id);
+        }
+        for (dx, dy, square) in template.footprint.iter() {
+            let scaffold_type = match square {
+                FootprintSquare::Wall => ScaffoldType::Wall,
+                FootprintSquare::Floor => ScaffoldType::Floor,
+                FootprintSquare::Door => ScaffoldType::Door(template_index),
+                FootprintSquare::Station => ScaffoldType::Station(template_index),
+                FootprintSquare::Field => ScaffoldType::Field,
+            };
+            Scaffold::create(game, village_id, center.translate(*dx, *dy), scaffold_type);
+        }
+        if let Some(village) = game.villages_system.villages.get_mut(village_id) {
+            village.buildings.push((template_index, center));
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_building_templates_load() {
+        let templates = load_building_templates();
+        assert_eq!(templates.len(), BUILDING_TEMPLATE_FILES.len());
+    }
+
+    #[test]
+    fn footprint_is_centered() {
+        let template = BuildingTemplate::parse("name test\nfootprint\n###\n#D#\n#.#\n").unwrap();
+        assert!(template.footprint.contains(&(0, 0, FootprintSquare::Door)));
+        assert!(template
+            .footprint
+            .contains(&(0, -1, FootprintSquare::Floor)));
+        assert!(template.footprint.contains(&(-1, 1, FootprintSquare::Wall)));
+
+        let template =
+            BuildingTemplate::parse("// A comment\nname test\nfootprint\n#_#\n").unwrap();
+        assert_eq!(template.name, "test");
+        assert!(template.footprint.contains(&(1, 0, FootprintSquare::Wall)));
+    }
+}
@@ -0,0 +1,511 @@
+use crate::resources::{ResourceAmount, Resources};
+use crate::terrain::TerrainSpriteComponent;
+
+use super::*;
+
+pub const STOCKPILE_DISTANCE: f64 = 50.0;
+const WORKSHOP_DISTANCE: f64 = 50.0;
+// Larger than a square, as mined squares are solid until they're dug out
+const GATHER_DISTANCE: f64 = 60.0;
+const GATHER_SEARCH_RADIUS_SQUARES: i64 = 15;
+const GATHER_TIME: u32 = 60;
+const TOOLS_WORK_TIME: u32 = 120;
+
+// How much of each resource the village tries to keep in its stockpile
+pub fn desired_stock() -> Resources {
+    let mut desired = Resources::new();
+    desired.set_resource_amount(ResourceType::Wood, ResourceAmount(20));
+    desired.set_resource_amount(ResourceType::Stone, ResourceAmount(10));
+    desired.set_resource_amount(ResourceType::Gems, ResourceAmount(5));
+    desired.set_resource_amount(ResourceType::Tools, ResourceAmount(5));
+    desired
+}
+
+#[derive(Clone, Debug)]
+pub struct Recipe {
+    pub inputs: Resources,
+    pub outputs: Resources,
+    pub work_time: u32,
+}
+
+impl Recipe {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "tools" => Some(Recipe::tools()),
+            _ => None,
+        }
+    }
+    pub fn tools() -> Self {
+        let mut inputs = Resources::wood(ResourceAmount(2));
+        inputs.set_resource_amount(ResourceType::Stone, ResourceAmount(1));
+        Recipe {
+            inputs,
+            outputs: Resources::of(ResourceType::Tools, ResourceAmount(1)),
+            work_time: TOOLS_WORK_TIME,
+        }
+    }
+}
+
+pub struct Workshop {
+    pub village_id: VillageId,
+    pub game_object_id: GameObjectId,
+    pub recipe: Recipe,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum EconomyJob {
+    Gather(ResourceType),
+    Craft(WorkshopId),
+}
+
+impl VillagesSystem {
+    pub fn create_stockpile(
+        game: &mut Game,
+        village_id: VillageId,
+        coords: SquareCoords,
+    ) -> GameObjectId {
+        let game_object_id = GameObject::create_game(game, coords.center_pixel());
+        TerrainSpriteComponent::add_to(game, game_object_id, coords, FLOOR_SPRITE);
+        BasicDrawingComponent::add_to(game, game_object_id, FLAG_SPRITE, DEFAULT_DEPTH);
+        ResourceHolderComponent::add_to(game, game_object_id);
+        if let Some(village) = game.villages_system.villages.get_mut(village_id) {
+            village.stockpile = Some(game_object_id);
+        }
+        game_object_id
+    }
+    pub fn create_workshop(
+        game: &mut Game,
+        village_id: VillageId,
+        coords: SquareCoords,
+        recipe: Recipe,
+    ) -> WorkshopId {
+        let id = game.get_id();
+        let game_object_id = GameObject::create_game(game, coords.center_pixel());
+        TerrainSpriteComponent::add_to(game, game_object_id, coords, FLOOR_SPRITE);
+        let workshop = Workshop {
+            village_id,
+            game_object_id,
+            recipe,
+        };
+        game.villages_system.workshops.insert(id, workshop);
+        id
+    }
+    pub fn get_stockpile(&self, village_id: VillageId) -> Option<GameObjectId> {
+        self.villages
+            .get(village_id)
+            .and_then(|village| village.stockpile)
+    }
+    pub fn get_stockpile_resources(game: &Game, village_id: VillageId) -> Resources {
+        game.villages_system
+            .get_stockpile(village_id)
+            .map(|stockpile| stockpile.get_resources(game))
+            .unwrap_or(Resources::new())
+    }
+    // Picks the most useful job for the resource the stockpile is proportionally shortest of
+    pub fn choose_economy_job(game: &Game, village_id: VillageId) -> Option<EconomyJob> {
+        game.villages_system.get_stockpile(village_id)?;
+        let stock = VillagesSystem::get_stockpile_resources(game, village_id);
+        let mut shortfalls = Vec::new();
+        for (resource_type, desired_amount) in desired_stock().resource_amounts.iter() {
+            let have = stock.get_resource_amount(*resource_type);
+            if have < *desired_amount {
+                let shortfall = (desired_amount.0 - have.0) as f64 / desired_amount.0 as f64;
+                shortfalls.push((*resource_type, shortfall));
+            }
+        }
+        shortfalls.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        for (resource_type, _shortfall) in shortfalls {
+            let workshop = game
+                .villages_system
+                .workshops
+                .iter()
+                .find(|(_id, workshop)| {
+                    workshop.village_id == village_id
+                        && workshop.recipe.outputs.get_resource_amount(resource_type)
+                            > ResourceAmount(0)
+                        && stock.contains(&workshop.recipe.inputs)
+                });
+            if let Some((workshop_id, _)) = workshop {
+                return Some(EconomyJob::Craft(workshop_id));
+            }
+            match resource_type {
+                ResourceType::Wood | ResourceType::Stone | ResourceType::Gems => {
+                    return Some(EconomyJob::Gather(resource_type));
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+fn is_gatherable(game: &Game, square: SquareCoords, resource_type: ResourceType) -> bool {
+    let chunk_square = match game.terrain.get_chunk_square(square) {
+        Some(chunk_square) => chunk_square,
+        None => return false,
+    };
+    match resource_type {
+        // Don't dig up the village, or walls built by villagers
+        ResourceType::Stone => {
+            chunk_square.base_solid
+                && chunk_square.solids.is_empty()
+                && !game.villages_system.is_reserved(square)
+        }
+        ResourceType::Gems => chunk_square.detritus.contains(&GEM_FLOWER_SPRITE),
+        _ => false,
+    }
+}
+
+fn find_gatherable_square(
+    game: &Game,
+    coords: PixelCoords,
+    resource_type: ResourceType,
+) -> Option<SquareCoords> {
+    let center: SquareCoords = coords.into();
+    square_of_coords_centered(center, GATHER_SEARCH_RADIUS_SQUARES)
+        .into_iter()
+        .filter(|square| is_gatherable(game, *square, resource_type))
+        .min_by(|a, b| {
+            let distance_a = coords.get_distance_to(&a.center_pixel());
+            let distance_b = coords.get_distance_to(&b.center_pixel());
+            distance_a.partial_cmp(&distance_b).unwrap()
+        })
+}
+
+fn gather_square(game: &mut Game, square: SquareCoords, resource_type: ResourceType) -> bool {
+    if !is_gatherable(game, square, resource_type) {
+        return false;
+    }
+    game.terrain
+        .modify_chunk_square(square, |chunk_square| match resource_type {
+            ResourceType::Stone => {
+                chunk_square.base_solid = false;
+                chunk_square.base_sprite = DIRT_SPRITE;
+            }
+            _ => chunk_square
+                .detritus
+                .retain(|sprite| *sprite != GEM_FLOWER_SPRITE),
+        })
+}
+
+// Mines stone from rock walls or picks gem flowers
+pub struct GatherBehaviour {
+    mind_id: MindId,
+    resource_type: ResourceType,
+    target: Option<SquareCoords>,
+    gather_at: Option<u32>,
+}
+
+impl GatherBehaviour {
+    pub fn new(game: &mut Game, mind_id: MindId) -> BehaviourId {
+        let id = game.get_id();
+        let behaviour = GatherBehaviour {
+            mind_id,
+            resource_type: ResourceType::Stone,
+            target: None,
+            gather_at: None,
+        };
+        game.villages_system.gather_behaviours.insert(id, behaviour);
+        id
+    }
+    pub fn activate(game: &mut Game, id: BehaviourId, resource_type: ResourceType) {
+        let behaviour = game.villages_system.gather_behaviours.get_mut(id).unwrap();
+        behaviour.resource_type = resource_type;
+        behaviour.target = None;
+        behaviour.gather_at = None;
+    }
+    pub fn remove(game: &mut Game, id: BehaviourId) {
+        game.villages_system.gather_behaviours.remove(id);
+    }
+    pub fn step(game: &mut Game) {
+        let mut to_search = Vec::new();
+        let mut to_gather = Vec::new();
+        for (id, behaviour) in game.villages_system.gather_behaviours.iter_mut() {
+            let game_object_id = match game.behaviour_system.minds.get(behaviour.mind_id) {
+                Some(mind) if mind.active_behaviour == Some(id) => mind.game_object_id,
+                _ => continue,
+            };
+            let target = match behaviour.target {
+                Some(target) => target,
+                None => {
+                    to_search.push((id, game_object_id));
+                    continue;
+                }
+            };
+            let target_coords = target.center_pixel();
+            let my_coords = game_object_id.get_coords(&game.game_objects);
+            if my_coords.get_distance_to(&target_coords) > GATHER_DISTANCE {
+                game_object_id.intend_move_to_point(
+                    &mut game.movement_system.intend_move_system,
+                    target_coords,
+                );
+                continue;
+            }
+            match behaviour.gather_at {
+                None => {
+                    game_object_id.intend_stop(&mut game.movement_system.intend_move_system);
+                    behaviour.gather_at = Some(game.tick_counter + GATHER_TIME);
+                }
+                Some(gather_at) if game.tick_counter >= gather_at => {
+                    to_gather.push((game_object_id, target, behaviour.resource_type));
+                    behaviour.target = None;
+                    behaviour.gather_at = None;
+                    behaviour
+                        .mind_id
+                        .set_active_behaviour(&mut game.behaviour_system.minds, None);
+                }
+                _ => {}
+            }
+        }
+        for (id, game_object_id) in to_search {
+            let behaviour = game.villages_system.gather_behaviours.get(id).unwrap();
+            let coords = game_object_id.get_coords(&game.game_objects);
+            let target = find_gatherable_square(game, coords, behaviour.resource_type);
+            let behaviour = game.villages_system.gather_behaviours.get_mut(id).unwrap();
+            behaviour.target = target;
+            if target.is_none() {
+                behaviour
+                    .mind_id
+                    .set_active_behaviour(&mut game.behaviour_system.minds, None);
+            }
+        }
+        for (game_object_id, square, resource_type) in to_gather {
+            if gather_square(game, square, resource_type) {
+                game_object_id.add_resources(game, Resources::of(resource_type, ResourceAmount(1)));
+            }
+        }
+    }
+}
+
+// Takes a recipe's inputs from the stockpile, works them at a workshop, then returns the outputs
+pub struct CraftBehaviour {
+    mind_id: MindId,
+    workshop_id: Option<WorkshopId>,
+    finish_at: Option<u32>,
+}
+
+impl CraftBehaviour {
+    pub fn new(game: &mut Game, mind_id: MindId) -> BehaviourId {
+        let id = game.get_id();
+        let behaviour = CraftBehaviour {
+            mind_id,
+            workshop_id: None,
+            finish_at: None,
+        };
+        game.villages_system.craft_behaviours.insert(id, behaviour);
+        id
+    }
+    pub fn activate(game: &mut Game, id: BehaviourId, workshop_id: WorkshopId) {
+        let behaviour = game.villages_system.craft_behaviours.get_mut(id).unwrap();
+        behaviour.workshop_id = Some(workshop_id);
+        behaviour.finish_at = None;
+    }
+    pub fn remove(game: &mut Game, id: BehaviourId) {
+        game.villages_system.craft_behaviours.remove(id);
+    }
+    pub fn step(game: &mut Game) {
+        let mut to_start = Vec::new();
+        let mut to_finish = Vec::new();
+        let workshops = &game.villages_system.workshops;
+        for (id, behaviour) in game.villages_system.craft_behaviours.iter_mut() {
+            let game_object_id = match game.behaviour_system.minds.get(behaviour.mind_id) {
+                Some(mind) if mind.active_behaviour == Some(id) => mind.game_object_id,
+                _ => continue,
+            };
+            let workshop = match behaviour
+                .workshop_id
+                .and_then(|workshop_id| workshops.get(workshop_id))
+            {
+                Some(workshop) => workshop,
+                None => {
+                    behaviour.workshop_id = None;
+                    behaviour
+                        .mind_id
+                        .set_active_behaviour(&mut game.behaviour_system.minds, None);
+                    continue;
+                }
+            };
+            match behaviour.finish_at {
+                None => {
+                    let workshop_coords = workshop.game_object_id.get_coords(&game.game_objects);
+                    let my_coords = game_object_id.get_coords(&game.game_objects);
+                    if my_coords.get_distance_to(&workshop_coords) > WORKSHOP_DISTANCE {
+                        game_object_id.intend_move_to_point(
+                            &mut game.movement_system.intend_move_system,
+                            workshop_coords,
+                        );
+                    } else {
+                        game_object_id.intend_stop(&mut game.movement_system.intend_move_system);
+                        to_start.push((
+                            id,
+                            game_object_id,
+                            behaviour.workshop_id.unwrap(),
+                            workshop.recipe.clone(),
+                        ));
+                    }
+                }
+                Some(finish_at) if game.tick_counter >= finish_at => {
+                    to_finish.push((
+                        game_object_id,
+                        workshop.village_id,
+                        workshop.recipe.outputs.clone(),
+                    ));
+                    behaviour.workshop_id = None;
+                    behaviour.finish_at = None;
+                    behaviour
+                        .mind_id
+                        .set_active_behaviour(&mut game.behaviour_system.minds, None);
+                }
+                _ => {}
+            }
+        }
+        for (id, game_object_id, workshop_id, recipe) in to_start {
+            let stockpile = game
+                .villages_system
+                .workshops
+                .get(workshop_id)
+                .and_then(|workshop| game.villages_system.get_stockpile(workshop.village_id));
+            let started = match stockpile {
+                Some(stockpile) => stockpile.spend_resources(game, recipe.inputs),
+                None => false,
+            };
+            let finish_at = game.tick_counter + recipe.work_time;
+            let behaviour = game.villages_system.craft_behaviours.get_mut(id).unwrap();
+            if started {
+                behaviour.finish_at = Some(finish_at);
+                game_object_id.speak_safe(game, "CLANG CLANG", Some(finish_at));
+            } else {
+                behaviour.workshop_id = None;
+                behaviour
+                    .mind_id
+                    .set_active_behaviour(&mut game.behaviour_system.minds, None);
+            }
+        }
+        for (game_object_id, village_id, outputs) in to_finish {
+            match game.villages_system.get_stockpile(village_id) {
+                Some(stockpile) => stockpile.add_resources(game, outputs),
+                None => game_object_id.add_resources(game, outputs),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::Chunk;
+
+    fn create_village(game: &mut Game) -> (VillageId, SquareCoords) {
+        let square = SquareCoords::new(Plane(0), 5, 5);
+        Chunk::generate(game, square.into(), 0);
+        let village_id = game.get_id();
+        game.villages_system
+            .villages
+            .insert(village_id, Village::new(square.into()));
+        (village_id, square)
+    }
+
+    fn stock(wood: i32, stone: i32, gems: i32, tools: i32) -> Resources {
+        let mut resources = Resources::wood(ResourceAmount(wood));
+        resources.set_resource_amount(ResourceType::Stone, ResourceAmount(stone));
+        resources.set_resource_amount(ResourceType::Gems, ResourceAmount(gems));
+        resources.set_resource_amount(ResourceType::Tools, ResourceAmount(tools));
+        resources
+    }
+
+    #[test]
+    fn stockpile_holds_resources_for_its_village() {
+        let mut game = Game::new();
+        let (village_id, square) = create_village(&mut game);
+        assert_eq!(game.villages_system.get_stockpile(village_id), None);
+        assert_eq!(
+            VillagesSystem::get_stockpile_resources(&game, village_id),
+            Resources::new()
+        );
+
+        let stockpile = VillagesSystem::create_stockpile(&mut game, village_id, square);
+        assert_eq!(
+            game.villages_system.get_stockpile(village_id),
+            Some(stockpile)
+        );
+        stockpile.add_resources(&mut game, stock(3, 2, 0, 0));
+        stockpile.add_resources(&mut game, Resources::wood(ResourceAmount(1)));
+        let resources = VillagesSystem::get_stockpile_resources(&game, village_id);
+        assert_eq!(
+            resources.get_resource_amount(ResourceType::Wood),
+            ResourceAmount(4)
+        );
+        assert_eq!(
+            resources.get_resource_amount(ResourceType::Stone),
+            ResourceAmount(2)
+        );
+    }
+
+    #[test]
+    fn jobs_are_chosen_for_the_largest_shortfall() {
+        let mut game = Game::new();
+        let (village_id, square) = create_village(&mut game);
+        // No stockpile to fill
+        assert!(VillagesSystem::choose_economy_job(&game, village_id).is_none());
+
+        let stockpile = VillagesSystem::create_stockpile(&mut game, village_id, square);
+        stockpile.add_resources(&mut game, stock(20, 2, 4, 5));
+        assert!(matches!(
+            VillagesSystem::choose_economy_job(&game, village_id),
+            Some(EconomyJob::Gather(ResourceType::Stone))
+        ));
+
+        stockpile.add_resources(&mut game, stock(0, 8, 1, 0));
+        assert!(VillagesSystem::choose_economy_job(&game, village_id).is_none());
+    }
+
+    #[test]
+    fn tools_are_crafted_only_when_a_workshop_has_its_inputs() {
+        let mut game = Game::new();
+        let (village_id, square) = create_village(&mut game);
+        let stockpile = VillagesSystem::create_stockpile(&mut game, village_id, square);
+        stockpile.add_resources(&mut game, stock(18, 10, 5, 0));
+        // Tools can't be gathered, so the next shortfall is worked on instead
+        assert!(matches!(
+            VillagesSystem::choose_economy_job(&game, village_id),
+            Some(EconomyJob::Gather(ResourceType::Wood))
+        ));
+
+        let workshop_id = VillagesSystem::create_workshop(
+            &mut game,
+            village_id,
+            square.translate(1, 0),
+            Recipe::tools(),
+        );
+        assert!(matches!(
+            VillagesSystem::choose_economy_job(&game, village_id),
+            Some(EconomyJob::Craft(id)) if id == workshop_id
+        ));
+
+        stockpile.spend_resources(&mut game, stock(0, 10, 0, 0));
+        assert!(matches!(
+            VillagesSystem::choose_economy_job(&game, village_id),
+            Some(EconomyJob::Gather(ResourceType::Stone))
+        ));
+    }
+
+    #[test]
+    fn mined_squares_are_redrawn_as_dirt() {
+        let mut game = Game::new();
+        let square = SquareCoords::new(Plane(0), 5, 5);
+        Chunk::generate(&mut game, square.into(), 0);
+        game.terrain.modify_chunk_square(square, |chunk_square| {
+            chunk_square.base_solid = true;
+            chunk_square.base_sprite = WALL_SPRITE;
+        });
+        assert!(!gather_square(&mut game, square, ResourceType::Gems));
+        assert!(gather_square(&mut game, square, ResourceType::Stone));
+        assert!(!gather_square(&mut game, square, ResourceType::Stone));
+
+        let chunk_coords: TerrainChunkCoords = square.into();
+        let relative = square.relative_to_chunk(chunk_coords).unwrap();
+        let chunk = game.terrain.chunks.get(&chunk_coords).unwrap();
+        assert!(chunk.squares_to_redraw.contains(&relative));
+        assert_eq!(chunk.get_square_sprites(relative), vec![DIRT_SPRITE]);
+    }
+}
@@ -2,14 +2,19 @@ use rand::thread_rng;
 use wolf_hash_map::WolfHashMap;
 
 use crate::hunting::HuntingSystem;
+use crate::perception::{PerceiverComponent, PerceptionSystem};
+use crate::threat::{ThreatSystem, ThreatTableComponent};
 
 use super::*;
 
 const SQUADPORT_DISTANCE: f64 = 200.0;
 const HUNT_RADIUS: f64 = 600.0;
 const SQUAD_REPULSION_FORCE: f64 = 2500.0;
+// Given to the rest of the squad when one of them is hurt
+const SQUAD_ALARM_THREAT: f64 = 50.0;
 
 pub struct Squad {
+    pub village_id: VillageId,
     pub forces: WolfHashMap<GameObjectId, (f64, f64)>,
     pub target_position: PixelCoords,
     pub members: Vec<GameObjectId>,
@@ -20,6 +25,10 @@ impl Squad {
     pub fn step(game: &mut Game) {
         let mut flag_movements = Vec::new();
         for (_id, squad) in game.villages_system.squads.iter_mut() {
+            let village_center = match game.villages_system.villages.get(squad.village_id) {
+                Some(village) => village.get_center(),
+                None => squad.target_position,
+            };
             // If squad not reached point, next squad
             let mut new_position = true;
             let mut forces = WolfHashMap::new();
@@ -66,10 +75,10 @@ impl Squad {
             }
             squad.forces = forces;
             if new_position {
+                // Patrol around the village
                 let x = thread_rng().gen_range(-500..500);
                 let y = thread_rng().gen_range(-500..500);
-                squad.target_position =
-                    PixelCoords::new_to_fixed(squad.target_position.get_plane(), x, y);
+                squad.target_position = village_center.translate(x, y);
             }
             flag_movements.push((squad.flag, squad.target_position));
         }
@@ -85,6 +94,43 @@ pub struct GuardMind {
 }
 
 impl GuardMind {
+    pub fn subscribe_to_events(game: &mut Game) {
+        EventBus::subscribe(
+            game,
+            GameEventKind::DealtDamage,
+            EventFilter::All,
+            GuardMind::raise_alarm,
+        );
+    }
+    // Hurting one guard sets the whole squad on the attacker
+    fn raise_alarm(game: &mut Game, event: &GameEvent) {
+        let (firer_id, target_id) = match event {
+            GameEvent::DealtDamage {
+                firer_id,
+                target_id,
+                ..
+            } => (*firer_id, *target_id),
+            _ => return,
+        };
+        let squad_id = match game
+            .villages_system
+            .guard_minds
+            .iter()
+            .find(|(_id, guard_mind)| guard_mind.owner_id == target_id)
+        {
+            Some((_id, guard_mind)) => guard_mind.squad_id,
+            None => return,
+        };
+        let members = match game.villages_system.squads.get(squad_id) {
+            Some(squad) => squad.members.clone(),
+            None => return,
+        };
+        for member in members {
+            if member != target_id && game.game_objects.get(member).is_some() {
+                ThreatSystem::add_threat(game, member, firer_id, SQUAD_ALARM_THREAT);
+            }
+        }
+    }
     pub fn step(game: &mut Game) {
         let mut squadporters = Vec::new();
         let mut hunters = Vec::new();
@@ -111,9 +157,17 @@ impl GuardMind {
                     let offset_direction = PixelCoords::new_at_zero().get_direction_to(&offset);
                     squadporters.push((guard_mind.owner_id, offset_direction));
                 } else {
-                    if let Some(prey) =
-                        HuntingSystem::get_closest_prey(game, guard_mind.owner_id, HUNT_RADIUS)
-                    {
+                    // Go after whoever's been causing trouble before whoever's closest
+                    let prey = ThreatSystem::get_valid_target(game, guard_mind.owner_id)
+                        .and_then(|target_id| HuntingSystem::get_prey_id(game, target_id))
+                        .or_else(|| {
+                            PerceptionSystem::get_closest_perceived_prey(
+                                game,
+                                guard_mind.owner_id,
+                                HUNT_RADIUS,
+                            )
+                        });
+                    if let Some(prey) = prey {
                         hunters.push((id, prey));
                     }
                 }
@@ -143,6 +197,8 @@ impl GuardMind {
 pub struct GuardMindComponent {
     component_id: ComponentId,
     hunter_behaviour_component: ComponentId,
+    perceiver_component: ComponentId,
+    threat_table_component: ComponentId,
     mind_id: MindId,
 }
 
@@ -150,6 +206,8 @@ impl Component for GuardMindComponent {
     fn on_remove(self: Box<Self>, game: &mut Game, owner: GameObjectId) {
         game.villages_system.guard_minds.remove(self.mind_id);
         owner.remove_component(game, self.hunter_behaviour_component);
+        owner.remove_component(game, self.perceiver_component);
+        owner.remove_component(game, self.threat_table_component);
     }
 
     fn get_component_id(&self) -> ComponentId {
@@ -161,6 +219,8 @@ impl GuardMindComponent {
         let component_id = game.get_id();
         let mind_id = MindComponent::add_to(game, owner_id);
         let hunter_behaviour_component = HunterBehaviourComponent::add_to(game, owner_id, mind_id);
+        let perceiver_component = PerceiverComponent::add_to(game, owner_id);
+        let threat_table_component = ThreatTableComponent::add_to(game, owner_id);
         let guard_mind = GuardMind {
             squad_id,
             owner_id,
@@ -169,6 +229,8 @@ impl GuardMindComponent {
         let comp = GuardMindComponent {
             component_id,
             hunter_behaviour_component: hunter_behaviour_component.component_id,
+            perceiver_component,
+            threat_table_component,
             mind_id,
         };
         owner_id.add_component(game, comp);
@@ -197,25 +259,29 @@ pub fn spawn_guard(game: &mut Game, squad_id: SquadId, coords: PixelCoords) -> G
     DeleteOnDeathComponent::add_to(game, owner_id);
     owner_id
 }
-pub fn spawn_guard_squad(game: &mut Game) {
+pub fn spawn_guard_squad(game: &mut Game, village_id: VillageId) {
     let squad_id = game.get_id();
+    let center = match game.villages_system.villages.get(village_id) {
+        Some(village) => village.get_center(),
+        None => PixelCoords::new_at_zero(),
+    };
     let mut members = Vec::new();
     for _ in 0..5 {
         let dx = thread_rng().gen_range(-256..256);
         let dy = thread_rng().gen_range(-256..256);
-        members.push(spawn_guard(
-            game,
-            squad_id,
-            PixelCoords::new_to_fixed(Plane(0), dx, dy),
-        ));
+        members.push(spawn_guard(game, squad_id, center.translate(dx, dy)));
     }
-    let flag = GameObject::create_game(game, PixelCoords::new_at_zero());
+    let flag = GameObject::create_game(game, center);
     BasicDrawingComponent::add_to(game, flag, FLAG_SPRITE, DEFAULT_DEPTH);
     let squad = Squad {
+        village_id,
         members,
-        target_position: PixelCoords::new_at_zero(),
+        target_position: center,
         forces: WolfHashMap::new(),
         flag,
     };
     game.villages_system.squads.insert(squad_id, squad);
+    if let Some(village) = game.villages_system.villages.get_mut(village_id) {
+        village.squads.insert(squad_id);
+    }
 }
@@ -9,26 +9,32 @@ use std::f64::consts::PI;
 
 mod build_house;
 mod building_ability;
+mod building_template;
 mod city_block;
+mod economy;
 mod guards;
-mod layout;
 mod needs_deconstruction;
 mod reproduce;
 mod reservations;
+mod roads;
 mod scaffold;
 mod tavern;
+mod village;
 mod villager;
 
 pub use build_house::*;
 pub use building_ability::*;
+pub use building_template::*;
 pub use city_block::*;
+pub use economy::*;
 pub use guards::*;
-pub use layout::*;
 pub use needs_deconstruction::*;
 pub use reproduce::*;
 pub use reservations::*;
+pub use roads::*;
 pub use scaffold::*;
 pub use tavern::*;
+pub use village::*;
 pub use villager::*;
 use wolf_hash_map::WolfHashMap;
 use wolf_hash_map::WolfHashSet;
@@ -44,17 +50,19 @@ pub struct VillagesSystem {
     doors_map: IdMap<GameObjectId, GameObjectId>,
     guard_minds: IdMap<MindId, GuardMind>,
     squads: IdMap<SquadId, Squad>,
-    reserved: WolfHashSet<CityBlockChunkCoords>,
-    city_block_spiraler: Spiraler<CITY_BLOCK_SIZE>,
-    next_city_block: Option<CityBlock>,
+    villages: IdMap<VillageId, Village>,
+    territory: WolfHashMap<CityBlockChunkCoords, VillageId>,
     needs_deconstruction: IdMap<NeedsDeconstructionId, NeedsDeconstruction>,
-    unassigned_needs_deconstruction: WolfHashSet<NeedsDeconstructionId>,
     needs_deconstruction_lookup:
         WolfHashMap<CityBlockChunkCoords, WolfHashSet<NeedsDeconstructionId>>,
     deconstruct_behaviours: IdMap<BehaviourId, DeconstructBehaviour>,
     scaffolds: IdMap<ScaffoldId, Scaffold>,
-    unassigned_scaffolds: WolfHashSet<ScaffoldId>,
     build_scaffold_behaviours: IdMap<BehaviourId, BuildScaffoldBehaviour>,
+    workshops: IdMap<WorkshopId, Workshop>,
+    gather_behaviours: IdMap<BehaviourId, GatherBehaviour>,
+    craft_behaviours: IdMap<BehaviourId, CraftBehaviour>,
+    building_templates: Vec<BuildingTemplate>,
+    road_network: RoadNetwork,
 }
 
 impl VillagesSystem {
@@ -68,32 +76,37 @@ impl VillagesSystem {
             doors_map: IdMap::new(),
             guard_minds: IdMap::new(),
             squads: IdMap::new(),
-            reserved: WolfHashSet::new(),
-            city_block_spiraler: Spiraler::new(),
-            next_city_block: None,
+            villages: IdMap::new(),
+            territory: WolfHashMap::new(),
             needs_deconstruction: IdMap::new(),
-            unassigned_needs_deconstruction: WolfHashSet::new(),
             needs_deconstruction_lookup: WolfHashMap::new(),
             deconstruct_behaviours: IdMap::new(),
             scaffolds: IdMap::new(),
-            unassigned_scaffolds: WolfHashSet::new(),
             build_scaffold_behaviours: IdMap::new(),
+            workshops: IdMap::new(),
+            gather_behaviours: IdMap::new(),
+            craft_behaviours: IdMap::new(),
+            building_templates: load_building_templates(),
+            road_network: RoadNetwork::new(),
         }
     }
     pub fn is_reserved(&self, coords: SquareCoords) -> bool {
-        self.reserved.contains(&coords.into())
+        self.territory.contains_key(&coords.into())
     }
     pub fn step(game: &mut Game) {
+        VillagesSystem::step_job_boards(game);
         VillagerMind::step(game);
         VillagerBehaviour::step(game);
         ReproduceBehaviour::step(game);
         BuildHouseBehaviour::step(game);
         BuildScaffoldBehaviour::step(game);
         DeconstructBehaviour::step(game);
+        GatherBehaviour::step(game);
+        CraftBehaviour::step(game);
         GuardMind::step(game);
         Squad::step(game);
     }
-    pub fn create_villager(game: &mut Game, coords: PixelCoords) {
+    pub fn create_villager(game: &mut Game, village_id: VillageId, coords: PixelCoords) {
         let game_object_id = GameObject::create_game(game, coords);
         WalkerComponent::add_to(game, game_object_id, VILLAGER_SPEED, VILLAGER_SPEED / 2.0);
         DamageableComponent::add_to(game, game_object_id);
@@ -109,6 +122,9 @@ impl VillagesSystem {
         AllegianceComponent::add_to(game, game_object_id, allegiances);
         BasicAbilityUserComponent::add_to(game, game_object_id, vec![AbilityTypeId::FireballId]);
         add_health_bar(game, game_object_id);
-        VillagerMind::new(game, game_object_id);
+        VillagerMind::new(game, game_object_id, village_id);
+        if let Some(village) = game.villages_system.villages.get_mut(village_id) {
+            village.residents.insert(game_object_id);
+        }
     }
 }
@@ -44,9 +44,18 @@ impl Component for NeedsDeconstructionComponent {
             .needs_deconstruction
             .remove(self.needs_deconstruction_id)
             .unwrap();
-        game.villages_system
-            .unassigned_needs_deconstruction
-            .remove(&self.needs_deconstruction_id);
+        if let Some(village_id) = game
+            .villages_system
+            .territory
+            .get(&needs_deconstruction.coords)
+            .map(|x| *x)
+        {
+            VillagesSystem::complete_task(
+                game,
+                village_id,
+                VillageTask::Deconstruct(self.needs_deconstruction_id),
+            );
+        }
         if let hash_map::Entry::Occupied(mut occ) = game
             .villages_system
             .needs_deconstruction_lookup
@@ -70,6 +79,7 @@ impl Component for NeedsDeconstructionComponent {
 
 pub struct DeconstructBehaviour {
     mind_id: MindId,
+    village_id: VillageId,
     target: Option<(NeedsDeconstructionId, GameObjectId)>,
 }
 
@@ -87,7 +97,22 @@ impl DeconstructBehaviour {
                 continue;
             }
             let my_coords = mind.game_object_id.get_coords(&game.game_objects);
-            if let Some((_, target)) = behaviour.target {
+            if let Some((needs_deconstruction_id, target)) = behaviour.target {
+                let still_claimed = game
+                    .villages_system
+                    .villages
+                    .get(behaviour.village_id)
+                    .map(|village| {
+                        village
+                            .job_board
+                            .is_claimed_by(VillageTask::Deconstruct(needs_deconstruction_id), id)
+                    })
+                    .unwrap_or(false);
+                if !still_claimed {
+                    behaviour.target = None;
+                    mind.active_behaviour = None;
+                    continue;
+                }
                 if let Some(target_coords) = target.get_coords_safe(&game.game_objects) {
                     let distance = my_coords.get_distance_to(&target_coords);
                     if distance < DECONSTRUCT_RANGE {
@@ -112,10 +137,11 @@ impl DeconstructBehaviour {
             id.remove(game);
         }
     }
-    pub fn new(game: &mut Game, mind_id: MindId) -> BehaviourId {
+    pub fn new(game: &mut Game, mind_id: MindId, village_id: VillageId) -> BehaviourId {
         let id = game.get_id();
         let behaviour = DeconstructBehaviour {
             mind_id,
+            village_id,
             target: None,
         };
         game.villages_system
@@ -126,23 +152,39 @@ impl DeconstructBehaviour {
     pub fn remove(game: &mut Game, id: BehaviourId) {
         if let Some(behaviour) = game.villages_system.deconstruct_behaviours.remove(id) {
             if let Some((needs_deconstruction_id, _)) = behaviour.target {
-                if game
-                    .villages_system
-                    .needs_deconstruction
-                    .contains_key(needs_deconstruction_id)
-                {
-                    game.villages_system
-                        .unassigned_needs_deconstruction
-                        .insert(needs_deconstruction_id);
+                if let Some(village) = game.villages_system.villages.get_mut(behaviour.village_id) {
+                    village
+                        .job_board
+                        .release(VillageTask::Deconstruct(needs_deconstruction_id), id);
                 }
             }
         }
     }
-    pub fn activate(
-        game: &mut Game,
-        id: BehaviourId,
-        needs_deconstruction_id: NeedsDeconstructionId,
-    ) {
+    pub fn is_task(task: &VillageTask) -> bool {
+        matches!(task, VillageTask::Deconstruct(_))
+    }
+    // Claims something to deconstruct from the village's job board, returning false if there's nothing
+    pub fn activate(game: &mut Game, id: BehaviourId) -> bool {
+        let village_id = game
+            .villages_system
+            .deconstruct_behaviours
+            .get(id)
+            .unwrap()
+            .village_id;
+        let tick_counter = game.tick_counter;
+        let task = game
+            .villages_system
+            .villages
+            .get_mut(village_id)
+            .and_then(|village| {
+                village
+                    .job_board
+                    .claim(DeconstructBehaviour::is_task, id, tick_counter)
+            });
+        let needs_deconstruction_id = match task {
+            Some(VillageTask::Deconstruct(needs_deconstruction_id)) => needs_deconstruction_id,
+            _ => return false,
+        };
         let game_object_id = game
             .villages_system
             .needs_deconstruction
@@ -154,8 +196,6 @@ impl DeconstructBehaviour {
             .get_mut(id)
             .unwrap()
             .target = Some((needs_deconstruction_id, game_object_id));
-        game.villages_system
-            .unassigned_needs_deconstruction
-            .remove(&needs_deconstruction_id);
+        true
     }
 }
@@ -8,6 +8,7 @@ const REPRODUCE_JITTER: f64 = 5.0;
 
 pub struct ReproduceBehaviour {
     mind_id: MindId,
+    village_id: VillageId,
     partner_id: Option<GameObjectId>,
     birth_at: Option<u32>,
     started_looking: bool,
@@ -36,7 +37,10 @@ impl ReproduceBehaviour {
                 }
                 if let Some(birth_at) = behaviour.birth_at {
                     if game.tick_counter > birth_at {
-                        to_birth.push(mind.game_object_id.get_coords(&game.game_objects));
+                        to_birth.push((
+                            behaviour.village_id,
+                            mind.game_object_id.get_coords(&game.game_objects),
+                        ));
                         mind.active_behaviour = None;
                     } else {
                         let dx = thread_rng().gen_range(-REPRODUCE_JITTER..REPRODUCE_JITTER);
@@ -116,8 +120,8 @@ impl ReproduceBehaviour {
             a.speak_safe(game, "COME HERE", Some(game.tick_counter + 100));
             a.intend_follow(&mut game.movement_system.intend_move_system, b);
         }
-        for coords in to_birth {
-            VillagesSystem::create_villager(game, coords);
+        for (village_id, coords) in to_birth {
+            VillagesSystem::create_villager(game, village_id, coords);
         }
     }
     pub fn begin_behaviour(game: &mut Game, behaviour_id: BehaviourId) {
@@ -130,10 +134,11 @@ impl ReproduceBehaviour {
         behaviour.birth_at = None;
         behaviour.started_looking = false;
     }
-    pub fn new(game: &mut Game, mind_id: MindId) -> BehaviourId {
+    pub fn new(game: &mut Game, mind_id: MindId, village_id: VillageId) -> BehaviourId {
         let id = game.get_id();
         let behaviour = ReproduceBehaviour {
             mind_id,
+            village_id,
             partner_id: None,
             birth_at: None,
             started_looking: false,
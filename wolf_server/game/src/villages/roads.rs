@@ -0,0 +1,382 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::biomes::{get_generated_terrain_type, get_terrain_type, TerrainType};
+use crate::terrain::TerrainSpriteComponent;
+
+use super::*;
+
+// Stops road planning running away when there's no way through
+const MAX_ROAD_SEARCH_SQUARES: usize = 20000;
+const EXISTING_ROAD_COST: u32 = 1;
+const OPEN_GROUND_COST: u32 = 3;
+const SAND_COST: u32 = 4;
+const BRIDGE_COST: u32 = 12;
+// How much worse it is to grow away from the roads than along them
+const OFF_ROAD_GROWTH_MULTIPLIER: u32 = 3;
+const BAD_GROUND_GROWTH_PENALTY: u32 = 50;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RoadSquare {
+    Road,
+    Bridge,
+}
+
+fn get_adjacent_squares(square: SquareCoords) -> [SquareCoords; 4] {
+    [
+        square.translate(1, 0),
+        square.translate(-1, 0),
+        square.translate(0, 1),
+        square.translate(0, -1),
+    ]
+}
+
+fn manhattan_distance(a: SquareCoords, b: SquareCoords) -> u32 {
+    ((a.get_x() - b.get_x()).abs() + (a.get_y() - b.get_y()).abs()) as u32
+}
+
+// Roads that touch or overlap share squares, so the network is just the set of road squares
+pub struct RoadNetwork {
+    pub squares: WolfHashMap<SquareCoords, RoadSquare>,
+}
+
+impl RoadNetwork {
+    pub fn new() -> Self {
+        RoadNetwork {
+            squares: WolfHashMap::new(),
+        }
+    }
+    pub fn contains(&self, square: SquareCoords) -> bool {
+        self.squares.contains_key(&square)
+    }
+    pub fn get_neighbours(&self, square: SquareCoords) -> Vec<SquareCoords> {
+        get_adjacent_squares(square)
+            .iter()
+            .filter(|adjacent| self.contains(**adjacent))
+            .map(|x| *x)
+            .collect()
+    }
+    // Distance along the roads to every square connected to start
+    pub fn get_distances_from(&self, start: SquareCoords) -> WolfHashMap<SquareCoords, u32> {
+        let mut distances = WolfHashMap::new();
+        if !self.contains(start) {
+            return distances;
+        }
+        let mut to_visit = std::collections::VecDeque::new();
+        distances.insert(start, 0);
+        to_visit.push_back(start);
+        while let Some(square) = to_visit.pop_front() {
+            let distance = *distances.get(&square).unwrap();
+            for neighbour in self.get_neighbours(square) {
+                if !distances.contains_key(&neighbour) {
+                    distances.insert(neighbour, distance + 1);
+                    to_visit.push_back(neighbour);
+                }
+            }
+        }
+        distances
+    }
+    pub fn get_closest_square(&self, coords: SquareCoords) -> Option<SquareCoords> {
+        self.squares
+            .iter()
+            .map(|(square, _)| *square)
+            .filter(|square| square.get_plane() == coords.get_plane())
+            .min_by_key(|square| manhattan_distance(*square, coords))
+    }
+}
+
+// None if a road can't go through the square
+fn get_road_cost(game: &Game, square: SquareCoords) -> Option<u32> {
+    if game.villages_system.road_network.contains(square) {
+        return Some(EXISTING_ROAD_COST);
+    }
+    if game.terrain.is_square_solid(square) {
+        return None;
+    }
+    match get_terrain_type(game, square) {
+        TerrainType::Rock => None,
+        TerrainType::Water => Some(BRIDGE_COST),
+        TerrainType::Grass | TerrainType::Dirt => Some(OPEN_GROUND_COST),
+        TerrainType::Sand => Some(SAND_COST),
+    }
+}
+
+// A* over squares, preferring existing roads and going around rock and water where it can
+pub fn plan_road(game: &Game, start: SquareCoords, end: SquareCoords) -> Option<Vec<SquareCoords>> {
+    if start.get_plane() != end.get_plane() {
+        return None;
+    }
+    let mut best_costs: WolfHashMap<SquareCoords, u32> = WolfHashMap::new();
+    let mut came_from: WolfHashMap<SquareCoords, SquareCoords> = WolfHashMap::new();
+    let mut open = BinaryHeap::new();
+    best_costs.insert(start, 0);
+    open.push(Reverse((
+        manhattan_distance(start, end) * EXISTING_ROAD_COST,
+        0,
+        start.get_x(),
+        start.get_y(),
+    )));
+    let mut searched = 0;
+    while let Some(Reverse((_estimate, cost, x, y))) = open.pop() {
+        let square = SquareCoords::new(start.get_plane(), x, y);
+        if square == end {
+            let mut path = vec![end];
+            let mut current = end;
+            while let Some(previous) = came_from.get(&current) {
+                path.push(*previous);
+                current = *previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        if cost > *best_costs.get(&square).unwrap() {
+            continue;
+        }
+        searched += 1;
+        if searched > MAX_ROAD_SEARCH_SQUARES {
+            return None;
+        }
+        for adjacent in get_adjacent_squares(square).iter() {
+            let step_cost = match get_road_cost(game, *adjacent) {
+                Some(step_cost) => step_cost,
+                None => continue,
+            };
+            let new_cost = cost + step_cost;
+            if best_costs
+                .get(adjacent)
+                .map(|best| new_cost < *best)
+                .unwrap_or(true)
+            {
+                best_costs.insert(*adjacent, new_cost);
+                came_from.insert(*adjacent, square);
+                let estimate = new_cost + manhattan_distance(*adjacent, end) * EXISTING_ROAD_COST;
+                open.push(Reverse((
+                    estimate,
+                    new_cost,
+                    adjacent.get_x(),
+                    adjacent.get_y(),
+                )));
+            }
+        }
+    }
+    None
+}
+
+pub fn create_road_square(game: &mut Game, coords: SquareCoords, road_square: RoadSquare) {
+    let sprite = match road_square {
+        RoadSquare::Road => FLOOR_SPRITE,
+        RoadSquare::Bridge => BRIDGE_SPRITE,
+    };
+    let owner_id = GameObject::create_game(game, coords.center_pixel());
+    TerrainSpriteComponent::add_to(game, owner_id, coords, sprite);
+}
+
+impl VillagesSystem {
+    // Plans a road and lays down any squares that aren't already part of the network
+    pub fn build_road(game: &mut Game, start: SquareCoords, end: SquareCoords) -> bool {
+        let path = match plan_road(game, start, end) {
+            Some(path) => path,
+            None => return false,
+        };
+        for square in path {
+            if game.villages_system.road_network.contains(square) {
+                continue;
+            }
+            let road_square = match get_terrain_type(game, square) {
+                TerrainType::Water => RoadSquare::Bridge,
+                _ => RoadSquare::Road,
+            };
+            create_road_square(game, square, road_square);
+            game.villages_system
+                .road_network
+                .squares
+                .insert(square, road_square);
+        }
+        true
+    }
+    // Grows towards the neighbouring block that's quickest to reach by road, avoiding rock and water
+    pub fn choose_next_city_block(game: &Game, village_id: VillageId) -> CityBlockChunkCoords {
+        let village = game.villages_system.villages.get(village_id).unwrap();
+        if village.city_blocks.is_empty()
+            && !game.villages_system.territory.contains_key(&village.origin)
+        {
+            return village.origin;
+        }
+        let hub = village.origin.center_square();
+        let road_network = &game.villages_system.road_network;
+        let road_distances = road_network
+            .get_closest_square(hub)
+            .map(|closest| road_network.get_distances_from(closest))
+            .unwrap_or(WolfHashMap::new());
+        let mut candidates = Vec::new();
+        let mut frontier: Vec<CityBlockChunkCoords> =
+            village.city_blocks.iter().map(|x| *x).collect();
+        frontier.push(village.origin);
+        // If we're boxed in, look further out
+        while candidates.is_empty() {
+            let mut next_frontier = Vec::new();
+            for block in frontier {
+                for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                    let candidate = block.translate(dx, dy);
+                    if game.villages_system.territory.contains_key(&candidate) {
+                        next_frontier.push(candidate);
+                    } else {
+                        candidates.push(candidate);
+                    }
+                }
+            }
+            frontier = next_frontier;
+        }
+        candidates
+            .into_iter()
+            .min_by_key(|candidate| {
+                let center = candidate.center_square();
+                let by_road = road_distances
+                    .iter()
+                    .map(|(square, distance)| {
+                        distance + manhattan_distance(*square, center) * OFF_ROAD_GROWTH_MULTIPLIER
+                    })
+                    .min();
+                let distance =
+                    by_road.unwrap_or(manhattan_distance(hub, center) * OFF_ROAD_GROWTH_MULTIPLIER);
+                let bad_ground = [
+                    center,
+                    center.translate(CITY_BLOCK_SIZE / 4, 0),
+                    center.translate(-CITY_BLOCK_SIZE / 4, 0),
+                    center.translate(0, CITY_BLOCK_SIZE / 4),
+                    center.translate(0, -CITY_BLOCK_SIZE / 4),
+                ]
+                .iter()
+                .filter(|square| match get_generated_terrain_type(game, **square) {
+                    TerrainType::Rock | TerrainType::Water => true,
+                    _ => false,
+                })
+                .count() as u32;
+                distance + bad_ground * BAD_GROUND_GROWTH_PENALTY
+            })
+            .unwrap()
+    }
+    // Joins a newly claimed block onto the roads
+    pub fn connect_city_block(
+        game: &mut Game,
+        village_id: VillageId,
+        coords: CityBlockChunkCoords,
+    ) {
+        let hub = match game.villages_system.villages.get(village_id) {
+            Some(village) => village.origin.center_square(),
+            None => return,
+        };
+        let center = coords.center_square();
+        let start = game
+            .villages_system
+            .road_network
+            .get_closest_square(center)
+            .unwrap_or(hub);
+        if start != center {
+            VillagesSystem::build_road(game, start, center);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::{Chunk, TerrainSprite};
+
+    // Dirt everywhere around the origin, so the noise doesn't get a say
+    fn create_game() -> Game {
+        let mut game = Game::new();
+        for cx in -2..3 {
+            for cy in -2..3 {
+                let square = SquareCoords::new(
+                    Plane(0),
+                    cx * TERRAIN_CHUNK_SIZE_SQUARES,
+                    cy * TERRAIN_CHUNK_SIZE_SQUARES,
+                );
+                Chunk::generate(&mut game, square.into(), DIRT_SPRITE);
+            }
+        }
+        game
+    }
+
+    fn assert_connected(path: &[SquareCoords], start: SquareCoords, end: SquareCoords) {
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        for pair in path.windows(2) {
+            assert_eq!(manhattan_distance(pair[0], pair[1]), 1);
+        }
+    }
+
+    #[test]
+    fn roads_go_around_rock() {
+        let mut game = create_game();
+        for y in 0..8 {
+            game.terrain
+                .modify_chunk_square(SquareCoords::new(Plane(0), 5, y), |chunk_square| {
+                    chunk_square.base_solid = true;
+                    chunk_square.base_sprite = WALL_SPRITE;
+                });
+        }
+        let start = SquareCoords::new(Plane(0), 2, 5);
+        let end = SquareCoords::new(Plane(0), 8, 5);
+        let path = plan_road(&game, start, end).unwrap();
+        assert_connected(&path, start, end);
+        assert!(path
+            .iter()
+            .all(|square| !game.terrain.is_square_solid(*square)));
+        // Over the top, as that's the short way round
+        assert!(path.contains(&SquareCoords::new(Plane(0), 5, 8)));
+
+        // Dug out rock can be built on
+        game.terrain
+            .modify_chunk_square(SquareCoords::new(Plane(0), 5, 5), |chunk_square| {
+                chunk_square.base_solid = false;
+                chunk_square.base_sprite = DIRT_SPRITE;
+            });
+        let path = plan_road(&game, start, end).unwrap();
+        assert_eq!(path.len(), 7);
+    }
+
+    #[test]
+    fn roads_bridge_water_rather_than_going_far_around() {
+        let mut game = create_game();
+        let river_x = 5;
+        let (river_start, river_end) = (
+            -2 * TERRAIN_CHUNK_SIZE_SQUARES,
+            3 * TERRAIN_CHUNK_SIZE_SQUARES,
+        );
+        for y in river_start..river_end {
+            game.terrain.modify_chunk_square(
+                SquareCoords::new(Plane(0), river_x, y),
+                |chunk_square| {
+                    chunk_square.base_sprite = WATER_SPRITE;
+                },
+            );
+        }
+        let start = SquareCoords::new(Plane(0), 2, 5);
+        let end = SquareCoords::new(Plane(0), 8, 5);
+        assert!(VillagesSystem::build_road(&mut game, start, end));
+        let bridge = SquareCoords::new(Plane(0), river_x, 5);
+        assert_eq!(
+            game.villages_system.road_network.squares.get(&bridge),
+            Some(&RoadSquare::Bridge)
+        );
+        assert_eq!(
+            game.villages_system.road_network.squares.get(&start),
+            Some(&RoadSquare::Road)
+        );
+
+        let water = SquareCoords::new(Plane(0), river_x, 6);
+        assert!(!game.terrain.get_chunk_square(bridge).unwrap().is_walkable());
+        TerrainSprite::step(&mut game);
+        assert!(game.terrain.get_chunk_square(bridge).unwrap().is_walkable());
+        assert!(!game.terrain.get_chunk_square(water).unwrap().is_walkable());
+    }
+
+    #[test]
+    fn roads_cant_be_planned_between_planes() {
+        let game = create_game();
+        let start = SquareCoords::new(Plane(0), 0, 0);
+        assert!(plan_road(&game, start, SquareCoords::new(Plane(1), 0, 0)).is_none());
+    }
+}
@@ -2,53 +2,102 @@ use crate::terrain::TerrainSpriteComponent;
 
 use super::*;
 
+#[derive(Clone, Copy, Debug)]
 pub enum ScaffoldType {
     Wall,
+    Floor,
+    // Doors and stations take their interior or recipe from the building's template
+    Door(BuildingTemplateIndex),
+    Station(BuildingTemplateIndex),
+    Field,
 }
 pub struct Scaffold {
     scaffold_type: ScaffoldType,
+    village_id: VillageId,
     game_object_id: GameObjectId,
 }
 
 impl Scaffold {
-    pub fn create(game: &mut Game, coords: SquareCoords) {
+    pub fn create(
+        game: &mut Game,
+        village_id: VillageId,
+        coords: SquareCoords,
+        scaffold_type: ScaffoldType,
+    ) {
         let id = game.get_id();
         let owner_id = GameObject::create_game(game, coords.center_pixel());
         TerrainSpriteComponent::add_to(game, owner_id, coords, SCAFFOLD_SPRITE);
         DamageableComponent::add_to(game, owner_id);
         let scaffold = Scaffold {
-            scaffold_type: ScaffoldType::Wall,
+            scaffold_type,
+            village_id,
             game_object_id: owner_id,
         };
         game.villages_system.scaffolds.insert(id, scaffold);
-        game.villages_system.unassigned_scaffolds.insert(id);
+        VillagesSystem::post_task(game, village_id, VillageTask::BuildScaffold(id));
     }
     pub fn build(game: &mut Game, id: ScaffoldId) {
-        if let Some(scaffold) = game.villages_system.scaffolds.remove(id) {
-            if let Some(coords) = scaffold.game_object_id.get_coords_safe(&game.game_objects) {
-                create_wall(game, coords.into());
-                scaffold.game_object_id.remove(game);
+        if let Some(scaffold) = game.villages_system.scaffolds.get(id) {
+            let game_object_id = scaffold.game_object_id;
+            let village_id = scaffold.village_id;
+            let scaffold_type = scaffold.scaffold_type;
+            if let Some(coords) = game_object_id.get_coords_safe(&game.game_objects) {
+                let coords: SquareCoords = coords.into();
+                match scaffold_type {
+                    ScaffoldType::Wall => create_wall(game, coords),
+                    ScaffoldType::Floor => create_floor(game, coords),
+                    ScaffoldType::Door(template_index) => {
+                        let interior = game.villages_system.building_templates[template_index]
+                            .interior
+                            .clone();
+                        match interior {
+                            Some(interior) => {
+                                let exit = create_interior(game, &interior, coords);
+                                create_portal(game, coords, exit);
+                            }
+                            None => create_floor(game, coords),
+                        }
+                    }
+                    ScaffoldType::Station(template_index) => {
+                        create_floor(game, coords);
+                        let recipe = game.villages_system.building_templates[template_index]
+                            .recipe
+                            .clone();
+                        if let Some(recipe) = recipe {
+                            VillagesSystem::create_workshop(game, village_id, coords, recipe);
+                        }
+                    }
+                    ScaffoldType::Field => create_field(game, coords),
+                }
+                game_object_id.remove(game);
             }
             Scaffold::remove(game, id);
         }
     }
     pub fn remove(game: &mut Game, id: ScaffoldId) {
-        game.villages_system.scaffolds.remove(id);
-        game.villages_system.unassigned_scaffolds.remove(&id);
+        if let Some(scaffold) = game.villages_system.scaffolds.remove(id) {
+            VillagesSystem::complete_task(
+                game,
+                scaffold.village_id,
+                VillageTask::BuildScaffold(id),
+            );
+        }
     }
 }
 
 pub struct BuildScaffoldBehaviour {
     mind_id: MindId,
+    village_id: VillageId,
     target: Option<(ScaffoldId, GameObjectId)>,
 }
 
 const SCAFFOLD_BUILD_DISTANCE: f64 = 50.0;
 impl BuildScaffoldBehaviour {
-    pub fn new(game: &mut Game, mind_id: MindId) -> BehaviourId {
+    pub fn new(game: &mut Game, mind_id: MindId, village_id: VillageId) -> BehaviourId {
         let id = game.get_id();
         let behaviour = BuildScaffoldBehaviour {
             mind_id,
+            village_id,
             target: None,
         };
         game.villages_system
@@ -56,7 +105,31 @@ impl BuildScaffoldBehaviour {
             .insert(id, behaviour);
         id
     }
-    pub fn activate(game: &mut Game, id: BehaviourId, scaffold_id: ScaffoldId) {
+    pub fn is_task(task: &VillageTask) -> bool {
+        matches!(task, VillageTask::BuildScaffold(_))
+    }
+    // Claims a scaffold from the village's job board, returning false if there are none left
+    pub fn activate(game: &mut Game, id: BehaviourId) -> bool {
+        let village_id = game
+            .villages_system
+            .build_scaffold_behaviours
+            .get(id)
+            .unwrap()
+            .village_id;
+        let tick_counter = game.tick_counter;
+        let task = game
+            .villages_system
+            .villages
+            .get_mut(village_id)
+            .and_then(|village| {
+                village
+                    .job_board
+                    .claim(BuildScaffoldBehaviour::is_task, id, tick_counter)
+            });
+        let scaffold_id = match task {
+            Some(VillageTask::BuildScaffold(scaffold_id)) => scaffold_id,
+            _ => return false,
+        };
         let target_game_object_id = game
             .villages_system
             .scaffolds
@@ -68,17 +141,15 @@ impl BuildScaffoldBehaviour {
             .get_mut(id)
             .unwrap()
             .target = Some((scaffold_id, target_game_object_id));
-        game.villages_system
-            .unassigned_scaffolds
-            .remove(&scaffold_id);
+        true
     }
     pub fn remove(game: &mut Game, id: BehaviourId) {
         if let Some(behaviour) = game.villages_system.build_scaffold_behaviours.remove(id) {
             if let Some((scaffold_id, _)) = behaviour.target {
-                if game.villages_system.scaffolds.contains_key(scaffold_id) {
-                    game.villages_system
-                        .unassigned_scaffolds
-                        .insert(scaffold_id);
+                if let Some(village) = game.villages_system.villages.get_mut(behaviour.village_id) {
+                    village
+                        .job_board
+                        .release(VillageTask::BuildScaffold(scaffold_id), id);
                 }
             }
         }
@@ -98,6 +169,22 @@ impl BuildScaffoldBehaviour {
             }
             let my_coords = mind.game_object_id.get_coords(&game.game_objects);
             if let Some((target, target_game_object_id)) = behaviour.target {
+                let still_claimed = game
+                    .villages_system
+                    .villages
+                    .get(behaviour.village_id)
+                    .map(|village| {
+                        village
+                            .job_board
+                            .is_claimed_by(VillageTask::BuildScaffold(target), id)
+                    })
+                    .unwrap_or(false);
+                if !still_claimed {
+                    // Timed out, or someone else finished it
+                    behaviour.target = None;
+                    mind.active_behaviour = None;
+                    continue;
+                }
                 if let Some(target_coords) =
                     target_game_object_id.get_coords_safe(&game.game_objects)
                 {
@@ -1,16 +0,0 @@
-
-enum VillageTask {
-    BuildHouse,
-    BuildScaffold,
-}
-
-
-struct Building {
-}
-struct Village {
-}
-struct VillageTasksState {
-}
-
-fn step_village_tasks(state: &mut VillageTasksState) {
-}
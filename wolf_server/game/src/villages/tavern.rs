@@ -1,7 +1,9 @@
-use rand::thread_rng;
 use wolf_hash_map::WolfHashSet;
 
-use crate::terrain::{notify_new_chunk, Chunk, ChunkLoader, TerrainSpriteComponent};
+use crate::terrain::{
+    get_chunk_index_from_relative_coords, notify_new_chunk, Chunk, ChunkLoader,
+    TerrainSpriteComponent,
+};
 
 use super::*;
 
@@ -15,64 +17,50 @@ pub fn create_portal(game: &mut Game, coords_1: SquareCoords, coords_2: SquareCo
     game.villages_system.doors_map.insert(door_1, door_2);
     game.villages_system.doors_map.insert(door_2, door_1);
 }
-pub fn create_tavern(game: &mut Game, coords: SquareCoords) {
+// Lays out an interior on a new plane, returning where its exit door is
+pub fn create_interior(
+    game: &mut Game,
+    interior: &Vec<(u8, u8, InteriorSquare)>,
+    entrance: SquareCoords,
+) -> SquareCoords {
     let new_plane: Plane = game.get_plane();
-    let door_2_coords = SquareCoords::new(new_plane, 0, 0);
-
-    // Destroy anything present
-    let destroy_width = 5 * SQUARE_SIZE_PIXELS / 2;
-    let hit_box = HitBox::new(coords.center_pixel(), destroy_width, destroy_width);
-    let colliding = CollisionSystem::get_colliding(game, CollisionGroupId::Damageable, hit_box);
-    for id in colliding {
-        id.send_death_signal(game);
+    let origin = SquareCoords::new(new_plane, 0, 0);
+    let mut exit = origin;
+    let mut interior_chunk = Chunk::new(VOID_SPRITE);
+    for chunk_square in interior_chunk.chunk_squares.iter_mut() {
+        chunk_square.base_solid = true;
     }
-    // Create door on main plane
-    let door_1 = GameObject::create_game(game, coords.center_pixel());
-    TerrainSpriteComponent::add_to(game, door_1, coords, DOOR_SPRITE);
-    // Add floor
-    for dx in -1..2 {
-        for dy in -1..2 {
-            let floor_coords = coords.translate(dx, dy);
-            TerrainSpriteComponent::add_to(game, door_1, floor_coords, FLOOR_SPRITE);
-        }
-    }
-    // Add walls
-    let mut wall_coords = coords.translate(-2, 2);
-    let mut wdx = 1;
-    let mut wdy = 0;
-    let facing = thread_rng().gen_range(0..4);
-    for side in 0..4 {
-        for square in 0..4 {
-            if side != facing || square != 2 {
-                Scaffold::create(game, wall_coords);
+    for (x, y, square) in interior.iter() {
+        let index = get_chunk_index_from_relative_coords(ChunkRelativeSquareCoords::new(*x, *y));
+        let chunk_square = &mut interior_chunk.chunk_squares[index];
+        match square {
+            InteriorSquare::Wall => {
+                chunk_square.base_solid = true;
+                chunk_square.base_sprite = WALL_SPRITE;
+            }
+            InteriorSquare::Floor => {
+                chunk_square.base_solid = false;
+                chunk_square.base_sprite = FLOOR_SPRITE;
+            }
+            InteriorSquare::Exit => {
+                chunk_square.base_solid = false;
+                chunk_square.base_sprite = FLOOR_SPRITE;
+                exit = origin.translate(*x as i64, *y as i64);
             }
-            wall_coords = wall_coords.translate(wdx, wdy)
         }
-        // Keep tavern loaded
-        let wdx_next = wdy;
-        wdy = -wdx;
-        wdx = wdx_next;
     }
-    door_1.add_collision_group(game, CollisionGroupId::Portal);
-
-    // Create exit door
-    let door_2 = GameObject::create_game(game, door_2_coords.center_pixel());
-    TerrainSpriteComponent::add_to(game, door_2, door_2_coords, DOOR_SPRITE);
-    door_2.add_collision_group(game, CollisionGroupId::Portal);
+    game.terrain.chunks.insert(origin.into(), interior_chunk);
 
-    // TODO: allow for unloading/reloading taverns
-    // Keep tavern loaded
+    // TODO: allow for unloading/reloading interiors
+    // Keep interior loaded
     let chunk_loader = {
         let mut loaded_chunks = WolfHashSet::new();
-        for coords in [coords, door_2_coords] {
+        for coords in [entrance, origin] {
             let chunk_coords: TerrainChunkCoords = coords.into();
             loaded_chunks.extend(square_of_coords_centered(chunk_coords, 1).into_iter());
         }
         ChunkLoader { loaded_chunks }
     };
-    game.terrain
-        .chunks
-        .insert(door_2_coords.into(), Chunk::new(FLOOR_SPRITE));
     let big_wall_id = game.get_id();
     for dx in -1..2 {
         for dy in -1..2 {
@@ -84,18 +72,17 @@ pub fn create_tavern(game: &mut Game, coords: SquareCoords) {
                 chunk.chunk_squares[i].solids.insert(big_wall_id);
             }
             game.terrain.chunks.insert(
-                Into::<TerrainChunkCoords>::into(door_2_coords).translate(dx, dy),
+                Into::<TerrainChunkCoords>::into(origin).translate(dx, dy),
                 chunk,
             );
         }
     }
-    notify_new_chunk(game, door_2_coords.into());
-    let tavern_loader_id = game.get_id();
+    notify_new_chunk(game, origin.into());
+    let interior_loader_id = game.get_id();
     game.terrain
         .chunk_loaders
-        .insert(tavern_loader_id, chunk_loader);
-    game.villages_system.doors_map.insert(door_1, door_2);
-    game.villages_system.doors_map.insert(door_2, door_1);
+        .insert(interior_loader_id, chunk_loader);
+    exit
 }
 pub fn traverse_doors(game: &mut Game, game_object_id: GameObjectId) {
     let hit_box = game_object_id.get_hit_box(game);
@@ -112,4 +99,5 @@ pub fn traverse_doors(game: &mut Game, game_object_id: GameObjectId) {
 Buildings:
 A building is a PORTAL to the BUILDING PLANE
 Tavern (Villagers drink here)
+Layouts for these live in data/buildings
 */
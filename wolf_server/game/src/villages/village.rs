@@ -0,0 +1,259 @@
+use rand::thread_rng;
+
+use super::*;
+
+// Claims that aren't completed in this time go back on the board
+const JOB_CLAIM_TIMEOUT: u32 = 1200;
+const FOUNDING_SPREAD: i64 = 128;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum VillageTask {
+    BuildScaffold(ScaffoldId),
+    Deconstruct(NeedsDeconstructionId),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct JobClaim {
+    pub claimed_by: BehaviourId,
+    pub expires_at: u32,
+}
+
+pub struct JobBoard {
+    jobs: WolfHashMap<VillageTask, Option<JobClaim>>,
+}
+
+impl JobBoard {
+    pub fn new() -> Self {
+        JobBoard {
+            jobs: WolfHashMap::new(),
+        }
+    }
+    pub fn post(&mut self, task: VillageTask) {
+        self.jobs.entry(task).or_insert(None);
+    }
+    pub fn has_unclaimed<F: Fn(&VillageTask) -> bool>(&self, filter: F) -> bool {
+        self.jobs
+            .iter()
+            .any(|(task, claim)| claim.is_none() && filter(task))
+    }
+    pub fn claim<F: Fn(&VillageTask) -> bool>(
+        &mut self,
+        filter: F,
+        claimed_by: BehaviourId,
+        tick_counter: u32,
+    ) -> Option<VillageTask> {
+        let (task, claim) = self
+            .jobs
+            .iter_mut()
+            .find(|(task, claim)| claim.is_none() && filter(task))?;
+        *claim = Some(JobClaim {
+            claimed_by,
+            expires_at: tick_counter + JOB_CLAIM_TIMEOUT,
+        });
+        Some(*task)
+    }
+    pub fn is_claimed_by(&self, task: VillageTask, claimed_by: BehaviourId) -> bool {
+        matches!(
+            self.jobs.get(&task),
+            Some(Some(claim)) if claim.claimed_by == claimed_by
+        )
+    }
+    // Puts a claimed task back on the board, if it still exists
+    pub fn release(&mut self, task: VillageTask, claimed_by: BehaviourId) {
+        if self.is_claimed_by(task, claimed_by) {
+            self.jobs.insert(task, None);
+        }
+    }
+    pub fn complete(&mut self, task: VillageTask) {
+        self.jobs.remove(&task);
+    }
+    pub fn time_out_claims(&mut self, tick_counter: u32) {
+        for (_task, claim) in self.jobs.iter_mut() {
+            if let Some(JobClaim { expires_at, .. }) = claim {
+                if *expires_at <= tick_counter {
+                    *claim = None;
+                }
+            }
+        }
+    }
+}
+
+pub struct Village {
+    pub origin: CityBlockChunkCoords,
+    pub residents: WolfHashSet<GameObjectId>,
+    pub squads: WolfHashSet<SquadId>,
+    pub buildings: Vec<(BuildingTemplateIndex, SquareCoords)>,
+    pub city_blocks: WolfHashSet<CityBlockChunkCoords>,
+    pub stockpile: Option<GameObjectId>,
+    pub job_board: JobBoard,
+    next_city_block: Option<CityBlock>,
+}
+
+impl Village {
+    pub fn new(origin: CityBlockChunkCoords) -> Self {
+        Village {
+            origin,
+            residents: WolfHashSet::new(),
+            squads: WolfHashSet::new(),
+            buildings: Vec::new(),
+            city_blocks: WolfHashSet::new(),
+            stockpile: None,
+            job_board: JobBoard::new(),
+            next_city_block: None,
+        }
+    }
+    pub fn get_center(&self) -> PixelCoords {
+        self.origin.center_square().center_pixel()
+    }
+    pub fn needs_new_city_block(&self) -> bool {
+        match &self.next_city_block {
+            Some(next_city_block) => next_city_block.is_full(),
+            None => true,
+        }
+    }
+    pub fn get_building_reservation(&mut self) -> Option<ReservationChunkCoords> {
+        self.next_city_block
+            .as_mut()
+            .map(|next_city_block| next_city_block.get_reservation())
+    }
+}
+
+impl VillagesSystem {
+    pub fn found_village(game: &mut Game, coords: SquareCoords, population: usize) -> VillageId {
+        let village_id = game.get_id();
+        let village = Village::new(coords.into());
+        let center = village.get_center();
+        game.villages_system.villages.insert(village_id, village);
+        for _ in 0..population {
+            let dx = thread_rng().gen_range(-FOUNDING_SPREAD..FOUNDING_SPREAD);
+            let dy = thread_rng().gen_range(-FOUNDING_SPREAD..FOUNDING_SPREAD);
+            VillagesSystem::create_villager(game, village_id, center.translate(dx, dy));
+        }
+        spawn_guard_squad(game, village_id);
+        village_id
+    }
+    pub fn get_village_at(&self, coords: SquareCoords) -> Option<VillageId> {
+        self.territory.get(&coords.into()).map(|x| *x)
+    }
+    pub fn claim_city_block(game: &mut Game, village_id: VillageId, coords: CityBlockChunkCoords) {
+        if game
+            .villages_system
+            .territory
+            .insert(coords, village_id)
+            .is_some()
+        {
+            tracing::warn!(?coords, "Attempted to recreate city block");
+        }
+        let village = game.villages_system.villages.get_mut(village_id).unwrap();
+        village.city_blocks.insert(coords);
+        village.next_city_block = Some(CityBlock::new(coords.center_square().into()));
+        if let Some(to_deconstruct) = game
+            .villages_system
+            .needs_deconstruction_lookup
+            .get(&coords)
+        {
+            for needs_deconstruction_id in to_deconstruct.iter() {
+                village
+                    .job_board
+                    .post(VillageTask::Deconstruct(*needs_deconstruction_id));
+            }
+        }
+        let mut square = coords.bottom_left();
+        let mut direction = (1, 0);
+        for _side in 0..4 {
+            for _square in 0..CITY_BLOCK_SIZE - 1 {
+                Scaffold::create(game, village_id, square, ScaffoldType::Wall);
+                square = square.translate(direction.0, direction.1);
+            }
+            direction = (-direction.1, direction.0);
+        }
+        VillagesSystem::connect_city_block(game, village_id, coords);
+        // The corner of each block is clear of buildings, so keep the stockpile there
+        if game
+            .villages_system
+            .villages
+            .get(village_id)
+            .unwrap()
+            .stockpile
+            .is_none()
+        {
+            let corner = coords.bottom_left().translate(2, 2);
+            VillagesSystem::create_stockpile(game, village_id, corner);
+        }
+    }
+    pub fn post_task(game: &mut Game, village_id: VillageId, task: VillageTask) {
+        if let Some(village) = game.villages_system.villages.get_mut(village_id) {
+            village.job_board.post(task);
+        }
+    }
+    pub fn complete_task(game: &mut Game, village_id: VillageId, task: VillageTask) {
+        if let Some(village) = game.villages_system.villages.get_mut(village_id) {
+            village.job_board.complete(task);
+        }
+    }
+    pub fn step_job_boards(game: &mut Game) {
+        for (_id, village) in game.villages_system.villages.iter_mut() {
+            village.job_board.time_out_claims(game.tick_counter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_scaffold(task: &VillageTask) -> bool {
+        matches!(task, VillageTask::BuildScaffold(_))
+    }
+
+    #[test]
+    fn jobs_are_claimed_once_until_released_or_completed() {
+        let mut job_board = JobBoard::new();
+        let task = VillageTask::BuildScaffold(ScaffoldId(1));
+        job_board.post(task);
+        job_board.post(VillageTask::Deconstruct(NeedsDeconstructionId(2)));
+        assert!(job_board.has_unclaimed(is_scaffold));
+
+        assert_eq!(job_board.claim(is_scaffold, BehaviourId(3), 0), Some(task));
+        assert!(job_board.is_claimed_by(task, BehaviourId(3)));
+        assert!(!job_board.has_unclaimed(is_scaffold));
+        assert_eq!(job_board.claim(is_scaffold, BehaviourId(4), 0), None);
+        // Posting again doesn't take the claim away
+        job_board.post(task);
+        assert!(job_board.is_claimed_by(task, BehaviourId(3)));
+
+        // Only the claimer can give it back
+        job_board.release(task, BehaviourId(4));
+        assert!(job_board.is_claimed_by(task, BehaviourId(3)));
+        job_board.release(task, BehaviourId(3));
+        assert_eq!(job_board.claim(is_scaffold, BehaviourId(4), 0), Some(task));
+
+        job_board.complete(task);
+        assert!(!job_board.is_claimed_by(task, BehaviourId(4)));
+        assert!(!job_board.has_unclaimed(is_scaffold));
+        assert!(job_board.has_unclaimed(|_task| true));
+    }
+
+    #[test]
+    fn claims_time_out() {
+        let mut job_board = JobBoard::new();
+        let task = VillageTask::BuildScaffold(ScaffoldId(1));
+        job_board.post(task);
+        job_board.claim(is_scaffold, BehaviourId(3), 100);
+        job_board.time_out_claims(100 + JOB_CLAIM_TIMEOUT - 1);
+        assert!(job_board.is_claimed_by(task, BehaviourId(3)));
+        job_board.time_out_claims(100 + JOB_CLAIM_TIMEOUT);
+        assert!(!job_board.is_claimed_by(task, BehaviourId(3)));
+        assert_eq!(job_board.claim(is_scaffold, BehaviourId(4), 0), Some(task));
+    }
+
+    #[test]
+    fn founded_villages_have_residents_and_guards() {
+        let mut game = Game::new();
+        let village_id =
+            VillagesSystem::found_village(&mut game, SquareCoords::new(Plane(0), 0, 0), 3);
+        let village = game.villages_system.villages.get(village_id).unwrap();
+        assert_eq!(village.residents.len(), 3);
+        assert_eq!(village.squads.len(), 1);
+    }
+}
@@ -2,17 +2,37 @@ use super::*;
 
 pub struct VillagerBehaviour {
     mind_id: MindId,
+    village_id: VillageId,
 }
 
 impl VillagerBehaviour {
     pub fn step(game: &mut Game) {
         let mut to_delete = Vec::new();
         let mut to_throw = Vec::new();
+        let mut to_deposit = Vec::new();
+        let game_objects = &game.game_objects;
         for (id, villager) in game.villages_system.villager_behaviours.iter() {
             if let Some(mind) = game.behaviour_system.minds.get_mut(villager.mind_id) {
                 if mind.active_behaviour != Some(id) {
                     continue;
                 }
+                let stockpile = game.villages_system.get_stockpile(villager.village_id);
+                let stockpile_coords =
+                    stockpile.and_then(|stockpile| stockpile.get_coords_safe(game_objects));
+                // Haul to the stockpile if there is one, otherwise leave it lying around
+                if let Some(stockpile_coords) = stockpile_coords {
+                    let coords = mind.game_object_id.get_coords(game_objects);
+                    if coords.get_distance_to(&stockpile_coords) < STOCKPILE_DISTANCE {
+                        mind.active_behaviour = None;
+                        to_deposit.push((mind.game_object_id, stockpile.unwrap()));
+                    } else {
+                        mind.game_object_id.intend_move_to_point(
+                            &mut game.movement_system.intend_move_system,
+                            stockpile_coords,
+                        );
+                    }
+                    continue;
+                }
                 let direction = Angle::enforce_range(rand::thread_rng().gen_range(0.0..PI * 2.0));
                 mind.game_object_id.intend_move_in_direction_minimal(
                     &mut game.movement_system.intend_move_system,
@@ -29,6 +49,9 @@ impl VillagerBehaviour {
         for id in to_throw {
             id.drop_resources(game);
         }
+        for (id, stockpile) in to_deposit {
+            id.transfer_resources(game, stockpile);
+        }
         for id in to_delete {
             VillagerBehaviour::remove(game, id);
         }
@@ -36,9 +59,12 @@ impl VillagerBehaviour {
     pub fn remove(game: &mut Game, id: BehaviourId) {
         game.villages_system.villager_behaviours.remove(id);
     }
-    pub fn new(game: &mut Game, mind_id: MindId) -> BehaviourId {
+    pub fn new(game: &mut Game, mind_id: MindId, village_id: VillageId) -> BehaviourId {
         let villager_behaviour_id = game.get_id();
-        let villager_behaviour = VillagerBehaviour { mind_id };
+        let villager_behaviour = VillagerBehaviour {
+            mind_id,
+            village_id,
+        };
         game.villages_system
             .villager_behaviours
             .insert(villager_behaviour_id, villager_behaviour);
@@ -48,6 +74,8 @@ impl VillagerBehaviour {
 
 pub struct VillagerMind {
     mind_id: MindId,
+    village_id: VillageId,
+    game_object_id: GameObjectId,
     woodcutter_behaviour_id: BehaviourId,
     villager_behaviour_id: BehaviourId,
     reproduce_behaviour_id: BehaviourId,
@@ -55,6 +83,8 @@ pub struct VillagerMind {
     build_house_behaviour_id: BehaviourId,
     build_scaffold_behaviour_id: BehaviourId,
     deconstruct_behaviour_id: BehaviourId,
+    gather_behaviour_id: BehaviourId,
+    craft_behaviour_id: BehaviourId,
 }
 
 impl VillagerMind {
@@ -63,32 +93,60 @@ impl VillagerMind {
         let mut scaffold_behaviours_to_activate = Vec::new();
         let mut deconstruct_behaviours_to_activate = Vec::new();
         let mut new_reproduce = Vec::new();
+        let mut gather_behaviours_to_activate = Vec::new();
+        let mut craft_behaviours_to_activate = Vec::new();
         for (id, villager_mind) in game.villages_system.villager_minds.iter() {
             let mut new_behaviour_id = None;
             if let Some(mind) = game.behaviour_system.minds.get(villager_mind.mind_id) {
                 if mind.active_behaviour.is_none() {
                     let resources = mind.game_object_id.get_resources(game);
                     let reproduce_chance = 1.0 / (game.villages_system.villager_minds.len() as f64);
-                    let scaffold_id = game.villages_system.unassigned_scaffolds.iter().next();
-                    let needs_deconstruction_id = game
-                        .villages_system
-                        .unassigned_needs_deconstruction
-                        .iter()
-                        .next();
-                    if resources.get_resource_amount(ResourceType::Wood).0 > 3 {
+                    let village = game.villages_system.villages.get(villager_mind.village_id);
+                    let has_job = |filter: fn(&VillageTask) -> bool| {
+                        village
+                            .map(|village| village.job_board.has_unclaimed(filter))
+                            .unwrap_or(false)
+                    };
+                    let carrying_goods = resources
+                        .resource_amounts
+                        .keys()
+                        .any(|resource_type| *resource_type != ResourceType::Wood);
+                    if resources.get_resource_amount(ResourceType::Wood).0 > 3 || carrying_goods {
                         new_behaviour_id = Some(villager_mind.villager_behaviour_id);
-                    } else if let Some(needs_deconstruction_id) = needs_deconstruction_id {
+                    } else if has_job(DeconstructBehaviour::is_task) {
                         new_behaviour_id = Some(villager_mind.deconstruct_behaviour_id);
                         deconstruct_behaviours_to_activate.push((
+                            villager_mind.mind_id,
                             villager_mind.deconstruct_behaviour_id,
-                            *needs_deconstruction_id,
                         ));
-                    } else if rand::thread_rng().gen_bool(0.3) && scaffold_id.is_some() {
+                    } else if rand::thread_rng().gen_bool(0.3)
+                        && has_job(BuildScaffoldBehaviour::is_task)
+                    {
                         new_behaviour_id = Some(villager_mind.build_scaffold_behaviour_id);
                         scaffold_behaviours_to_activate.push((
+                            villager_mind.mind_id,
                             villager_mind.build_scaffold_behaviour_id,
-                            *scaffold_id.unwrap(),
                         ));
+                    } else if let Some(job) = rand::thread_rng()
+                        .gen_bool(0.5)
+                        .then(|| VillagesSystem::choose_economy_job(game, villager_mind.village_id))
+                        .flatten()
+                    {
+                        match job {
+                            EconomyJob::Gather(ResourceType::Wood) => {
+                                new_behaviour_id = Some(villager_mind.woodcutter_behaviour_id);
+                            }
+                            EconomyJob::Gather(resource_type) => {
+                                new_behaviour_id = Some(villager_mind.gather_behaviour_id);
+                                gather_behaviours_to_activate
+                                    .push((villager_mind.gather_behaviour_id, resource_type));
+                            }
+                            EconomyJob::Craft(workshop_id) => {
+                                new_behaviour_id = Some(villager_mind.craft_behaviour_id);
+                                craft_behaviours_to_activate
+                                    .push((villager_mind.craft_behaviour_id, workshop_id));
+                            }
+                        }
                     } else if rand::thread_rng().gen_bool(0.5) {
                         new_behaviour_id = Some(villager_mind.build_house_behaviour_id);
                     } else if rand::thread_rng().gen_bool(reproduce_chance) {
@@ -113,29 +171,44 @@ impl VillagerMind {
         for id in new_reproduce {
             ReproduceBehaviour::begin_behaviour(game, id);
         }
-        for (id, scaffold_id) in scaffold_behaviours_to_activate {
-            BuildScaffoldBehaviour::activate(game, id, scaffold_id);
+        // Several villagers may have gone for the same job this step
+        for (mind_id, id) in scaffold_behaviours_to_activate {
+            if !BuildScaffoldBehaviour::activate(game, id) {
+                mind_id.set_active_behaviour(&mut game.behaviour_system.minds, None);
+            }
+        }
+        for (mind_id, id) in deconstruct_behaviours_to_activate {
+            if !DeconstructBehaviour::activate(game, id) {
+                mind_id.set_active_behaviour(&mut game.behaviour_system.minds, None);
+            }
+        }
+        for (id, resource_type) in gather_behaviours_to_activate {
+            GatherBehaviour::activate(game, id, resource_type);
         }
-        for (id, needs_deconstruction_id) in deconstruct_behaviours_to_activate {
-            DeconstructBehaviour::activate(game, id, needs_deconstruction_id);
+        for (id, workshop_id) in craft_behaviours_to_activate {
+            CraftBehaviour::activate(game, id, workshop_id);
         }
         for id in to_delete {
             VillagerMind::delete(game, id);
         }
     }
-    pub fn new(game: &mut Game, owner_id: GameObjectId) {
+    pub fn new(game: &mut Game, owner_id: GameObjectId, village_id: VillageId) {
         let villager_mind_id = game.get_id();
         let mind_id = MindComponent::add_to(game, owner_id);
         let woodcutter_behaviour_id = WoodcutterBehaviour::new(game, mind_id);
-        let villager_behaviour_id = VillagerBehaviour::new(game, mind_id);
+        let villager_behaviour_id = VillagerBehaviour::new(game, mind_id, village_id);
         let hunter_behaviour_id =
             HunterBehaviourComponent::add_to(game, owner_id, mind_id).hunter_behaviour;
-        let build_house_behaviour_id = BuildHouseBehaviour::new(game, mind_id);
-        let reproduce_behaviour_id = ReproduceBehaviour::new(game, mind_id);
-        let build_scaffold_behaviour_id = BuildScaffoldBehaviour::new(game, mind_id);
-        let deconstruct_behaviour_id = DeconstructBehaviour::new(game, mind_id);
+        let build_house_behaviour_id = BuildHouseBehaviour::new(game, mind_id, village_id);
+        let reproduce_behaviour_id = ReproduceBehaviour::new(game, mind_id, village_id);
+        let build_scaffold_behaviour_id = BuildScaffoldBehaviour::new(game, mind_id, village_id);
+        let deconstruct_behaviour_id = DeconstructBehaviour::new(game, mind_id, village_id);
+        let gather_behaviour_id = GatherBehaviour::new(game, mind_id);
+        let craft_behaviour_id = CraftBehaviour::new(game, mind_id);
         let villager_mind = VillagerMind {
             mind_id,
+            village_id,
+            game_object_id: owner_id,
             woodcutter_behaviour_id,
             villager_behaviour_id,
             hunter_behaviour_id,
@@ -143,6 +216,8 @@ impl VillagerMind {
             reproduce_behaviour_id,
             build_scaffold_behaviour_id,
             deconstruct_behaviour_id,
+            gather_behaviour_id,
+            craft_behaviour_id,
         };
         game.villages_system
             .villager_minds
@@ -155,6 +230,11 @@ impl VillagerMind {
             ReproduceBehaviour::remove(game, mind.reproduce_behaviour_id);
             BuildHouseBehaviour::remove(game, mind.build_house_behaviour_id);
             DeconstructBehaviour::remove(game, mind.deconstruct_behaviour_id);
+            GatherBehaviour::remove(game, mind.gather_behaviour_id);
+            CraftBehaviour::remove(game, mind.craft_behaviour_id);
+            if let Some(village) = game.villages_system.villages.get_mut(mind.village_id) {
+                village.residents.remove(&mind.game_object_id);
+            }
         }
     }
 }
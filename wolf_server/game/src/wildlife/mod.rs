@@ -1,14 +1,7 @@
-use crate::{
-    damage::{DamageableComponent, DeleteOnDeathComponent, DieOnNoHealthComponent},
-    game::*,
-    hunting::PreyComponent,
-    resources::{ResourceAmount, ResourceDropperComponent, Resources},
-};
+use crate::{game::*, prefabs::PrefabSystem};
 use rand::thread_rng;
 use std::f64::consts::PI;
-use wolf_hash_map::WolfHashMap;
 
-const HOPPER_CREATURE_SPEED: f64 = 4.0;
 const GRAZE_DISTANCE: f64 = 300.0;
 const GRAZE_CLOSE_ENOUGH: f64 = 50.0;
 const GRAZE_REST_TIME: u32 = 250;
@@ -112,20 +105,10 @@ impl WanderingHerbivoreComponent {
     }
 }
 
-pub fn create_hopper_creature(game: &mut Game, coords: PixelCoords) {
-    let game_object_id = GameObject::create_game(game, coords);
-    HopperComponent::add_to(game, game_object_id, HOPPER_CREATURE_SPEED);
-    WanderingHerbivoreComponent::add_to(game, game_object_id);
-    let mut sprites = WolfHashMap::new();
-    sprites.insert(CardinalDirection::Left, CREATURE_SPRITE_LEFT);
-    sprites.insert(CardinalDirection::Right, CREATURE_SPRITE_RIGHT);
-    sprites.insert(CardinalDirection::Up, CREATURE_SPRITE_UP);
-    sprites.insert(CardinalDirection::Down, CREATURE_SPRITE_DOWN);
-    FacingSpriteComponent::add_to(game, game_object_id, sprites, DEFAULT_DEPTH);
-    DamageableComponent::add_to(game, game_object_id);
-    PreyComponent::add_to(game, game_object_id);
-    DieOnNoHealthComponent::add_to(game, game_object_id);
-    DeleteOnDeathComponent::add_to(game, game_object_id);
-    ResourceDropperComponent::add_to(game, game_object_id, Resources::food(ResourceAmount(20)));
-    add_health_bar(game, game_object_id);
+// See data/prefabs/creatures.ron
+pub fn create_hopper_creature(
+    game: &mut Game,
+    coords: PixelCoords,
+) -> Result<GameObjectId, String> {
+    PrefabSystem::spawn(game, "hopper", coords)
 }
@@ -7,11 +7,14 @@ use proc_macro2::Span;
 use quote::{quote, ToTokens};
 use syn::parse::{Parse, ParseStream, Result};
 use syn::punctuated::Punctuated;
-use syn::{parse_macro_input, Ident, Token, Type};
+use syn::{parse_macro_input, Ident, Path, Token, Type};
 
+// Takes the path to the crate with SignalListeners and Combinable first, e.g.
+// define_signal_listener!(::signal_listeners; Damage, &mut Game, damage: Health)
 #[proc_macro]
 pub fn define_signal_listener(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(tokens as SignalListenerInput);
+    let signals = input.signals_path;
     let name_as_string = input.name.to_string();
     let snake_case_name = to_snake_case(&name_as_string);
     let trait_name = Ident::new(
@@ -68,14 +71,19 @@ pub fn define_signal_listener(tokens: proc_macro::TokenStream) -> proc_macro::To
         }
     });
     let collect_result = input.return_type.clone().map(|_| quote!(let result =));
+    // Results are combined in dispatch order, and a result that stops propagation ends the send
     let combine_result = input.return_type.clone().map(|_| {
         quote! {
+            let stop = #signals::Combinable::stops_propagation(&result);
             if let Some(current_collected_result) = collected_result{
-                collected_result = Some(current_collected_result.combine_result(result));
+                collected_result = Some(#signals::Combinable::combine_result(current_collected_result, result));
             }
             else{
                 collected_result = Some(result);
             }
+            if stop {
+                break;
+            }
         }
     });
     let return_clause = input.return_type.clone().map(|_| {
@@ -97,11 +105,14 @@ pub fn define_signal_listener(tokens: proc_macro::TokenStream) -> proc_macro::To
         quote!(return)
     };
     let output = quote! {
-        type #listeners_type_name = id::IdMap<ComponentId, Box<#trait_name>>;
+        type #listeners_type_name = #signals::SignalListeners<ComponentId, dyn #trait_name>;
         pub trait #trait_name{
             fn #receive_signal_func_name(&self, #game_arg, owner: GameObjectId, #(#args),*)#return_type_with_arrow;
             fn get_listener_id(&self) -> ComponentId;
-            fn clone_box(&self) -> Box<dyn #trait_name>;
+            // Higher priorities are sent the signal first, equal priorities in the order they were added
+            fn get_listener_priority(&self) -> i32 {
+                0
+            }
         }
         pub trait #sender_trait_name{
             fn #send_signal_func_name(&self, #game_arg, #(#args),*)#return_type_with_arrow_optional;
@@ -109,38 +120,34 @@ pub fn define_signal_listener(tokens: proc_macro::TokenStream) -> proc_macro::To
         }
         impl #sender_trait_name for GameObjectId{
             fn #send_signal_func_name(&self, #game_arg, #(#args),*)#return_type_with_arrow_optional{
-                let listeners = {
-                    if let Some(this) = game.game_objects.get(*self){
-                        this.listeners.get::<#listeners_type_name>()
-                            .map(|listeners|listeners
-                                 .iter()
-                                 .map(|(_key, item)|item.clone_box())
-                                 .collect::<Vec<Box<#trait_name>>>()
-                            )
-                    }
-                    else{
-                        #early_signal_send_return
-                    }
+                let mut cursor = match game.game_objects.get(*self)
+                    .and_then(|this| this.listeners.get::<#listeners_type_name>()) {
+                    Some(listeners) => listeners.start_send(),
+                    None => #early_signal_send_return,
                 };
                 #init_result
-                if let Some(listeners) = listeners{
-                    for listener in listeners.iter(){
-                        #collect_result listener.#receive_signal_func_name(
-                            game,
-                            self.clone(),
-                            #(#arg_names_only),*
-                        );
-                        #combine_result
-                    }
+                loop {
+                    // Looked up again each time, as earlier listeners can add and remove listeners
+                    let listener = match game.game_objects.get(*self)
+                        .and_then(|this| this.listeners.get::<#listeners_type_name>())
+                        .and_then(|listeners| listeners.next_listener(&mut cursor)) {
+                        Some(listener) => listener,
+                        None => break,
+                    };
+                    #collect_result listener.#receive_signal_func_name(
+                        game,
+                        self.clone(),
+                        #(#arg_names_only),*
+                    );
+                    #combine_result
                 }
                 #return_clause
             }
             fn #send_signal_to_func_name(&self, #game_arg, listener_id: ComponentId, #(#args),*)#return_type_with_arrow_optional{
                 if let Some(this) = game.game_objects.get(*self){
                     if let Some(listeners) = this.listeners.get::<#listeners_type_name>() {
-                        if let Some(listener) = listeners.get(listener_id) {
-                            let cloned_listener = listener.clone_box();
-                            let collected_result = Some(cloned_listener.#receive_signal_func_name(
+                        if let Some(listener) = listeners.get(listener_id).map(std::rc::Rc::clone) {
+                            let collected_result = Some(listener.#receive_signal_func_name(
                                 game,
                                 self.clone(),
                                 #(#arg_names_only),*
@@ -158,11 +165,11 @@ pub fn define_signal_listener(tokens: proc_macro::TokenStream) -> proc_macro::To
         }
         impl #holder_trait_name for GameObject{
             fn #add_listener_func_name<T: #trait_name + 'static>(&mut self, listener_unboxed: T){
-                let listener: Box<dyn #trait_name> = Box::new(listener_unboxed);
+                let listener: std::rc::Rc<dyn #trait_name> = std::rc::Rc::new(listener_unboxed);
                 let listeners = self.listeners
                     .entry::<#listeners_type_name>()
-                    .or_insert(id::IdMap::new());
-                listeners.insert(listener.get_listener_id(), listener);
+                    .or_insert(#signals::SignalListeners::new());
+                listeners.insert(listener.get_listener_id(), listener.get_listener_priority(), listener);
             }
             fn #remove_listener_func_name(&mut self, id: ComponentId) {
                 let listeners_entry = self.listeners.entry::<#listeners_type_name>();
@@ -254,6 +261,7 @@ impl Parse for GameRef {
     }
 }
 struct SignalListenerInput {
+    signals_path: Path,
     name: Ident,
     game_ref: GameRef,
     args: Vec<Argument>,
@@ -261,12 +269,15 @@ struct SignalListenerInput {
 }
 impl Parse for SignalListenerInput {
     fn parse(input: ParseStream) -> Result<Self> {
+        let signals_path = input.parse::<Path>()?;
+        input.parse::<Token![;]>()?;
         let name = input.parse::<Ident>()?;
         let _ignore_comma = input.parse::<Token![,]>();
         let game_ref = input.parse::<GameRef>()?;
         let early_return = input.parse::<ReturnType>().ok();
         if early_return.is_some() {
             return Ok(SignalListenerInput {
+                signals_path,
                 name,
                 game_ref,
                 args: Vec::new(),
@@ -288,6 +299,7 @@ impl Parse for SignalListenerInput {
             })
             .collect();
         Ok(SignalListenerInput {
+            signals_path,
             name,
             game_ref,
             args,
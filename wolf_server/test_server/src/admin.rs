@@ -0,0 +1,172 @@
+use game::*;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::*;
+
+use crate::metrics::metrics_thread;
+
+/*
+Admin commands come from stdin and from a socket that only listens locally (admin_host.txt if
+it's there). Over the socket it's one command per line, answered with whatever the command
+printed, then "ok" or "error <why>" on a line of its own.
+
+Commands are run on the main thread between ticks. Pausing and stepping are handled here,
+everything else is passed on to the game.
+*/
+
+const ADMIN_HOST_FILE: &str = "admin_host.txt";
+const DEFAULT_ADMIN_HOST: &str = "127.0.0.1:7777";
+
+const SERVER_COMMAND_USAGES: [&str; 3] = ["pause", "resume", "step [ticks]"];
+
+type AdminReply = Result<Vec<String>, String>;
+
+pub struct AdminRequest {
+    line: String,
+    reply_sender: Sender<AdminReply>,
+}
+
+pub struct ServerControl {
+    paused: bool,
+    steps_to_run: u32,
+}
+
+impl ServerControl {
+    pub fn new() -> Self {
+        ServerControl {
+            paused: false,
+            steps_to_run: 0,
+        }
+    }
+    // Call once a tick
+    pub fn should_step(&mut self) -> bool {
+        if !self.paused {
+            true
+        } else if self.steps_to_run > 0 {
+            self.steps_to_run -= 1;
+            true
+        } else {
+            false
+        }
+    }
+    pub fn handle_requests(&mut self, game: &mut Game, admin_receiver: &Receiver<AdminRequest>) {
+        for request in admin_receiver.try_iter() {
+            let reply = self.run(game, &request.line);
+            // Whoever asked may have gone already
+            let _ = request.reply_sender.send(reply);
+        }
+    }
+    fn run(&mut self, game: &mut Game, line: &str) -> AdminReply {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["pause"] => {
+                self.paused = true;
+                Ok(vec![format!("Paused at tick {}", game.tick_counter)])
+            }
+            ["resume"] => {
+                self.paused = false;
+                Ok(vec!["Resumed".to_string()])
+            }
+            ["step"] | ["step", _] => {
+                let ticks = match words.get(1) {
+                    Some(ticks) => ticks
+                        .parse()
+                        .map_err(|_| format!("{} isn't a number of ticks", ticks))?,
+                    None => 1,
+                };
+                self.paused = true;
+                self.steps_to_run = self.steps_to_run.saturating_add(ticks);
+                Ok(vec![format!("Stepping {} ticks", ticks)])
+            }
+            ["help"] => Ok(SERVER_COMMAND_USAGES
+                .iter()
+                .chain(get_admin_command_usages().iter())
+                .map(|usage| usage.to_string())
+                .collect()),
+            _ => run_admin_command(game, line),
+        }
+    }
+}
+
+pub fn start_admin_threads() -> Receiver<AdminRequest> {
+    let (request_sender, request_receiver) = channel();
+    let console_sender = request_sender.clone();
+    std::thread::spawn(move || console_thread(console_sender));
+    let metrics_sender = request_sender.clone();
+    std::thread::spawn(move || metrics_thread(metrics_sender));
+    std::thread::spawn(move || admin_socket_thread(request_sender));
+    request_receiver
+}
+
+pub fn send_request(request_sender: &Sender<AdminRequest>, line: String) -> Option<AdminReply> {
+    let (reply_sender, reply_receiver) = channel();
+    request_sender
+        .send(AdminRequest { line, reply_sender })
+        .ok()?;
+    reply_receiver.recv().ok()
+}
+
+fn console_thread(request_sender: Sender<AdminRequest>) {
+    let stdin = std::io::stdin();
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        match send_request(&request_sender, line) {
+            Some(Ok(output)) => {
+                for output_line in output {
+                    println!("{}", output_line);
+                }
+            }
+            Some(Err(error)) => println!("error {}", error),
+            None => break,
+        }
+    }
+}
+
+fn admin_socket_thread(request_sender: Sender<AdminRequest>) {
+    let host = std::fs::read_to_string(ADMIN_HOST_FILE)
+        .map(|host| host.trim().to_string())
+        .unwrap_or(DEFAULT_ADMIN_HOST.to_string());
+    let listener = match TcpListener::bind(&host) {
+        Ok(listener) => listener,
+        Err(error) => {
+            tracing::warn!("Unable to bind admin socket to {} due to {}", host, error);
+            return;
+        }
+    };
+    tracing::info!("Admin socket on {}", host);
+    for stream in listener.incoming() {
+        if let Ok(stream) = stream {
+            let request_sender = request_sender.clone();
+            std::thread::spawn(move || admin_client_thread(stream, request_sender));
+        }
+    }
+}
+
+fn admin_client_thread(stream: TcpStream, request_sender: Sender<AdminRequest>) {
+    let mut output_stream = match stream.try_clone() {
+        Ok(output_stream) => output_stream,
+        Err(_) => return,
+    };
+    for line in BufReader::new(stream).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        let reply = match send_request(&request_sender, line) {
+            Some(Ok(mut output)) => {
+                output.push("ok".to_string());
+                output
+            }
+            Some(Err(error)) => vec![format!("error {}", error)],
+            None => break,
+        };
+        let mut text = reply.join("\n");
+        text.push('\n');
+        if output_stream.write_all(text.as_bytes()).is_err() {
+            break;
+        }
+    }
+}
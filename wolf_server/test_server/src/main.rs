@@ -1,45 +1,101 @@
 use game::*;
+use std::io::Write;
 use std::net::*;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::*;
+use std::sync::Arc;
 use std::time;
+use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
 use wolf_interface::*;
 
+mod admin;
+use admin::*;
+mod metrics;
+
+const PLAYER_SAVE_DIRECTORY: &str = "saves/players";
+
 struct Client {
     command_receiver: Receiver<Command>,
     server_message_sender: Sender<Vec<ServerMessage>>,
     player_id: Option<PlayerId>,
+    // Added to by the client's send thread, taken into the metrics every tick
+    bytes_sent: Arc<AtomicU64>,
+}
+
+// Counts what's written, so bytes sent can be reported per player
+struct CountingWriter<W: Write> {
+    inner: W,
+    bytes_written: Arc<AtomicU64>,
+}
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.bytes_written
+            .fetch_add(written as u64, Ordering::Relaxed);
+        Ok(written)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
 }
 
 fn main() {
+    // RUST_LOG=info,game=debug logs how long every tick and system took
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or(EnvFilter::new("info")))
+        .with_span_events(FmtSpan::CLOSE)
+        .init();
+
     let mut game = Game::new();
+    game.progression_system.save_directory = Some(PLAYER_SAVE_DIRECTORY.into());
     game.initialise();
 
     let (client_sender, client_receiver) = channel();
     std::thread::spawn(move || net_thread(client_sender));
 
+    let admin_receiver = start_admin_threads();
+    let mut server_control = ServerControl::new();
+
     let mut clients: Vec<Client> = Vec::new();
 
     loop {
         let start_time = time::Instant::now();
-        game.step();
+        server_control.handle_requests(&mut game, &admin_receiver);
+        if server_control.should_step() {
+            game.step();
+        }
         for new_client in client_receiver.try_iter() {
             clients.push(new_client);
         }
         let mut clients_to_remove = Vec::new();
         for (index, mut client) in clients.iter_mut().enumerate() {
             if let Some(player_id) = client.player_id {
+                game.metrics_system.add_counter(
+                    "wolf_bytes_sent_total",
+                    "Bytes sent to each player",
+                    &[("player", &player_id.0.to_string())],
+                    client.bytes_sent.swap(0, Ordering::Relaxed) as f64,
+                );
                 let player = game
                     .player_system
                     .players
                     .get_mut(player_id)
                     .expect("Player got deleted before client was cleaned up!");
-                for command in client.command_receiver.try_iter() {
-                    player.commands.push(command);
+                loop {
+                    match client.command_receiver.try_recv() {
+                        Ok(command) => player.commands.push(command),
+                        Err(TryRecvError::Empty) => break,
+                        // The client's receive thread has stopped, so they're gone
+                        Err(TryRecvError::Disconnected) => {
+                            clients_to_remove.push(index);
+                            break;
+                        }
+                    }
                 }
                 let mut new_server_messages = Vec::new();
                 std::mem::swap(&mut player.server_messages, &mut new_server_messages);
                 if let Some(error) = client.server_message_sender.send(new_server_messages).err() {
-                    println!("Error sending server messages due to {}", error);
+                    tracing::warn!("Error sending server messages due to {}", error);
                     clients_to_remove.push(index);
                 }
             } else {
@@ -48,9 +104,17 @@ fn main() {
             }
         }
         clients_to_remove.sort();
+        clients_to_remove.dedup();
         //loop in reverse order to avoid messing up indices
         for index in clients_to_remove.into_iter().rev() {
-            clients.swap_remove(index);
+            let client = clients.swap_remove(index);
+            if let Some(player_id) = client.player_id {
+                Player::remove(&mut game, player_id);
+                game.metrics_system.remove_series(
+                    "wolf_bytes_sent_total",
+                    &[("player", &player_id.0.to_string())],
+                );
+            }
         }
         let end_time = time::Instant::now();
         let elapsed = end_time - start_time;
@@ -58,17 +122,25 @@ fn main() {
         if target_time > elapsed {
             let sleep_time = target_time - elapsed;
             std::thread::sleep(sleep_time);
-        } else if game.tick_counter % 100 == 0 {
-            println!(
-                "elapsed {} millseconds less than target_time",
-                (elapsed - target_time).as_millis()
+        } else {
+            game.metrics_system.add_counter(
+                "wolf_tick_overruns_total",
+                "Ticks that took longer than the target time",
+                &[],
+                1.0,
             );
+            if game.tick_counter % 100 == 0 {
+                tracing::warn!(
+                    overrun_ms = (elapsed - target_time).as_millis() as u64,
+                    "Tick took longer than the target time"
+                );
+            }
         }
     }
 }
 fn net_thread(client_sender: Sender<Client>) {
     let host = std::fs::read_to_string("host.txt").expect("unable to read host.txt!");
-    println!("Binding to {}", host);
+    tracing::info!("Binding to {}", host);
     let listener = TcpListener::bind(host.trim()).expect("Unable to bind to port");
     for input_stream in listener.incoming() {
         if let Ok(input_stream) = input_stream {
@@ -77,15 +149,22 @@ fn net_thread(client_sender: Sender<Client>) {
                 .expect("Could not disable Nagle's!");
             let (command_sender, command_receiver) = channel();
             let (server_message_sender, server_message_receiver) = channel();
+            let bytes_sent = Arc::new(AtomicU64::new(0));
             client_sender
                 .send(Client {
                     command_receiver,
                     server_message_sender,
                     player_id: None,
+                    bytes_sent: Arc::clone(&bytes_sent),
                 })
                 .unwrap();
             std::thread::spawn(move || {
-                client_thread(input_stream, command_sender, server_message_receiver)
+                client_thread(
+                    input_stream,
+                    command_sender,
+                    server_message_receiver,
+                    bytes_sent,
+                )
             });
         }
     }
@@ -94,21 +173,26 @@ fn client_thread(
     input_stream: TcpStream,
     command_sender: Sender<Command>,
     server_message_receiver: Receiver<Vec<ServerMessage>>,
+    bytes_sent: Arc<AtomicU64>,
 ) {
     let output_stream = input_stream.try_clone();
     if let Ok(output_stream) = output_stream {
+        let output_stream = CountingWriter {
+            inner: output_stream,
+            bytes_written: bytes_sent,
+        };
         std::thread::spawn(move || client_send_thread(output_stream, server_message_receiver));
         std::thread::spawn(move || client_receive_thread(input_stream, command_sender));
     }
 }
 
 fn client_send_thread(
-    mut output_stream: TcpStream,
+    mut output_stream: CountingWriter<TcpStream>,
     server_message_receiver: Receiver<Vec<ServerMessage>>,
 ) {
     for server_messages in server_message_receiver.iter() {
         if let Err(e) = server_messages.wolf_serialise(&mut output_stream) {
-            println!("Unable to write server messages due to {}", e);
+            tracing::warn!("Unable to write server messages due to {}", e);
             //TODO: clean up client
             break;
         }
@@ -126,7 +210,7 @@ fn client_receive_thread(mut input_stream: TcpStream, command_sender: Sender<Com
                 }
             }
             Err(e) => {
-                println!("Unable to read server messages due to {}", e);
+                tracing::warn!("Unable to read server messages due to {}", e);
                 break;
             }
         }
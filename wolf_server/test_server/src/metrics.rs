@@ -0,0 +1,76 @@
+use crate::admin::*;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+/*
+Serves the game's metrics in the Prometheus text format over plain HTTP, only listening locally
+(metrics_host.txt if it's there). Every scrape is the "metrics" admin command, so it's answered
+on the main thread between ticks like any other.
+*/
+
+const METRICS_HOST_FILE: &str = "metrics_host.txt";
+const DEFAULT_METRICS_HOST: &str = "127.0.0.1:9898";
+// Scrapes are answered one at a time, so a scraper that stops talking can't hold up the rest
+const SCRAPE_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub fn metrics_thread(request_sender: Sender<AdminRequest>) {
+    let host = std::fs::read_to_string(METRICS_HOST_FILE)
+        .map(|host| host.trim().to_string())
+        .unwrap_or(DEFAULT_METRICS_HOST.to_string());
+    let listener = match TcpListener::bind(&host) {
+        Ok(listener) => listener,
+        Err(error) => {
+            tracing::warn!(
+                "Unable to bind metrics endpoint to {} due to {}",
+                host,
+                error
+            );
+            return;
+        }
+    };
+    tracing::info!("Metrics on http://{}/metrics", host);
+    for stream in listener.incoming() {
+        if let Ok(stream) = stream {
+            // Whoever was scraping went away, they can try again
+            let _ = answer_scrape(stream, &request_sender);
+        }
+    }
+}
+
+fn answer_scrape(
+    mut stream: TcpStream,
+    request_sender: &Sender<AdminRequest>,
+) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(SCRAPE_TIMEOUT))?;
+    stream.set_write_timeout(Some(SCRAPE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // The headers don't matter, but they're read so the client isn't cut off mid-send
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+    let (status, body) = if path != "/metrics" {
+        ("404 Not Found", "Metrics are at /metrics\n".to_string())
+    } else {
+        match send_request(request_sender, "metrics".to_string()) {
+            Some(Ok(lines)) => ("200 OK", lines.join("\n") + "\n"),
+            Some(Err(error)) => ("500 Internal Server Error", error + "\n"),
+            None => (
+                "503 Service Unavailable",
+                "The server is stopping\n".to_string(),
+            ),
+        }
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}